@@ -0,0 +1,55 @@
+//! Text chunking for embedding
+
+/// Split text into chunks of at most `max_chars` characters
+///
+/// Paragraphs are kept together where possible; paragraphs longer than a chunk
+/// are split with `overlap` characters of context carried into the next chunk.
+pub fn chunk_text(text: &str, max_chars: usize, overlap: usize) -> Vec<String> {
+    let overlap = overlap.min(max_chars / 2);
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    let mut current_chars = 0;
+
+    let paragraphs = text
+        .split("\n\n")
+        .map(str::trim)
+        .filter(|p| !p.is_empty());
+
+    for paragraph in paragraphs {
+        let paragraph_chars = paragraph.chars().count();
+
+        // Flush the current chunk if the paragraph doesn't fit
+        if current_chars > 0 && current_chars + 2 + paragraph_chars > max_chars {
+            chunks.push(std::mem::take(&mut current));
+            current_chars = 0;
+        }
+
+        if paragraph_chars <= max_chars {
+            if current_chars > 0 {
+                current.push_str("\n\n");
+                current_chars += 2;
+            }
+            current.push_str(paragraph);
+            current_chars += paragraph_chars;
+            continue;
+        }
+
+        // Split long paragraphs into overlapping windows
+        let chars: Vec<char> = paragraph.chars().collect();
+        let mut start = 0;
+        while start < chars.len() {
+            let end = (start + max_chars).min(chars.len());
+            chunks.push(chars[start..end].iter().collect());
+            if end == chars.len() {
+                break;
+            }
+            start = end - overlap;
+        }
+    }
+
+    if current_chars > 0 {
+        chunks.push(current);
+    }
+
+    chunks
+}
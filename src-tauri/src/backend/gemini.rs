@@ -0,0 +1,128 @@
+//! Gemini File Search backend
+
+use async_trait::async_trait;
+use std::path::Path;
+
+use super::{
+    OperationStatus, QueryResult, RemoteDocument, RemoteStore, SearchBackend, UploadStatus,
+    UploadedDocument,
+};
+use crate::extract;
+use crate::gemini::types::CustomMetadata;
+use crate::gemini::GeminiClient;
+
+/// Map Gemini document state to internal status
+fn map_gemini_state_to_status(state: Option<&str>) -> String {
+    match state {
+        Some("STATE_ACTIVE") | Some("ACTIVE") => "completed".to_string(),
+        Some("STATE_PENDING") | Some("PENDING") => "processing".to_string(),
+        Some("STATE_FAILED") | Some("FAILED") => "failed".to_string(),
+        Some(s) if s.to_lowercase().contains("active") => "completed".to_string(),
+        Some(s) if s.to_lowercase().contains("pending") => "processing".to_string(),
+        Some(s) if s.to_lowercase().contains("fail") => "failed".to_string(),
+        _ => "completed".to_string(),
+    }
+}
+
+#[async_trait]
+impl SearchBackend for GeminiClient {
+    async fn create_store(&self, display_name: &str) -> Result<RemoteStore, String> {
+        let store = GeminiClient::create_store(self, display_name).await?;
+
+        Ok(RemoteStore {
+            name: store.name,
+            create_time: store.create_time,
+            update_time: store.update_time,
+            active_documents_count: store.active_documents_count,
+            pending_documents_count: store.pending_documents_count,
+            failed_documents_count: store.failed_documents_count,
+            size_bytes: store.size_bytes,
+        })
+    }
+
+    async fn delete_store(&self, store_name: &str, force: bool) -> Result<(), String> {
+        GeminiClient::delete_store(self, store_name, force).await
+    }
+
+    // File Search stores can't be updated, so `rename_store` keeps the no-op default
+
+    async fn upload_document(
+        &self,
+        store_name: &str,
+        path: &Path,
+        display_name: &str,
+        custom_metadata: &[CustomMetadata],
+    ) -> Result<UploadedDocument, String> {
+        // Read the file, converting formats Gemini doesn't accept to text locally
+        let upload = extract::prepare_upload(path).await?;
+
+        let operation = GeminiClient::upload_document(
+            self,
+            store_name,
+            upload.bytes,
+            &upload.mime_type,
+            Some(display_name),
+            custom_metadata,
+        )
+        .await?;
+
+        Ok(UploadedDocument {
+            upload_mime_type: upload.mime_type,
+            status: UploadStatus::Pending {
+                operation_name: operation.name,
+            },
+        })
+    }
+
+    async fn get_operation(&self, operation_name: &str) -> Result<OperationStatus, String> {
+        let operation = GeminiClient::get_operation(self, operation_name).await?;
+
+        if !operation.done {
+            return Ok(OperationStatus::Running);
+        }
+
+        if let Some(error) = operation.error {
+            return Ok(OperationStatus::Failed(error.message));
+        }
+
+        Ok(OperationStatus::Done {
+            document_name: operation.response.and_then(|r| r.document_name),
+        })
+    }
+
+    async fn get_document(&self, document_name: &str) -> Result<RemoteDocument, String> {
+        let document = GeminiClient::get_document(self, document_name).await?;
+
+        Ok(RemoteDocument {
+            status: map_gemini_state_to_status(document.state.as_deref()),
+            name: document.name,
+            mime_type: document.mime_type,
+            custom_metadata: document.custom_metadata.unwrap_or_default(),
+        })
+    }
+
+    async fn delete_document(&self, document_name: &str) -> Result<(), String> {
+        GeminiClient::delete_document(self, document_name).await
+    }
+
+    async fn query(&self, store_names: &[String], query: &str) -> Result<QueryResult, String> {
+        let result = self.query_stores(store_names, query).await?;
+
+        Ok(QueryResult {
+            content: result.content,
+            sources: result.sources,
+        })
+    }
+
+    async fn suggest_questions(
+        &self,
+        store_names: &[String],
+        locale: &str,
+    ) -> Result<Vec<String>, String> {
+        GeminiClient::suggest_questions(self, store_names, locale).await
+    }
+
+    async fn check_connection(&self) -> Result<(), String> {
+        GeminiClient::check_connection(self).await
+    }
+}
@@ -0,0 +1,340 @@
+//! Local retrieval backend
+//!
+//! Documents are extracted and chunked locally, embedded and stored in the
+//! local database. Queries retrieve the most similar chunks and pass them to a
+//! model for the answer. Embeddings and generation each use either the
+//! OpenAI-compatible server (Ollama, llama.cpp, vLLM, ...) or the Gemini API,
+//! as configured in [`RetrievalSettings`]. Stores flagged local-only refuse
+//! Gemini for both, so their content never leaves the machine.
+
+use async_trait::async_trait;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use super::chunking::chunk_text;
+use super::openai::OpenAiClient;
+use super::{
+    parse_question_list, OperationStatus, QueryResult, RemoteDocument, RemoteStore,
+    SearchBackend, UploadStatus, UploadedDocument, LOCAL_STORE_PREFIX,
+};
+use crate::db::{Database, NewChunk};
+use crate::extract::{self, MIME_TEXT_PLAIN};
+use crate::gemini::types::CustomMetadata;
+use crate::gemini::GeminiClient;
+use crate::settings::{self, ModelProvider, RetrievalSettings};
+
+/// Chunks sampled for suggesting questions
+const SUGGEST_SAMPLE_SIZE: usize = 6;
+
+/// Purpose of an embedding, for providers that embed documents and queries differently
+#[derive(Debug, Clone, Copy)]
+enum EmbeddingTask {
+    Document,
+    Query,
+}
+
+impl EmbeddingTask {
+    fn gemini_task_type(&self) -> &'static str {
+        match self {
+            Self::Document => "RETRIEVAL_DOCUMENT",
+            Self::Query => "RETRIEVAL_QUERY",
+        }
+    }
+}
+
+// ============================================================================
+// Backend
+// ============================================================================
+
+/// Backend with local chunking, embedding storage and retrieval
+pub struct LocalBackend {
+    openai: OpenAiClient,
+    gemini: Arc<GeminiClient>,
+    data_dir: PathBuf,
+    db: Arc<Database>,
+}
+
+impl LocalBackend {
+    pub fn new(data_dir: PathBuf, db: Arc<Database>, gemini: Arc<GeminiClient>) -> Self {
+        Self {
+            openai: OpenAiClient::new(data_dir.clone()),
+            gemini,
+            data_dir,
+            db,
+        }
+    }
+
+    fn settings(&self) -> RetrievalSettings {
+        settings::get_retrieval_settings_sync(&self.data_dir)
+    }
+
+    /// Refuse remote models for stores flagged local-only
+    fn check_local_only(
+        &self,
+        store_names: &[String],
+        provider: ModelProvider,
+    ) -> Result<(), String> {
+        if provider == ModelProvider::Gemini && self.db.has_local_only_store(store_names)? {
+            return Err(
+                "Local-only stores can't use Gemini; switch the local index to the local server"
+                    .to_string(),
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Identifier of the configured embedding model, stored with each chunk
+    fn embedding_model_id(&self, settings: &RetrievalSettings) -> String {
+        match settings.embedding_provider {
+            ModelProvider::Local => format!("local:{}", self.openai.settings().embedding_model),
+            ModelProvider::Gemini => format!("gemini:{}", settings.gemini_embedding_model),
+        }
+    }
+
+    async fn embed(
+        &self,
+        settings: &RetrievalSettings,
+        input: &[String],
+        task: EmbeddingTask,
+    ) -> Result<Vec<Vec<f32>>, String> {
+        match settings.embedding_provider {
+            ModelProvider::Local => self.openai.embed(input).await,
+            ModelProvider::Gemini => {
+                self.gemini
+                    .embed_contents(&settings.gemini_embedding_model, input, task.gemini_task_type())
+                    .await
+            }
+        }
+    }
+
+    async fn generate(
+        &self,
+        settings: &RetrievalSettings,
+        system_prompt: String,
+        user_message: String,
+        temperature: f32,
+    ) -> Result<String, String> {
+        match settings.generation_provider {
+            ModelProvider::Local => self.openai.chat(system_prompt, user_message, temperature).await,
+            ModelProvider::Gemini => {
+                self.gemini
+                    .generate_text(system_prompt, user_message, temperature)
+                    .await
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl SearchBackend for LocalBackend {
+    /// Stores only exist locally, so creating one needs no request
+    async fn create_store(&self, _display_name: &str) -> Result<RemoteStore, String> {
+        let now = chrono::Utc::now().to_rfc3339();
+
+        Ok(RemoteStore {
+            name: format!("{}{}", LOCAL_STORE_PREFIX, Uuid::new_v4()),
+            create_time: Some(now.clone()),
+            update_time: Some(now),
+            active_documents_count: 0,
+            pending_documents_count: 0,
+            failed_documents_count: 0,
+            size_bytes: 0,
+        })
+    }
+
+    async fn delete_store(&self, store_name: &str, _force: bool) -> Result<(), String> {
+        let store_name = store_name.to_string();
+        self.db
+            .call(move |db| db.delete_store_chunks(&store_name))
+            .await
+    }
+
+    /// Extract, chunk and embed the document; it is ready as soon as this returns
+    async fn upload_document(
+        &self,
+        store_name: &str,
+        path: &Path,
+        display_name: &str,
+        _custom_metadata: &[CustomMetadata],
+    ) -> Result<UploadedDocument, String> {
+        let settings = self.settings();
+        self.check_local_only(&[store_name.to_string()], settings.embedding_provider)?;
+
+        let text = extract::extract_text(path)
+            .await?
+            .ok_or_else(|| "File type can't be converted to text for local indexing".to_string())?;
+
+        let chunks = chunk_text(&text, settings.chunk_chars, settings.chunk_overlap_chars);
+        if chunks.is_empty() {
+            return Err("Document contains no text".to_string());
+        }
+
+        let embeddings = self
+            .embed(&settings, &chunks, EmbeddingTask::Document)
+            .await?;
+
+        let chunks: Vec<NewChunk> = chunks
+            .into_iter()
+            .zip(embeddings)
+            .map(|(content, embedding)| NewChunk { content, embedding })
+            .collect();
+
+        let document_name = format!("{}/documents/{}", store_name, Uuid::new_v4());
+        let (store_name, name, display_name, model_id) = (
+            store_name.to_string(),
+            document_name.clone(),
+            display_name.to_string(),
+            self.embedding_model_id(&settings),
+        );
+        self.db
+            .call(move |db| {
+                db.replace_document_chunks(&store_name, &name, &display_name, &model_id, &chunks)
+            })
+            .await?;
+
+        Ok(UploadedDocument {
+            upload_mime_type: MIME_TEXT_PLAIN.to_string(),
+            status: UploadStatus::Completed {
+                document_name,
+                mime_type: Some(MIME_TEXT_PLAIN.to_string()),
+            },
+        })
+    }
+
+    /// Uploads complete synchronously, so there are never pending operations
+    async fn get_operation(&self, operation_name: &str) -> Result<OperationStatus, String> {
+        Err(format!("Unknown operation: {}", operation_name))
+    }
+
+    async fn get_document(&self, document_name: &str) -> Result<RemoteDocument, String> {
+        let name = document_name.to_string();
+        if !self
+            .db
+            .call(move |db| db.has_document_chunks(&name))
+            .await?
+        {
+            return Err(format!("NOT_FOUND: {}", document_name));
+        }
+
+        Ok(RemoteDocument {
+            name: document_name.to_string(),
+            mime_type: Some(MIME_TEXT_PLAIN.to_string()),
+            status: "completed".to_string(),
+            custom_metadata: vec![],
+        })
+    }
+
+    async fn delete_document(&self, document_name: &str) -> Result<(), String> {
+        let document_name = document_name.to_string();
+        self.db
+            .call(move |db| db.delete_document_chunks(&document_name))
+            .await
+    }
+
+    async fn query(&self, store_names: &[String], query: &str) -> Result<QueryResult, String> {
+        let settings = self.settings();
+        self.check_local_only(store_names, settings.embedding_provider)?;
+        self.check_local_only(store_names, settings.generation_provider)?;
+
+        let query_embedding = self
+            .embed(&settings, &[query.to_string()], EmbeddingTask::Query)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| "No embedding returned for query".to_string())?;
+
+        // Scoring reads every embedding of the stores, off the async executor
+        let (names, model_id, top_k) = (
+            store_names.to_vec(),
+            self.embedding_model_id(&settings),
+            settings.top_k,
+        );
+        let chunks = self
+            .db
+            .call(move |db| db.search_chunks(&names, &model_id, &query_embedding, top_k))
+            .await?;
+
+        let context = chunks
+            .iter()
+            .enumerate()
+            .map(|(i, chunk)| format!("[{}] {}\n{}", i + 1, chunk.display_name, chunk.content))
+            .collect::<Vec<_>>()
+            .join("\n\n---\n\n");
+
+        let now = chrono::Local::now();
+        let system_prompt = format!(
+            r#"You are a knowledgeable assistant that answers questions based on the user's documents.
+
+## Response Guidelines
+- Answer only from the document excerpts below and cite the document names you use
+- If the excerpts don't contain the answer, clearly say so
+- Answer in the same language as the user's question
+- Format your response using Markdown, using tables for structured information
+
+## Environment Context
+- Current Time: {}
+
+## Document Excerpts
+{}"#,
+            now.format("%Y-%m-%d %H:%M:%S"),
+            if context.is_empty() { "(no matching documents)" } else { &context }
+        );
+
+        let content = self
+            .generate(&settings, system_prompt, query.to_string(), 0.7)
+            .await?;
+
+        // Sources in retrieval order, without duplicates
+        let mut sources: Vec<String> = Vec::new();
+        for chunk in chunks {
+            if !sources.contains(&chunk.display_name) {
+                sources.push(chunk.display_name);
+            }
+        }
+
+        Ok(QueryResult { content, sources })
+    }
+
+    async fn suggest_questions(
+        &self,
+        store_names: &[String],
+        locale: &str,
+    ) -> Result<Vec<String>, String> {
+        let settings = self.settings();
+        self.check_local_only(store_names, settings.generation_provider)?;
+
+        let names = store_names.to_vec();
+        let chunks = self
+            .db
+            .call(move |db| db.sample_chunks(&names, SUGGEST_SAMPLE_SIZE))
+            .await?;
+        if chunks.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let locale_instruction = match locale {
+            "zh-TW" => "繁體中文",
+            "ja" => "日本語",
+            _ => "English",
+        };
+
+        let excerpts = chunks
+            .iter()
+            .map(|chunk| format!("{}\n{}", chunk.display_name, chunk.content))
+            .collect::<Vec<_>>()
+            .join("\n\n---\n\n");
+
+        let prompt = format!(
+            "Based on the document excerpts, suggest 5 interesting questions that a user might want to ask about the content. \
+            Return ONLY a JSON array of 5 question strings, no other text. \
+            The questions should be in {}.",
+            locale_instruction
+        );
+
+        let content = self.generate(&settings, prompt, excerpts, 0.9).await?;
+
+        Ok(parse_question_list(&content))
+    }
+}
@@ -0,0 +1,348 @@
+//! Search backends
+//!
+//! A [`SearchBackend`] hosts stores and documents and answers grounded
+//! queries over them. The Gemini File Search API is one implementation; the
+//! local backend chunks and embeds documents into a local vector index and
+//! answers from the retrieved chunks, e.g. with a local Ollama or llama.cpp
+//! server for a fully offline mode.
+//!
+//! Each store records the backend it was created in. Remote resource names
+//! (stores, documents, operations) are prefixed per backend, so resources can
+//! be routed back to their backend by name alone.
+
+mod chunking;
+mod gemini;
+mod local;
+mod openai;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::db::{Database, Store};
+use crate::gemini::types::CustomMetadata;
+
+pub use local::LocalBackend;
+
+/// Name prefix of stores created by the local backend
+pub const LOCAL_STORE_PREFIX: &str = "localStores/";
+
+/// Available backend implementations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackendKind {
+    #[default]
+    Gemini,
+    Local,
+}
+
+impl BackendKind {
+    /// Value stored in the `stores.backend` column
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Gemini => "gemini",
+            Self::Local => "local",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "gemini" => Ok(Self::Gemini),
+            "local" => Ok(Self::Local),
+            other => Err(format!("Unknown search backend: {}", other)),
+        }
+    }
+
+    /// Backend for a new store, given the requested backend and local-only flag
+    ///
+    /// Local-only stores live in the local index.
+    pub fn for_new_store(requested: Option<Self>, local_only: bool) -> Result<Self, String> {
+        match (requested, local_only) {
+            (Some(Self::Gemini), true) => {
+                Err("Local-only stores can't use Gemini File Search".to_string())
+            }
+            (None, true) => Ok(Self::Local),
+            (requested, _) => Ok(requested.unwrap_or_default()),
+        }
+    }
+
+    /// Backend owning a remote resource (store, document or operation name)
+    pub fn from_resource_name(name: &str) -> Self {
+        if name.starts_with(LOCAL_STORE_PREFIX) {
+            Self::Local
+        } else {
+            Self::Gemini
+        }
+    }
+}
+
+/// A store created in a backend
+#[derive(Debug, Clone)]
+pub struct RemoteStore {
+    pub name: String,
+    pub create_time: Option<String>,
+    pub update_time: Option<String>,
+    pub active_documents_count: i64,
+    pub pending_documents_count: i64,
+    pub failed_documents_count: i64,
+    pub size_bytes: i64,
+}
+
+/// State of a document right after upload
+#[derive(Debug, Clone)]
+pub enum UploadStatus {
+    /// Processing continues in the backend; poll the operation
+    Pending { operation_name: String },
+    /// The document is ready to be queried
+    Completed {
+        document_name: String,
+        mime_type: Option<String>,
+    },
+}
+
+/// Result of uploading a document
+#[derive(Debug, Clone)]
+pub struct UploadedDocument {
+    /// Mime type of the content that was actually uploaded
+    pub upload_mime_type: String,
+    pub status: UploadStatus,
+}
+
+/// State of a long-running upload operation
+#[derive(Debug, Clone)]
+pub enum OperationStatus {
+    Running,
+    Failed(String),
+    /// Finished, with the name of the created document when the backend reports it
+    Done { document_name: Option<String> },
+}
+
+/// A document stored in a backend
+#[derive(Debug, Clone)]
+pub struct RemoteDocument {
+    pub name: String,
+    pub mime_type: Option<String>,
+    /// Internal document status (`processing`, `completed` or `failed`)
+    pub status: String,
+    /// Custom metadata entries, empty for backends without metadata
+    pub custom_metadata: Vec<CustomMetadata>,
+}
+
+/// Answer to a grounded query
+#[derive(Debug, Clone)]
+pub struct QueryResult {
+    pub content: String,
+    /// Display names of the documents the answer is grounded in
+    pub sources: Vec<String>,
+}
+
+/// Operations the app needs from a search backend
+#[async_trait]
+pub trait SearchBackend: Send + Sync {
+    /// Create a store
+    async fn create_store(&self, display_name: &str) -> Result<RemoteStore, String>;
+
+    /// Delete a store; `force` also deletes its documents
+    async fn delete_store(&self, store_name: &str, force: bool) -> Result<(), String>;
+
+    /// Change a store's display name
+    ///
+    /// Backends that can't rename stores keep the name they were created
+    /// with; the app only shows the local title anyway.
+    async fn rename_store(&self, _store_name: &str, _display_name: &str) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Upload the file at `path` to a store
+    async fn upload_document(
+        &self,
+        store_name: &str,
+        path: &Path,
+        display_name: &str,
+        custom_metadata: &[CustomMetadata],
+    ) -> Result<UploadedDocument, String>;
+
+    /// Get the state of an upload operation
+    async fn get_operation(&self, operation_name: &str) -> Result<OperationStatus, String>;
+
+    /// Get a document by name
+    async fn get_document(&self, document_name: &str) -> Result<RemoteDocument, String>;
+
+    /// Delete a document
+    async fn delete_document(&self, document_name: &str) -> Result<(), String>;
+
+    /// Answer a question grounded in the documents of the given stores
+    async fn query(&self, store_names: &[String], query: &str) -> Result<QueryResult, String>;
+
+    /// Suggest questions about the documents of the given stores
+    async fn suggest_questions(
+        &self,
+        store_names: &[String],
+        locale: &str,
+    ) -> Result<Vec<String>, String>;
+
+    /// Check that the backend can be reached over the network
+    ///
+    /// Backends running on this machine are always reachable.
+    async fn check_connection(&self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// The configured backend implementations
+pub struct Backends {
+    db: Arc<Database>,
+    gemini: Arc<dyn SearchBackend>,
+    local: Arc<dyn SearchBackend>,
+}
+
+impl Backends {
+    pub fn new(
+        db: Arc<Database>,
+        gemini: Arc<dyn SearchBackend>,
+        local: Arc<dyn SearchBackend>,
+    ) -> Arc<Self> {
+        Arc::new(Self { db, gemini, local })
+    }
+
+    pub fn get(&self, kind: BackendKind) -> Arc<dyn SearchBackend> {
+        match kind {
+            BackendKind::Gemini => self.gemini.clone(),
+            BackendKind::Local => self.local.clone(),
+        }
+    }
+
+    /// Backend a store was created in
+    ///
+    /// Local-only stores are never handed to a remote backend, so their
+    /// documents can't be uploaded even if the store record is inconsistent.
+    pub fn for_store(&self, store: &Store) -> Result<Arc<dyn SearchBackend>, String> {
+        let kind = BackendKind::parse(&store.backend)?;
+
+        if store.local_only && kind != BackendKind::Local {
+            return Err("Local-only stores are never uploaded".to_string());
+        }
+
+        Ok(self.get(kind))
+    }
+
+    /// Give a store's remote counterpart the store's title, where supported
+    ///
+    /// Stores not created remotely yet get their title when background sync
+    /// creates them.
+    pub async fn rename_store(&self, store: &Store) -> Result<(), String> {
+        match &store.gemini_name {
+            Some(name) => self.for_store(store)?.rename_store(name, &store.title).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Check that the remote backend can be reached, for sync's offline mode
+    pub async fn check_connection(&self) -> Result<(), String> {
+        self.gemini.check_connection().await
+    }
+
+    /// Backend owning a remote resource
+    pub fn for_resource(&self, name: &str) -> Arc<dyn SearchBackend> {
+        self.get(BackendKind::from_resource_name(name))
+    }
+
+    /// Answer a question grounded in stores of any backend
+    ///
+    /// Each backend answers from its own stores; the answers are joined and
+    /// the sources merged, in the order the stores were given. Trashed
+    /// documents stay in their remote store until purged, so they are left
+    /// out of the sources, though the answer may still draw on them.
+    pub async fn query(&self, store_names: &[String], query: &str) -> Result<QueryResult, String> {
+        let groups = group_by_backend(store_names);
+        if groups.is_empty() {
+            return Err("No stores provided".to_string());
+        }
+
+        let mut answers = Vec::new();
+        let mut sources: Vec<String> = Vec::new();
+
+        for (kind, names) in groups {
+            let result = self.get(kind).query(&names, query).await?;
+
+            if !result.content.trim().is_empty() {
+                answers.push(result.content);
+            }
+            for source in result.sources {
+                if !sources.contains(&source) {
+                    sources.push(source);
+                }
+            }
+        }
+
+        let names = store_names.to_vec();
+        let trashed = self
+            .db
+            .call(move |db| db.get_trashed_document_names(&names))
+            .await?;
+        sources.retain(|source| !trashed.contains(source));
+
+        Ok(QueryResult {
+            content: answers.join("\n\n"),
+            sources,
+        })
+    }
+
+    /// Suggest questions about stores of any backend
+    ///
+    /// Suggestions are taken from each backend in turn, as many as the
+    /// longest list so mixing backends doesn't lengthen the list.
+    pub async fn suggest_questions(
+        &self,
+        store_names: &[String],
+        locale: &str,
+    ) -> Result<Vec<String>, String> {
+        let mut lists = Vec::new();
+        for (kind, names) in group_by_backend(store_names) {
+            lists.push(self.get(kind).suggest_questions(&names, locale).await?);
+        }
+
+        let limit = lists.iter().map(Vec::len).max().unwrap_or(0);
+        let mut questions: Vec<String> = Vec::new();
+
+        for i in 0..limit {
+            for question in lists.iter().filter_map(|list| list.get(i)) {
+                if !questions.contains(question) {
+                    questions.push(question.clone());
+                }
+            }
+        }
+        questions.truncate(limit);
+
+        Ok(questions)
+    }
+}
+
+/// Store names grouped by the backend owning them, in order of first appearance
+fn group_by_backend(store_names: &[String]) -> Vec<(BackendKind, Vec<String>)> {
+    let mut groups: Vec<(BackendKind, Vec<String>)> = Vec::new();
+
+    for name in store_names {
+        let kind = BackendKind::from_resource_name(name);
+        match groups.iter_mut().find(|(k, _)| *k == kind) {
+            Some((_, names)) => names.push(name.clone()),
+            None => groups.push((kind, vec![name.clone()])),
+        }
+    }
+
+    groups
+}
+
+/// Parse a list of questions returned by a model as a JSON array,
+/// tolerating a surrounding Markdown code block
+pub(crate) fn parse_question_list(content: &str) -> Vec<String> {
+    let cleaned = content
+        .trim()
+        .trim_start_matches("```json")
+        .trim_start_matches("```")
+        .trim_end_matches("```")
+        .trim();
+
+    serde_json::from_str(cleaned).unwrap_or_default()
+}
@@ -0,0 +1,183 @@
+//! Client for OpenAI-compatible servers
+//!
+//! Talks to the `/embeddings` and `/chat/completions` endpoints of Ollama,
+//! llama.cpp, vLLM and other OpenAI-compatible servers.
+
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::settings::{self, OpenAiSettings};
+
+/// Chunks embedded per request
+const EMBEDDING_BATCH_SIZE: usize = 32;
+
+// ============================================================================
+// API Types
+// ============================================================================
+
+#[derive(Serialize)]
+struct EmbeddingRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+#[derive(Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    #[serde(default)]
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+#[derive(Serialize)]
+struct ChatMessage {
+    role: &'static str,
+    content: String,
+}
+
+#[derive(Serialize)]
+struct ChatRequest<'a> {
+    model: &'a str,
+    messages: Vec<ChatMessage>,
+    temperature: f32,
+    stream: bool,
+}
+
+#[derive(Deserialize)]
+struct ChatResponse {
+    #[serde(default)]
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Deserialize)]
+struct ChatChoice {
+    message: ChatResponseMessage,
+}
+
+#[derive(Deserialize)]
+struct ChatResponseMessage {
+    #[serde(default)]
+    content: Option<String>,
+}
+
+// ============================================================================
+// Client
+// ============================================================================
+
+/// Client for the configured OpenAI-compatible server
+pub struct OpenAiClient {
+    client: Client,
+    data_dir: PathBuf,
+}
+
+impl OpenAiClient {
+    pub fn new(data_dir: PathBuf) -> Self {
+        let client = Client::builder()
+            .timeout(std::time::Duration::from_secs(600))
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self { client, data_dir }
+    }
+
+    pub fn settings(&self) -> OpenAiSettings {
+        settings::get_openai_settings_sync(&self.data_dir)
+    }
+
+    /// POST a JSON request to an endpoint below the configured base URL
+    async fn post<T: Serialize, D: serde::de::DeserializeOwned>(
+        &self,
+        settings: &OpenAiSettings,
+        path: &str,
+        body: &T,
+    ) -> Result<D, String> {
+        let url = format!("{}/{}", settings.base_url.trim_end_matches('/'), path);
+
+        let mut request = self.client.post(&url).json(body);
+        if let Some(api_key) = settings.api_key.as_deref().filter(|k| !k.is_empty()) {
+            request = request.bearer_auth(api_key);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach {}: {}", url, e))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(format!("API error ({}): {}", status, body));
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse response: {}", e))
+    }
+
+    /// Embed texts, returning one vector per input in order
+    pub async fn embed(&self, input: &[String]) -> Result<Vec<Vec<f32>>, String> {
+        let settings = self.settings();
+        let mut embeddings = Vec::with_capacity(input.len());
+
+        for batch in input.chunks(EMBEDDING_BATCH_SIZE) {
+            let request = EmbeddingRequest {
+                model: &settings.embedding_model,
+                input: batch,
+            };
+            let mut response: EmbeddingResponse = self.post(&settings, "embeddings", &request).await?;
+
+            if response.data.len() != batch.len() {
+                return Err(format!(
+                    "Expected {} embeddings, got {}",
+                    batch.len(),
+                    response.data.len()
+                ));
+            }
+
+            response.data.sort_by_key(|d| d.index);
+            embeddings.extend(response.data.into_iter().map(|d| d.embedding));
+        }
+
+        Ok(embeddings)
+    }
+
+    /// Generate a chat completion for a system prompt and a user message
+    pub async fn chat(
+        &self,
+        system_prompt: String,
+        user_message: String,
+        temperature: f32,
+    ) -> Result<String, String> {
+        let settings = self.settings();
+        let request = ChatRequest {
+            model: &settings.chat_model,
+            messages: vec![
+                ChatMessage {
+                    role: "system",
+                    content: system_prompt,
+                },
+                ChatMessage {
+                    role: "user",
+                    content: user_message,
+                },
+            ],
+            temperature,
+            stream: false,
+        };
+
+        let response: ChatResponse = self.post(&settings, "chat/completions", &request).await?;
+
+        Ok(response
+            .choices
+            .into_iter()
+            .next()
+            .and_then(|c| c.message.content)
+            .unwrap_or_default())
+    }
+}
@@ -0,0 +1,273 @@
+//! Database backups
+//!
+//! Backups are copies of `stores.db` in the `backups/` directory of the data
+//! directory, taken with the SQLite online backup API on a schedule and
+//! before every restore. At startup the database is checked with
+//! `PRAGMA integrity_check`; a damaged file is set aside and replaced with
+//! the newest backup that passes the check, or with an empty database.
+
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use crate::db::{check_file_integrity, Database, DATABASE_FILE};
+use crate::settings;
+
+/// Directory of backups, in the data directory
+pub const BACKUPS_DIR: &str = "backups";
+
+const BACKUP_PREFIX: &str = "stores-";
+const BACKUP_EXTENSION: &str = "db";
+
+/// How often the scheduler checks whether a backup is due
+const CHECK_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// Files SQLite keeps next to the database in WAL mode
+const SIDECAR_SUFFIXES: &[&str] = &["-wal", "-shm"];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupInfo {
+    pub name: String,
+    pub path: String,
+    pub size: u64,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreResult {
+    pub restored: BackupInfo,
+    /// Backup of the database as it was before the restore
+    pub previous: BackupInfo,
+}
+
+/// What happened when a damaged database was found at startup
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Recovery {
+    pub error: String,
+    /// Where the damaged file was moved
+    pub damaged_path: String,
+    /// Backup the database was restored from, `None` when starting empty
+    pub restored_from: Option<String>,
+}
+
+// ============================================================================
+// Backups
+// ============================================================================
+
+fn backups_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join(BACKUPS_DIR)
+}
+
+fn backup_info(path: &Path) -> Result<BackupInfo, String> {
+    let metadata = fs::metadata(path).map_err(|e| format!("Failed to read backup: {}", e))?;
+    let created_at: DateTime<Local> = metadata.modified().unwrap_or(SystemTime::now()).into();
+
+    Ok(BackupInfo {
+        name: path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        path: path.to_string_lossy().to_string(),
+        size: metadata.len(),
+        created_at: created_at.to_rfc3339(),
+    })
+}
+
+/// Backups in the data directory, newest first
+pub fn list_backups(data_dir: &Path) -> Result<Vec<BackupInfo>, String> {
+    let entries = match fs::read_dir(backups_dir(data_dir)) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(format!("Failed to list backups: {}", e)),
+    };
+
+    let mut backups = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            let name = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or_default();
+            name.starts_with(BACKUP_PREFIX)
+                && path.extension().and_then(|e| e.to_str()) == Some(BACKUP_EXTENSION)
+        })
+        .map(|path| backup_info(&path))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Names embed the creation time, so they sort chronologically
+    backups.sort_by(|a, b| b.name.cmp(&a.name));
+    Ok(backups)
+}
+
+/// Back up the database, then delete backups beyond the newest `keep`
+pub fn create_backup(db: &Database, data_dir: &Path, keep: usize) -> Result<BackupInfo, String> {
+    let backup = write_backup(db, data_dir)?;
+    prune_backups(data_dir, keep)?;
+    Ok(backup)
+}
+
+fn write_backup(db: &Database, data_dir: &Path) -> Result<BackupInfo, String> {
+    let dir = backups_dir(data_dir);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create backups directory: {}", e))?;
+
+    let name = format!(
+        "{}{}.{}",
+        BACKUP_PREFIX,
+        Local::now().format("%Y%m%d-%H%M%S-%3f"),
+        BACKUP_EXTENSION
+    );
+    let path = dir.join(name);
+    db.backup_to(&path)?;
+
+    backup_info(&path)
+}
+
+/// Delete all but the newest `keep` backups
+pub fn prune_backups(data_dir: &Path, keep: usize) -> Result<(), String> {
+    for backup in list_backups(data_dir)?.into_iter().skip(keep.max(1)) {
+        fs::remove_file(&backup.path)
+            .map_err(|e| format!("Failed to delete backup {}: {}", backup.name, e))?;
+    }
+
+    Ok(())
+}
+
+/// Replace the database with a backup, after backing up its current state
+///
+/// Nothing is pruned here, so the restored backup stays available until the
+/// next scheduled backup.
+pub fn restore_backup(db: &Database, data_dir: &Path, name: &str) -> Result<RestoreResult, String> {
+    let restored = list_backups(data_dir)?
+        .into_iter()
+        .find(|b| b.name == name)
+        .ok_or_else(|| format!("Backup not found: {}", name))?;
+
+    let previous = write_backup(db, data_dir)?;
+    db.restore_from(Path::new(&restored.path))?;
+
+    Ok(RestoreResult { restored, previous })
+}
+
+/// Whether the newest backup is older than `interval`
+fn backup_due(data_dir: &Path, interval: Duration) -> bool {
+    let newest = list_backups(data_dir)
+        .ok()
+        .and_then(|backups| backups.into_iter().next())
+        .and_then(|b| DateTime::parse_from_rfc3339(&b.created_at).ok());
+
+    match newest {
+        Some(created_at) => {
+            let age = Local::now().signed_duration_since(created_at);
+            age.to_std().map(|age| age >= interval).unwrap_or(false)
+        }
+        None => true,
+    }
+}
+
+/// Start the task taking scheduled backups
+///
+/// Settings are read on every check, so changes apply without a restart.
+pub fn start_backup_task(db: Arc<Database>, data_dir: PathBuf) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let settings = settings::get_backup_settings_sync(&data_dir);
+            let interval = Duration::from_secs(u64::from(settings.interval_hours) * 3600);
+
+            if settings.interval_hours > 0 && backup_due(&data_dir, interval) {
+                let db = db.clone();
+                let data_dir = data_dir.clone();
+                let keep = settings.keep as usize;
+
+                match tokio::task::spawn_blocking(move || create_backup(&db, &data_dir, keep)).await
+                {
+                    Ok(Ok(backup)) => eprintln!("Database backed up to {}", backup.path),
+                    Ok(Err(e)) => eprintln!("Scheduled backup failed: {}", e),
+                    Err(e) => eprintln!("Backup task failed: {}", e),
+                }
+            }
+
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+// ============================================================================
+// Startup check
+// ============================================================================
+
+/// Open the database in a data directory, recovering from a damaged file
+///
+/// Only a file failing the integrity check is replaced; other errors (for
+/// example a failing migration) are returned as they are.
+pub fn open_database(data_dir: &Path) -> Result<(Database, Option<Recovery>), String> {
+    let path = data_dir.join(DATABASE_FILE);
+
+    let problems = match path.exists() {
+        true => check_file_integrity(&path)?,
+        false => vec![],
+    };
+    if problems.is_empty() {
+        return Ok((Database::new(data_dir)?, None));
+    }
+
+    let error = problems.join("; ");
+    eprintln!("Database is damaged, recovering: {}", error);
+    let damaged_path = set_aside(&path)?;
+
+    let backup = list_backups(data_dir)?.into_iter().find(|b| {
+        match check_file_integrity(Path::new(&b.path)) {
+            Ok(problems) if problems.is_empty() => true,
+            Ok(problems) => {
+                eprintln!(
+                    "Skipping damaged backup {}: {}",
+                    b.name,
+                    problems.join("; ")
+                );
+                false
+            }
+            Err(e) => {
+                eprintln!("Skipping backup {}: {}", b.name, e);
+                false
+            }
+        }
+    });
+
+    if let Some(backup) = &backup {
+        fs::copy(&backup.path, &path)
+            .map_err(|e| format!("Failed to restore backup {}: {}", backup.name, e))?;
+    }
+
+    let recovery = Recovery {
+        error,
+        damaged_path: damaged_path.to_string_lossy().to_string(),
+        restored_from: backup.map(|b| b.name),
+    };
+
+    Ok((Database::new(data_dir)?, Some(recovery)))
+}
+
+/// Move a damaged database and its WAL files out of the way, keeping them for inspection
+fn set_aside(path: &Path) -> Result<PathBuf, String> {
+    let suffix = format!(".damaged-{}", Local::now().format("%Y%m%d-%H%M%S"));
+    let target = PathBuf::from(format!("{}{}", path.display(), suffix));
+
+    fs::rename(path, &target).map_err(|e| format!("Failed to move damaged database: {}", e))?;
+
+    for sidecar in SIDECAR_SUFFIXES {
+        let from = PathBuf::from(format!("{}{}", path.display(), sidecar));
+        if from.exists() {
+            let to = PathBuf::from(format!("{}{}", target.display(), sidecar));
+            fs::rename(&from, &to)
+                .map_err(|e| format!("Failed to move damaged database: {}", e))?;
+        }
+    }
+
+    Ok(target)
+}
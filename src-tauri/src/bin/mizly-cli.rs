@@ -0,0 +1,5 @@
+//! Command-line entry point, see `mizly_lib::cli`
+
+fn main() -> std::process::ExitCode {
+    mizly_lib::cli::run()
+}
@@ -0,0 +1,528 @@
+//! Portable store bundles
+//!
+//! A bundle is a zip archive holding a `manifest.json` with the store's
+//! definition and document list, and optionally the document files under
+//! `files/`. Document paths inside the store's directory are recorded
+//! relative to it, so a bundle can be imported on another machine where the
+//! same directory lives elsewhere. Importing recreates the store and queues
+//! its documents, which the regular sync loop then uploads.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use uuid::Uuid;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::backend::{BackendKind, Backends};
+use crate::commands::documents::{validate_files, FileInfo};
+use crate::db::{Database, Document, NewStore, Store, StoreUpdate};
+use crate::gemini::types::CustomMetadata;
+use crate::validation::{RejectedFile, RejectionReason};
+
+/// Name of the manifest entry in a bundle
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Identifies bundles created by this app
+const BUNDLE_FORMAT: &str = "mizly-store-bundle";
+
+/// Current manifest version; bundles with a newer version are rejected
+const BUNDLE_VERSION: u32 = 1;
+
+/// Directory of extracted bundle files, in the data directory
+const IMPORTS_DIR: &str = "imports";
+
+// ============================================================================
+// Manifest
+// ============================================================================
+
+/// Contents of `manifest.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleManifest {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: String,
+    pub store: BundleStore,
+    pub documents: Vec<BundleDocument>,
+}
+
+/// Store definition, without anything tied to the exporting machine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleStore {
+    pub title: String,
+    pub directory_path: Option<String>,
+    pub backend: String,
+    pub local_only: bool,
+    pub include_patterns: Option<String>,
+    pub exclude_patterns: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub color: Option<String>,
+    #[serde(default)]
+    pub icon: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleDocument {
+    pub name: String,
+    /// Path relative to the store directory (with `/` separators), or absolute
+    /// for files outside of it
+    pub path: String,
+    pub relative: bool,
+    pub content_type: Option<String>,
+    pub size: Option<i64>,
+    /// SHA-256 of the file, hex encoded
+    pub hash: Option<String>,
+    #[serde(default)]
+    pub custom_metadata: Vec<CustomMetadata>,
+    /// Archive entry holding the file, when files were included
+    pub file: Option<String>,
+}
+
+/// Result of exporting a store
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportStoreResult {
+    pub path: String,
+    pub documents: usize,
+    pub files_included: usize,
+    /// Names of documents whose files couldn't be read, exported without them
+    pub missing: Vec<String>,
+}
+
+/// Result of importing a bundle
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportStoreResult {
+    pub store: Store,
+    /// Documents created and queued for upload
+    pub documents: Vec<Document>,
+    /// Files that failed validation, or outside the store directory without
+    /// `allow_absolute_paths`, and were not queued
+    pub rejected: Vec<RejectedFile>,
+    /// Names of documents whose local file differs from the exported one,
+    /// including existing files an included file was not extracted over
+    pub changed: Vec<String>,
+}
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+/// SHA-256 of a file, hex encoded like the hashes computed by the frontend
+fn hash_file(path: &Path) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher)?;
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Path of a document relative to the store directory, if inside it
+fn relative_path(directory: Option<&str>, path: &str) -> Option<String> {
+    let relative = Path::new(path).strip_prefix(directory?).ok()?;
+    let parts: Vec<String> = relative
+        .components()
+        .map(|c| match c {
+            Component::Normal(part) => Some(part.to_string_lossy().to_string()),
+            _ => None,
+        })
+        .collect::<Option<_>>()?;
+
+    (!parts.is_empty()).then(|| parts.join("/"))
+}
+
+/// Join a manifest path below `base`, refusing paths that would escape it
+fn join_relative(base: &Path, relative: &str) -> Result<PathBuf, String> {
+    let mut path = base.to_path_buf();
+
+    for part in relative.split('/') {
+        let mut components = Path::new(part).components();
+        match (components.next(), components.next()) {
+            (Some(Component::Normal(_)), None) => path.push(part),
+            _ => return Err(format!("Invalid path in bundle: {}", relative)),
+        }
+    }
+
+    Ok(path)
+}
+
+fn read_manifest(archive: &mut ZipArchive<File>) -> Result<BundleManifest, String> {
+    let entry = archive
+        .by_name(MANIFEST_FILE)
+        .map_err(|_| "Not a store bundle: manifest.json is missing".to_string())?;
+    let manifest: BundleManifest = serde_json::from_reader(entry)
+        .map_err(|e| format!("Failed to read bundle manifest: {}", e))?;
+
+    if manifest.format != BUNDLE_FORMAT {
+        return Err("Not a store bundle".to_string());
+    }
+
+    if manifest.version > BUNDLE_VERSION {
+        return Err(format!(
+            "Bundle version {} is newer than this app supports, update the app",
+            manifest.version
+        ));
+    }
+
+    Ok(manifest)
+}
+
+// ============================================================================
+// Export
+// ============================================================================
+
+/// Write the store's definition and documents to a bundle at `path`
+///
+/// Custom metadata is read from the store's backend for synced documents that
+/// don't carry it locally. With `include_files`, the document files are
+/// added to the archive so the bundle works without access to the originals.
+pub async fn export_store(
+    db: &Arc<Database>,
+    backends: &Backends,
+    store_id: &str,
+    path: &Path,
+    include_files: bool,
+) -> Result<ExportStoreResult, String> {
+    let store_id = store_id.to_string();
+    let (store, documents) = db
+        .call(move |db| {
+            Ok((
+                db.get_store(&store_id)?,
+                db.get_documents_by_store(&store_id)?,
+            ))
+        })
+        .await?;
+    let backend = backends.for_store(&store)?;
+
+    let mut entries = Vec::with_capacity(documents.len());
+    for document in documents {
+        let custom_metadata = match (&document.custom_metadata, &document.gemini_name) {
+            (Some(json), _) => serde_json::from_str(json).unwrap_or_default(),
+            (None, Some(name)) => match backend.get_document(name).await {
+                Ok(remote) => remote.custom_metadata,
+                Err(e) => {
+                    eprintln!("Failed to get metadata of document {}: {}", document.id, e);
+                    vec![]
+                }
+            },
+            (None, None) => vec![],
+        };
+
+        entries.push((document, custom_metadata));
+    }
+
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || write_bundle(store, entries, &path, include_files))
+        .await
+        .map_err(|e| format!("Export task failed: {}", e))?
+}
+
+fn write_bundle(
+    store: Store,
+    entries: Vec<(Document, Vec<CustomMetadata>)>,
+    path: &Path,
+    include_files: bool,
+) -> Result<ExportStoreResult, String> {
+    let directory = store.directory_path.as_deref();
+    let mut documents = Vec::with_capacity(entries.len());
+    let mut sources = Vec::with_capacity(entries.len());
+    let mut missing = Vec::new();
+
+    for (index, (document, custom_metadata)) in entries.into_iter().enumerate() {
+        let (hash, readable) = match hash_file(Path::new(&document.path)) {
+            Ok(hash) => (Some(hash), true),
+            Err(_) => {
+                missing.push(document.name.clone());
+                (document.hash.clone(), false)
+            }
+        };
+
+        // Number entries so documents with the same name don't collide
+        let file =
+            (include_files && readable).then(|| format!("files/{}/{}", index, document.name));
+
+        let (path, relative) = match relative_path(directory, &document.path) {
+            Some(relative) => (relative, true),
+            None => (document.path.clone(), false),
+        };
+
+        documents.push(BundleDocument {
+            name: document.name,
+            path,
+            relative,
+            content_type: document.content_type.or(document.mime_type),
+            size: document.size,
+            hash,
+            custom_metadata,
+            file,
+        });
+        sources.push(document.path);
+    }
+
+    let manifest = BundleManifest {
+        format: BUNDLE_FORMAT.to_string(),
+        version: BUNDLE_VERSION,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        store: BundleStore {
+            title: store.title,
+            directory_path: store.directory_path,
+            backend: store.backend,
+            local_only: store.local_only,
+            include_patterns: store.include_patterns,
+            exclude_patterns: store.exclude_patterns,
+            description: store.description,
+            color: store.color,
+            icon: store.icon,
+        },
+        documents,
+    };
+
+    let result = write_archive(&manifest, &sources, path);
+    if result.is_err() {
+        let _ = fs::remove_file(path);
+    }
+
+    Ok(ExportStoreResult {
+        path: path.to_string_lossy().to_string(),
+        documents: manifest.documents.len(),
+        files_included: result?,
+        missing,
+    })
+}
+
+/// Write the manifest and included files, returning the number of files
+///
+/// `sources` are the local paths of the manifest's documents, in order.
+fn write_archive(
+    manifest: &BundleManifest,
+    sources: &[String],
+    path: &Path,
+) -> Result<usize, String> {
+    let file = File::create(path).map_err(|e| format!("Failed to create bundle: {}", e))?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    let json = serde_json::to_vec_pretty(manifest)
+        .map_err(|e| format!("Failed to serialize bundle manifest: {}", e))?;
+    zip.start_file(MANIFEST_FILE, options)
+        .map_err(|e| format!("Failed to write bundle: {}", e))?;
+    zip.write_all(&json)
+        .map_err(|e| format!("Failed to write bundle: {}", e))?;
+
+    let mut files = 0;
+    for (document, source) in manifest.documents.iter().zip(sources) {
+        let Some(entry) = &document.file else {
+            continue;
+        };
+
+        let mut source =
+            File::open(source).map_err(|e| format!("Failed to read {}: {}", document.name, e))?;
+        zip.start_file(entry.as_str(), options)
+            .map_err(|e| format!("Failed to write bundle: {}", e))?;
+        io::copy(&mut source, &mut zip).map_err(|e| format!("Failed to write bundle: {}", e))?;
+        files += 1;
+    }
+
+    zip.finish()
+        .map_err(|e| format!("Failed to write bundle: {}", e))?;
+
+    Ok(files)
+}
+
+// ============================================================================
+// Import
+// ============================================================================
+
+/// Recreate a store from a bundle and queue its documents for upload
+///
+/// Files included in the bundle are extracted to `directory`, or to the data
+/// directory when omitted, without overwriting existing files. Otherwise
+/// relative paths are resolved against `directory`, falling back to the
+/// exported store directory.
+///
+/// Absolute paths of files that were outside the store directory and not
+/// included could point anywhere on this machine, so those documents are
+/// only queued with `allow_absolute_paths`.
+pub fn import_store(
+    db: &Database,
+    data_dir: &Path,
+    bundle_path: &Path,
+    directory: Option<&Path>,
+    allow_absolute_paths: bool,
+) -> Result<ImportStoreResult, String> {
+    let file = File::open(bundle_path).map_err(|e| format!("Failed to open bundle: {}", e))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| format!("Failed to read bundle: {}", e))?;
+    let manifest = read_manifest(&mut archive)?;
+
+    let backend = BackendKind::for_new_store(
+        Some(BackendKind::parse(&manifest.store.backend)?),
+        manifest.store.local_only,
+    )?;
+    let store_id = Uuid::new_v4().to_string();
+
+    let has_files = manifest.documents.iter().any(|d| d.file.is_some());
+    let extract_dir =
+        (directory.is_none() && has_files).then(|| data_dir.join(IMPORTS_DIR).join(&store_id));
+    let base = match (directory, &extract_dir) {
+        (Some(directory), _) => Some(directory.to_path_buf()),
+        (None, Some(extract_dir)) => Some(extract_dir.clone()),
+        (None, None) => manifest.store.directory_path.as_ref().map(PathBuf::from),
+    };
+
+    // Check the store before extracting anything, a bad one fails the import
+    let store = NewStore {
+        id: store_id,
+        backend: backend.as_str().to_string(),
+        local_only: manifest.store.local_only,
+        include_patterns: manifest.store.include_patterns.clone(),
+        exclude_patterns: manifest.store.exclude_patterns.clone(),
+        details: StoreUpdate {
+            title: manifest.store.title.clone(),
+            directory_path: base.as_deref().map(|b| b.to_string_lossy().to_string()),
+            description: manifest.store.description.clone(),
+            color: manifest.store.color.clone(),
+            icon: manifest.store.icon.clone(),
+        }
+        .validated()?,
+    };
+
+    let imported = bundle_files(
+        &mut archive,
+        &manifest,
+        data_dir,
+        base.as_deref(),
+        allow_absolute_paths,
+    )
+    .and_then(|mut bundle| {
+        let (documents, invalid) = validate_files(bundle.files);
+        bundle.rejected.extend(invalid);
+
+        let (store, documents) = db.create_store_with_documents(&store, &documents)?;
+        Ok(ImportStoreResult {
+            store,
+            documents,
+            rejected: bundle.rejected,
+            changed: bundle.changed,
+        })
+    });
+
+    // Nothing was created, so files extracted for the store are unused
+    if imported.is_err() {
+        if let Some(extract_dir) = extract_dir {
+            let _ = fs::remove_dir_all(extract_dir);
+        }
+    }
+
+    imported
+}
+
+/// Bundle documents resolved to local files
+struct BundleFiles {
+    files: Vec<FileInfo>,
+    /// Documents outside the store directory, without `allow_absolute_paths`
+    rejected: Vec<RejectedFile>,
+    /// Names of documents whose local file differs from the exported one
+    changed: Vec<String>,
+}
+
+/// Resolve the bundle's documents to local files, extracting included ones
+fn bundle_files(
+    archive: &mut ZipArchive<File>,
+    manifest: &BundleManifest,
+    data_dir: &Path,
+    base: Option<&Path>,
+    allow_absolute_paths: bool,
+) -> Result<BundleFiles, String> {
+    let mut files = Vec::with_capacity(manifest.documents.len());
+    let mut rejected = Vec::new();
+    let mut changed = Vec::new();
+
+    for document in &manifest.documents {
+        let path = match (base, document.relative) {
+            (Some(base), true) => join_relative(base, &document.path)?,
+            _ => PathBuf::from(&document.path),
+        };
+
+        let path = match &document.file {
+            Some(entry) => {
+                // Files from outside the store directory keep their entry path
+                let target = match (base, document.relative) {
+                    (Some(_), true) => path,
+                    _ => join_relative(base.unwrap_or(data_dir), entry)?,
+                };
+                // An existing file is kept, and listed in `changed` if it differs
+                if !target.exists() {
+                    extract_entry(archive, entry, &target)?;
+                }
+                target
+            }
+            None if !document.relative && !allow_absolute_paths => {
+                rejected.push(RejectedFile {
+                    name: document.name.clone(),
+                    path: document.path.clone(),
+                    reason: RejectionReason::NotFound,
+                    message: "Outside the store directory, allow absolute paths to import it"
+                        .to_string(),
+                });
+                continue;
+            }
+            None => path,
+        };
+
+        files.push(file_info(document, path, &mut changed));
+    }
+
+    Ok(BundleFiles {
+        files,
+        rejected,
+        changed,
+    })
+}
+
+fn extract_entry(archive: &mut ZipArchive<File>, entry: &str, target: &Path) -> Result<(), String> {
+    let mut source = archive
+        .by_name(entry)
+        .map_err(|e| format!("Failed to read {} from bundle: {}", entry, e))?;
+
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+
+    let mut file =
+        File::create(target).map_err(|e| format!("Failed to extract {}: {}", entry, e))?;
+    io::copy(&mut source, &mut file).map_err(|e| format!("Failed to extract {}: {}", entry, e))?;
+
+    Ok(())
+}
+
+/// Describe a bundle document at its local path, noting content changes
+fn file_info(document: &BundleDocument, path: PathBuf, changed: &mut Vec<String>) -> FileInfo {
+    let local_hash = hash_file(&path).ok();
+
+    if let (Some(expected), Some(actual)) = (&document.hash, &local_hash) {
+        if expected != actual {
+            changed.push(document.name.clone());
+        }
+    }
+
+    let custom_metadata = (!document.custom_metadata.is_empty())
+        .then(|| serde_json::to_string(&document.custom_metadata).ok())
+        .flatten();
+
+    FileInfo {
+        name: document.name.clone(),
+        path: path.to_string_lossy().to_string(),
+        content_type: document.content_type.clone(),
+        size: None,
+        hash: local_hash.or_else(|| document.hash.clone()),
+        custom_metadata,
+    }
+}
@@ -0,0 +1,624 @@
+//! Command-line interface
+//!
+//! `mizly-cli` works on the same `stores.db` and settings as the desktop app.
+//! Commands queue work in the database and print JSON to stdout; errors are
+//! printed as JSON to stderr with a non-zero exit code.
+//!
+//! `mcp` serves the stores to coding agents over stdio instead, see
+//! [`crate::mcp`].
+//!
+//! Uploads and deletions are done by whichever process holds the sync lease:
+//! while the app runs it uploads what the CLI queues, otherwise `sync` does.
+
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+use uuid::Uuid;
+
+use crate::backend::{BackendKind, Backends, LocalBackend};
+use crate::bundle;
+use crate::commands::documents::{queue_documents, FileInfo};
+use crate::db::{migrations, Database, DocumentListQuery, DocumentSort, StoreUpdate, TagChange};
+use crate::events::{EventSink, NoopEventSink};
+use crate::gemini::GeminiClient;
+use crate::mcp::McpServer;
+use crate::polling::{poll_pending_operations, start_polling_task, PollingState};
+use crate::settings;
+use crate::sync::{run_sync_cycle, start_sync_task, SyncState};
+
+/// Interval between operation checks of `sync --once`
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+// ============================================================================
+// Arguments
+// ============================================================================
+
+#[derive(Parser)]
+#[command(name = "mizly-cli", version, about = "Manage and query Mizly stores")]
+struct Cli {
+    /// Directory holding stores.db and settings.json (defaults to the app's)
+    #[arg(long, global = true, env = "MIZLY_DATA_DIR")]
+    data_dir: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Manage stores
+    #[command(subcommand)]
+    Stores(StoresCommand),
+    /// Manage documents
+    #[command(subcommand)]
+    Docs(DocsCommand),
+    /// Upload queued stores and documents and process deletions
+    Sync {
+        /// Run one sync pass and wait for processing instead of running until interrupted
+        #[arg(long)]
+        once: bool,
+        /// Seconds `--once` waits for uploaded documents to finish processing
+        #[arg(long, default_value_t = 300)]
+        timeout: u64,
+        /// Print the work queued for sync instead of syncing
+        #[arg(long, conflicts_with = "once")]
+        status: bool,
+    },
+    /// List deleted stores and documents with when they are purged
+    Trash,
+    /// Ask a question grounded in stores
+    Ask {
+        /// Store id or title, repeat for several stores
+        #[arg(long = "store", required = true)]
+        stores: Vec<String>,
+        question: String,
+    },
+    /// Serve stores to coding agents as MCP tools over stdio
+    Mcp,
+    /// Show or roll back database migrations
+    #[command(subcommand)]
+    Migrations(MigrationsCommand),
+}
+
+#[derive(Subcommand)]
+enum StoresCommand {
+    /// List stores with their document counts
+    List,
+    /// Create a store, queued for creation in its backend
+    Create {
+        title: String,
+        /// Directory the store's documents come from
+        #[arg(long)]
+        directory: Option<PathBuf>,
+        /// Search backend (`gemini` or `local`)
+        #[arg(long, value_parser = BackendKind::parse)]
+        backend: Option<BackendKind>,
+        /// Keep documents on this machine (implies the local backend)
+        #[arg(long)]
+        local_only: bool,
+    },
+    /// Edit a store; options left out keep their value, empty ones clear it
+    Update {
+        /// Store id or title
+        store: String,
+        /// New title, also given to the remote store where the backend can rename
+        #[arg(long)]
+        title: Option<String>,
+        #[arg(long)]
+        directory: Option<PathBuf>,
+        #[arg(long)]
+        description: Option<String>,
+        /// Color as #rrggbb
+        #[arg(long)]
+        color: Option<String>,
+        /// Short text, e.g. an emoji, shown in place of the folder icon
+        #[arg(long)]
+        icon: Option<String>,
+    },
+    /// Move a store and its documents to the trash
+    Delete {
+        /// Store id or title
+        store: String,
+    },
+    /// Take a store and its documents out of the trash
+    Restore {
+        /// Store id
+        id: String,
+    },
+    /// Write a store's definition and document list to a bundle
+    Export {
+        /// Store id or title
+        store: String,
+        /// Bundle file to create
+        path: PathBuf,
+        /// Add the document files to the bundle
+        #[arg(long)]
+        include_files: bool,
+    },
+    /// Recreate a store from a bundle, queueing its documents for upload
+    Import {
+        /// Bundle file
+        path: PathBuf,
+        /// Where the documents live on this machine, or where to extract included files
+        #[arg(long)]
+        directory: Option<PathBuf>,
+        /// Also queue documents from outside the store directory at their exported paths
+        #[arg(long)]
+        allow_absolute_paths: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum DocsCommand {
+    /// Queue files for upload to a store
+    Add {
+        /// Store id or title
+        #[arg(long)]
+        store: String,
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+    },
+    /// Move documents to the trash
+    Rm {
+        #[arg(required = true)]
+        ids: Vec<String>,
+    },
+    /// List a page of a store's documents
+    List {
+        /// Store id or title
+        #[arg(long)]
+        store: String,
+        /// Sort by `name`, `size`, `created` or `status`
+        #[arg(long, value_parser = DocumentSort::parse, default_value = "created")]
+        sort: DocumentSort,
+        #[arg(long)]
+        desc: bool,
+        #[arg(long)]
+        status: Option<String>,
+        #[arg(long)]
+        sync_status: Option<String>,
+        /// Exact mime type, or a prefix such as `text/`
+        #[arg(long)]
+        mime_type: Option<String>,
+        /// Part of the document name
+        #[arg(long)]
+        name: Option<String>,
+        /// `nextCursor` of the previous page
+        #[arg(long)]
+        cursor: Option<String>,
+        #[arg(long)]
+        limit: Option<u32>,
+    },
+    /// Take documents out of the trash
+    Restore {
+        #[arg(required = true)]
+        ids: Vec<String>,
+    },
+    /// Queue failed documents for another upload
+    Retry {
+        #[arg(required = true)]
+        ids: Vec<String>,
+    },
+    /// Upload documents again, replacing their remote copies
+    Reupload {
+        #[arg(required = true)]
+        ids: Vec<String>,
+    },
+    /// Add and remove document tags
+    Tag {
+        /// Tag to add (repeatable)
+        #[arg(long)]
+        add: Vec<String>,
+        /// Tag to remove (repeatable)
+        #[arg(long)]
+        remove: Vec<String>,
+        #[arg(required = true)]
+        ids: Vec<String>,
+    },
+    /// Move documents to another store, trashing each source once its copy is uploaded
+    Move {
+        /// Target store id or title
+        #[arg(long)]
+        to: String,
+        #[arg(required = true)]
+        ids: Vec<String>,
+    },
+    /// Copy documents to another store
+    Copy {
+        /// Target store id or title
+        #[arg(long)]
+        to: String,
+        #[arg(required = true)]
+        ids: Vec<String>,
+    },
+    /// Show the status of documents, by id or for a whole store
+    Status {
+        /// Store id or title
+        #[arg(long)]
+        store: Option<String>,
+        ids: Vec<String>,
+    },
+    /// Show what happened to a document: queued, uploaded, failed, deleted...
+    Timeline { id: String },
+}
+
+#[derive(Subcommand)]
+enum MigrationsCommand {
+    /// List the migrations applied to the database
+    List,
+    /// Undo the migrations newer than a version, e.g. before installing an older release
+    Rollback {
+        /// Version to keep; later migrations are undone
+        version: u32,
+    },
+}
+
+// ============================================================================
+// Output
+// ============================================================================
+
+/// Sink that prints events as JSON lines, for `sync` without `--once`
+struct JsonLinesSink;
+
+impl EventSink for JsonLinesSink {
+    fn emit(&self, event: &str, payload: Value) {
+        println!("{}", json!({ "event": event, "payload": payload }));
+    }
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<Value, String> {
+    serde_json::to_value(value).map_err(|e| format!("Failed to serialize output: {}", e))
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Database and settings of one data directory
+struct Context {
+    data_dir: PathBuf,
+    db: Arc<Database>,
+}
+
+impl Context {
+    fn open(data_dir: Option<PathBuf>) -> Result<Self, String> {
+        let data_dir = match data_dir {
+            Some(dir) => dir,
+            None => settings::default_data_dir()?,
+        };
+        let db = Arc::new(Database::new(&data_dir)?);
+
+        Ok(Self { data_dir, db })
+    }
+
+    fn trash_retention_days(&self) -> u32 {
+        settings::get_trash_settings_sync(&self.data_dir).retention_days
+    }
+
+    fn backends(&self) -> Arc<Backends> {
+        let gemini_client = Arc::new(GeminiClient::new(self.data_dir.clone()));
+        Backends::new(
+            self.db.clone(),
+            gemini_client.clone(),
+            Arc::new(LocalBackend::new(
+                self.data_dir.clone(),
+                self.db.clone(),
+                gemini_client,
+            )),
+        )
+    }
+}
+
+async fn stores_command(ctx: &Context, command: StoresCommand) -> Result<Value, String> {
+    match command {
+        StoresCommand::List => to_json(&ctx.db.get_stores()?),
+        StoresCommand::Create {
+            title,
+            directory,
+            backend,
+            local_only,
+        } => {
+            let backend = BackendKind::for_new_store(backend, local_only)?;
+            let directory = directory.map(|d| d.to_string_lossy().to_string());
+            let store = ctx.db.create_store(
+                &Uuid::new_v4().to_string(),
+                &title,
+                directory.as_deref(),
+                backend.as_str(),
+                local_only,
+            )?;
+
+            to_json(&store)
+        }
+        StoresCommand::Update {
+            store,
+            title,
+            directory,
+            description,
+            color,
+            icon,
+        } => {
+            let previous = ctx.db.find_store(&store)?;
+            let update = StoreUpdate {
+                title: title.unwrap_or_else(|| previous.title.clone()),
+                directory_path: directory
+                    .map(|d| d.to_string_lossy().to_string())
+                    .or(previous.directory_path),
+                description: description.or(previous.description),
+                color: color.or(previous.color),
+                icon: icon.or(previous.icon),
+            };
+            let store = ctx.db.update_store(&previous.id, &update)?;
+
+            // Like the app, keep the local edit if the remote rename fails
+            if store.title != previous.title {
+                if let Err(e) = ctx.backends().rename_store(&store).await {
+                    eprintln!("Failed to rename store {} remotely: {}", store.id, e);
+                }
+            }
+
+            to_json(&store)
+        }
+        StoresCommand::Delete { store } => {
+            let store = ctx.db.find_store(&store)?;
+
+            // Sync deletes the store remotely after its time in the trash, like the app does
+            ctx.db.soft_delete_store(&store.id)?;
+
+            Ok(json!({ "deleted": store.id }))
+        }
+        StoresCommand::Restore { id } => to_json(&ctx.db.restore_store(&id)?),
+        StoresCommand::Export {
+            store,
+            path,
+            include_files,
+        } => {
+            let store = ctx.db.find_store(&store)?;
+            let result =
+                bundle::export_store(&ctx.db, &ctx.backends(), &store.id, &path, include_files)
+                    .await?;
+
+            to_json(&result)
+        }
+        StoresCommand::Import {
+            path,
+            directory,
+            allow_absolute_paths,
+        } => to_json(&bundle::import_store(
+            &ctx.db,
+            &ctx.data_dir,
+            &path,
+            directory.as_deref(),
+            allow_absolute_paths,
+        )?),
+    }
+}
+
+fn migrations_command(ctx: &Context, command: MigrationsCommand) -> Result<Value, String> {
+    // Opening the database applied the pending migrations already
+    let conn = ctx.db.writer()?;
+
+    match command {
+        MigrationsCommand::List => to_json(&migrations::applied_migrations(&conn)?),
+        MigrationsCommand::Rollback { version } => {
+            let undone = migrations::rollback_migrations(&conn, version)?;
+            Ok(json!({ "version": version, "undone": undone }))
+        }
+    }
+}
+
+async fn docs_command(ctx: &Context, command: DocsCommand) -> Result<Value, String> {
+    match command {
+        DocsCommand::Add { store, paths } => {
+            let store = ctx.db.find_store(&store)?;
+
+            let files = paths.into_iter().map(FileInfo::from_path).collect();
+
+            to_json(&queue_documents(&ctx.db, &store.id, files)?)
+        }
+        DocsCommand::List {
+            store,
+            sort,
+            desc,
+            status,
+            sync_status,
+            mime_type,
+            name,
+            cursor,
+            limit,
+        } => {
+            let store = ctx.db.find_store(&store)?;
+            let query = DocumentListQuery {
+                sort,
+                descending: desc,
+                status,
+                sync_status,
+                mime_type,
+                name,
+                cursor,
+                limit,
+            };
+            to_json(&ctx.db.list_documents(&store.id, &query)?)
+        }
+        DocsCommand::Rm { ids } => to_json(&ctx.db.delete_documents(&ids)?),
+        DocsCommand::Restore { ids } => to_json(
+            &ids.iter()
+                .map(|id| ctx.db.restore_document(id))
+                .collect::<Result<Vec<_>, _>>()?,
+        ),
+        DocsCommand::Retry { ids } => to_json(&ctx.db.retry_documents(&ids)?),
+        DocsCommand::Reupload { ids } => to_json(&ctx.db.reupload_documents(&ids)?),
+        DocsCommand::Tag { add, remove, ids } => {
+            to_json(&ctx.db.tag_documents(&ids, &TagChange { add, remove })?)
+        }
+        DocsCommand::Move { to, ids } => {
+            let store = ctx.db.find_store(&to)?;
+            to_json(&ctx.db.move_documents(&ids, &store.id)?)
+        }
+        DocsCommand::Copy { to, ids } => {
+            let store = ctx.db.find_store(&to)?;
+            to_json(&ctx.db.copy_documents(&ids, &store.id)?)
+        }
+        DocsCommand::Status { store, ids } => {
+            if !ids.is_empty() {
+                let documents = ids
+                    .iter()
+                    .map(|id| ctx.db.get_document(id))
+                    .collect::<Result<Vec<_>, _>>()?;
+                return to_json(&documents);
+            }
+
+            let store = store.ok_or_else(|| "Pass --store or document ids".to_string())?;
+            let store = ctx.db.find_store(&store)?;
+            to_json(&ctx.db.get_documents_by_store(&store.id)?)
+        }
+        DocsCommand::Timeline { id } => to_json(&ctx.db.get_document_timeline(&id)?),
+    }
+}
+
+/// Run one sync pass, then poll until uploads finish processing or `timeout`
+async fn sync_once(
+    ctx: &Context,
+    sync_state: &SyncState,
+    timeout: Duration,
+) -> Result<Value, String> {
+    if !sync_state.acquire_lease(&ctx.db)? {
+        return Ok(json!({
+            "synced": false,
+            "reason": "Another Mizly process is syncing and will upload queued changes",
+        }));
+    }
+
+    let events = NoopEventSink;
+    let backends = ctx.backends();
+    let polling_state = PollingState::new();
+    let started = Instant::now();
+
+    run_sync_cycle(&events, &ctx.db, &backends, sync_state, &polling_state).await;
+
+    while poll_pending_operations(&events, &ctx.db, &backends).await? {
+        if started.elapsed() > timeout {
+            break;
+        }
+        sleep(POLL_INTERVAL).await;
+    }
+
+    let processing = ctx.db.get_documents_with_pending_operations()?.len();
+
+    Ok(json!({
+        "synced": true,
+        "processing": processing,
+        "stores": ctx.db.get_stores()?,
+    }))
+}
+
+/// Run the sync and polling tasks until interrupted, printing events
+async fn sync_forever(ctx: &Context, sync_state: Arc<SyncState>) -> Result<Value, String> {
+    let events: Arc<dyn EventSink> = Arc::new(JsonLinesSink);
+    let backends = ctx.backends();
+    let polling_state = PollingState::new();
+
+    start_polling_task(
+        events.clone(),
+        ctx.db.clone(),
+        backends.clone(),
+        polling_state.clone(),
+    );
+    start_sync_task(events, ctx.db.clone(), backends, sync_state, polling_state);
+
+    tokio::signal::ctrl_c()
+        .await
+        .map_err(|e| format!("Failed to wait for interrupt: {}", e))?;
+
+    Ok(json!({ "stopped": true }))
+}
+
+async fn ask(ctx: &Context, stores: Vec<String>, question: String) -> Result<Value, String> {
+    if question.trim().is_empty() {
+        return Err("Query cannot be empty".to_string());
+    }
+
+    let store_names = stores
+        .iter()
+        .map(|key| {
+            let store = ctx.db.find_store(key)?;
+            store
+                .gemini_name
+                .ok_or_else(|| format!("Store {} isn't synced yet", store.title))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let result = ctx.backends().query(&store_names, &question).await?;
+
+    Ok(json!({ "content": result.content, "sources": result.sources }))
+}
+
+async fn execute(cli: Cli) -> Result<Value, String> {
+    let ctx = Context::open(cli.data_dir)?;
+
+    match cli.command {
+        Command::Stores(command) => stores_command(&ctx, command).await,
+        Command::Docs(command) => docs_command(&ctx, command).await,
+        Command::Sync {
+            once,
+            timeout,
+            status,
+        } => {
+            let sync_state = SyncState::new();
+            sync_state.set_trash_retention_days(ctx.trash_retention_days());
+
+            // The queue and the app's pause are shared, cycles and errors belong to the
+            // process that syncs. Syncing from the CLI is explicit and ignores the pause.
+            if status {
+                sync_state.set_paused(settings::get_sync_settings_sync(&ctx.data_dir).paused);
+                return to_json(&sync_state.status(&ctx.db)?);
+            }
+
+            let result = if once {
+                sync_once(&ctx, &sync_state, Duration::from_secs(timeout)).await
+            } else {
+                sync_forever(&ctx, sync_state.clone()).await
+            };
+
+            // Let a running app take over without waiting for the lease to expire
+            let _ = sync_state.release_lease(&ctx.db);
+            result
+        }
+        Command::Trash => to_json(&ctx.db.get_trash(ctx.trash_retention_days())?),
+        Command::Ask { stores, question } => ask(&ctx, stores, question).await,
+        Command::Migrations(command) => migrations_command(&ctx, command),
+        Command::Mcp => {
+            let server = McpServer::new(ctx.db.clone(), ctx.backends());
+            let stdin = tokio::io::BufReader::new(tokio::io::stdin());
+            server.serve(stdin, tokio::io::stdout()).await?;
+
+            // Responses were written to stdout already
+            Ok(Value::Null)
+        }
+    }
+}
+
+/// CLI entry point
+pub fn run() -> ExitCode {
+    let cli = Cli::parse();
+
+    match tauri::async_runtime::block_on(execute(cli)) {
+        Ok(Value::Null) => ExitCode::SUCCESS,
+        Ok(output) => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&output).unwrap_or_default()
+            );
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{}", json!({ "error": e }));
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,50 @@
+//! Database backup commands
+
+use std::sync::Arc;
+use tauri::{AppHandle, State};
+
+use crate::backup::{self, BackupInfo, RestoreResult};
+use crate::db::Database;
+use crate::settings;
+use crate::sync::SyncState;
+
+#[tauri::command]
+pub async fn list_backups(app_handle: AppHandle) -> Result<Vec<BackupInfo>, String> {
+    backup::list_backups(&settings::app_data_dir(&app_handle)?)
+}
+
+/// Back up the database now, pruning old backups like scheduled ones
+#[tauri::command]
+pub async fn create_backup(
+    app_handle: AppHandle,
+    db: State<'_, Arc<Database>>,
+) -> Result<BackupInfo, String> {
+    let data_dir = settings::app_data_dir(&app_handle)?;
+    let keep = settings::get_backup_settings_sync(&data_dir).keep as usize;
+    let db = db.inner().clone();
+
+    tokio::task::spawn_blocking(move || backup::create_backup(&db, &data_dir, keep))
+        .await
+        .map_err(|e| format!("Backup task failed: {}", e))?
+}
+
+/// Replace the database with a backup, backing up the current state first
+#[tauri::command]
+pub async fn restore_backup(
+    app_handle: AppHandle,
+    db: State<'_, Arc<Database>>,
+    sync_state: State<'_, Arc<SyncState>>,
+    name: String,
+) -> Result<RestoreResult, String> {
+    let data_dir = settings::app_data_dir(&app_handle)?;
+    let db = db.inner().clone();
+
+    let result = tokio::task::spawn_blocking(move || backup::restore_backup(&db, &data_dir, &name))
+        .await
+        .map_err(|e| format!("Restore task failed: {}", e))??;
+
+    // Pending uploads and deletions in the restored state are picked up by sync
+    sync_state.notify();
+
+    Ok(result)
+}
@@ -0,0 +1,65 @@
+//! Store bundle export and import commands
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{AppHandle, State};
+
+use crate::backend::Backends;
+use crate::bundle::{self, ExportStoreResult, ImportStoreResult};
+use crate::db::Database;
+use crate::settings;
+use crate::sync::SyncState;
+
+/// Export a store's definition and document list, optionally with its files
+#[tauri::command]
+pub async fn export_store(
+    db: State<'_, Arc<Database>>,
+    backends: State<'_, Arc<Backends>>,
+    id: String,
+    path: String,
+    include_files: Option<bool>,
+) -> Result<ExportStoreResult, String> {
+    bundle::export_store(
+        &db,
+        &backends,
+        &id,
+        &PathBuf::from(path),
+        include_files.unwrap_or(false),
+    )
+    .await
+}
+
+/// Recreate a store from a bundle and queue its documents for upload
+///
+/// `directory_path` is where the bundle's documents live on this machine, or
+/// where included files are extracted. Documents outside the exported store
+/// directory are only queued at their absolute paths with `allow_absolute_paths`.
+#[tauri::command]
+pub async fn import_store(
+    app_handle: AppHandle,
+    db: State<'_, Arc<Database>>,
+    sync_state: State<'_, Arc<SyncState>>,
+    path: String,
+    directory_path: Option<String>,
+    allow_absolute_paths: Option<bool>,
+) -> Result<ImportStoreResult, String> {
+    let data_dir = settings::app_data_dir(&app_handle)?;
+    let db = db.inner().clone();
+
+    let result = tokio::task::spawn_blocking(move || {
+        bundle::import_store(
+            &db,
+            &data_dir,
+            &PathBuf::from(path),
+            directory_path.as_deref().map(PathBuf::from).as_deref(),
+            allow_absolute_paths.unwrap_or(false),
+        )
+    })
+    .await
+    .map_err(|e| format!("Import task failed: {}", e))??;
+
+    // Notify background sync to create the store and upload its documents
+    sync_state.notify();
+
+    Ok(result)
+}
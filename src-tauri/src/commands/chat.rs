@@ -1,9 +1,14 @@
 //! Chat-related Tauri commands
 
 use serde::Serialize;
-use tauri::AppHandle;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::State;
 
-use crate::gemini::GeminiClient;
+use crate::backend::Backends;
+use crate::db::Database;
+use crate::export::{self, Conversation, ExportConversationResult, ExportFormat};
+use crate::state::AppState;
 
 /// Chat query result for frontend
 #[derive(Debug, Clone, Serialize)]
@@ -16,7 +21,8 @@ pub struct ChatResult {
 /// Query stores with a question
 #[tauri::command]
 pub async fn query_stores(
-    app_handle: AppHandle,
+    backends: State<'_, Arc<Backends>>,
+    state: State<'_, AppState>,
     store_names: Vec<String>,
     query: String,
 ) -> Result<ChatResult, String> {
@@ -28,8 +34,12 @@ pub async fn query_stores(
         return Err("Query cannot be empty".to_string());
     }
 
-    let client = GeminiClient::new(app_handle);
-    let result = client.query_stores(&store_names, &query).await?;
+    // Remember the stores for the clipboard shortcut
+    if let Ok(mut last_query_stores) = state.last_query_stores.lock() {
+        *last_query_stores = store_names.clone();
+    }
+
+    let result = backends.query(&store_names, &query).await?;
 
     Ok(ChatResult {
         content: result.content,
@@ -40,7 +50,7 @@ pub async fn query_stores(
 /// Get suggested questions based on store content
 #[tauri::command]
 pub async fn suggest_questions(
-    app_handle: AppHandle,
+    backends: State<'_, Arc<Backends>>,
     store_names: Vec<String>,
     locale: String,
 ) -> Result<Vec<String>, String> {
@@ -48,6 +58,24 @@ pub async fn suggest_questions(
         return Ok(vec![]);
     }
 
-    let client = GeminiClient::new(app_handle);
-    client.suggest_questions(&store_names, &locale).await
+    backends.suggest_questions(&store_names, &locale).await
+}
+
+/// Export a conversation with its resolved citations to Markdown, HTML or PDF
+///
+/// The format is taken from the file extension when not given.
+#[tauri::command]
+pub async fn export_conversation(
+    db: State<'_, Arc<Database>>,
+    conversation: Conversation,
+    path: String,
+    format: Option<ExportFormat>,
+) -> Result<ExportConversationResult, String> {
+    let db = db.inner().clone();
+
+    tokio::task::spawn_blocking(move || {
+        export::export_conversation(&db, &conversation, format, &PathBuf::from(path))
+    })
+    .await
+    .map_err(|e| format!("Export task failed: {}", e))?
 }
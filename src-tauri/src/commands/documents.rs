@@ -1,12 +1,18 @@
 //! Document-related Tauri commands
 
+use serde::Serialize;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tauri::State;
 use tauri_plugin_opener::OpenerExt;
 use uuid::Uuid;
 
-use crate::db::{Database, Document};
+use crate::db::{
+    BatchReport, Database, Document, DocumentEvent, DocumentListQuery, DocumentPage, NewDocument,
+    TagChange, TransferDocumentsResult,
+};
 use crate::sync::SyncState;
+use crate::validation::{validate_upload_file, RejectedFile};
 
 #[derive(serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -16,6 +22,39 @@ pub struct FileInfo {
     pub content_type: Option<String>,
     pub size: Option<i64>,
     pub hash: Option<String>,
+    /// Gemini custom metadata entries as a JSON array
+    #[serde(default)]
+    pub custom_metadata: Option<String>,
+}
+
+impl FileInfo {
+    /// Describe a file on disk, for callers without a file picker (CLI, HTTP API)
+    pub(crate) fn from_path(path: PathBuf) -> Self {
+        // Store absolute paths, the sync may run from another directory
+        let path = std::fs::canonicalize(&path).unwrap_or(path);
+
+        Self {
+            name: path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            content_type: mime_guess::from_path(&path).first().map(|m| m.to_string()),
+            path: path.to_string_lossy().to_string(),
+            size: None,
+            hash: None,
+            custom_metadata: None,
+        }
+    }
+}
+
+/// Result of queueing files for upload
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadDocumentsResult {
+    /// Documents created and queued for upload
+    pub documents: Vec<Document>,
+    /// Files that failed validation and were not queued
+    pub rejected: Vec<RejectedFile>,
 }
 
 #[tauri::command]
@@ -23,40 +62,98 @@ pub async fn get_documents(
     db: State<'_, Arc<Database>>,
     store_id: String,
 ) -> Result<Vec<Document>, String> {
-    db.get_documents_by_store(&store_id)
+    db.call(move |db| db.get_documents_by_store(&store_id))
+        .await
 }
 
+/// List a page of a store's documents, sorted and filtered
 #[tauri::command]
-pub async fn upload_documents(
+pub async fn list_documents(
     db: State<'_, Arc<Database>>,
-    sync_state: State<'_, Arc<SyncState>>,
     store_id: String,
+    query: DocumentListQuery,
+) -> Result<DocumentPage, String> {
+    db.call(move |db| db.list_documents(&store_id, &query))
+        .await
+}
+
+/// Get a document's activity, oldest first
+#[tauri::command]
+pub async fn get_document_timeline(
+    db: State<'_, Arc<Database>>,
+    id: String,
+) -> Result<Vec<DocumentEvent>, String> {
+    db.call(move |db| db.get_document_timeline(&id)).await
+}
+
+/// Validate files and create their documents, queued for upload
+///
+/// Shared by the upload command, the CLI and the HTTP API.
+pub(crate) fn queue_documents(
+    db: &Database,
+    store_id: &str,
     files: Vec<FileInfo>,
-) -> Result<Vec<Document>, String> {
+) -> Result<UploadDocumentsResult, String> {
+    let (documents, rejected) = validate_files(files);
+
+    Ok(UploadDocumentsResult {
+        documents: db.create_documents(store_id, &documents)?,
+        rejected,
+    })
+}
+
+/// Split files into new documents and the ones that can't be uploaded
+pub(crate) fn validate_files(files: Vec<FileInfo>) -> (Vec<NewDocument>, Vec<RejectedFile>) {
     let mut documents = Vec::new();
+    let mut rejected = Vec::new();
 
     for file in files {
-        let doc_id = Uuid::new_v4().to_string();
-
-        // Create document in local database immediately (optimistic)
-        // sync_status defaults to 'pending'
-        let doc = db.create_document(
-            &doc_id,
-            &store_id,
-            &file.name,
-            &file.path,
-            file.content_type.as_deref(),
-            file.size,
-            file.hash.as_deref(),
-        )?;
-
-        documents.push(doc);
+        // Reject files that can't be uploaded instead of creating doomed rows
+        let size = match validate_upload_file(Path::new(&file.path)) {
+            Ok(size) => size as i64,
+            Err((reason, message)) => {
+                rejected.push(RejectedFile {
+                    name: file.name,
+                    path: file.path,
+                    reason,
+                    message,
+                });
+                continue;
+            }
+        };
+
+        documents.push(NewDocument {
+            id: Uuid::new_v4().to_string(),
+            name: file.name,
+            path: file.path,
+            content_type: file.content_type,
+            size: file.size.or(Some(size)),
+            hash: file.hash,
+            custom_metadata: file.custom_metadata,
+        });
     }
 
+    (documents, rejected)
+}
+
+#[tauri::command]
+pub async fn upload_documents(
+    db: State<'_, Arc<Database>>,
+    sync_state: State<'_, Arc<SyncState>>,
+    store_id: String,
+    files: Vec<FileInfo>,
+) -> Result<UploadDocumentsResult, String> {
+    // Validation reads every file, keep it off the async executor too
+    let result = db
+        .call(move |db| queue_documents(db, &store_id, files))
+        .await?;
+
     // Notify background sync to pick up the new documents
-    sync_state.notify();
+    if !result.documents.is_empty() {
+        sync_state.notify();
+    }
 
-    Ok(documents)
+    Ok(result)
 }
 
 #[tauri::command]
@@ -67,7 +164,7 @@ pub async fn delete_document(
 ) -> Result<(), String> {
     // Soft delete the document (sets deleted_at timestamp)
     // Background sync will handle the API deletion
-    db.soft_delete_document(&id)?;
+    db.call(move |db| db.soft_delete_document(&id)).await?;
 
     // Notify background sync to process soft deleted documents
     sync_state.notify();
@@ -75,6 +172,102 @@ pub async fn delete_document(
     Ok(())
 }
 
+/// Move documents to the trash in one batch
+#[tauri::command]
+pub async fn delete_documents(
+    db: State<'_, Arc<Database>>,
+    sync_state: State<'_, Arc<SyncState>>,
+    ids: Vec<String>,
+) -> Result<BatchReport, String> {
+    let report = db.call(move |db| db.delete_documents(&ids)).await?;
+    notify_batch(&sync_state, &report);
+    Ok(report)
+}
+
+/// Queue failed documents for another upload
+#[tauri::command]
+pub async fn retry_documents(
+    db: State<'_, Arc<Database>>,
+    sync_state: State<'_, Arc<SyncState>>,
+    ids: Vec<String>,
+) -> Result<BatchReport, String> {
+    let report = db.call(move |db| db.retry_documents(&ids)).await?;
+    notify_batch(&sync_state, &report);
+    Ok(report)
+}
+
+/// Queue documents for upload again, replacing their remote copies
+#[tauri::command]
+pub async fn reupload_documents(
+    db: State<'_, Arc<Database>>,
+    sync_state: State<'_, Arc<SyncState>>,
+    ids: Vec<String>,
+) -> Result<BatchReport, String> {
+    let report = db.call(move |db| db.reupload_documents(&ids)).await?;
+    notify_batch(&sync_state, &report);
+    Ok(report)
+}
+
+/// Add and remove document tags, re-uploading documents already uploaded
+#[tauri::command]
+pub async fn tag_documents(
+    db: State<'_, Arc<Database>>,
+    sync_state: State<'_, Arc<SyncState>>,
+    ids: Vec<String>,
+    change: TagChange,
+) -> Result<BatchReport, String> {
+    let report = db.call(move |db| db.tag_documents(&ids, &change)).await?;
+    notify_batch(&sync_state, &report);
+    Ok(report)
+}
+
+/// Wake background sync once for a whole batch
+fn notify_batch(sync_state: &SyncState, report: &BatchReport) {
+    if report.succeeded > 0 {
+        sync_state.notify();
+    }
+}
+
+/// Copy documents to another store, the copies are uploaded by background sync
+#[tauri::command]
+pub async fn copy_documents(
+    db: State<'_, Arc<Database>>,
+    sync_state: State<'_, Arc<SyncState>>,
+    ids: Vec<String>,
+    target_store_id: String,
+) -> Result<TransferDocumentsResult, String> {
+    let result = db
+        .call(move |db| db.copy_documents(&ids, &target_store_id))
+        .await?;
+
+    if !result.documents.is_empty() {
+        sync_state.notify();
+    }
+
+    Ok(result)
+}
+
+/// Move documents to another store
+///
+/// Each source is moved to the trash once its copy is uploaded and completed.
+#[tauri::command]
+pub async fn move_documents(
+    db: State<'_, Arc<Database>>,
+    sync_state: State<'_, Arc<SyncState>>,
+    ids: Vec<String>,
+    target_store_id: String,
+) -> Result<TransferDocumentsResult, String> {
+    let result = db
+        .call(move |db| db.move_documents(&ids, &target_store_id))
+        .await?;
+
+    if !result.documents.is_empty() {
+        sync_state.notify();
+    }
+
+    Ok(result)
+}
+
 #[tauri::command]
 pub async fn get_documents_by_uids(
     db: State<'_, Arc<Database>>,
@@ -82,7 +275,8 @@ pub async fn get_documents_by_uids(
 ) -> Result<Vec<Document>, String> {
     // API returns file names (display names) in groundingMetadata, not gemini_names
     // Search by document name field instead
-    db.get_documents_by_display_names(&document_uids)
+    db.call(move |db| db.get_documents_by_display_names(&document_uids))
+        .await
 }
 
 #[tauri::command]
@@ -0,0 +1,60 @@
+//! Local HTTP API settings commands
+
+use std::sync::Arc;
+use tauri::{AppHandle, State};
+
+use crate::http_api::HttpApiState;
+use crate::settings::{self, HttpApiSettings};
+
+/// Restart the server with new settings, then save them
+async fn apply_and_save(
+    app_handle: &AppHandle,
+    http_api: &HttpApiState,
+    settings: HttpApiSettings,
+) -> Result<HttpApiSettings, String> {
+    let data_dir = settings::app_data_dir(app_handle)?;
+    settings::validate_http_api_settings(&settings)?;
+
+    http_api.apply(&settings).await?;
+    settings::write_http_api_settings(&data_dir, &settings)?;
+
+    Ok(settings)
+}
+
+#[tauri::command]
+pub async fn get_http_api_settings(app_handle: AppHandle) -> Result<HttpApiSettings, String> {
+    Ok(settings::get_http_api_settings_sync(
+        &settings::app_data_dir(&app_handle)?,
+    ))
+}
+
+/// Enable or disable the HTTP API, generating a token on first use
+#[tauri::command]
+pub async fn set_http_api_settings(
+    app_handle: AppHandle,
+    http_api: State<'_, Arc<HttpApiState>>,
+    enabled: bool,
+    port: u16,
+) -> Result<HttpApiSettings, String> {
+    let mut settings = settings::get_http_api_settings_sync(&settings::app_data_dir(&app_handle)?);
+    settings.enabled = enabled;
+    settings.port = port;
+
+    if settings.token.is_none() {
+        settings.token = Some(settings::generate_api_token());
+    }
+
+    apply_and_save(&app_handle, &http_api, settings).await
+}
+
+/// Replace the token, revoking access of clients using the old one
+#[tauri::command]
+pub async fn regenerate_http_api_token(
+    app_handle: AppHandle,
+    http_api: State<'_, Arc<HttpApiState>>,
+) -> Result<HttpApiSettings, String> {
+    let mut settings = settings::get_http_api_settings_sync(&settings::app_data_dir(&app_handle)?);
+    settings.token = Some(settings::generate_api_token());
+
+    apply_and_save(&app_handle, &http_api, settings).await
+}
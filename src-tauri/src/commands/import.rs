@@ -0,0 +1,153 @@
+//! Directory import Tauri commands
+
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::State;
+use uuid::Uuid;
+
+use crate::db::{Database, Document};
+use crate::import::{scan_directory, ImportPreview, ImportRules, SkippedFile};
+use crate::sync::SyncState;
+
+/// Result of importing a directory
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportDirectoryResult {
+    /// Documents created and queued for upload
+    pub documents: Vec<Document>,
+    /// Files and directories left out of the import
+    pub skipped: Vec<SkippedFile>,
+    /// Not all importable files were imported
+    pub truncated: bool,
+}
+
+/// Resolve the rules for an import: explicit rules win over the store's saved rules
+fn resolve_rules(
+    db: &Database,
+    store_id: Option<&str>,
+    rules: Option<ImportRules>,
+) -> Result<ImportRules, String> {
+    match (rules, store_id) {
+        (Some(rules), _) => Ok(rules),
+        (None, Some(store_id)) => Ok(ImportRules::from_store(&db.get_store(store_id)?)),
+        (None, None) => Ok(ImportRules::default()),
+    }
+}
+
+/// Paths of the documents already in a store
+fn existing_paths(db: &Database, store_id: Option<&str>) -> Result<HashSet<String>, String> {
+    match store_id {
+        Some(store_id) => Ok(db
+            .get_documents_by_store(store_id)?
+            .into_iter()
+            .map(|doc| doc.path)
+            .collect()),
+        None => Ok(HashSet::new()),
+    }
+}
+
+async fn scan(
+    path: String,
+    rules: ImportRules,
+    existing_paths: HashSet<String>,
+) -> Result<ImportPreview, String> {
+    tokio::task::spawn_blocking(move || {
+        scan_directory(&PathBuf::from(path), &rules, &existing_paths)
+    })
+    .await
+    .map_err(|e| format!("Directory scan task failed: {}", e))?
+}
+
+/// Preview which files a directory import would add without changing anything
+///
+/// When `rules` is omitted, the rules saved on the store are used.
+#[tauri::command]
+pub async fn preview_directory_import(
+    db: State<'_, Arc<Database>>,
+    store_id: Option<String>,
+    path: String,
+    rules: Option<ImportRules>,
+) -> Result<ImportPreview, String> {
+    let (rules, existing_paths) = db
+        .call(move |db| {
+            let rules = resolve_rules(db, store_id.as_deref(), rules)?;
+            Ok((rules, existing_paths(db, store_id.as_deref())?))
+        })
+        .await?;
+
+    scan(path, rules, existing_paths).await
+}
+
+/// Import all matching files of a directory into a store
+///
+/// Explicit `rules` are saved on the store for later imports. Files already
+/// in the store are skipped, so importing the same directory again only adds
+/// new files.
+#[tauri::command]
+pub async fn import_directory(
+    db: State<'_, Arc<Database>>,
+    sync_state: State<'_, Arc<SyncState>>,
+    store_id: String,
+    path: String,
+    rules: Option<ImportRules>,
+) -> Result<ImportDirectoryResult, String> {
+    let save_rules = rules.is_some();
+    let (rules, existing_paths) = {
+        let store_id = store_id.clone();
+        db.call(move |db| {
+            let rules = resolve_rules(db, Some(&store_id), rules)?;
+            Ok((rules, existing_paths(db, Some(&store_id))?))
+        })
+        .await?
+    };
+
+    let preview = scan(path, rules.clone(), existing_paths).await?;
+    let files = preview.files;
+
+    let documents = db
+        .call(move |db| {
+            if save_rules {
+                db.update_store_import_rules(
+                    &store_id,
+                    rules.include_patterns.as_deref(),
+                    rules.exclude_patterns.as_deref(),
+                )?;
+            }
+
+            let mut documents = Vec::new();
+            for file in files {
+                let doc_id = Uuid::new_v4().to_string();
+                let content_type = mime_guess::from_path(&file.path)
+                    .first_or_octet_stream()
+                    .to_string();
+
+                // sync_status defaults to 'pending'
+                let doc = db.create_document(
+                    &doc_id,
+                    &store_id,
+                    &file.name,
+                    &file.path,
+                    Some(&content_type),
+                    Some(file.size as i64),
+                    None,
+                )?;
+
+                documents.push(doc);
+            }
+            Ok(documents)
+        })
+        .await?;
+
+    // Notify background sync to pick up the new documents
+    if !documents.is_empty() {
+        sync_state.notify();
+    }
+
+    Ok(ImportDirectoryResult {
+        documents,
+        skipped: preview.skipped,
+        truncated: preview.truncated,
+    })
+}
@@ -1,9 +1,23 @@
+pub mod backup;
+pub mod bundle;
 pub mod chat;
 pub mod documents;
+pub mod http_api;
+pub mod import;
+pub mod search;
 pub mod stores;
+pub mod sync;
+pub mod trash;
 pub mod window;
 
+pub use backup::*;
+pub use bundle::*;
 pub use chat::*;
 pub use documents::*;
+pub use http_api::*;
+pub use import::*;
+pub use search::*;
 pub use stores::*;
+pub use sync::*;
+pub use trash::*;
 pub use window::*;
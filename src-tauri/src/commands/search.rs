@@ -0,0 +1,24 @@
+//! Local search Tauri commands
+
+use std::sync::Arc;
+use tauri::State;
+
+use crate::db::{Database, LocalSearchResult};
+
+/// Maximum number of local search results returned
+const LOCAL_SEARCH_LIMIT: i64 = 20;
+
+/// Keyword search over the local full-text index (works offline)
+#[tauri::command]
+pub async fn search_local(
+    db: State<'_, Arc<Database>>,
+    query: String,
+    store_ids: Option<Vec<String>>,
+) -> Result<Vec<LocalSearchResult>, String> {
+    if query.trim().is_empty() {
+        return Ok(vec![]);
+    }
+
+    db.call(move |db| db.search_local(&query, &store_ids.unwrap_or_default(), LOCAL_SEARCH_LIMIT))
+        .await
+}
@@ -2,14 +2,15 @@ use std::sync::Arc;
 use tauri::State;
 use uuid::Uuid;
 
-use crate::db::{Store, StoreWithStatus, Database};
+use crate::backend::{BackendKind, Backends};
+use crate::db::{Store, StoreUpdate, StoreWithStatus, Database};
 use crate::sync::SyncState;
 
 #[tauri::command]
 pub async fn get_stores(
     db: State<'_, Arc<Database>>,
 ) -> Result<Vec<StoreWithStatus>, String> {
-    db.get_stores()
+    db.call(|db| db.get_stores()).await
 }
 
 #[tauri::command]
@@ -17,7 +18,7 @@ pub async fn get_store(
     db: State<'_, Arc<Database>>,
     id: String,
 ) -> Result<Store, String> {
-    db.get_store(&id)
+    db.call(move |db| db.get_store(&id)).await
 }
 
 #[tauri::command]
@@ -26,12 +27,27 @@ pub async fn create_store(
     sync_state: State<'_, Arc<SyncState>>,
     title: String,
     directory_path: Option<String>,
+    backend: Option<BackendKind>,
+    local_only: Option<bool>,
 ) -> Result<Store, String> {
     let id = Uuid::new_v4().to_string();
+    let local_only = local_only.unwrap_or(false);
+
+    let backend = BackendKind::for_new_store(backend, local_only)?;
 
     // Create store in local database immediately (optimistic)
     // sync_status defaults to 'pending'
-    let store = db.create_store(&id, &title, directory_path.as_deref())?;
+    let store = db
+        .call(move |db| {
+            db.create_store(
+                &id,
+                &title,
+                directory_path.as_deref(),
+                backend.as_str(),
+                local_only,
+            )
+        })
+        .await?;
 
     // Notify background sync to pick up the new store
     sync_state.notify();
@@ -39,18 +55,41 @@ pub async fn create_store(
     Ok(store)
 }
 
+/// Edit a store, renaming it remotely too when the backend supports it
+#[tauri::command]
+pub async fn update_store(
+    db: State<'_, Arc<Database>>,
+    backends: State<'_, Arc<Backends>>,
+    id: String,
+    update: StoreUpdate,
+) -> Result<Store, String> {
+    let (previous, store) = db
+        .call(move |db| {
+            let previous = db.get_store(&id)?;
+            let store = db.update_store(&id, &update)?;
+            Ok((previous, store))
+        })
+        .await?;
+
+    // The local edit stands even if the remote rename fails
+    if store.title != previous.title {
+        if let Err(e) = backends.rename_store(&store).await {
+            eprintln!("Failed to rename store {} remotely: {}", store.id, e);
+        }
+    }
+
+    Ok(store)
+}
+
 #[tauri::command]
 pub async fn delete_store(
     db: State<'_, Arc<Database>>,
     sync_state: State<'_, Arc<SyncState>>,
     id: String,
 ) -> Result<(), String> {
-    // Soft delete all documents in this store first
-    db.soft_delete_documents_by_store(&id)?;
-
-    // Soft delete the store (sets deleted_at timestamp)
-    // Background sync will handle the API deletion
-    db.soft_delete_store(&id)?;
+    // Move the store and its documents to the trash (sets deleted_at timestamp)
+    // Background sync will handle the API deletion once the grace period is over
+    db.call(move |db| db.soft_delete_store(&id)).await?;
 
     // Notify background sync to process soft deleted items
     sync_state.notify();
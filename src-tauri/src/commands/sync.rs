@@ -0,0 +1,58 @@
+//! Sync status commands: queue depth, pause and resume
+
+use std::sync::Arc;
+use tauri::{AppHandle, Manager, State};
+
+use crate::db::Database;
+use crate::settings::{self, SyncSettings};
+use crate::sync::{SyncState, SyncStatus};
+
+/// Queue depth per sync stage, last cycle, last error and next wakeup
+#[tauri::command]
+pub async fn get_sync_status(
+    db: State<'_, Arc<Database>>,
+    sync_state: State<'_, Arc<SyncState>>,
+) -> Result<SyncStatus, String> {
+    let sync_state = sync_state.inner().clone();
+    db.call(move |db| sync_state.status(db)).await
+}
+
+/// Stop uploads, deletions and polling until sync is resumed, also after a restart
+#[tauri::command]
+pub async fn pause_sync(
+    app_handle: AppHandle,
+    db: State<'_, Arc<Database>>,
+) -> Result<SyncStatus, String> {
+    set_sync_paused(&app_handle, true)?;
+    sync_status(&app_handle, &db).await
+}
+
+#[tauri::command]
+pub async fn resume_sync(
+    app_handle: AppHandle,
+    db: State<'_, Arc<Database>>,
+) -> Result<SyncStatus, String> {
+    set_sync_paused(&app_handle, false)?;
+    sync_status(&app_handle, &db).await
+}
+
+/// Save the pause state and hand it to the sync task, also used by the tray menu
+///
+/// The sync task reports the change with its next `sync-status` event.
+pub(crate) fn set_sync_paused(app_handle: &AppHandle, paused: bool) -> Result<(), String> {
+    settings::write_sync_settings(
+        &settings::app_data_dir(app_handle)?,
+        &SyncSettings { paused },
+    )?;
+
+    let sync_state = app_handle.state::<Arc<SyncState>>();
+    sync_state.set_paused(paused);
+    sync_state.notify();
+
+    Ok(())
+}
+
+async fn sync_status(app_handle: &AppHandle, db: &Arc<Database>) -> Result<SyncStatus, String> {
+    let sync_state = app_handle.state::<Arc<SyncState>>().inner().clone();
+    db.call(move |db| sync_state.status(db)).await
+}
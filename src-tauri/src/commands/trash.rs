@@ -0,0 +1,68 @@
+//! Trash commands: deleted stores and documents kept for a grace period
+
+use std::sync::Arc;
+use tauri::{AppHandle, State};
+
+use crate::db::{Database, Document, Store, Trash};
+use crate::settings::{self, TrashSettings};
+use crate::sync::SyncState;
+
+#[tauri::command]
+pub async fn get_trash(
+    db: State<'_, Arc<Database>>,
+    sync_state: State<'_, Arc<SyncState>>,
+) -> Result<Trash, String> {
+    let retention_days = sync_state.trash_retention_days();
+    db.call(move |db| db.get_trash(retention_days)).await
+}
+
+/// Restore a store with the documents deleted along with it
+#[tauri::command]
+pub async fn restore_store(
+    db: State<'_, Arc<Database>>,
+    sync_state: State<'_, Arc<SyncState>>,
+    id: String,
+) -> Result<Store, String> {
+    let store = db.call(move |db| db.restore_store(&id)).await?;
+
+    // Uploads queued before the deletion carry on
+    sync_state.notify();
+
+    Ok(store)
+}
+
+#[tauri::command]
+pub async fn restore_document(
+    db: State<'_, Arc<Database>>,
+    sync_state: State<'_, Arc<SyncState>>,
+    id: String,
+) -> Result<Document, String> {
+    let document = db.call(move |db| db.restore_document(&id)).await?;
+
+    sync_state.notify();
+
+    Ok(document)
+}
+
+#[tauri::command]
+pub async fn get_trash_settings(app_handle: AppHandle) -> Result<TrashSettings, String> {
+    Ok(settings::get_trash_settings_sync(&settings::app_data_dir(
+        &app_handle,
+    )?))
+}
+
+/// Save the grace period, applied from the next sync cycle
+#[tauri::command]
+pub async fn set_trash_settings(
+    app_handle: AppHandle,
+    sync_state: State<'_, Arc<SyncState>>,
+    settings: TrashSettings,
+) -> Result<(), String> {
+    settings::write_trash_settings(&settings::app_data_dir(&app_handle)?, &settings)?;
+    sync_state.set_trash_retention_days(settings.retention_days);
+
+    // Items past a shorter grace period are purged right away
+    sync_state.notify();
+
+    Ok(())
+}
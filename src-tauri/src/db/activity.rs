@@ -0,0 +1,185 @@
+//! Per-document activity log
+//!
+//! `document_events` is append-only: the database operations that move a
+//! document through its life (queued, uploaded, processed, failed, deleted,
+//! purged) add a row in the transaction of their update, so a failed
+//! document shows how it got there and not only its last error. Background
+//! sync prunes old events; a purged document's timeline ages out with them.
+
+use rusqlite::types::Type;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+
+use super::connection::CachedStatements;
+use super::trash::purge_cutoff;
+use super::Database;
+
+/// Days document events are kept
+pub const DOCUMENT_EVENT_RETENTION_DAYS: u32 = 90;
+
+/// Most recent events kept per document, older ones are pruned
+pub const MAX_EVENTS_PER_DOCUMENT: i64 = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentEventKind {
+    /// Waiting for background sync to upload it
+    Queued,
+    UploadStarted,
+    /// The backend accepted the file, processing may still be running
+    UploadFinished,
+    /// The backend finished processing, the document is searchable
+    OperationDone,
+    Failed,
+    Deleted,
+    Restored,
+    /// Deleted for good once its time in the trash was up
+    Purged,
+}
+
+impl DocumentEventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DocumentEventKind::Queued => "queued",
+            DocumentEventKind::UploadStarted => "upload_started",
+            DocumentEventKind::UploadFinished => "upload_finished",
+            DocumentEventKind::OperationDone => "operation_done",
+            DocumentEventKind::Failed => "failed",
+            DocumentEventKind::Deleted => "deleted",
+            DocumentEventKind::Restored => "restored",
+            DocumentEventKind::Purged => "purged",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "queued" => Some(DocumentEventKind::Queued),
+            "upload_started" => Some(DocumentEventKind::UploadStarted),
+            "upload_finished" => Some(DocumentEventKind::UploadFinished),
+            "operation_done" => Some(DocumentEventKind::OperationDone),
+            "failed" => Some(DocumentEventKind::Failed),
+            "deleted" => Some(DocumentEventKind::Deleted),
+            "restored" => Some(DocumentEventKind::Restored),
+            "purged" => Some(DocumentEventKind::Purged),
+            _ => None,
+        }
+    }
+}
+
+/// One entry of a document's timeline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentEvent {
+    pub id: i64,
+    pub document_id: String,
+    pub kind: DocumentEventKind,
+    /// Failure reason, or a note such as where a document was moved from
+    pub message: Option<String>,
+    pub created_at: String,
+}
+
+/// Append an event to a document's timeline, on the connection of the change it records
+pub(crate) fn record_document_event(
+    conn: &Connection,
+    document_id: &str,
+    kind: DocumentEventKind,
+    message: Option<&str>,
+) -> Result<(), String> {
+    conn.execute_cached(
+        "INSERT INTO document_events (document_id, kind, message) VALUES (?1, ?2, ?3)",
+        params![document_id, kind.as_str(), message],
+    )
+    .map_err(|e| format!("Failed to record document event: {}", e))?;
+
+    Ok(())
+}
+
+impl Database {
+    /// Append an event that has no database change of its own, e.g. an upload starting
+    pub fn record_document_event(
+        &self,
+        document_id: &str,
+        kind: DocumentEventKind,
+        message: Option<&str>,
+    ) -> Result<(), String> {
+        let conn = self.writer()?;
+        record_document_event(&conn, document_id, kind, message)
+    }
+
+    /// Get a document's events, oldest first
+    ///
+    /// Trashed documents keep their events, the timeline shows their deletion.
+    pub fn get_document_timeline(&self, document_id: &str) -> Result<Vec<DocumentEvent>, String> {
+        let conn = self.reader()?;
+
+        let mut stmt = conn
+            .prepare_cached(
+                r#"
+                SELECT id, document_id, kind, message, created_at
+                FROM document_events
+                WHERE document_id = ?1
+                ORDER BY id ASC
+                "#,
+            )
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let events = stmt
+            .query_map(params![document_id], |row| {
+                let kind: String = row.get(2)?;
+                Ok(DocumentEvent {
+                    id: row.get(0)?,
+                    document_id: row.get(1)?,
+                    kind: DocumentEventKind::parse(&kind).ok_or_else(|| {
+                        rusqlite::Error::InvalidColumnType(2, "kind".to_string(), Type::Text)
+                    })?,
+                    message: row.get(3)?,
+                    created_at: row.get(4)?,
+                })
+            })
+            .map_err(|e| format!("Failed to query document events: {}", e))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to collect document events: {}", e))?;
+
+        Ok(events)
+    }
+
+    /// Delete events older than `retention_days` or beyond the `max_per_document`
+    /// most recent of a document
+    ///
+    /// Purged documents keep their events, ending with the purge, until they
+    /// expire too.
+    ///
+    /// Returns the number of events deleted.
+    pub fn prune_document_events(
+        &self,
+        retention_days: u32,
+        max_per_document: i64,
+    ) -> Result<usize, String> {
+        let conn = self.writer()?;
+
+        let expired = conn
+            .execute_cached(
+                "DELETE FROM document_events WHERE created_at <= datetime('now', ?1)",
+                params![purge_cutoff(retention_days)],
+            )
+            .map_err(|e| format!("Failed to prune document events: {}", e))?;
+
+        let excess = conn
+            .execute_cached(
+                r#"
+                DELETE FROM document_events
+                WHERE id IN (
+                    SELECT id FROM (
+                        SELECT id, ROW_NUMBER() OVER (PARTITION BY document_id ORDER BY id DESC) AS position
+                        FROM document_events
+                    )
+                    WHERE position > ?1
+                )
+                "#,
+                params![max_per_document],
+            )
+            .map_err(|e| format!("Failed to prune document events: {}", e))?;
+
+        Ok(expired + excess)
+    }
+}
@@ -0,0 +1,95 @@
+//! Online backups, restores and integrity checks of the database file
+
+use rusqlite::backup::Progress;
+use rusqlite::{Connection, DatabaseName, ErrorCode, OpenFlags};
+use std::path::Path;
+
+use super::migrations::run_migrations;
+use super::Database;
+
+/// Messages kept from a failed integrity check
+const MAX_INTEGRITY_ERRORS: usize = 5;
+
+impl Database {
+    /// Copy the database to `path` with the SQLite online backup API
+    ///
+    /// Writers are only blocked while pages are copied. The copy is written
+    /// next to `path` and renamed into place, so a backup is never partial.
+    pub fn backup_to(&self, path: &Path) -> Result<(), String> {
+        let partial = path.with_extension("partial");
+
+        {
+            let conn = self.writer()?;
+            conn.backup(DatabaseName::Main, &partial, None)
+                .map_err(|e| format!("Failed to back up database: {}", e))?;
+        }
+
+        // The copy inherits WAL mode; make it a single self-contained file
+        let copy =
+            Connection::open(&partial).map_err(|e| format!("Failed to open backup: {}", e))?;
+        copy.pragma_update(None, "journal_mode", "DELETE")
+            .map_err(|e| format!("Failed to finish backup: {}", e))?;
+        drop(copy);
+
+        std::fs::rename(&partial, path).map_err(|e| format!("Failed to save backup: {}", e))
+    }
+
+    /// Replace the database's contents with a backup
+    ///
+    /// The backup is checked first, and migrated afterwards when it was taken
+    /// by an older version.
+    pub fn restore_from(&self, path: &Path) -> Result<(), String> {
+        let problems = check_file_integrity(path)?;
+        if !problems.is_empty() {
+            return Err(format!("Backup is damaged: {}", problems.join("; ")));
+        }
+
+        let mut conn = self.writer()?;
+        conn.restore(DatabaseName::Main, path, None::<fn(Progress)>)
+            .map_err(|e| format!("Failed to restore backup: {}", e))?;
+
+        conn.pragma_update(None, "journal_mode", "WAL")
+            .map_err(|e| format!("Failed to enable WAL: {}", e))?;
+        run_migrations(&conn)
+    }
+
+    /// Problems found by `PRAGMA integrity_check`, empty when the database is sound
+    pub fn check_integrity(&self) -> Result<Vec<String>, String> {
+        let conn = self.writer()?;
+        integrity_check(&conn)
+    }
+}
+
+/// Problems found by `PRAGMA integrity_check` in a database file, empty when it is sound
+///
+/// The file is neither created nor migrated. A file that is not a database
+/// counts as damaged; errors that say nothing about the file, like a lock
+/// held by another process, are returned as errors.
+pub fn check_file_integrity(path: &Path) -> Result<Vec<String>, String> {
+    let conn = Connection::open_with_flags(
+        path,
+        OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+    .map_err(|e| format!("Failed to open database: {}", e))?;
+
+    integrity_check(&conn)
+}
+
+fn integrity_check(conn: &Connection) -> Result<Vec<String>, String> {
+    let messages = conn.prepare("PRAGMA integrity_check").and_then(|mut stmt| {
+        stmt.query_map([], |row| row.get::<_, String>(0))?
+            .take(MAX_INTEGRITY_ERRORS)
+            .collect::<Result<Vec<_>, _>>()
+    });
+
+    match messages {
+        Ok(messages) if messages == ["ok"] => Ok(vec![]),
+        Ok(messages) => Ok(messages),
+        Err(rusqlite::Error::SqliteFailure(e, _))
+            if matches!(e.code, ErrorCode::DatabaseCorrupt | ErrorCode::NotADatabase) =>
+        {
+            Ok(vec![e.to_string()])
+        }
+        Err(e) => Err(format!("Failed to check database integrity: {}", e)),
+    }
+}
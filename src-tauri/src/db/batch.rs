@@ -0,0 +1,350 @@
+//! Batch operations on documents: delete, retry, re-upload and tag
+//!
+//! Each batch runs in one transaction and reports per document, so one
+//! missing or busy document doesn't fail the others. Uploads themselves are
+//! left to background sync, which callers notify once per batch.
+
+use rusqlite::{params, OptionalExtension, Transaction};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use super::activity::{record_document_event, DocumentEventKind};
+use super::connection::CachedStatements;
+use super::Database;
+use crate::gemini::types::{CustomMetadata, StringListValue};
+
+/// Custom metadata key holding a document's tags as a string list
+pub const TAGS_METADATA_KEY: &str = "tags";
+
+/// Tags to add to and remove from documents
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagChange {
+    #[serde(default)]
+    pub add: Vec<String>,
+    #[serde(default)]
+    pub remove: Vec<String>,
+}
+
+/// Outcome of a batch operation for one document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchItem {
+    pub id: String,
+    /// Display name, None when the document doesn't exist
+    pub name: Option<String>,
+    pub ok: bool,
+    /// Why the document was skipped, or a note on what was done
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchReport {
+    pub succeeded: usize,
+    pub failed: usize,
+    pub items: Vec<BatchItem>,
+}
+
+/// Per-document outcome: a note on success, or why the document was skipped
+type ItemResult<T> = Result<T, String>;
+
+enum BatchAction<'a> {
+    Delete,
+    Retry,
+    Reupload,
+    Tag(&'a TagChange),
+}
+
+/// Document of a batch, as read in the transaction
+struct BatchDocument {
+    name: String,
+    path: String,
+    status: String,
+    custom_metadata: Option<String>,
+}
+
+impl Database {
+    /// Move documents to the trash
+    pub fn delete_documents(&self, ids: &[String]) -> Result<BatchReport, String> {
+        self.run_batch(ids, BatchAction::Delete)
+    }
+
+    /// Queue failed documents for another upload
+    pub fn retry_documents(&self, ids: &[String]) -> Result<BatchReport, String> {
+        self.run_batch(ids, BatchAction::Retry)
+    }
+
+    /// Queue documents for upload again, e.g. after their file changed
+    ///
+    /// The remote copy is replaced once the new upload has started.
+    pub fn reupload_documents(&self, ids: &[String]) -> Result<BatchReport, String> {
+        self.run_batch(ids, BatchAction::Reupload)
+    }
+
+    /// Add and remove tags, kept in the custom metadata sent with uploads
+    ///
+    /// Remote metadata can't be edited, so uploaded documents whose tags
+    /// change are queued for re-upload.
+    pub fn tag_documents(&self, ids: &[String], change: &TagChange) -> Result<BatchReport, String> {
+        let change = TagChange {
+            add: normalize_tags(&change.add),
+            remove: normalize_tags(&change.remove),
+        };
+        if change.add.is_empty() && change.remove.is_empty() {
+            return Err("No tags to add or remove".to_string());
+        }
+
+        self.run_batch(ids, BatchAction::Tag(&change))
+    }
+
+    fn run_batch(&self, ids: &[String], action: BatchAction) -> Result<BatchReport, String> {
+        let mut conn = self.writer()?;
+        let tx = conn
+            .transaction()
+            .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+
+        let mut items = Vec::with_capacity(ids.len());
+
+        for id in ids {
+            let document = tx
+                .query_row_cached(
+                    r#"
+                    SELECT d.name, d.path, d.status, d.custom_metadata
+                    FROM documents d
+                    JOIN stores s ON s.id = d.store_id
+                    WHERE d.id = ?1 AND d.deleted_at IS NULL AND s.deleted_at IS NULL
+                    "#,
+                    params![id],
+                    |row| {
+                        Ok(BatchDocument {
+                            name: row.get(0)?,
+                            path: row.get(1)?,
+                            status: row.get(2)?,
+                            custom_metadata: row.get(3)?,
+                        })
+                    },
+                )
+                .optional()
+                .map_err(|e| format!("Failed to read document: {}", e))?;
+
+            let Some(document) = document else {
+                items.push(BatchItem {
+                    id: id.clone(),
+                    name: None,
+                    ok: false,
+                    message: Some("Document not found".to_string()),
+                });
+                continue;
+            };
+
+            let item = match apply(&tx, id, &document, &action)? {
+                Ok(message) => BatchItem {
+                    id: id.clone(),
+                    name: Some(document.name),
+                    ok: true,
+                    message,
+                },
+                Err(message) => BatchItem {
+                    id: id.clone(),
+                    name: Some(document.name),
+                    ok: false,
+                    message: Some(message),
+                },
+            };
+            items.push(item);
+        }
+
+        tx.commit()
+            .map_err(|e| format!("Failed to commit batch: {}", e))?;
+
+        let succeeded = items.iter().filter(|item| item.ok).count();
+
+        Ok(BatchReport {
+            succeeded,
+            failed: items.len() - succeeded,
+            items,
+        })
+    }
+}
+
+/// Apply an action to one document
+///
+/// The outer error aborts the batch, the inner one skips the document.
+fn apply(
+    tx: &Transaction,
+    id: &str,
+    document: &BatchDocument,
+    action: &BatchAction,
+) -> Result<ItemResult<Option<String>>, String> {
+    match action {
+        BatchAction::Delete => {
+            tx.execute_cached(
+                r#"
+                UPDATE documents
+                SET deleted_at = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP
+                WHERE id = ?1
+                "#,
+                params![id],
+            )
+            .map_err(|e| format!("Failed to soft delete document: {}", e))?;
+
+            record_document_event(tx, id, DocumentEventKind::Deleted, None)?;
+
+            Ok(Ok(None))
+        }
+        BatchAction::Retry => {
+            if document.status != "failed" {
+                return Ok(Err("Only failed documents can be retried".to_string()));
+            }
+
+            Ok(queue_upload(tx, id, document, "Retried")?.map(|()| None))
+        }
+        BatchAction::Reupload => {
+            match document.status.as_str() {
+                "pending" => return Ok(Err("Already queued for upload".to_string())),
+                "processing" => return Ok(Err(upload_in_progress())),
+                _ => {}
+            }
+
+            Ok(queue_upload(tx, id, document, "Re-uploaded")?.map(|()| None))
+        }
+        BatchAction::Tag(change) => {
+            if document.status == "processing" {
+                return Ok(Err(upload_in_progress()));
+            }
+
+            let mut metadata: Vec<CustomMetadata> = match document.custom_metadata.as_deref() {
+                Some(json) => match serde_json::from_str(json) {
+                    Ok(metadata) => metadata,
+                    Err(e) => return Ok(Err(format!("Invalid custom metadata: {}", e))),
+                },
+                None => Vec::new(),
+            };
+
+            if !apply_tags(&mut metadata, change) {
+                return Ok(Ok(Some("Tags unchanged".to_string())));
+            }
+
+            let json = if metadata.is_empty() {
+                None
+            } else {
+                Some(
+                    serde_json::to_string(&metadata)
+                        .map_err(|e| format!("Failed to serialize custom metadata: {}", e))?,
+                )
+            };
+
+            tx.execute_cached(
+                r#"
+                UPDATE documents
+                SET custom_metadata = ?1, updated_at = CURRENT_TIMESTAMP
+                WHERE id = ?2
+                "#,
+                params![json, id],
+            )
+            .map_err(|e| format!("Failed to update document metadata: {}", e))?;
+
+            // Pending and failed documents send the new tags with their next upload
+            if document.status != "completed" {
+                return Ok(Ok(None));
+            }
+
+            Ok(queue_upload(tx, id, document, "Tags changed")?
+                .map(|()| Some("Queued for re-upload with the new tags".to_string())))
+        }
+    }
+}
+
+/// Reset a document to be uploaded by background sync
+///
+/// `gemini_name` is kept so sync deletes the previous remote copy once the
+/// new upload has started. `reason` is noted in the document's timeline.
+fn queue_upload(
+    tx: &Transaction,
+    id: &str,
+    document: &BatchDocument,
+    reason: &str,
+) -> Result<ItemResult<()>, String> {
+    // Uploads read the local file
+    let size = match std::fs::metadata(Path::new(&document.path)) {
+        Ok(metadata) if metadata.is_file() => metadata.len() as i64,
+        _ => return Ok(Err(format!("File not found: {}", document.path))),
+    };
+
+    tx.execute_cached(
+        r#"
+        UPDATE documents
+        SET status = 'pending', sync_status = 'pending', operation_name = NULL, error_message = NULL,
+            size = ?1, indexed_at = NULL, updated_at = CURRENT_TIMESTAMP
+        WHERE id = ?2
+        "#,
+        params![size, id],
+    )
+    .map_err(|e| format!("Failed to queue document for upload: {}", e))?;
+
+    record_document_event(tx, id, DocumentEventKind::Queued, Some(reason))?;
+
+    Ok(Ok(()))
+}
+
+fn upload_in_progress() -> String {
+    "Upload in progress, try again once it finishes".to_string()
+}
+
+/// Trim tags, dropping blank and repeated ones
+fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags.iter().map(|tag| tag.trim()) {
+        if !tag.is_empty() && !normalized.iter().any(|t| t == tag) {
+            normalized.push(tag.to_string());
+        }
+    }
+    normalized
+}
+
+/// Apply a tag change to custom metadata, returning whether it changed
+fn apply_tags(metadata: &mut Vec<CustomMetadata>, change: &TagChange) -> bool {
+    let position = metadata.iter().position(|m| m.key == TAGS_METADATA_KEY);
+    let current: Vec<String> = position
+        .and_then(|i| metadata[i].string_list_value.as_ref())
+        .map(|list| list.values.clone())
+        .unwrap_or_default();
+
+    let mut tags: Vec<String> = current
+        .iter()
+        .filter(|tag| !change.remove.contains(tag))
+        .cloned()
+        .collect();
+    for tag in &change.add {
+        if !tags.contains(tag) {
+            tags.push(tag.clone());
+        }
+    }
+
+    if tags == current {
+        return false;
+    }
+
+    // Tags were removed or added, so an entry exists or `tags` isn't empty
+    if tags.is_empty() {
+        if let Some(i) = position {
+            metadata.remove(i);
+        }
+        return true;
+    }
+
+    let entry = CustomMetadata {
+        key: TAGS_METADATA_KEY.to_string(),
+        string_value: None,
+        numeric_value: None,
+        string_list_value: Some(StringListValue { values: tags }),
+    };
+
+    match position {
+        Some(i) => metadata[i] = entry,
+        None => metadata.push(entry),
+    }
+
+    true
+}
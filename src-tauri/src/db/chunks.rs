@@ -0,0 +1,272 @@
+//! Database operations for document chunks and their embeddings
+//!
+//! Used by backends that retrieve locally instead of through a hosted file
+//! search. Vectors are stored as little-endian `f32` blobs and searched by
+//! brute-force cosine similarity, which is fast enough for desktop-sized stores.
+//! Each chunk records the embedding model that produced its vector, so vectors
+//! from a previously configured model are never compared with new ones.
+
+use rusqlite::{params, params_from_iter};
+
+use super::connection::CachedStatements;
+use super::Database;
+
+/// A chunk of document text with its embedding
+#[derive(Debug, Clone)]
+pub struct NewChunk {
+    pub content: String,
+    pub embedding: Vec<f32>,
+}
+
+/// A chunk returned by a similarity search
+#[derive(Debug, Clone)]
+pub struct ScoredChunk {
+    pub document_name: String,
+    pub display_name: String,
+    pub content: String,
+    /// Cosine similarity to the query (higher is better)
+    pub score: f32,
+}
+
+fn encode_embedding(embedding: &[f32]) -> Vec<u8> {
+    embedding.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+fn decode_embedding(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect()
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+
+    let (mut dot, mut norm_a, mut norm_b) = (0.0f32, 0.0f32, 0.0f32);
+    for (x, y) in a.iter().zip(b) {
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a.sqrt() * norm_b.sqrt())
+    }
+}
+
+/// `?n, ?n+1, ...` placeholders for an IN clause
+pub(super) fn placeholders(count: usize, start: usize) -> String {
+    (0..count)
+        .map(|i| format!("?{}", i + start))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl Database {
+    /// Replace the chunks of a document
+    pub fn replace_document_chunks(
+        &self,
+        store_name: &str,
+        document_name: &str,
+        display_name: &str,
+        embedding_model: &str,
+        chunks: &[NewChunk],
+    ) -> Result<(), String> {
+        let mut conn = self.writer()?;
+        let tx = conn
+            .transaction()
+            .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+
+        tx.execute_cached(
+            "DELETE FROM document_chunks WHERE document_name = ?1",
+            params![document_name],
+        )
+        .map_err(|e| format!("Failed to clear document chunks: {}", e))?;
+
+        {
+            let mut stmt = tx
+                .prepare_cached(
+                    r#"
+                    INSERT INTO document_chunks (store_name, document_name, display_name, chunk_index, content, embedding, embedding_model)
+                    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                    "#,
+                )
+                .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+            for (index, chunk) in chunks.iter().enumerate() {
+                stmt.execute(params![
+                    store_name,
+                    document_name,
+                    display_name,
+                    index as i64,
+                    chunk.content,
+                    encode_embedding(&chunk.embedding),
+                    embedding_model,
+                ])
+                .map_err(|e| format!("Failed to insert document chunk: {}", e))?;
+            }
+        }
+
+        tx.commit()
+            .map_err(|e| format!("Failed to commit document chunks: {}", e))
+    }
+
+    /// Whether a document has any chunks
+    pub fn has_document_chunks(&self, document_name: &str) -> Result<bool, String> {
+        let conn = self.reader()?;
+
+        conn.query_row_cached(
+            "SELECT EXISTS(SELECT 1 FROM document_chunks WHERE document_name = ?1)",
+            params![document_name],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Failed to check document chunks: {}", e))
+    }
+
+    pub fn delete_document_chunks(&self, document_name: &str) -> Result<(), String> {
+        let conn = self.writer()?;
+
+        conn.execute_cached(
+            "DELETE FROM document_chunks WHERE document_name = ?1",
+            params![document_name],
+        )
+        .map_err(|e| format!("Failed to delete document chunks: {}", e))?;
+
+        Ok(())
+    }
+
+    pub fn delete_store_chunks(&self, store_name: &str) -> Result<(), String> {
+        let conn = self.writer()?;
+
+        conn.execute_cached(
+            "DELETE FROM document_chunks WHERE store_name = ?1",
+            params![store_name],
+        )
+        .map_err(|e| format!("Failed to delete store chunks: {}", e))?;
+
+        Ok(())
+    }
+
+    /// Find the chunks embedded with `embedding_model` most similar to `embedding`
+    /// in the given stores, leaving out trashed documents
+    pub fn search_chunks(
+        &self,
+        store_names: &[String],
+        embedding_model: &str,
+        embedding: &[f32],
+        limit: usize,
+    ) -> Result<Vec<ScoredChunk>, String> {
+        if store_names.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let conn = self.reader()?;
+
+        let sql = format!(
+            r#"
+            SELECT c.document_name, c.display_name, c.content, c.embedding
+            FROM document_chunks c
+            LEFT JOIN documents d ON d.gemini_name = c.document_name
+            WHERE c.embedding_model = ?1 AND c.store_name IN ({})
+            AND d.deleted_at IS NULL
+            "#,
+            placeholders(store_names.len(), 2)
+        );
+
+        let mut params: Vec<&dyn rusqlite::ToSql> = vec![&embedding_model];
+        params.extend(store_names.iter().map(|s| s as &dyn rusqlite::ToSql));
+
+        let mut stmt = conn
+            .prepare_cached(&sql)
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let mut chunks = stmt
+            .query_map(params.as_slice(), |row| {
+                let bytes: Vec<u8> = row.get(3)?;
+                Ok(ScoredChunk {
+                    document_name: row.get(0)?,
+                    display_name: row.get(1)?,
+                    content: row.get(2)?,
+                    score: cosine_similarity(embedding, &decode_embedding(&bytes)),
+                })
+            })
+            .map_err(|e| format!("Failed to query document chunks: {}", e))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to collect document chunks: {}", e))?;
+
+        chunks.sort_by(|a, b| b.score.total_cmp(&a.score));
+        chunks.truncate(limit);
+
+        Ok(chunks)
+    }
+
+    /// Random chunks from the given stores, e.g. for suggesting questions
+    pub fn sample_chunks(
+        &self,
+        store_names: &[String],
+        limit: usize,
+    ) -> Result<Vec<ScoredChunk>, String> {
+        if store_names.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let conn = self.reader()?;
+
+        let sql = format!(
+            r#"
+            SELECT c.document_name, c.display_name, c.content
+            FROM document_chunks c
+            LEFT JOIN documents d ON d.gemini_name = c.document_name
+            WHERE c.store_name IN ({})
+            AND d.deleted_at IS NULL
+            ORDER BY RANDOM()
+            LIMIT ?1
+            "#,
+            placeholders(store_names.len(), 2)
+        );
+
+        let limit = limit as i64;
+        let mut params: Vec<&dyn rusqlite::ToSql> = vec![&limit];
+        params.extend(store_names.iter().map(|s| s as &dyn rusqlite::ToSql));
+
+        let mut stmt = conn
+            .prepare_cached(&sql)
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let chunks = stmt
+            .query_map(params.as_slice(), |row| {
+                Ok(ScoredChunk {
+                    document_name: row.get(0)?,
+                    display_name: row.get(1)?,
+                    content: row.get(2)?,
+                    score: 0.0,
+                })
+            })
+            .map_err(|e| format!("Failed to query document chunks: {}", e))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to collect document chunks: {}", e))?;
+
+        Ok(chunks)
+    }
+
+    /// Whether any of the given stores (by remote name) is flagged local-only
+    pub fn has_local_only_store(&self, store_names: &[String]) -> Result<bool, String> {
+        if store_names.is_empty() {
+            return Ok(false);
+        }
+
+        let conn = self.reader()?;
+
+        let sql = format!(
+            "SELECT EXISTS(SELECT 1 FROM stores WHERE local_only = 1 AND gemini_name IN ({}))",
+            placeholders(store_names.len(), 1)
+        );
+
+        conn.query_row_cached(&sql, params_from_iter(store_names.iter()), |row| row.get(0))
+            .map_err(|e| format!("Failed to check local-only stores: {}", e))
+    }
+}
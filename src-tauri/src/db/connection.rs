@@ -1,42 +1,151 @@
-use rusqlite::Connection;
-use std::path::PathBuf;
-use std::sync::Mutex;
-use tauri::AppHandle;
-use tauri::Manager;
+use rusqlite::{Connection, OpenFlags, Params, Row};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, TryLockError};
+use std::time::Duration;
 
 use super::migrations::run_migrations;
 
+/// Database file name inside the data directory
+pub const DATABASE_FILE: &str = "stores.db";
+
+/// How long a statement waits for another process's write lock
+const BUSY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Read-only connections, so listings, searches and the sync read side by side
+const READERS: usize = 4;
+
+/// Prepared statements kept per connection
+const STATEMENT_CACHE_CAPACITY: usize = 64;
+
+/// Connections to `stores.db`: one writer and a few readers
+///
+/// SQLite allows a single writer at a time, so all writes share one
+/// connection. In WAL mode readers don't wait for it, which keeps large
+/// listings from stalling uploads and the other way round. Blocking calls
+/// from async code should go through [`Database::call`].
 pub struct Database {
-    pub conn: Mutex<Connection>,
+    // Fields drop in order: readers close first so the writer, closing last,
+    // checkpoints the WAL and removes it
+    readers: Vec<Mutex<Connection>>,
+    writer: Mutex<Connection>,
+    next_reader: AtomicUsize,
 }
 
 impl Database {
-    pub fn new(app_handle: &AppHandle) -> Result<Self, String> {
-        let db_path = get_database_path(app_handle)?;
+    /// Open the database in a data directory
+    pub fn new(data_dir: &Path) -> Result<Self, String> {
+        Self::open(&data_dir.join(DATABASE_FILE))
+    }
 
+    /// Open (or create) the database at a path and run migrations
+    pub fn open(db_path: &Path) -> Result<Self, String> {
         // Ensure parent directory exists
         if let Some(parent) = db_path.parent() {
             std::fs::create_dir_all(parent)
                 .map_err(|e| format!("Failed to create database directory: {}", e))?;
         }
 
-        let conn = Connection::open(&db_path)
-            .map_err(|e| format!("Failed to open database: {}", e))?;
+        let writer =
+            Connection::open(db_path).map_err(|e| format!("Failed to open database: {}", e))?;
+        configure(&writer)?;
+
+        // WAL lets readers (the pool's, the CLI, MCP server, backups) run alongside the writer
+        writer
+            .pragma_update(None, "journal_mode", "WAL")
+            .map_err(|e| format!("Failed to enable WAL: {}", e))?;
+        writer
+            .pragma_update(None, "synchronous", "NORMAL")
+            .map_err(|e| format!("Failed to set synchronous mode: {}", e))?;
 
         // Run migrations
-        run_migrations(&conn)?;
+        run_migrations(&writer)?;
+
+        // Readers are opened once the schema is in place
+        let readers = (0..READERS)
+            .map(|_| {
+                let reader = Connection::open_with_flags(
+                    db_path,
+                    OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+                )
+                .map_err(|e| format!("Failed to open database: {}", e))?;
+                configure(&reader)?;
+                Ok(Mutex::new(reader))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
 
         Ok(Self {
-            conn: Mutex::new(conn),
+            readers,
+            writer: Mutex::new(writer),
+            next_reader: AtomicUsize::new(0),
         })
     }
+
+    /// The connection for writes, and reads that must see them in the same transaction
+    pub fn writer(&self) -> Result<MutexGuard<'_, Connection>, String> {
+        self.writer.lock().map_err(|e| e.to_string())
+    }
+
+    /// A read-only connection, an idle one if there is one
+    pub fn reader(&self) -> Result<MutexGuard<'_, Connection>, String> {
+        for reader in &self.readers {
+            match reader.try_lock() {
+                Ok(conn) => return Ok(conn),
+                Err(TryLockError::WouldBlock) => continue,
+                Err(TryLockError::Poisoned(e)) => return Err(e.to_string()),
+            }
+        }
+
+        // All busy, wait for the next one in turn
+        let index = self.next_reader.fetch_add(1, Ordering::Relaxed) % self.readers.len();
+        self.readers[index].lock().map_err(|e| e.to_string())
+    }
+
+    /// Run database work on the blocking thread pool
+    ///
+    /// rusqlite calls block; from async code (commands, sync, polling) they go
+    /// through here so a slow query or a wait for the writer never holds up
+    /// the async executor.
+    pub async fn call<T, F>(self: &Arc<Self>, f: F) -> Result<T, String>
+    where
+        F: FnOnce(&Database) -> Result<T, String> + Send + 'static,
+        T: Send + 'static,
+    {
+        let db = self.clone();
+        tokio::task::spawn_blocking(move || f(&db))
+            .await
+            .map_err(|e| format!("Database task failed: {}", e))?
+    }
+}
+
+fn configure(conn: &Connection) -> Result<(), String> {
+    // The app and the CLI may write at the same time, wait for each other's locks
+    conn.busy_timeout(BUSY_TIMEOUT)
+        .map_err(|e| format!("Failed to set busy timeout: {}", e))?;
+    conn.set_prepared_statement_cache_capacity(STATEMENT_CACHE_CAPACITY);
+    Ok(())
 }
 
-fn get_database_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
-    let app_data_dir = app_handle
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+/// `execute` and `query_row` through the connection's statement cache
+pub(crate) trait CachedStatements {
+    fn execute_cached<P: Params>(&self, sql: &str, params: P) -> rusqlite::Result<usize>;
 
-    Ok(app_data_dir.join("stores.db"))
+    fn query_row_cached<T, P, F>(&self, sql: &str, params: P, f: F) -> rusqlite::Result<T>
+    where
+        P: Params,
+        F: FnOnce(&Row<'_>) -> rusqlite::Result<T>;
+}
+
+impl CachedStatements for Connection {
+    fn execute_cached<P: Params>(&self, sql: &str, params: P) -> rusqlite::Result<usize> {
+        self.prepare_cached(sql)?.execute(params)
+    }
+
+    fn query_row_cached<T, P, F>(&self, sql: &str, params: P, f: F) -> rusqlite::Result<T>
+    where
+        P: Params,
+        F: FnOnce(&Row<'_>) -> rusqlite::Result<T>,
+    {
+        self.prepare_cached(sql)?.query_row(params, f)
+    }
 }
@@ -1,8 +1,15 @@
 //! Database operations for documents
 
-use rusqlite::{params, OptionalExtension, Row};
+use base64::Engine;
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Row};
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
 
+use super::activity::{record_document_event, DocumentEventKind};
+use super::connection::CachedStatements;
+use super::trash::purge_cutoff;
 use super::Database;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,6 +31,97 @@ pub struct Document {
     pub deleted_at: Option<String>,
     pub created_at: String,
     pub updated_at: String,
+    /// Mime type sent to Gemini, differs from `mime_type` when converted locally
+    pub upload_mime_type: Option<String>,
+    /// Gemini custom metadata entries as a JSON array, sent with the upload
+    pub custom_metadata: Option<String>,
+}
+
+/// Default page size of `list_documents`
+pub const DEFAULT_DOCUMENT_PAGE_SIZE: u32 = 100;
+
+/// Largest page `list_documents` returns
+pub const MAX_DOCUMENT_PAGE_SIZE: u32 = 500;
+
+/// Sort order of a document listing, ties broken by id
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DocumentSort {
+    Name,
+    Size,
+    #[default]
+    Created,
+    Status,
+}
+
+impl DocumentSort {
+    /// Sorted expression, matching the indexes of migrations 20 and 22
+    fn expression(self) -> &'static str {
+        match self {
+            DocumentSort::Name => "name COLLATE NOCASE",
+            DocumentSort::Size => "IFNULL(size, 0)",
+            DocumentSort::Created => "created_at",
+            DocumentSort::Status => "IFNULL(status, 'pending')",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "name" => Ok(Self::Name),
+            "size" => Ok(Self::Size),
+            "created" => Ok(Self::Created),
+            "status" => Ok(Self::Status),
+            other => Err(format!("Unknown document sort: {}", other)),
+        }
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            DocumentSort::Name => "name",
+            DocumentSort::Size => "size",
+            DocumentSort::Created => "created",
+            DocumentSort::Status => "IFNULL(status, 'pending')",
+        }
+    }
+
+    /// Value of the sorted expression for a document, kept in cursors
+    fn value(self, document: &Document) -> Value {
+        match self {
+            DocumentSort::Name => json!(document.name),
+            DocumentSort::Size => json!(document.size.unwrap_or(0)),
+            DocumentSort::Created => json!(document.created_at),
+            DocumentSort::Status => json!(document.status),
+        }
+    }
+}
+
+/// Page, sort and filters of a document listing
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DocumentListQuery {
+    pub sort: DocumentSort,
+    pub descending: bool,
+    pub status: Option<String>,
+    pub sync_status: Option<String>,
+    /// Exact mime type, or a prefix ending in `/` such as `text/`
+    pub mime_type: Option<String>,
+    /// Case-insensitive part of the name
+    pub name: Option<String>,
+    /// `next_cursor` of the previous page, None for the first page
+    pub cursor: Option<String>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentPage {
+    pub documents: Vec<Document>,
+    /// Cursor of the next page, None on the last page
+    pub next_cursor: Option<String>,
+    /// Documents matching the filters, across all pages
+    pub total: i64,
+    /// Documents per status, matching every filter but the status
+    pub status_counts: BTreeMap<String, i64>,
 }
 
 #[allow(dead_code)]
@@ -37,18 +135,20 @@ pub struct PendingDocument {
 }
 
 /// Struct for soft-deleted documents with their associated store gemini_name
+///
+/// Both names are None for documents that were never uploaded.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SoftDeletedDocument {
     pub id: String,
-    pub gemini_name: String,
-    pub store_gemini_name: String,
+    pub gemini_name: Option<String>,
+    pub store_gemini_name: Option<String>,
 }
 
 /// Maps a database row to Document struct
 /// Expected columns: id, store_id, gemini_name, operation_name, name, path, content_type, mime_type,
-///                   size, hash, status, sync_status, error_message, deleted_at, created_at, updated_at
-fn map_row_to_document(row: &Row) -> rusqlite::Result<Document> {
+///                   size, hash, status, sync_status, error_message, deleted_at, created_at, updated_at, upload_mime_type, custom_metadata
+pub(super) fn map_row_to_document(row: &Row) -> rusqlite::Result<Document> {
     Ok(Document {
         id: row.get(0)?,
         store_id: row.get(1)?,
@@ -66,10 +166,77 @@ fn map_row_to_document(row: &Row) -> rusqlite::Result<Document> {
         deleted_at: row.get(13)?,
         created_at: row.get(14)?,
         updated_at: row.get(15)?,
+        upload_mime_type: row.get(16)?,
+        custom_metadata: row.get(17)?,
     })
 }
 
+/// A document to queue for upload
+#[derive(Debug, Clone, Default)]
+pub struct NewDocument {
+    pub id: String,
+    pub name: String,
+    pub path: String,
+    pub content_type: Option<String>,
+    pub size: Option<i64>,
+    pub hash: Option<String>,
+    pub custom_metadata: Option<String>,
+}
+
+/// Insert a pending document and its queued event, inside the caller's transaction
+pub(super) fn insert_document(
+    conn: &Connection,
+    store_id: &str,
+    document: &NewDocument,
+) -> Result<(), String> {
+    conn.execute_cached(
+        r#"
+        INSERT INTO documents (id, store_id, name, path, content_type, size, hash, custom_metadata, status)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, 'pending')
+        "#,
+        params![
+            document.id,
+            store_id,
+            document.name,
+            document.path,
+            document.content_type,
+            document.size,
+            document.hash,
+            document.custom_metadata
+        ],
+    )
+    .map_err(|e| format!("Failed to create document: {}", e))?;
+
+    record_document_event(conn, &document.id, DocumentEventKind::Queued, None)
+}
+
 impl Database {
+    /// Queue several documents in one store, all or nothing
+    pub fn create_documents(
+        &self,
+        store_id: &str,
+        documents: &[NewDocument],
+    ) -> Result<Vec<Document>, String> {
+        {
+            let mut conn = self.writer()?;
+            let tx = conn
+                .transaction()
+                .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+
+            for document in documents {
+                insert_document(&tx, store_id, document)?;
+            }
+
+            tx.commit()
+                .map_err(|e| format!("Failed to commit documents: {}", e))?;
+        } // Release lock here
+
+        documents
+            .iter()
+            .map(|document| self.get_document(&document.id))
+            .collect()
+    }
+
     pub fn create_document(
         &self,
         id: &str,
@@ -80,28 +247,27 @@ impl Database {
         size: Option<i64>,
         hash: Option<&str>,
     ) -> Result<Document, String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
-
-        conn.execute(
-            r#"
-            INSERT INTO documents (id, store_id, name, path, content_type, size, hash, status)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 'pending')
-            "#,
-            params![id, store_id, name, path, content_type, size, hash],
-        )
-        .map_err(|e| format!("Failed to create document: {}", e))?;
-
-        drop(conn);
-        self.get_document(id)
+        let document = NewDocument {
+            id: id.to_string(),
+            name: name.to_string(),
+            path: path.to_string(),
+            content_type: content_type.map(str::to_string),
+            size,
+            hash: hash.map(str::to_string),
+            custom_metadata: None,
+        };
+
+        let mut documents = self.create_documents(store_id, &[document])?;
+        Ok(documents.remove(0))
     }
 
     pub fn get_document(&self, id: &str) -> Result<Document, String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let conn = self.reader()?;
 
-        conn.query_row(
+        conn.query_row_cached(
             r#"
             SELECT id, store_id, gemini_name, operation_name, name, path, content_type, mime_type,
-                   size, hash, status, sync_status, error_message, deleted_at, created_at, updated_at
+                   size, hash, status, sync_status, error_message, deleted_at, created_at, updated_at, upload_mime_type, custom_metadata
             FROM documents
             WHERE id = ?1 AND deleted_at IS NULL
             "#,
@@ -112,13 +278,13 @@ impl Database {
     }
 
     pub fn get_documents_by_store(&self, store_id: &str) -> Result<Vec<Document>, String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let conn = self.reader()?;
 
         let mut stmt = conn
-            .prepare(
+            .prepare_cached(
                 r#"
                 SELECT id, store_id, gemini_name, operation_name, name, path, content_type, mime_type,
-                       size, hash, status, sync_status, error_message, deleted_at, created_at, updated_at
+                       size, hash, status, sync_status, error_message, deleted_at, created_at, updated_at, upload_mime_type, custom_metadata
                 FROM documents
                 WHERE store_id = ?1 AND deleted_at IS NULL
                 ORDER BY created_at ASC
@@ -135,24 +301,150 @@ impl Database {
         Ok(documents)
     }
 
+    /// List a page of a store's documents, sorted and filtered
+    pub fn list_documents(
+        &self,
+        store_id: &str,
+        query: &DocumentListQuery,
+    ) -> Result<DocumentPage, String> {
+        let limit = query
+            .limit
+            .unwrap_or(DEFAULT_DOCUMENT_PAGE_SIZE)
+            .clamp(1, MAX_DOCUMENT_PAGE_SIZE);
+        let sort = query.sort.expression();
+        let (order, after) = if query.descending {
+            ("DESC", "<")
+        } else {
+            ("ASC", ">")
+        };
+
+        let (filters, filter_params) = document_filters(store_id, query, true);
+        let (count_filters, count_params) = document_filters(store_id, query, false);
+
+        let mut page_filters = filters.clone();
+        let mut page_params = filter_params.clone();
+        if let Some(cursor) = &query.cursor {
+            let (value, id) = decode_cursor(cursor, query.sort)?;
+            page_filters.push_str(&format!(" AND ({}, id) {} (?, ?)", sort, after));
+            page_params.push(value);
+            page_params.push(SqlValue::Text(id));
+        }
+
+        let conn = self.reader()?;
+
+        // Filters vary with the query, so these statements aren't cached
+        page_params.push(SqlValue::Integer(limit as i64 + 1));
+        let mut documents = conn
+            .prepare(&format!(
+                r#"
+                SELECT id, store_id, gemini_name, operation_name, name, path, content_type, mime_type,
+                       size, hash, status, sync_status, error_message, deleted_at, created_at, updated_at, upload_mime_type, custom_metadata
+                FROM documents
+                WHERE {}
+                ORDER BY {} {}, id {}
+                LIMIT ?
+                "#,
+                page_filters, sort, order, order
+            ))
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?
+            .query_map(params_from_iter(page_params), map_row_to_document)
+            .map_err(|e| format!("Failed to query documents: {}", e))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to collect documents: {}", e))?;
+
+        // The extra row tells whether there's another page
+        let next_cursor = if documents.len() > limit as usize {
+            documents.truncate(limit as usize);
+            documents
+                .last()
+                .map(|last| encode_cursor(query.sort, last))
+        } else {
+            None
+        };
+
+        let total = conn
+            .query_row(
+                &format!("SELECT COUNT(*) FROM documents WHERE {}", filters),
+                params_from_iter(filter_params),
+                |row| row.get(0),
+            )
+            .map_err(|e| format!("Failed to count documents: {}", e))?;
+
+        let status_counts = conn
+            .prepare(&format!(
+                "SELECT IFNULL(status, 'pending'), COUNT(*) FROM documents WHERE {} GROUP BY 1",
+                count_filters
+            ))
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?
+            .query_map(params_from_iter(count_params), |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+            })
+            .map_err(|e| format!("Failed to count documents: {}", e))?
+            .collect::<Result<BTreeMap<_, _>, _>>()
+            .map_err(|e| format!("Failed to collect document counts: {}", e))?;
+
+        Ok(DocumentPage {
+            documents,
+            next_cursor,
+            total,
+            status_counts,
+        })
+    }
+
+    /// Set the custom metadata sent with the document's next upload
+    pub fn update_document_custom_metadata(
+        &self,
+        id: &str,
+        custom_metadata: Option<&str>,
+    ) -> Result<(), String> {
+        let conn = self.writer()?;
+
+        conn.execute_cached(
+            r#"
+            UPDATE documents
+            SET custom_metadata = ?1, updated_at = CURRENT_TIMESTAMP
+            WHERE id = ?2
+            "#,
+            params![custom_metadata, id],
+        )
+        .map_err(|e| format!("Failed to update document metadata: {}", e))?;
+
+        Ok(())
+    }
+
     /// Update document with Gemini operation info after upload initiation
     pub fn update_document_operation(
         &self,
         id: &str,
         operation_name: &str,
+        upload_mime_type: &str,
     ) -> Result<(), String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let mut conn = self.writer()?;
+        let tx = conn
+            .transaction()
+            .map_err(|e| format!("Failed to begin transaction: {}", e))?;
 
-        conn.execute(
+        tx.execute_cached(
             r#"
             UPDATE documents
-            SET operation_name = ?1, status = 'processing', sync_status = 'synced', updated_at = CURRENT_TIMESTAMP
-            WHERE id = ?2
+            SET operation_name = ?1, upload_mime_type = ?2, status = 'processing', sync_status = 'synced',
+                updated_at = CURRENT_TIMESTAMP
+            WHERE id = ?3
             "#,
-            params![operation_name, id],
+            params![operation_name, upload_mime_type, id],
         )
         .map_err(|e| format!("Failed to update document operation: {}", e))?;
 
+        record_document_event(
+            &tx,
+            id,
+            DocumentEventKind::UploadFinished,
+            Some(operation_name),
+        )?;
+
+        tx.commit()
+            .map_err(|e| format!("Failed to commit document operation: {}", e))?;
+
         Ok(())
     }
 
@@ -164,9 +456,12 @@ impl Database {
         mime_type: Option<&str>,
         status: &str,
     ) -> Result<(), String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let mut conn = self.writer()?;
+        let tx = conn
+            .transaction()
+            .map_err(|e| format!("Failed to begin transaction: {}", e))?;
 
-        conn.execute(
+        tx.execute_cached(
             r#"
             UPDATE documents
             SET gemini_name = ?1, mime_type = ?2, status = ?3, operation_name = NULL, updated_at = CURRENT_TIMESTAMP
@@ -176,14 +471,58 @@ impl Database {
         )
         .map_err(|e| format!("Failed to update document from gemini: {}", e))?;
 
+        let kind = if status == "failed" {
+            DocumentEventKind::Failed
+        } else {
+            DocumentEventKind::OperationDone
+        };
+        record_document_event(&tx, id, kind, None)?;
+
+        tx.commit()
+            .map_err(|e| format!("Failed to commit document update: {}", e))?;
+
+        Ok(())
+    }
+
+    /// Mark a document as uploaded and ready, for backends that finish processing during upload
+    pub fn update_document_uploaded(
+        &self,
+        id: &str,
+        gemini_name: &str,
+        mime_type: Option<&str>,
+        upload_mime_type: &str,
+    ) -> Result<(), String> {
+        let mut conn = self.writer()?;
+        let tx = conn
+            .transaction()
+            .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+
+        tx.execute_cached(
+            r#"
+            UPDATE documents
+            SET gemini_name = ?1, mime_type = ?2, upload_mime_type = ?3, status = 'completed',
+                sync_status = 'synced', operation_name = NULL, updated_at = CURRENT_TIMESTAMP
+            WHERE id = ?4
+            "#,
+            params![gemini_name, mime_type, upload_mime_type, id],
+        )
+        .map_err(|e| format!("Failed to update uploaded document: {}", e))?;
+
+        // Processing finished during the upload
+        record_document_event(&tx, id, DocumentEventKind::UploadFinished, None)?;
+        record_document_event(&tx, id, DocumentEventKind::OperationDone, None)?;
+
+        tx.commit()
+            .map_err(|e| format!("Failed to commit uploaded document: {}", e))?;
+
         Ok(())
     }
 
     #[allow(dead_code)]
     pub fn update_document_status(&self, id: &str, status: &str) -> Result<(), String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let conn = self.writer()?;
 
-        conn.execute(
+        conn.execute_cached(
             r#"
             UPDATE documents
             SET status = ?1, updated_at = CURRENT_TIMESTAMP
@@ -203,9 +542,12 @@ impl Database {
         status: &str,
         error_message: &str,
     ) -> Result<(), String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let mut conn = self.writer()?;
+        let tx = conn
+            .transaction()
+            .map_err(|e| format!("Failed to begin transaction: {}", e))?;
 
-        conn.execute(
+        tx.execute_cached(
             r#"
             UPDATE documents
             SET status = ?1, error_message = ?2, updated_at = CURRENT_TIMESTAMP
@@ -215,22 +557,31 @@ impl Database {
         )
         .map_err(|e| format!("Failed to update document error: {}", e))?;
 
+        if status == "failed" {
+            record_document_event(&tx, id, DocumentEventKind::Failed, Some(error_message))?;
+        }
+
+        tx.commit()
+            .map_err(|e| format!("Failed to commit document error: {}", e))?;
+
         Ok(())
     }
 
     /// Get documents with pending operations (for polling)
+    ///
+    /// Trashed documents are included, so their remote name is known by the
+    /// time they are purged.
     pub fn get_documents_with_pending_operations(&self) -> Result<Vec<Document>, String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let conn = self.reader()?;
 
         let mut stmt = conn
-            .prepare(
+            .prepare_cached(
                 r#"
                 SELECT id, store_id, gemini_name, operation_name, name, path, content_type, mime_type,
-                       size, hash, status, sync_status, error_message, deleted_at, created_at, updated_at
+                       size, hash, status, sync_status, error_message, deleted_at, created_at, updated_at, upload_mime_type, custom_metadata
                 FROM documents
                 WHERE operation_name IS NOT NULL
                 AND status NOT IN ('completed', 'failed')
-                AND deleted_at IS NULL
                 "#,
             )
             .map_err(|e| format!("Failed to prepare statement: {}", e))?;
@@ -246,9 +597,12 @@ impl Database {
 
     /// Soft delete a document by setting deleted_at timestamp
     pub fn soft_delete_document(&self, id: &str) -> Result<(), String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let mut conn = self.writer()?;
+        let tx = conn
+            .transaction()
+            .map_err(|e| format!("Failed to begin transaction: {}", e))?;
 
-        conn.execute(
+        let deleted = tx.execute_cached(
             r#"
             UPDATE documents
             SET deleted_at = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP
@@ -258,46 +612,42 @@ impl Database {
         )
         .map_err(|e| format!("Failed to soft delete document: {}", e))?;
 
-        Ok(())
-    }
-
-    /// Soft delete all documents in a store
-    pub fn soft_delete_documents_by_store(&self, store_id: &str) -> Result<(), String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        if deleted > 0 {
+            record_document_event(&tx, id, DocumentEventKind::Deleted, None)?;
+        }
 
-        conn.execute(
-            r#"
-            UPDATE documents
-            SET deleted_at = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP
-            WHERE store_id = ?1 AND deleted_at IS NULL
-            "#,
-            params![store_id],
-        )
-        .map_err(|e| format!("Failed to soft delete documents by store: {}", e))?;
+        tx.commit()
+            .map_err(|e| format!("Failed to commit document deletion: {}", e))?;
 
         Ok(())
     }
 
-    /// Get all soft-deleted documents for background sync processing
-    pub fn get_soft_deleted_documents(&self) -> Result<Vec<SoftDeletedDocument>, String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+    /// Get the documents deleted at least `older_than_days` ago, for background sync to purge
+    ///
+    /// Documents still being processed wait for their operation, which gives
+    /// them the remote name to delete.
+    pub fn get_soft_deleted_documents(
+        &self,
+        older_than_days: u32,
+    ) -> Result<Vec<SoftDeletedDocument>, String> {
+        let conn = self.reader()?;
 
         let mut stmt = conn
-            .prepare(
+            .prepare_cached(
                 r#"
                 SELECT d.id, d.gemini_name, s.gemini_name
                 FROM documents d
                 JOIN stores s ON s.id = d.store_id
                 WHERE d.deleted_at IS NOT NULL
-                AND d.gemini_name IS NOT NULL
-                AND s.gemini_name IS NOT NULL
+                AND d.deleted_at <= datetime('now', ?1)
+                AND NOT (d.operation_name IS NOT NULL AND d.status NOT IN ('completed', 'failed'))
                 ORDER BY d.deleted_at ASC
                 "#,
             )
             .map_err(|e| format!("Failed to prepare statement: {}", e))?;
 
         let documents = stmt
-            .query_map([], |row| {
+            .query_map(params![purge_cutoff(older_than_days)], |row| {
                 Ok(SoftDeletedDocument {
                     id: row.get(0)?,
                     gemini_name: row.get(1)?,
@@ -313,18 +663,33 @@ impl Database {
 
     /// Hard delete a document (permanently remove from database)
     pub fn hard_delete_document(&self, id: &str) -> Result<(), String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let mut conn = self.writer()?;
+        let tx = conn
+            .transaction()
+            .map_err(|e| format!("Failed to begin transaction: {}", e))?;
 
-        conn.execute("DELETE FROM documents WHERE id = ?1", params![id])
+        tx.execute_cached("DELETE FROM documents_fts WHERE document_id = ?1", params![id])
+            .map_err(|e| format!("Failed to remove document index: {}", e))?;
+
+        let purged = tx
+            .execute_cached("DELETE FROM documents WHERE id = ?1", params![id])
             .map_err(|e| format!("Failed to hard delete document: {}", e))?;
 
+        // The timeline outlives the document until its events age out
+        if purged > 0 {
+            record_document_event(&tx, id, DocumentEventKind::Purged, None)?;
+        }
+
+        tx.commit()
+            .map_err(|e| format!("Failed to commit document purge: {}", e))?;
+
         Ok(())
     }
 
     pub fn update_document_sync_status(&self, id: &str, sync_status: &str) -> Result<(), String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let conn = self.writer()?;
 
-        conn.execute(
+        conn.execute_cached(
             r#"
             UPDATE documents
             SET sync_status = ?1, updated_at = CURRENT_TIMESTAMP
@@ -339,12 +704,12 @@ impl Database {
 
     /// Get a document by its display name (file name)
     pub fn get_document_by_display_name(&self, name: &str) -> Result<Option<Document>, String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let conn = self.reader()?;
 
-        conn.query_row(
+        conn.query_row_cached(
             r#"
             SELECT id, store_id, gemini_name, operation_name, name, path, content_type, mime_type,
-                   size, hash, status, sync_status, error_message, deleted_at, created_at, updated_at
+                   size, hash, status, sync_status, error_message, deleted_at, created_at, updated_at, upload_mime_type, custom_metadata
             FROM documents
             WHERE name = ?1 AND deleted_at IS NULL
             "#,
@@ -361,13 +726,13 @@ impl Database {
             return Ok(Vec::new());
         }
 
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let conn = self.reader()?;
 
         let placeholders: Vec<String> = names.iter().enumerate().map(|(i, _)| format!("?{}", i + 1)).collect();
         let query = format!(
             r#"
             SELECT id, store_id, gemini_name, operation_name, name, path, content_type, mime_type,
-                   size, hash, status, sync_status, error_message, deleted_at, created_at, updated_at
+                   size, hash, status, sync_status, error_message, deleted_at, created_at, updated_at, upload_mime_type, custom_metadata
             FROM documents
             WHERE name IN ({}) AND deleted_at IS NULL
             "#,
@@ -375,7 +740,7 @@ impl Database {
         );
 
         let mut stmt = conn
-            .prepare(&query)
+            .prepare_cached(&query)
             .map_err(|e| format!("Failed to prepare statement: {}", e))?;
 
         let params: Vec<&dyn rusqlite::ToSql> = names.iter().map(|s| s as &dyn rusqlite::ToSql).collect();
@@ -390,13 +755,13 @@ impl Database {
     }
 
     pub fn get_pending_upload_documents(&self) -> Result<Vec<Document>, String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let conn = self.reader()?;
 
         let mut stmt = conn
-            .prepare(
+            .prepare_cached(
                 r#"
                 SELECT d.id, d.store_id, d.gemini_name, d.operation_name, d.name, d.path, d.content_type, d.mime_type,
-                       d.size, d.hash, d.status, d.sync_status, d.error_message, d.deleted_at, d.created_at, d.updated_at
+                       d.size, d.hash, d.status, d.sync_status, d.error_message, d.deleted_at, d.created_at, d.updated_at, d.upload_mime_type, d.custom_metadata
                 FROM documents d
                 JOIN stores s ON s.id = d.store_id
                 WHERE d.sync_status = 'pending'
@@ -417,3 +782,79 @@ impl Database {
         Ok(documents)
     }
 }
+
+// ============================================================================
+// Document listing
+// ============================================================================
+
+/// WHERE clause and parameters of a listing's filters
+///
+/// The status filter is left out for the per-status counts.
+fn document_filters(
+    store_id: &str,
+    query: &DocumentListQuery,
+    with_status: bool,
+) -> (String, Vec<SqlValue>) {
+    let mut sql = "store_id = ? AND deleted_at IS NULL".to_string();
+    let mut params = vec![SqlValue::Text(store_id.to_string())];
+
+    if let Some(status) = query.status.as_ref().filter(|_| with_status) {
+        sql.push_str(" AND IFNULL(status, 'pending') = ?");
+        params.push(SqlValue::Text(status.clone()));
+    }
+    if let Some(sync_status) = &query.sync_status {
+        sql.push_str(" AND IFNULL(sync_status, 'pending') = ?");
+        params.push(SqlValue::Text(sync_status.clone()));
+    }
+    if let Some(mime_type) = query.mime_type.as_deref().filter(|m| !m.is_empty()) {
+        if mime_type.ends_with('/') {
+            sql.push_str(r" AND COALESCE(mime_type, content_type) LIKE ? ESCAPE '\'");
+            params.push(SqlValue::Text(format!("{}%", escape_like(mime_type))));
+        } else {
+            sql.push_str(" AND COALESCE(mime_type, content_type) = ?");
+            params.push(SqlValue::Text(mime_type.to_string()));
+        }
+    }
+    if let Some(name) = query.name.as_deref().map(str::trim).filter(|n| !n.is_empty()) {
+        sql.push_str(r" AND name LIKE ? ESCAPE '\'");
+        params.push(SqlValue::Text(format!("%{}%", escape_like(name))));
+    }
+
+    (sql, params)
+}
+
+/// Escape the LIKE wildcards of a user value
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// Cursor after a document: the sort, its sorted value and id, opaque to callers
+fn encode_cursor(sort: DocumentSort, document: &Document) -> String {
+    let cursor = json!([sort.key(), sort.value(document), document.id]);
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(cursor.to_string())
+}
+
+fn decode_cursor(cursor: &str, sort: DocumentSort) -> Result<(SqlValue, String), String> {
+    let invalid = || "Invalid cursor".to_string();
+
+    let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(cursor)
+        .map_err(|_| invalid())?;
+    let parts: (String, Value, String) = serde_json::from_slice(&bytes).map_err(|_| invalid())?;
+
+    // Cursors only continue the listing they come from
+    if parts.0 != sort.key() {
+        return Err(invalid());
+    }
+
+    let value = match parts.1 {
+        Value::String(text) => SqlValue::Text(text),
+        Value::Number(number) => SqlValue::Integer(number.as_i64().ok_or_else(invalid)?),
+        _ => return Err(invalid()),
+    };
+
+    Ok((value, parts.2))
+}
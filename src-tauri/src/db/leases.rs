@@ -0,0 +1,50 @@
+//! Database operations for leases shared between processes
+//!
+//! A lease names a job that only one process may run at a time, such as
+//! uploading pending documents. The holder renews it while working; a lease
+//! that isn't renewed expires, so a crashed process never blocks the others.
+
+use rusqlite::params;
+
+use super::connection::CachedStatements;
+use super::Database;
+
+impl Database {
+    /// Take or renew a lease, returning whether `owner` now holds it
+    pub fn try_acquire_lease(
+        &self,
+        name: &str,
+        owner: &str,
+        ttl_secs: i64,
+    ) -> Result<bool, String> {
+        let conn = self.writer()?;
+
+        let changed = conn
+            .execute(
+                r#"
+                INSERT INTO sync_leases (name, owner, expires_at)
+                VALUES (?1, ?2, unixepoch() + ?3)
+                ON CONFLICT(name) DO UPDATE
+                SET owner = excluded.owner, expires_at = excluded.expires_at
+                WHERE sync_leases.owner = excluded.owner OR sync_leases.expires_at <= unixepoch()
+                "#,
+                params![name, owner, ttl_secs],
+            )
+            .map_err(|e| format!("Failed to acquire lease: {}", e))?;
+
+        Ok(changed > 0)
+    }
+
+    /// Give up a lease held by `owner`
+    pub fn release_lease(&self, name: &str, owner: &str) -> Result<(), String> {
+        let conn = self.writer()?;
+
+        conn.execute_cached(
+            "DELETE FROM sync_leases WHERE name = ?1 AND owner = ?2",
+            params![name, owner],
+        )
+        .map_err(|e| format!("Failed to release lease: {}", e))?;
+
+        Ok(())
+    }
+}
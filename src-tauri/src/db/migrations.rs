@@ -1,47 +1,52 @@
 //! Database migrations for the application
 //!
-//! This module handles database schema migrations.
-//! For existing databases, we add missing columns before creating indexes.
+//! Migrations are numbered and named, and each one runs in its own transaction
+//! together with its record in `schema_migrations`. The record keeps a SHA-256
+//! checksum of the migration's SQL, so a migration edited after it shipped is
+//! reported instead of leaving databases with diverging schemas: change the
+//! schema by appending a migration, never by editing one.
+//!
+//! Every migration has a down migration, run by `rollback_migrations` (for
+//! example before going back to an older release, see `mizly-cli migrations`).
+//!
+//! Databases from before versioned migrations recorded only version numbers,
+//! and got new columns from an ad hoc upgrade step. They are adopted once: the
+//! columns they may lack are added, then their records get names and checksums.
 
 use rusqlite::Connection;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
 
-/// Check if a column exists in a table
-fn column_exists(conn: &Connection, table: &str, column: &str) -> bool {
-    let sql = format!("PRAGMA table_info({})", table);
-    let mut stmt = match conn.prepare(&sql) {
-        Ok(s) => s,
-        Err(_) => return false,
-    };
-
-    let result = stmt.query_map([], |row| {
-        let name: String = row.get(1)?;
-        Ok(name)
-    });
+/// A schema change and how to undo it
+pub struct Migration {
+    pub version: u32,
+    pub name: &'static str,
+    pub up: &'static str,
+    pub down: &'static str,
+}
 
-    match result {
-        Ok(rows) => rows.filter_map(|r| r.ok()).any(|name| name == column),
-        Err(_) => false,
+impl Migration {
+    /// SHA-256 of the up migration, hex encoded
+    pub fn checksum(&self) -> String {
+        format!("{:x}", Sha256::digest(self.up.as_bytes()))
     }
 }
 
-/// Add a column if it doesn't exist
-fn add_column_if_not_exists(
-    conn: &Connection,
-    table: &str,
-    column: &str,
-    definition: &str,
-) -> Result<(), String> {
-    if !column_exists(conn, table, column) {
-        let sql = format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition);
-        conn.execute(&sql, [])
-            .map_err(|e| format!("Failed to add column {} to {}: {}", column, table, e))?;
-    }
-    Ok(())
+/// A migration recorded in `schema_migrations`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppliedMigration {
+    pub version: u32,
+    pub name: String,
+    pub checksum: String,
+    pub applied_at: String,
 }
 
-const MIGRATIONS: &[&str] = &[
-    // Migration 1: Create stores table
-    r#"
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "create_stores",
+        up: r#"
     CREATE TABLE IF NOT EXISTS stores (
         id TEXT PRIMARY KEY,
         gemini_name TEXT UNIQUE,
@@ -54,13 +59,21 @@ const MIGRATIONS: &[&str] = &[
         pending_documents_count INTEGER DEFAULT 0,
         failed_documents_count INTEGER DEFAULT 0,
         size_bytes INTEGER DEFAULT 0,
+        include_patterns TEXT,
+        exclude_patterns TEXT,
+        backend TEXT DEFAULT 'gemini',
+        local_only INTEGER DEFAULT 0,
         deleted_at DATETIME DEFAULT NULL,
         created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
         updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
     );
     "#,
-    // Migration 2: Create documents table
-    r#"
+        down: "DROP TABLE stores;",
+    },
+    Migration {
+        version: 2,
+        name: "create_documents",
+        up: r#"
     CREATE TABLE IF NOT EXISTS documents (
         id TEXT PRIMARY KEY,
         store_id TEXT NOT NULL,
@@ -75,109 +88,729 @@ const MIGRATIONS: &[&str] = &[
         status TEXT DEFAULT 'pending',
         sync_status TEXT DEFAULT 'pending',
         error_message TEXT,
+        indexed_at DATETIME DEFAULT NULL,
+        upload_mime_type TEXT,
+        custom_metadata TEXT,
         deleted_at DATETIME DEFAULT NULL,
         created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
         updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
         FOREIGN KEY (store_id) REFERENCES stores(id) ON DELETE CASCADE
     );
     "#,
-    // Migration 3: Create indexes for documents
-    r#"
+        down: "DROP TABLE documents;",
+    },
+    Migration {
+        version: 3,
+        name: "index_documents_status",
+        up: r#"
     CREATE INDEX IF NOT EXISTS idx_documents_status ON documents(status);
     "#,
-    r#"
+        down: "DROP INDEX idx_documents_status;",
+    },
+    Migration {
+        version: 4,
+        name: "index_documents_store",
+        up: r#"
     CREATE INDEX IF NOT EXISTS idx_documents_store ON documents(store_id);
     "#,
-    // Migration 5: Create index for soft delete queries
-    r#"
+        down: "DROP INDEX idx_documents_store;",
+    },
+    // Indexes for soft delete queries
+    Migration {
+        version: 5,
+        name: "index_documents_deleted",
+        up: r#"
     CREATE INDEX IF NOT EXISTS idx_documents_deleted ON documents(deleted_at);
     "#,
-    r#"
+        down: "DROP INDEX idx_documents_deleted;",
+    },
+    Migration {
+        version: 6,
+        name: "index_stores_deleted",
+        up: r#"
     CREATE INDEX IF NOT EXISTS idx_stores_deleted ON stores(deleted_at);
     "#,
-    // Migration 7: Create index for operation polling (column added in ensure_schema)
-    r#"
+        down: "DROP INDEX idx_stores_deleted;",
+    },
+    // Index for operation polling
+    Migration {
+        version: 7,
+        name: "index_documents_operation",
+        up: r#"
     CREATE INDEX IF NOT EXISTS idx_documents_operation ON documents(operation_name);
     "#,
-    // Migration 8: Create index for gemini_name lookups
-    r#"
+        down: "DROP INDEX idx_documents_operation;",
+    },
+    // Indexes for gemini_name lookups
+    Migration {
+        version: 8,
+        name: "index_stores_gemini_name",
+        up: r#"
     CREATE INDEX IF NOT EXISTS idx_stores_gemini_name ON stores(gemini_name);
     "#,
-    r#"
+        down: "DROP INDEX idx_stores_gemini_name;",
+    },
+    Migration {
+        version: 9,
+        name: "index_documents_gemini_name",
+        up: r#"
     CREATE INDEX IF NOT EXISTS idx_documents_gemini_name ON documents(gemini_name);
     "#,
+        down: "DROP INDEX idx_documents_gemini_name;",
+    },
+    // Full-text index for offline keyword search
+    // The trigram tokenizer also matches CJK text, which has no word separators
+    Migration {
+        version: 10,
+        name: "create_documents_fts",
+        up: r#"
+    CREATE VIRTUAL TABLE IF NOT EXISTS documents_fts USING fts5(
+        document_id UNINDEXED,
+        store_id UNINDEXED,
+        name,
+        content,
+        tokenize = 'trigram'
+    );
+    "#,
+        down: "DROP TABLE documents_fts;",
+    },
+    // Index for documents waiting to be indexed
+    Migration {
+        version: 11,
+        name: "index_documents_indexed",
+        up: r#"
+    CREATE INDEX IF NOT EXISTS idx_documents_indexed ON documents(indexed_at);
+    "#,
+        down: "DROP INDEX idx_documents_indexed;",
+    },
+    // Chunk table for backends with local retrieval
+    Migration {
+        version: 12,
+        name: "create_document_chunks",
+        up: r#"
+    CREATE TABLE IF NOT EXISTS document_chunks (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        store_name TEXT NOT NULL,
+        document_name TEXT NOT NULL,
+        display_name TEXT NOT NULL,
+        chunk_index INTEGER NOT NULL,
+        content TEXT NOT NULL,
+        embedding BLOB NOT NULL,
+        embedding_model TEXT
+    );
+    "#,
+        down: "DROP TABLE document_chunks;",
+    },
+    Migration {
+        version: 13,
+        name: "index_document_chunks_store",
+        up: r#"
+    CREATE INDEX IF NOT EXISTS idx_document_chunks_store ON document_chunks(store_name);
+    "#,
+        down: "DROP INDEX idx_document_chunks_store;",
+    },
+    Migration {
+        version: 14,
+        name: "index_document_chunks_document",
+        up: r#"
+    CREATE INDEX IF NOT EXISTS idx_document_chunks_document ON document_chunks(document_name);
+    "#,
+        down: "DROP INDEX idx_document_chunks_document;",
+    },
+    // Lease table so only one process (app or CLI) syncs at a time
+    Migration {
+        version: 15,
+        name: "create_sync_leases",
+        up: r#"
+    CREATE TABLE IF NOT EXISTS sync_leases (
+        name TEXT PRIMARY KEY,
+        owner TEXT NOT NULL,
+        expires_at INTEGER NOT NULL
+    );
+    "#,
+        down: "DROP TABLE sync_leases;",
+    },
+    // Restore the UNIQUE constraint on gemini_name, lost by databases that got
+    // the column from ALTER TABLE. Duplicates keep the oldest row; the others
+    // are queued to be created again.
+    Migration {
+        version: 16,
+        name: "rebuild_stores_unique_gemini_name",
+        up: r#"
+    UPDATE stores SET gemini_name = NULL, sync_status = 'pending'
+    WHERE gemini_name IS NOT NULL
+        AND rowid NOT IN (
+            SELECT MIN(rowid) FROM stores WHERE gemini_name IS NOT NULL GROUP BY gemini_name
+        );
+
+    CREATE TABLE stores_new (
+        id TEXT PRIMARY KEY,
+        gemini_name TEXT UNIQUE,
+        title TEXT NOT NULL,
+        directory_path TEXT,
+        sync_status TEXT DEFAULT 'pending',
+        create_time TEXT,
+        update_time TEXT,
+        active_documents_count INTEGER DEFAULT 0,
+        pending_documents_count INTEGER DEFAULT 0,
+        failed_documents_count INTEGER DEFAULT 0,
+        size_bytes INTEGER DEFAULT 0,
+        include_patterns TEXT,
+        exclude_patterns TEXT,
+        backend TEXT DEFAULT 'gemini',
+        local_only INTEGER DEFAULT 0,
+        deleted_at DATETIME DEFAULT NULL,
+        created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+        updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+    );
+
+    INSERT INTO stores_new (
+        id, gemini_name, title, directory_path, sync_status, create_time, update_time,
+        active_documents_count, pending_documents_count, failed_documents_count, size_bytes,
+        include_patterns, exclude_patterns, backend, local_only, deleted_at, created_at, updated_at
+    )
+    SELECT
+        id, gemini_name, title, directory_path, sync_status, create_time, update_time,
+        active_documents_count, pending_documents_count, failed_documents_count, size_bytes,
+        include_patterns, exclude_patterns, backend, local_only, deleted_at, created_at, updated_at
+    FROM stores;
+
+    DROP TABLE stores;
+    ALTER TABLE stores_new RENAME TO stores;
+
+    CREATE INDEX idx_stores_deleted ON stores(deleted_at);
+    CREATE INDEX idx_stores_gemini_name ON stores(gemini_name);
+    "#,
+        down: r#"
+    CREATE TABLE stores_old (
+        id TEXT PRIMARY KEY,
+        gemini_name TEXT,
+        title TEXT NOT NULL,
+        directory_path TEXT,
+        sync_status TEXT DEFAULT 'pending',
+        create_time TEXT,
+        update_time TEXT,
+        active_documents_count INTEGER DEFAULT 0,
+        pending_documents_count INTEGER DEFAULT 0,
+        failed_documents_count INTEGER DEFAULT 0,
+        size_bytes INTEGER DEFAULT 0,
+        include_patterns TEXT,
+        exclude_patterns TEXT,
+        backend TEXT DEFAULT 'gemini',
+        local_only INTEGER DEFAULT 0,
+        deleted_at DATETIME DEFAULT NULL,
+        created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+        updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+    );
+
+    INSERT INTO stores_old SELECT * FROM stores;
+
+    DROP TABLE stores;
+    ALTER TABLE stores_old RENAME TO stores;
+
+    CREATE INDEX idx_stores_deleted ON stores(deleted_at);
+    CREATE INDEX idx_stores_gemini_name ON stores(gemini_name);
+    "#,
+    },
+    // Same for documents. Documents left without a store by databases that
+    // ran without foreign keys are dropped, they can't be listed or synced.
+    Migration {
+        version: 17,
+        name: "rebuild_documents_unique_gemini_name",
+        up: r#"
+    UPDATE documents SET gemini_name = NULL, status = 'pending', sync_status = 'pending'
+    WHERE gemini_name IS NOT NULL
+        AND rowid NOT IN (
+            SELECT MIN(rowid) FROM documents WHERE gemini_name IS NOT NULL GROUP BY gemini_name
+        );
+
+    CREATE TABLE documents_new (
+        id TEXT PRIMARY KEY,
+        store_id TEXT NOT NULL,
+        gemini_name TEXT UNIQUE,
+        operation_name TEXT,
+        name TEXT NOT NULL,
+        path TEXT NOT NULL,
+        content_type TEXT,
+        mime_type TEXT,
+        size INTEGER,
+        hash TEXT,
+        status TEXT DEFAULT 'pending',
+        sync_status TEXT DEFAULT 'pending',
+        error_message TEXT,
+        indexed_at DATETIME DEFAULT NULL,
+        upload_mime_type TEXT,
+        custom_metadata TEXT,
+        deleted_at DATETIME DEFAULT NULL,
+        created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+        updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+        FOREIGN KEY (store_id) REFERENCES stores(id) ON DELETE CASCADE
+    );
+
+    INSERT INTO documents_new (
+        id, store_id, gemini_name, operation_name, name, path, content_type, mime_type,
+        size, hash, status, sync_status, error_message, indexed_at, upload_mime_type,
+        custom_metadata, deleted_at, created_at, updated_at
+    )
+    SELECT
+        id, store_id, gemini_name, operation_name, name, path, content_type, mime_type,
+        size, hash, status, sync_status, error_message, indexed_at, upload_mime_type,
+        custom_metadata, deleted_at, created_at, updated_at
+    FROM documents
+    WHERE store_id IN (SELECT id FROM stores);
+
+    DROP TABLE documents;
+    ALTER TABLE documents_new RENAME TO documents;
+
+    CREATE INDEX idx_documents_status ON documents(status);
+    CREATE INDEX idx_documents_store ON documents(store_id);
+    CREATE INDEX idx_documents_deleted ON documents(deleted_at);
+    CREATE INDEX idx_documents_operation ON documents(operation_name);
+    CREATE INDEX idx_documents_gemini_name ON documents(gemini_name);
+    CREATE INDEX idx_documents_indexed ON documents(indexed_at);
+    "#,
+        down: r#"
+    CREATE TABLE documents_old (
+        id TEXT PRIMARY KEY,
+        store_id TEXT NOT NULL,
+        gemini_name TEXT,
+        operation_name TEXT,
+        name TEXT NOT NULL,
+        path TEXT NOT NULL,
+        content_type TEXT,
+        mime_type TEXT,
+        size INTEGER,
+        hash TEXT,
+        status TEXT DEFAULT 'pending',
+        sync_status TEXT DEFAULT 'pending',
+        error_message TEXT,
+        indexed_at DATETIME DEFAULT NULL,
+        upload_mime_type TEXT,
+        custom_metadata TEXT,
+        deleted_at DATETIME DEFAULT NULL,
+        created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+        updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+        FOREIGN KEY (store_id) REFERENCES stores(id) ON DELETE CASCADE
+    );
+
+    INSERT INTO documents_old SELECT * FROM documents;
+
+    DROP TABLE documents;
+    ALTER TABLE documents_old RENAME TO documents;
+
+    CREATE INDEX idx_documents_status ON documents(status);
+    CREATE INDEX idx_documents_store ON documents(store_id);
+    CREATE INDEX idx_documents_deleted ON documents(deleted_at);
+    CREATE INDEX idx_documents_operation ON documents(operation_name);
+    CREATE INDEX idx_documents_gemini_name ON documents(gemini_name);
+    CREATE INDEX idx_documents_indexed ON documents(indexed_at);
+    "#,
+    },
+    // Description, color and icon a store is shown with in the app
+    Migration {
+        version: 18,
+        name: "add_stores_description_color_icon",
+        up: r#"
+    ALTER TABLE stores ADD COLUMN description TEXT;
+    ALTER TABLE stores ADD COLUMN color TEXT;
+    ALTER TABLE stores ADD COLUMN icon TEXT;
+    "#,
+        down: r#"
+    ALTER TABLE stores DROP COLUMN icon;
+    ALTER TABLE stores DROP COLUMN color;
+    ALTER TABLE stores DROP COLUMN description;
+    "#,
+    },
+    // Source of a document moved from another store, deleted once this copy is uploaded
+    Migration {
+        version: 19,
+        name: "add_documents_moved_from",
+        up: r#"
+    ALTER TABLE documents ADD COLUMN moved_from TEXT;
+    CREATE INDEX idx_documents_moved_from ON documents(moved_from);
+    "#,
+        down: r#"
+    DROP INDEX idx_documents_moved_from;
+    ALTER TABLE documents DROP COLUMN moved_from;
+    "#,
+    },
+    // Sort orders of the paginated document list, with the id breaking ties
+    Migration {
+        version: 20,
+        name: "index_documents_list_sorts",
+        up: r#"
+    CREATE INDEX idx_documents_store_name ON documents(store_id, name COLLATE NOCASE, id);
+    CREATE INDEX idx_documents_store_size ON documents(store_id, IFNULL(size, 0), id);
+    CREATE INDEX idx_documents_store_created ON documents(store_id, created_at, id);
+    CREATE INDEX idx_documents_store_status ON documents(store_id, status, id);
+    "#,
+        down: r#"
+    DROP INDEX idx_documents_store_status;
+    DROP INDEX idx_documents_store_created;
+    DROP INDEX idx_documents_store_size;
+    DROP INDEX idx_documents_store_name;
+    "#,
+    },
+    // Append-only activity log of each document, kept after the document is trashed
+    Migration {
+        version: 21,
+        name: "create_document_events",
+        up: r#"
+    CREATE TABLE document_events (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        document_id TEXT NOT NULL,
+        kind TEXT NOT NULL,
+        message TEXT,
+        created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+    );
+    CREATE INDEX idx_document_events_document ON document_events(document_id, id);
+    CREATE INDEX idx_document_events_created ON document_events(created_at);
+    "#,
+        down: r#"
+    DROP TABLE document_events;
+    "#,
+    },
+    // Documents without a status list as pending, so sort them as such
+    Migration {
+        version: 22,
+        name: "index_documents_list_status_sort",
+        up: r#"
+    DROP INDEX idx_documents_store_status;
+    CREATE INDEX idx_documents_store_status ON documents(store_id, IFNULL(status, 'pending'), id);
+    "#,
+        down: r#"
+    DROP INDEX idx_documents_store_status;
+    CREATE INDEX idx_documents_store_status ON documents(store_id, status, id);
+    "#,
+    },
 ];
 
-/// Ensure all required columns exist (for upgrading old databases)
-/// Note: SQLite doesn't allow adding UNIQUE columns via ALTER TABLE,
-/// so we add them without UNIQUE constraint for existing databases
-fn ensure_schema(conn: &Connection) -> Result<(), String> {
-    // Add missing columns to stores table
-    // Note: gemini_name is UNIQUE in new schema, but we can't add UNIQUE via ALTER TABLE
+// ============================================================================
+// Legacy databases
+// ============================================================================
+
+/// Check if a column exists in a table
+fn column_exists(conn: &Connection, table: &str, column: &str) -> bool {
+    let sql = format!("PRAGMA table_info({})", table);
+    let mut stmt = match conn.prepare(&sql) {
+        Ok(s) => s,
+        Err(_) => return false,
+    };
+
+    let result = stmt.query_map([], |row| {
+        let name: String = row.get(1)?;
+        Ok(name)
+    });
+
+    match result {
+        Ok(rows) => rows.filter_map(|r| r.ok()).any(|name| name == column),
+        Err(_) => false,
+    }
+}
+
+/// Check if a table exists
+fn table_exists(conn: &Connection, table: &str) -> bool {
+    conn.query_row(
+        "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1",
+        [table],
+        |_| Ok(()),
+    )
+    .is_ok()
+}
+
+/// Add a column if it doesn't exist
+/// Tables that don't exist yet are skipped, they are created by the migrations
+fn add_column_if_not_exists(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<(), String> {
+    if table_exists(conn, table) && !column_exists(conn, table, column) {
+        let sql = format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition);
+        conn.execute(&sql, [])
+            .map_err(|e| format!("Failed to add column {} to {}: {}", column, table, e))?;
+    }
+    Ok(())
+}
+
+/// Whether the database was created before migrations had names and checksums
+fn is_legacy(conn: &Connection) -> bool {
+    match table_exists(conn, "schema_migrations") {
+        true => !column_exists(conn, "schema_migrations", "checksum"),
+        false => table_exists(conn, "stores"),
+    }
+}
+
+/// Add the columns legacy databases may lack
+///
+/// Their tables were created by older versions of migrations 1 and 2 and
+/// extended in place, so the columns those migrations have since gained are
+/// added here. This list is frozen: new columns come with new migrations.
+/// SQLite can't add UNIQUE columns, migrations 16 and 17 restore the constraint.
+fn adopt_legacy_schema(conn: &Connection) -> Result<(), String> {
     add_column_if_not_exists(conn, "stores", "gemini_name", "TEXT")?;
     add_column_if_not_exists(conn, "stores", "sync_status", "TEXT DEFAULT 'pending'")?;
     add_column_if_not_exists(conn, "stores", "create_time", "TEXT")?;
     add_column_if_not_exists(conn, "stores", "update_time", "TEXT")?;
-    add_column_if_not_exists(conn, "stores", "active_documents_count", "INTEGER DEFAULT 0")?;
-    add_column_if_not_exists(conn, "stores", "pending_documents_count", "INTEGER DEFAULT 0")?;
-    add_column_if_not_exists(conn, "stores", "failed_documents_count", "INTEGER DEFAULT 0")?;
+    add_column_if_not_exists(
+        conn,
+        "stores",
+        "active_documents_count",
+        "INTEGER DEFAULT 0",
+    )?;
+    add_column_if_not_exists(
+        conn,
+        "stores",
+        "pending_documents_count",
+        "INTEGER DEFAULT 0",
+    )?;
+    add_column_if_not_exists(
+        conn,
+        "stores",
+        "failed_documents_count",
+        "INTEGER DEFAULT 0",
+    )?;
     add_column_if_not_exists(conn, "stores", "size_bytes", "INTEGER DEFAULT 0")?;
+    add_column_if_not_exists(conn, "stores", "include_patterns", "TEXT")?;
+    add_column_if_not_exists(conn, "stores", "exclude_patterns", "TEXT")?;
+    add_column_if_not_exists(conn, "stores", "backend", "TEXT DEFAULT 'gemini'")?;
+    add_column_if_not_exists(conn, "stores", "local_only", "INTEGER DEFAULT 0")?;
     add_column_if_not_exists(conn, "stores", "deleted_at", "DATETIME DEFAULT NULL")?;
 
-    // Add missing columns to documents table
     add_column_if_not_exists(conn, "documents", "gemini_name", "TEXT")?;
     add_column_if_not_exists(conn, "documents", "operation_name", "TEXT")?;
     add_column_if_not_exists(conn, "documents", "mime_type", "TEXT")?;
     add_column_if_not_exists(conn, "documents", "sync_status", "TEXT DEFAULT 'pending'")?;
     add_column_if_not_exists(conn, "documents", "error_message", "TEXT")?;
+    add_column_if_not_exists(conn, "documents", "indexed_at", "DATETIME DEFAULT NULL")?;
+    add_column_if_not_exists(conn, "documents", "upload_mime_type", "TEXT")?;
+    add_column_if_not_exists(conn, "documents", "custom_metadata", "TEXT")?;
     add_column_if_not_exists(conn, "documents", "deleted_at", "DATETIME DEFAULT NULL")?;
 
+    add_column_if_not_exists(conn, "document_chunks", "embedding_model", "TEXT")?;
+
+    add_column_if_not_exists(conn, "schema_migrations", "name", "TEXT")?;
+    add_column_if_not_exists(conn, "schema_migrations", "checksum", "TEXT")?;
+
     Ok(())
 }
 
+// ============================================================================
+// Running migrations
+// ============================================================================
+
+/// Migrations recorded in the database, oldest first
+pub fn applied_migrations(conn: &Connection) -> Result<Vec<AppliedMigration>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT version, COALESCE(name, ''), COALESCE(checksum, ''), COALESCE(applied_at, '')
+             FROM schema_migrations ORDER BY version",
+        )
+        .map_err(|e| format!("Failed to read migrations: {}", e))?;
+
+    let migrations = stmt
+        .query_map([], |row| {
+            Ok(AppliedMigration {
+                version: row.get(0)?,
+                name: row.get(1)?,
+                checksum: row.get(2)?,
+                applied_at: row.get(3)?,
+            })
+        })
+        .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| format!("Failed to read migrations: {}", e))?;
+
+    Ok(migrations)
+}
+
+/// Bring the database up to date with `MIGRATIONS`
 pub fn run_migrations(conn: &Connection) -> Result<(), String> {
-    // Enable foreign key support
-    conn.execute("PRAGMA foreign_keys = ON;", [])
-        .map_err(|e| format!("Failed to enable foreign keys: {}", e))?;
+    migrate(conn, MIGRATIONS)
+}
 
-    // Create migrations table if not exists
-    conn.execute(
-        r#"
-        CREATE TABLE IF NOT EXISTS schema_migrations (
-            version INTEGER PRIMARY KEY,
-            applied_at DATETIME DEFAULT CURRENT_TIMESTAMP
-        );
-        "#,
-        [],
-    )
-    .map_err(|e| format!("Failed to create migrations table: {}", e))?;
+/// Undo the migrations newer than `version`, newest first
+pub fn rollback_migrations(
+    conn: &Connection,
+    version: u32,
+) -> Result<Vec<AppliedMigration>, String> {
+    rollback(conn, MIGRATIONS, version)
+}
 
-    // Ensure schema has all required columns (for upgrading old databases)
-    ensure_schema(conn)?;
+/// Apply the migrations missing from the database, in order
+///
+/// Foreign keys are off while migrating so tables can be rebuilt; each
+/// migration is checked with `PRAGMA foreign_key_check` before it commits.
+pub fn migrate(conn: &Connection, migrations: &[Migration]) -> Result<(), String> {
+    with_foreign_keys_off(conn, || {
+        let legacy = is_legacy(conn);
 
-    // Get current version
-    let current_version: i32 = conn
-        .query_row(
-            "SELECT COALESCE(MAX(version), 0) FROM schema_migrations",
+        conn.execute(
+            r#"
+            CREATE TABLE IF NOT EXISTS schema_migrations (
+                version INTEGER PRIMARY KEY,
+                name TEXT,
+                checksum TEXT,
+                applied_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            );
+            "#,
             [],
-            |row| row.get(0),
         )
-        .unwrap_or(0);
+        .map_err(|e| format!("Failed to create migrations table: {}", e))?;
+
+        if legacy {
+            adopt_legacy_schema(conn)?;
+        }
 
-    // Run pending migrations
-    for (index, migration) in MIGRATIONS.iter().enumerate() {
-        let version = (index + 1) as i32;
-        if version > current_version {
-            conn.execute(migration, [])
-                .map_err(|e| format!("Failed to run migration {}: {}", version, e))?;
+        let applied = verify_applied(conn, migrations)?;
+
+        for migration in migrations {
+            if applied.contains(&migration.version) {
+                continue;
+            }
+
+            let tx = conn
+                .unchecked_transaction()
+                .map_err(|e| format!("Failed to start migration {}: {}", migration.version, e))?;
+
+            tx.execute_batch(migration.up).map_err(|e| {
+                format!(
+                    "Failed to run migration {} ({}): {}",
+                    migration.version, migration.name, e
+                )
+            })?;
+            check_foreign_keys(&tx, migration)?;
+
+            tx.execute(
+                "INSERT INTO schema_migrations (version, name, checksum) VALUES (?1, ?2, ?3)",
+                rusqlite::params![migration.version, migration.name, migration.checksum()],
+            )
+            .map_err(|e| format!("Failed to record migration {}: {}", migration.version, e))?;
+
+            tx.commit()
+                .map_err(|e| format!("Failed to commit migration {}: {}", migration.version, e))?;
+        }
 
+        Ok(())
+    })
+}
+
+/// Undo the applied migrations newer than `version`, newest first
+///
+/// Returns the migrations that were undone.
+pub fn rollback(
+    conn: &Connection,
+    migrations: &[Migration],
+    version: u32,
+) -> Result<Vec<AppliedMigration>, String> {
+    with_foreign_keys_off(conn, || {
+        verify_applied(conn, migrations)?;
+
+        let mut undone = Vec::new();
+        for applied in applied_migrations(conn)?.into_iter().rev() {
+            if applied.version <= version {
+                break;
+            }
+            let migration = migrations
+                .iter()
+                .find(|m| m.version == applied.version)
+                .ok_or_else(|| format!("Unknown migration {}", applied.version))?;
+
+            let tx = conn
+                .unchecked_transaction()
+                .map_err(|e| format!("Failed to start rollback of {}: {}", migration.version, e))?;
+
+            tx.execute_batch(migration.down).map_err(|e| {
+                format!(
+                    "Failed to roll back migration {} ({}): {}",
+                    migration.version, migration.name, e
+                )
+            })?;
+            check_foreign_keys(&tx, migration)?;
+
+            tx.execute(
+                "DELETE FROM schema_migrations WHERE version = ?1",
+                [migration.version],
+            )
+            .map_err(|e| format!("Failed to record rollback of {}: {}", migration.version, e))?;
+
+            tx.commit().map_err(|e| {
+                format!("Failed to commit rollback of {}: {}", migration.version, e)
+            })?;
+            undone.push(applied);
+        }
+
+        Ok(undone)
+    })
+}
+
+/// Check the recorded migrations against the code, returning their versions
+///
+/// Records without a checksum come from legacy databases and are adopted.
+fn verify_applied(conn: &Connection, migrations: &[Migration]) -> Result<Vec<u32>, String> {
+    let applied = applied_migrations(conn)?;
+
+    for record in &applied {
+        let migration = migrations
+            .iter()
+            .find(|m| m.version == record.version)
+            .ok_or_else(|| {
+                format!(
+                    "Database schema version {} is newer than this version of Mizly",
+                    record.version
+                )
+            })?;
+
+        if record.checksum.is_empty() {
             conn.execute(
-                "INSERT INTO schema_migrations (version) VALUES (?1)",
-                [version],
+                "UPDATE schema_migrations SET name = ?1, checksum = ?2 WHERE version = ?3",
+                rusqlite::params![migration.name, migration.checksum(), migration.version],
             )
-            .map_err(|e| format!("Failed to record migration {}: {}", version, e))?;
+            .map_err(|e| format!("Failed to record migration {}: {}", migration.version, e))?;
+        } else if record.name != migration.name || record.checksum != migration.checksum() {
+            return Err(format!(
+                "Migration {} ({}) was changed after it was applied",
+                migration.version, migration.name
+            ));
         }
     }
 
-    Ok(())
+    Ok(applied.into_iter().map(|m| m.version).collect())
+}
+
+fn check_foreign_keys(conn: &Connection, migration: &Migration) -> Result<(), String> {
+    let violation: Option<String> = conn
+        .query_row("PRAGMA foreign_key_check", [], |row| row.get(0))
+        .map(Some)
+        .or_else(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => Ok(None),
+            e => Err(e),
+        })
+        .map_err(|e| format!("Failed to check foreign keys: {}", e))?;
+
+    match violation {
+        Some(table) => Err(format!(
+            "Migration {} ({}) left rows in {} without their parent",
+            migration.version, migration.name, table
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Run `f` with foreign keys off, turning them back on whatever it returns
+///
+/// The pragma has no effect inside a transaction, so it's set around them.
+fn with_foreign_keys_off<T>(
+    conn: &Connection,
+    f: impl FnOnce() -> Result<T, String>,
+) -> Result<T, String> {
+    conn.execute("PRAGMA foreign_keys = OFF;", [])
+        .map_err(|e| format!("Failed to disable foreign keys: {}", e))?;
+
+    let result = f();
+
+    conn.execute("PRAGMA foreign_keys = ON;", [])
+        .map_err(|e| format!("Failed to enable foreign keys: {}", e))?;
+
+    result
 }
@@ -1,8 +1,26 @@
+pub mod activity;
+pub mod backup;
+pub mod batch;
+pub mod chunks;
 pub mod connection;
+pub mod leases;
 pub mod migrations;
+pub mod queue;
 pub mod stores;
 pub mod documents;
+pub mod search;
+pub mod trash;
+pub mod transfers;
 
-pub use connection::Database;
+pub use activity::*;
+pub use backup::check_file_integrity;
+pub use batch::*;
+pub use chunks::*;
+pub use connection::{Database, DATABASE_FILE};
+pub use leases::*;
+pub use queue::*;
 pub use stores::*;
 pub use documents::*;
+pub use search::*;
+pub use trash::*;
+pub use transfers::*;
@@ -0,0 +1,82 @@
+//! Work waiting for background sync, counted per stage for the sync status
+
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+
+use super::connection::CachedStatements;
+use super::trash::purge_cutoff;
+use super::Database;
+
+/// Queue depth of each sync stage
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncQueue {
+    /// Stores waiting to be created in their backend
+    pub stores_pending: i64,
+    /// Documents waiting to be uploaded
+    pub uploads_pending: i64,
+    /// Uploads started but not yet accepted by their backend
+    pub uploads_in_flight: i64,
+    /// Uploaded documents whose backend operation is being polled, trashed
+    /// ones included
+    pub operations_polling: i64,
+    /// Trashed stores and documents past their grace period, to delete
+    /// remotely; documents deleted along with their store count with it
+    pub deletions_pending: i64,
+}
+
+impl SyncQueue {
+    /// Whether no stage has work waiting
+    pub fn is_empty(&self) -> bool {
+        *self == SyncQueue::default()
+    }
+}
+
+impl Database {
+    /// Count the work of each sync stage, with the same conditions as the stages themselves
+    ///
+    /// An upload is in flight from its `upload_started` event until the
+    /// backend accepts it, so other processes see uploads of the lease holder.
+    pub fn get_sync_queue(&self, retention_days: u32) -> Result<SyncQueue, String> {
+        let conn = self.reader()?;
+
+        conn.query_row_cached(
+            r#"
+            SELECT
+                (SELECT COUNT(*) FROM stores
+                 WHERE (sync_status = 'pending' OR sync_status IS NULL) AND deleted_at IS NULL),
+                (SELECT COUNT(*) FROM documents d
+                 JOIN stores s ON s.id = d.store_id
+                 WHERE d.sync_status = 'pending' AND d.deleted_at IS NULL AND s.deleted_at IS NULL
+                 AND IFNULL((SELECT e.kind FROM document_events e WHERE e.document_id = d.id
+                             ORDER BY e.id DESC LIMIT 1), '') <> 'upload_started'),
+                (SELECT COUNT(*) FROM documents d
+                 JOIN stores s ON s.id = d.store_id
+                 WHERE d.sync_status = 'pending' AND d.deleted_at IS NULL AND s.deleted_at IS NULL
+                 AND (SELECT e.kind FROM document_events e WHERE e.document_id = d.id
+                      ORDER BY e.id DESC LIMIT 1) = 'upload_started'),
+                (SELECT COUNT(*) FROM documents
+                 WHERE operation_name IS NOT NULL
+                 AND status NOT IN ('completed', 'failed')),
+                (SELECT COUNT(*) FROM stores
+                 WHERE deleted_at IS NOT NULL AND deleted_at <= datetime('now', ?1))
+                + (SELECT COUNT(*) FROM documents d
+                   JOIN stores s ON s.id = d.store_id
+                   WHERE d.deleted_at IS NOT NULL AND d.deleted_at <= datetime('now', ?1)
+                   AND (s.deleted_at IS NULL OR d.deleted_at <> s.deleted_at)
+                   AND NOT (d.operation_name IS NOT NULL AND d.status NOT IN ('completed', 'failed')))
+            "#,
+            params![purge_cutoff(retention_days)],
+            |row| {
+                Ok(SyncQueue {
+                    stores_pending: row.get(0)?,
+                    uploads_pending: row.get(1)?,
+                    uploads_in_flight: row.get(2)?,
+                    operations_polling: row.get(3)?,
+                    deletions_pending: row.get(4)?,
+                })
+            },
+        )
+        .map_err(|e| format!("Failed to count sync queue: {}", e))
+    }
+}
@@ -0,0 +1,177 @@
+//! Database operations for the local full-text index
+
+use rusqlite::{params, Row};
+use serde::Serialize;
+
+use super::connection::CachedStatements;
+use super::documents::map_row_to_document;
+use super::{Database, Document};
+
+/// Maximum number of documents indexed per sync cycle
+const INDEX_BATCH_SIZE: i64 = 20;
+
+/// Trigram tokenizer cannot match terms shorter than this
+const MIN_TERM_CHARS: usize = 3;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalSearchResult {
+    #[serde(flatten)]
+    pub document: Document,
+    /// Matching excerpt with hits wrapped in `<mark>` tags
+    pub snippet: String,
+    /// BM25 rank (lower is better)
+    pub rank: f64,
+}
+
+/// Build an FTS5 query from user input
+/// Each term is quoted so FTS5 syntax characters in the input are matched literally
+fn build_match_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .filter(|term| term.chars().count() >= MIN_TERM_CHARS)
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect();
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+/// Maps a database row to LocalSearchResult struct
+/// Expected columns: the document columns (see map_row_to_document), snippet, rank
+fn map_row_to_search_result(row: &Row) -> rusqlite::Result<LocalSearchResult> {
+    Ok(LocalSearchResult {
+        document: map_row_to_document(row)?,
+        snippet: row.get(18)?,
+        rank: row.get(19)?,
+    })
+}
+
+impl Database {
+    /// Get documents that have not been indexed yet
+    pub fn get_pending_index_documents(&self) -> Result<Vec<Document>, String> {
+        let conn = self.reader()?;
+
+        let mut stmt = conn
+            .prepare_cached(
+                r#"
+                SELECT id, store_id, gemini_name, operation_name, name, path, content_type, mime_type,
+                       size, hash, status, sync_status, error_message, deleted_at, created_at, updated_at, upload_mime_type, custom_metadata
+                FROM documents
+                WHERE indexed_at IS NULL AND deleted_at IS NULL
+                ORDER BY created_at ASC
+                LIMIT ?1
+                "#,
+            )
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let documents = stmt
+            .query_map(params![INDEX_BATCH_SIZE], map_row_to_document)
+            .map_err(|e| format!("Failed to query pending index documents: {}", e))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to collect pending index documents: {}", e))?;
+
+        Ok(documents)
+    }
+
+    /// Replace the full-text index entry of a document and mark it as indexed
+    /// Documents without extractable content are indexed by name only
+    pub fn index_document(
+        &self,
+        id: &str,
+        store_id: &str,
+        name: &str,
+        content: Option<&str>,
+    ) -> Result<(), String> {
+        let mut conn = self.writer()?;
+        let tx = conn
+            .transaction()
+            .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+
+        tx.execute_cached(
+            "DELETE FROM documents_fts WHERE document_id = ?1",
+            params![id],
+        )
+        .map_err(|e| format!("Failed to clear document index: {}", e))?;
+
+        tx.execute_cached(
+            r#"
+            INSERT INTO documents_fts (document_id, store_id, name, content)
+            VALUES (?1, ?2, ?3, ?4)
+            "#,
+            params![id, store_id, name, content.unwrap_or_default()],
+        )
+        .map_err(|e| format!("Failed to index document: {}", e))?;
+
+        tx.execute_cached(
+            "UPDATE documents SET indexed_at = CURRENT_TIMESTAMP WHERE id = ?1",
+            params![id],
+        )
+        .map_err(|e| format!("Failed to mark document as indexed: {}", e))?;
+
+        tx.commit()
+            .map_err(|e| format!("Failed to commit document index: {}", e))
+    }
+
+    /// Search the local full-text index
+    /// An empty `store_ids` searches all stores
+    pub fn search_local(
+        &self,
+        query: &str,
+        store_ids: &[String],
+        limit: i64,
+    ) -> Result<Vec<LocalSearchResult>, String> {
+        let match_query = match build_match_query(query) {
+            Some(q) => q,
+            None => return Ok(Vec::new()),
+        };
+
+        let conn = self.reader()?;
+
+        let store_filter = if store_ids.is_empty() {
+            String::new()
+        } else {
+            let placeholders: Vec<String> = (0..store_ids.len())
+                .map(|i| format!("?{}", i + 3))
+                .collect();
+            format!("AND d.store_id IN ({})", placeholders.join(", "))
+        };
+
+        let query = format!(
+            r#"
+            SELECT d.id, d.store_id, d.gemini_name, d.operation_name, d.name, d.path, d.content_type, d.mime_type,
+                   d.size, d.hash, d.status, d.sync_status, d.error_message, d.deleted_at, d.created_at, d.updated_at, d.upload_mime_type, d.custom_metadata,
+                   snippet(documents_fts, -1, '<mark>', '</mark>', '…', 16),
+                   bm25(documents_fts) AS rank
+            FROM documents_fts
+            JOIN documents d ON d.id = documents_fts.document_id
+            JOIN stores s ON s.id = d.store_id
+            WHERE documents_fts MATCH ?1
+            AND d.deleted_at IS NULL
+            AND s.deleted_at IS NULL
+            {}
+            ORDER BY rank
+            LIMIT ?2
+            "#,
+            store_filter
+        );
+
+        let mut stmt = conn
+            .prepare_cached(&query)
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let mut params: Vec<&dyn rusqlite::ToSql> = vec![&match_query, &limit];
+        params.extend(store_ids.iter().map(|s| s as &dyn rusqlite::ToSql));
+
+        let results = stmt
+            .query_map(params.as_slice(), map_row_to_search_result)
+            .map_err(|e| format!("Failed to search local index: {}", e))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to collect search results: {}", e))?;
+
+        Ok(results)
+    }
+}
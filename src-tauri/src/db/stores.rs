@@ -3,6 +3,10 @@
 use rusqlite::{params, Row};
 use serde::{Deserialize, Serialize};
 
+use super::activity::DocumentEventKind;
+use super::connection::CachedStatements;
+use super::documents::{insert_document, Document, NewDocument};
+use super::trash::purge_cutoff;
 use super::Database;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,6 +26,19 @@ pub struct Store {
     pub deleted_at: Option<String>,
     pub created_at: String,
     pub updated_at: String,
+    /// Include globs for directory imports, one per line
+    pub include_patterns: Option<String>,
+    /// Gitignore-style exclude patterns for directory imports, one per line
+    pub exclude_patterns: Option<String>,
+    /// Search backend the store lives in (see `backend::BackendKind`)
+    pub backend: String,
+    /// Documents never leave the machine (no uploads, no remote models)
+    pub local_only: bool,
+    pub description: Option<String>,
+    /// `#rrggbb` color the store is shown with
+    pub color: Option<String>,
+    /// Short text, e.g. an emoji, shown in place of the folder icon
+    pub icon: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,11 +52,81 @@ pub struct StoreWithStatus {
     pub local_failed_count: i32,
 }
 
+/// Fields of a store that can be edited
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoreUpdate {
+    pub title: String,
+    pub directory_path: Option<String>,
+    pub description: Option<String>,
+    pub color: Option<String>,
+    pub icon: Option<String>,
+}
+
+/// Longest store icon, enough for emoji made of several code points
+const MAX_ICON_CHARS: usize = 8;
+
+fn non_blank(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|v| !v.is_empty())
+}
+
+impl StoreUpdate {
+    /// Check the fields, returning them trimmed with blanks cleared and the
+    /// color lowercased
+    pub fn validated(&self) -> Result<StoreUpdate, String> {
+        let title = self.title.trim();
+        if title.is_empty() {
+            return Err("Store title can't be empty".to_string());
+        }
+
+        let color = non_blank(self.color.as_deref()).map(|c| c.to_ascii_lowercase());
+        if let Some(color) = &color {
+            if !is_hex_color(color) {
+                return Err(format!("Invalid color {}, expected #rrggbb", color));
+            }
+        }
+
+        let icon = non_blank(self.icon.as_deref());
+        if icon.is_some_and(|i| i.chars().count() > MAX_ICON_CHARS) {
+            return Err(format!(
+                "Store icon can't be longer than {} characters",
+                MAX_ICON_CHARS
+            ));
+        }
+
+        Ok(StoreUpdate {
+            title: title.to_string(),
+            directory_path: non_blank(self.directory_path.as_deref()).map(str::to_string),
+            description: non_blank(self.description.as_deref()).map(str::to_string),
+            color,
+            icon: icon.map(str::to_string),
+        })
+    }
+}
+
+/// A store created together with its whole definition, e.g. from a bundle
+#[derive(Debug, Clone, Default)]
+pub struct NewStore {
+    pub id: String,
+    pub backend: String,
+    pub local_only: bool,
+    pub include_patterns: Option<String>,
+    pub exclude_patterns: Option<String>,
+    pub details: StoreUpdate,
+}
+
+fn is_hex_color(value: &str) -> bool {
+    value.len() == 7
+        && value.starts_with('#')
+        && value[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
 /// Maps a database row to Store struct
 /// Expected columns: id, gemini_name, title, directory_path, sync_status, create_time, update_time,
 ///                   active_documents_count, pending_documents_count, failed_documents_count, size_bytes,
-///                   deleted_at, created_at, updated_at
-fn map_row_to_store(row: &Row) -> rusqlite::Result<Store> {
+///                   deleted_at, created_at, updated_at, include_patterns, exclude_patterns, backend, local_only,
+///                   description, color, icon
+pub(super) fn map_row_to_store(row: &Row) -> rusqlite::Result<Store> {
     Ok(Store {
         id: row.get(0)?,
         gemini_name: row.get(1)?,
@@ -55,6 +142,13 @@ fn map_row_to_store(row: &Row) -> rusqlite::Result<Store> {
         deleted_at: row.get(11)?,
         created_at: row.get(12)?,
         updated_at: row.get(13)?,
+        include_patterns: row.get(14)?,
+        exclude_patterns: row.get(15)?,
+        backend: row.get::<_, Option<String>>(16)?.unwrap_or_else(|| "gemini".to_string()),
+        local_only: row.get::<_, Option<bool>>(17)?.unwrap_or(false),
+        description: row.get(18)?,
+        color: row.get(19)?,
+        icon: row.get(20)?,
     })
 }
 
@@ -64,16 +158,18 @@ impl Database {
         id: &str,
         title: &str,
         directory_path: Option<&str>,
+        backend: &str,
+        local_only: bool,
     ) -> Result<Store, String> {
         {
-            let conn = self.conn.lock().map_err(|e| e.to_string())?;
+            let conn = self.writer()?;
 
-            conn.execute(
+            conn.execute_cached(
                 r#"
-                INSERT INTO stores (id, title, directory_path)
-                VALUES (?1, ?2, ?3)
+                INSERT INTO stores (id, title, directory_path, backend, local_only)
+                VALUES (?1, ?2, ?3, ?4, ?5)
                 "#,
-                params![id, title, directory_path],
+                params![id, title, directory_path, backend, local_only],
             )
             .map_err(|e| format!("Failed to create store: {}", e))?;
         } // Release lock here
@@ -81,14 +177,65 @@ impl Database {
         self.get_store(id)
     }
 
+    /// Create a store with its settings and queued documents, all or nothing
+    pub fn create_store_with_documents(
+        &self,
+        store: &NewStore,
+        documents: &[NewDocument],
+    ) -> Result<(Store, Vec<Document>), String> {
+        let details = store.details.validated()?;
+
+        {
+            let mut conn = self.writer()?;
+            let tx = conn
+                .transaction()
+                .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+
+            tx.execute_cached(
+                r#"
+                INSERT INTO stores (id, title, directory_path, backend, local_only,
+                                    include_patterns, exclude_patterns, description, color, icon)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+                "#,
+                params![
+                    store.id,
+                    details.title,
+                    details.directory_path,
+                    store.backend,
+                    store.local_only,
+                    store.include_patterns,
+                    store.exclude_patterns,
+                    details.description,
+                    details.color,
+                    details.icon
+                ],
+            )
+            .map_err(|e| format!("Failed to create store: {}", e))?;
+
+            for document in documents {
+                insert_document(&tx, &store.id, document)?;
+            }
+
+            tx.commit()
+                .map_err(|e| format!("Failed to commit store: {}", e))?;
+        } // Release lock here
+
+        let documents = documents
+            .iter()
+            .map(|document| self.get_document(&document.id))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok((self.get_store(&store.id)?, documents))
+    }
+
     pub fn get_store(&self, id: &str) -> Result<Store, String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let conn = self.reader()?;
 
-        conn.query_row(
+        conn.query_row_cached(
             r#"
             SELECT id, gemini_name, title, directory_path, sync_status, create_time, update_time,
                    active_documents_count, pending_documents_count, failed_documents_count, size_bytes,
-                   deleted_at, created_at, updated_at
+                   deleted_at, created_at, updated_at, include_patterns, exclude_patterns, backend, local_only,
+                   description, color, icon
             FROM stores
             WHERE id = ?1 AND deleted_at IS NULL
             "#,
@@ -98,16 +245,37 @@ impl Database {
         .map_err(|e| format!("Failed to get store: {}", e))
     }
 
+    /// Find a store by id, or else by its exact title
+    pub fn find_store(&self, key: &str) -> Result<Store, String> {
+        if let Ok(store) = self.get_store(key) {
+            return Ok(store);
+        }
+
+        let mut matches: Vec<Store> = self
+            .get_stores()?
+            .into_iter()
+            .map(|s| s.store)
+            .filter(|s| s.title == key)
+            .collect();
+
+        match matches.len() {
+            0 => Err(format!("Store not found: {}", key)),
+            1 => Ok(matches.remove(0)),
+            _ => Err(format!("Several stores are titled {}, pass an id", key)),
+        }
+    }
+
     pub fn get_stores(&self) -> Result<Vec<StoreWithStatus>, String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let conn = self.reader()?;
 
         let mut stmt = conn
-            .prepare(
+            .prepare_cached(
                 r#"
                 SELECT
                     s.id, s.gemini_name, s.title, s.directory_path, s.sync_status, s.create_time, s.update_time,
                     s.active_documents_count, s.pending_documents_count, s.failed_documents_count, s.size_bytes,
-                    s.deleted_at, s.created_at, s.updated_at,
+                    s.deleted_at, s.created_at, s.updated_at, s.include_patterns, s.exclude_patterns, s.backend, s.local_only,
+                    s.description, s.color, s.icon,
                     COUNT(d.id) as document_count,
                     SUM(CASE WHEN d.status NOT IN ('completed', 'failed') THEN 1 ELSE 0 END) as local_pending_count,
                     SUM(CASE WHEN d.status = 'failed' THEN 1 ELSE 0 END) as local_failed_count
@@ -122,15 +290,15 @@ impl Database {
 
         let stores = stmt
             .query_map([], |row| {
-                let local_pending_count: i32 = row.get(15)?;
+                let local_pending_count: i32 = row.get(22)?;
                 let status = if local_pending_count > 0 { "processing" } else { "completed" };
 
                 Ok(StoreWithStatus {
                     store: map_row_to_store(row)?,
                     status: status.to_string(),
-                    document_count: row.get(14)?,
+                    document_count: row.get(21)?,
                     local_pending_count,
-                    local_failed_count: row.get(16)?,
+                    local_failed_count: row.get(23)?,
                 })
             })
             .map_err(|e| format!("Failed to query stores: {}", e))?
@@ -140,42 +308,74 @@ impl Database {
         Ok(stores)
     }
 
-    /// Soft delete a store by setting deleted_at timestamp
+    /// Soft delete a store and its documents, moving them to the trash
+    ///
+    /// The documents get the store's deleted_at, so restoring the store brings
+    /// back those and not the ones deleted on their own before.
     pub fn soft_delete_store(&self, id: &str) -> Result<(), String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let mut conn = self.writer()?;
+        let tx = conn
+            .transaction()
+            .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+
+        let deleted_at: String = tx
+            .query_row_cached("SELECT CURRENT_TIMESTAMP", [], |row| row.get(0))
+            .map_err(|e| format!("Failed to soft delete store: {}", e))?;
+
+        tx.execute_cached(
+            r#"
+            UPDATE documents
+            SET deleted_at = ?2, updated_at = ?2
+            WHERE store_id = ?1 AND deleted_at IS NULL
+            "#,
+            params![id, deleted_at],
+        )
+        .map_err(|e| format!("Failed to soft delete documents by store: {}", e))?;
 
-        conn.execute(
+        tx.execute_cached(
+            r#"
+            INSERT INTO document_events (document_id, kind, message)
+            SELECT id, ?3, 'Store deleted' FROM documents
+            WHERE store_id = ?1 AND deleted_at = ?2
+            "#,
+            params![id, deleted_at, DocumentEventKind::Deleted.as_str()],
+        )
+        .map_err(|e| format!("Failed to record document events: {}", e))?;
+
+        tx.execute_cached(
             r#"
             UPDATE stores
-            SET deleted_at = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP
+            SET deleted_at = ?2, updated_at = ?2
             WHERE id = ?1 AND deleted_at IS NULL
             "#,
-            params![id],
+            params![id, deleted_at],
         )
         .map_err(|e| format!("Failed to soft delete store: {}", e))?;
 
-        Ok(())
+        tx.commit()
+            .map_err(|e| format!("Failed to commit store deletion: {}", e))
     }
 
-    /// Get all soft-deleted stores for background sync processing
-    pub fn get_soft_deleted_stores(&self) -> Result<Vec<Store>, String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+    /// Get the stores deleted at least `older_than_days` ago, for background sync to purge
+    pub fn get_soft_deleted_stores(&self, older_than_days: u32) -> Result<Vec<Store>, String> {
+        let conn = self.reader()?;
 
         let mut stmt = conn
-            .prepare(
+            .prepare_cached(
                 r#"
                 SELECT id, gemini_name, title, directory_path, sync_status, create_time, update_time,
                        active_documents_count, pending_documents_count, failed_documents_count, size_bytes,
-                       deleted_at, created_at, updated_at
+                       deleted_at, created_at, updated_at, include_patterns, exclude_patterns, backend, local_only,
+                       description, color, icon
                 FROM stores
-                WHERE deleted_at IS NOT NULL
+                WHERE deleted_at IS NOT NULL AND deleted_at <= datetime('now', ?1)
                 ORDER BY deleted_at ASC
                 "#,
             )
             .map_err(|e| format!("Failed to prepare statement: {}", e))?;
 
         let stores = stmt
-            .query_map([], map_row_to_store)
+            .query_map(params![purge_cutoff(older_than_days)], map_row_to_store)
             .map_err(|e| format!("Failed to query soft deleted stores: {}", e))?
             .collect::<Result<Vec<_>, _>>()
             .map_err(|e| format!("Failed to collect soft deleted stores: {}", e))?;
@@ -185,11 +385,30 @@ impl Database {
 
     /// Hard delete a store (permanently remove from database)
     pub fn hard_delete_store(&self, id: &str) -> Result<(), String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let mut conn = self.writer()?;
+        let tx = conn
+            .transaction()
+            .map_err(|e| format!("Failed to begin transaction: {}", e))?;
 
-        conn.execute("DELETE FROM stores WHERE id = ?1", params![id])
+        // Its documents go with it, their timelines end with the purge
+        tx.execute_cached(
+            r#"
+            INSERT INTO document_events (document_id, kind)
+            SELECT id, ?2 FROM documents WHERE store_id = ?1
+            "#,
+            params![id, DocumentEventKind::Purged.as_str()],
+        )
+        .map_err(|e| format!("Failed to record document event: {}", e))?;
+
+        tx.execute_cached("DELETE FROM documents_fts WHERE store_id = ?1", params![id])
+            .map_err(|e| format!("Failed to remove store index: {}", e))?;
+
+        tx.execute_cached("DELETE FROM stores WHERE id = ?1", params![id])
             .map_err(|e| format!("Failed to hard delete store: {}", e))?;
 
+        tx.commit()
+            .map_err(|e| format!("Failed to commit store purge: {}", e))?;
+
         Ok(())
     }
 
@@ -199,9 +418,9 @@ impl Database {
         sync_status: &str,
         gemini_name: Option<&str>,
     ) -> Result<(), String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let conn = self.writer()?;
 
-        conn.execute(
+        conn.execute_cached(
             r#"
             UPDATE stores
             SET sync_status = ?1,
@@ -228,9 +447,9 @@ impl Database {
         failed_documents_count: i64,
         size_bytes: i64,
     ) -> Result<(), String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let conn = self.writer()?;
 
-        conn.execute(
+        conn.execute_cached(
             r#"
             UPDATE stores
             SET gemini_name = ?1,
@@ -260,15 +479,81 @@ impl Database {
         Ok(())
     }
 
+    /// Save the directory import rules of a store
+    pub fn update_store_import_rules(
+        &self,
+        id: &str,
+        include_patterns: Option<&str>,
+        exclude_patterns: Option<&str>,
+    ) -> Result<(), String> {
+        let conn = self.writer()?;
+
+        conn.execute_cached(
+            r#"
+            UPDATE stores
+            SET include_patterns = ?1,
+                exclude_patterns = ?2,
+                updated_at = CURRENT_TIMESTAMP
+            WHERE id = ?3 AND deleted_at IS NULL
+            "#,
+            params![include_patterns, exclude_patterns, id],
+        )
+        .map_err(|e| format!("Failed to update store import rules: {}", e))?;
+
+        Ok(())
+    }
+
+    /// Edit a store's title, directory, description, color and icon
+    ///
+    /// Blank values clear the optional fields. The title is also the remote
+    /// display name, see `Backends::rename_store`.
+    pub fn update_store(&self, id: &str, update: &StoreUpdate) -> Result<Store, String> {
+        let update = update.validated()?;
+
+        {
+            let conn = self.writer()?;
+
+            let updated = conn
+                .execute_cached(
+                    r#"
+                    UPDATE stores
+                    SET title = ?1,
+                        directory_path = ?2,
+                        description = ?3,
+                        color = ?4,
+                        icon = ?5,
+                        updated_at = CURRENT_TIMESTAMP
+                    WHERE id = ?6 AND deleted_at IS NULL
+                    "#,
+                    params![
+                        update.title,
+                        update.directory_path,
+                        update.description,
+                        update.color,
+                        update.icon,
+                        id
+                    ],
+                )
+                .map_err(|e| format!("Failed to update store: {}", e))?;
+
+            if updated == 0 {
+                return Err(format!("Store not found: {}", id));
+            }
+        } // Release lock here
+
+        self.get_store(id)
+    }
+
     pub fn get_pending_sync_stores(&self) -> Result<Vec<Store>, String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let conn = self.reader()?;
 
         let mut stmt = conn
-            .prepare(
+            .prepare_cached(
                 r#"
                 SELECT id, gemini_name, title, directory_path, sync_status, create_time, update_time,
                        active_documents_count, pending_documents_count, failed_documents_count, size_bytes,
-                       deleted_at, created_at, updated_at
+                       deleted_at, created_at, updated_at, include_patterns, exclude_patterns, backend, local_only,
+                       description, color, icon
                 FROM stores
                 WHERE (sync_status = 'pending' OR sync_status IS NULL) AND deleted_at IS NULL
                 "#,
@@ -0,0 +1,308 @@
+//! Database operations for moving and copying documents between stores
+//!
+//! A copy is a new document in the target store, queued for upload like any
+//! other. A moved document also records its source in `moved_from`; the
+//! source goes to the trash once the copy is uploaded and `completed`, so a
+//! failed upload never loses the original.
+
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use uuid::Uuid;
+
+use super::activity::{record_document_event, DocumentEventKind};
+use super::connection::CachedStatements;
+use super::{Database, Document};
+
+/// Whether the sources of a transfer stay in their store
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TransferMode {
+    Copy,
+    Move,
+}
+
+/// A document that was not transferred, with the reason
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RejectedDocument {
+    pub id: String,
+    /// Display name, None when the document doesn't exist
+    pub name: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferDocumentsResult {
+    /// Documents created in the target store, queued for upload
+    pub documents: Vec<Document>,
+    pub rejected: Vec<RejectedDocument>,
+}
+
+/// A move whose copy was uploaded, its source now in the trash
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FinishedMove {
+    pub document_id: String,
+    pub store_id: String,
+    pub source_document_id: String,
+    /// None when the source was purged in the meantime
+    pub source_store_id: Option<String>,
+}
+
+/// Source document of a transfer, as read in the transaction
+struct Source {
+    name: String,
+    store_id: String,
+    store_title: String,
+    path: String,
+    content_type: Option<String>,
+    size: Option<i64>,
+    hash: Option<String>,
+    custom_metadata: Option<String>,
+    local_only: bool,
+    /// Source or copy of an unfinished move
+    moving: bool,
+}
+
+impl Database {
+    /// Copy documents to another store, queueing the copies for upload
+    pub fn copy_documents(
+        &self,
+        ids: &[String],
+        target_store_id: &str,
+    ) -> Result<TransferDocumentsResult, String> {
+        self.transfer_documents(ids, target_store_id, TransferMode::Copy)
+    }
+
+    /// Move documents to another store
+    ///
+    /// The sources stay until their copies are uploaded, see
+    /// [`Database::finish_document_moves`].
+    pub fn move_documents(
+        &self,
+        ids: &[String],
+        target_store_id: &str,
+    ) -> Result<TransferDocumentsResult, String> {
+        self.transfer_documents(ids, target_store_id, TransferMode::Move)
+    }
+
+    /// Create the target documents in one transaction, rejecting per document
+    fn transfer_documents(
+        &self,
+        ids: &[String],
+        target_store_id: &str,
+        mode: TransferMode,
+    ) -> Result<TransferDocumentsResult, String> {
+        let target = self.get_store(target_store_id)?;
+        let mut created = Vec::new();
+        let mut rejected = Vec::new();
+
+        {
+            let mut conn = self.writer()?;
+            let tx = conn
+                .transaction()
+                .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+
+            for id in ids {
+                let source = tx
+                    .query_row_cached(
+                        r#"
+                        SELECT d.name, d.store_id, s.title, d.path, d.content_type, d.size, d.hash, d.custom_metadata,
+                               s.local_only,
+                               d.moved_from IS NOT NULL
+                               OR EXISTS(SELECT 1 FROM documents m WHERE m.moved_from = d.id AND m.deleted_at IS NULL)
+                        FROM documents d
+                        JOIN stores s ON s.id = d.store_id
+                        WHERE d.id = ?1 AND d.deleted_at IS NULL AND s.deleted_at IS NULL
+                        "#,
+                        params![id],
+                        |row| {
+                            Ok(Source {
+                                name: row.get(0)?,
+                                store_id: row.get(1)?,
+                                store_title: row.get(2)?,
+                                path: row.get(3)?,
+                                content_type: row.get(4)?,
+                                size: row.get(5)?,
+                                hash: row.get(6)?,
+                                custom_metadata: row.get(7)?,
+                                local_only: row.get::<_, Option<bool>>(8)?.unwrap_or(false),
+                                moving: row.get(9)?,
+                            })
+                        },
+                    )
+                    .optional()
+                    .map_err(|e| format!("Failed to read document: {}", e))?;
+
+                let Some(source) = source else {
+                    rejected.push(RejectedDocument {
+                        id: id.clone(),
+                        name: None,
+                        message: "Document not found".to_string(),
+                    });
+                    continue;
+                };
+
+                let in_target: bool = tx
+                    .query_row_cached(
+                        "SELECT EXISTS(SELECT 1 FROM documents WHERE store_id = ?1 AND path = ?2 AND deleted_at IS NULL)",
+                        params![target.id, source.path],
+                        |row| row.get(0),
+                    )
+                    .map_err(|e| format!("Failed to read document: {}", e))?;
+
+                let message = if source.store_id == target.id || in_target {
+                    Some("Already in the target store".to_string())
+                } else if mode == TransferMode::Move && source.moving {
+                    Some("Already being moved".to_string())
+                } else if source.local_only && !target.local_only {
+                    // The copy would be uploaded
+                    Some("Documents of local-only stores stay on this machine".to_string())
+                } else if !Path::new(&source.path).is_file() {
+                    // Copies are uploaded from the local file
+                    Some(format!("File not found: {}", source.path))
+                } else {
+                    None
+                };
+
+                if let Some(message) = message {
+                    rejected.push(RejectedDocument {
+                        id: id.clone(),
+                        name: Some(source.name),
+                        message,
+                    });
+                    continue;
+                }
+
+                let copy_id = Uuid::new_v4().to_string();
+                let moved_from = (mode == TransferMode::Move).then_some(id.as_str());
+
+                tx.execute_cached(
+                    r#"
+                    INSERT INTO documents (id, store_id, name, path, content_type, size, hash, custom_metadata, moved_from, status)
+                    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, 'pending')
+                    "#,
+                    params![
+                        copy_id,
+                        target.id,
+                        source.name,
+                        source.path,
+                        source.content_type,
+                        source.size,
+                        source.hash,
+                        source.custom_metadata,
+                        moved_from
+                    ],
+                )
+                .map_err(|e| format!("Failed to create document: {}", e))?;
+
+                let note = match mode {
+                    TransferMode::Copy => format!("Copied from {}", source.store_title),
+                    TransferMode::Move => format!("Moved from {}", source.store_title),
+                };
+                record_document_event(&tx, &copy_id, DocumentEventKind::Queued, Some(&note))?;
+
+                created.push(copy_id);
+            }
+
+            tx.commit()
+                .map_err(|e| format!("Failed to commit document transfer: {}", e))?;
+        } // Release lock here
+
+        let documents = created
+            .iter()
+            .map(|id| self.get_document(id))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(TransferDocumentsResult {
+            documents,
+            rejected,
+        })
+    }
+
+    /// Move the sources of moves whose copy is `completed` to the trash
+    ///
+    /// Called by background sync. Copies that failed keep their source, and
+    /// retrying them finishes the move later.
+    pub fn finish_document_moves(&self) -> Result<Vec<FinishedMove>, String> {
+        let mut conn = self.writer()?;
+        let tx = conn
+            .transaction()
+            .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+
+        // The statement is dropped with the temporaries, before the updates
+        let finished = tx
+            .prepare_cached(
+                r#"
+                SELECT d.id, d.store_id, d.moved_from, s.store_id
+                FROM documents d
+                LEFT JOIN documents s ON s.id = d.moved_from
+                WHERE d.moved_from IS NOT NULL
+                AND d.status = 'completed'
+                AND d.deleted_at IS NULL
+                "#,
+            )
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?
+            .query_map([], |row| {
+                Ok(FinishedMove {
+                    document_id: row.get(0)?,
+                    store_id: row.get(1)?,
+                    source_document_id: row.get(2)?,
+                    source_store_id: row.get(3)?,
+                })
+            })
+            .map_err(|e| format!("Failed to query finished moves: {}", e))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to collect finished moves: {}", e))?;
+
+        for finished in &finished {
+            tx.execute_cached(
+                r#"
+                UPDATE documents
+                SET deleted_at = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP
+                WHERE id = ?1 AND deleted_at IS NULL
+                "#,
+                params![finished.source_document_id],
+            )
+            .map_err(|e| format!("Failed to delete moved document: {}", e))?;
+
+            record_document_event(
+                &tx,
+                &finished.source_document_id,
+                DocumentEventKind::Deleted,
+                Some("Moved to another store"),
+            )?;
+
+            tx.execute_cached(
+                "UPDATE documents SET moved_from = NULL WHERE id = ?1",
+                params![finished.document_id],
+            )
+            .map_err(|e| format!("Failed to finish document move: {}", e))?;
+        }
+
+        tx.commit()
+            .map_err(|e| format!("Failed to commit document moves: {}", e))?;
+
+        Ok(finished)
+    }
+
+    /// Whether a moved document's copy is still being uploaded
+    pub fn has_document_moves_in_progress(&self) -> Result<bool, String> {
+        let conn = self.reader()?;
+
+        conn.query_row_cached(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM documents
+                WHERE moved_from IS NOT NULL
+                AND status NOT IN ('completed', 'failed')
+                AND deleted_at IS NULL
+            )
+            "#,
+            [],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Failed to query document moves: {}", e))
+    }
+}
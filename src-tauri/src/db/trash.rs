@@ -0,0 +1,259 @@
+//! Database operations for the trash
+//!
+//! Deleting a store or document only sets its deleted_at. It stays in the
+//! trash for the grace period of the settings and can be restored until
+//! background sync deletes it remotely and purges it.
+//!
+//! Trashed documents stay in their remote store until then, so Gemini can
+//! still retrieve them; queries leave them out of the cited sources.
+
+use rusqlite::{params, params_from_iter, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use super::activity::{record_document_event, DocumentEventKind};
+use super::chunks::placeholders;
+use super::connection::CachedStatements;
+use super::documents::map_row_to_document;
+use super::stores::map_row_to_store;
+use super::{Database, Document, Store};
+
+/// A deleted store with the documents deleted along with it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashedStore {
+    #[serde(flatten)]
+    pub store: Store,
+    pub document_count: i64,
+    /// When background sync purges it, in UTC
+    pub purge_at: String,
+}
+
+/// A document deleted on its own
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashedDocument {
+    #[serde(flatten)]
+    pub document: Document,
+    pub store_title: String,
+    /// When background sync purges it, in UTC
+    pub purge_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Trash {
+    pub stores: Vec<TrashedStore>,
+    /// Documents of deleted stores are left out, they come back with their store
+    pub documents: Vec<TrashedDocument>,
+}
+
+/// `datetime('now', ?)` modifier for items deleted at least `days` ago
+pub(super) fn purge_cutoff(days: u32) -> String {
+    format!("-{} days", days)
+}
+
+impl Database {
+    /// List the trash, most recently deleted first
+    pub fn get_trash(&self, retention_days: u32) -> Result<Trash, String> {
+        let conn = self.reader()?;
+        let purge_delay = format!("+{} days", retention_days);
+
+        let mut stmt = conn
+            .prepare_cached(
+                r#"
+                SELECT s.id, s.gemini_name, s.title, s.directory_path, s.sync_status, s.create_time, s.update_time,
+                       s.active_documents_count, s.pending_documents_count, s.failed_documents_count, s.size_bytes,
+                       s.deleted_at, s.created_at, s.updated_at, s.include_patterns, s.exclude_patterns, s.backend, s.local_only,
+                       s.description, s.color, s.icon,
+                       (SELECT COUNT(*) FROM documents d WHERE d.store_id = s.id AND d.deleted_at = s.deleted_at),
+                       datetime(s.deleted_at, ?1)
+                FROM stores s
+                WHERE s.deleted_at IS NOT NULL
+                ORDER BY s.deleted_at DESC
+                "#,
+            )
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let stores = stmt
+            .query_map(params![purge_delay], |row| {
+                Ok(TrashedStore {
+                    store: map_row_to_store(row)?,
+                    document_count: row.get(21)?,
+                    purge_at: row.get(22)?,
+                })
+            })
+            .map_err(|e| format!("Failed to query deleted stores: {}", e))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to collect deleted stores: {}", e))?;
+
+        let mut stmt = conn
+            .prepare_cached(
+                r#"
+                SELECT d.id, d.store_id, d.gemini_name, d.operation_name, d.name, d.path, d.content_type, d.mime_type,
+                       d.size, d.hash, d.status, d.sync_status, d.error_message, d.deleted_at, d.created_at, d.updated_at, d.upload_mime_type, d.custom_metadata,
+                       s.title, datetime(d.deleted_at, ?1)
+                FROM documents d
+                JOIN stores s ON s.id = d.store_id
+                WHERE d.deleted_at IS NOT NULL
+                AND s.deleted_at IS NULL
+                ORDER BY d.deleted_at DESC
+                "#,
+            )
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let documents = stmt
+            .query_map(params![purge_delay], |row| {
+                Ok(TrashedDocument {
+                    document: map_row_to_document(row)?,
+                    store_title: row.get(18)?,
+                    purge_at: row.get(19)?,
+                })
+            })
+            .map_err(|e| format!("Failed to query deleted documents: {}", e))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to collect deleted documents: {}", e))?;
+
+        Ok(Trash { stores, documents })
+    }
+
+    /// Names of the trashed documents in the given remote stores
+    ///
+    /// Names shared with a live document of those stores are left out, as a
+    /// source with that name may well be the live one.
+    pub fn get_trashed_document_names(
+        &self,
+        store_names: &[String],
+    ) -> Result<Vec<String>, String> {
+        if store_names.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let conn = self.reader()?;
+
+        let stores = placeholders(store_names.len(), 1);
+        let sql = format!(
+            r#"
+            SELECT DISTINCT d.name
+            FROM documents d
+            JOIN stores s ON s.id = d.store_id
+            WHERE s.gemini_name IN ({stores})
+            AND d.deleted_at IS NOT NULL
+            AND d.name NOT IN (
+                SELECT live.name
+                FROM documents live
+                JOIN stores ls ON ls.id = live.store_id
+                WHERE ls.gemini_name IN ({stores})
+                AND live.deleted_at IS NULL
+            )
+            "#
+        );
+
+        let mut stmt = conn
+            .prepare(&sql)
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let names = stmt
+            .query_map(params_from_iter(store_names), |row| row.get(0))
+            .map_err(|e| format!("Failed to query trashed documents: {}", e))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to collect trashed documents: {}", e))?;
+
+        Ok(names)
+    }
+
+    /// Take a store out of the trash, with the documents deleted along with it
+    pub fn restore_store(&self, id: &str) -> Result<Store, String> {
+        {
+            let mut conn = self.writer()?;
+            let tx = conn
+                .transaction()
+                .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+
+            let deleted_at: String = tx
+                .query_row_cached(
+                    "SELECT deleted_at FROM stores WHERE id = ?1 AND deleted_at IS NOT NULL",
+                    params![id],
+                    |row| row.get(0),
+                )
+                .optional()
+                .map_err(|e| format!("Failed to restore store: {}", e))?
+                .ok_or_else(|| format!("Store not found in the trash: {}", id))?;
+
+            tx.execute_cached(
+                r#"
+                INSERT INTO document_events (document_id, kind, message)
+                SELECT id, ?3, 'Store restored' FROM documents
+                WHERE store_id = ?1 AND deleted_at = ?2
+                "#,
+                params![id, deleted_at, DocumentEventKind::Restored.as_str()],
+            )
+            .map_err(|e| format!("Failed to record document events: {}", e))?;
+
+            tx.execute_cached(
+                r#"
+                UPDATE documents
+                SET deleted_at = NULL, updated_at = CURRENT_TIMESTAMP
+                WHERE store_id = ?1 AND deleted_at = ?2
+                "#,
+                params![id, deleted_at],
+            )
+            .map_err(|e| format!("Failed to restore documents: {}", e))?;
+
+            tx.execute_cached(
+                "UPDATE stores SET deleted_at = NULL, updated_at = CURRENT_TIMESTAMP WHERE id = ?1",
+                params![id],
+            )
+            .map_err(|e| format!("Failed to restore store: {}", e))?;
+
+            tx.commit()
+                .map_err(|e| format!("Failed to commit store restore: {}", e))?;
+        } // Release lock here
+
+        self.get_store(id)
+    }
+
+    /// Take a document out of the trash; its store must not be deleted
+    pub fn restore_document(&self, id: &str) -> Result<Document, String> {
+        {
+            let mut conn = self.writer()?;
+            let tx = conn
+                .transaction()
+                .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+
+            let (store_title, store_deleted): (String, bool) = tx
+                .query_row_cached(
+                    r#"
+                    SELECT s.title, s.deleted_at IS NOT NULL
+                    FROM documents d
+                    JOIN stores s ON s.id = d.store_id
+                    WHERE d.id = ?1 AND d.deleted_at IS NOT NULL
+                    "#,
+                    params![id],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .optional()
+                .map_err(|e| format!("Failed to restore document: {}", e))?
+                .ok_or_else(|| format!("Document not found in the trash: {}", id))?;
+
+            if store_deleted {
+                return Err(format!(
+                    "Store {} is in the trash, restore it first",
+                    store_title
+                ));
+            }
+
+            tx.execute_cached(
+                "UPDATE documents SET deleted_at = NULL, updated_at = CURRENT_TIMESTAMP WHERE id = ?1",
+                params![id],
+            )
+            .map_err(|e| format!("Failed to restore document: {}", e))?;
+
+            record_document_event(&tx, id, DocumentEventKind::Restored, None)?;
+
+            tx.commit()
+                .map_err(|e| format!("Failed to commit document restore: {}", e))?;
+        } // Release lock here
+
+        self.get_document(id)
+    }
+}
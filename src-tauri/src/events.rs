@@ -0,0 +1,49 @@
+//! Event sinks for sync progress
+//!
+//! The sync and polling tasks report progress through an [`EventSink`]
+//! instead of emitting Tauri events directly, so they can run without a
+//! window (CLI, tests, background daemon). [`TauriEventSink`] forwards
+//! events to the frontend.
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, Runtime, Wry};
+
+/// Receiver of named events with a JSON payload
+pub trait EventSink: Send + Sync {
+    fn emit(&self, event: &str, payload: Value);
+}
+
+/// Serialize a payload and publish it to a sink
+pub fn emit<T: Serialize>(events: &dyn EventSink, event: &str, payload: &T) {
+    match serde_json::to_value(payload) {
+        Ok(value) => events.emit(event, value),
+        Err(e) => eprintln!("Failed to serialize {} event: {}", event, e),
+    }
+}
+
+/// Sink that drops every event
+pub struct NoopEventSink;
+
+impl EventSink for NoopEventSink {
+    fn emit(&self, _event: &str, _payload: Value) {}
+}
+
+/// Sink that emits events to the frontend
+pub struct TauriEventSink<R: Runtime = Wry> {
+    app_handle: AppHandle<R>,
+}
+
+impl<R: Runtime> TauriEventSink<R> {
+    pub fn new(app_handle: AppHandle<R>) -> Self {
+        Self { app_handle }
+    }
+}
+
+impl<R: Runtime> EventSink for TauriEventSink<R> {
+    fn emit(&self, event: &str, payload: Value) {
+        if let Err(e) = self.app_handle.emit(event, payload) {
+            eprintln!("Failed to emit {} event: {}", event, e);
+        }
+    }
+}
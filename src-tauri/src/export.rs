@@ -0,0 +1,604 @@
+//! Conversation export
+//!
+//! Renders a chat conversation (questions, answers and their cited documents)
+//! to Markdown, a standalone HTML page or a PDF. Citations are resolved to
+//! the local documents they name, so exports list the files the answers were
+//! grounded in. All formats are rendered from the same Markdown: HTML through
+//! `pulldown-cmark`, PDF by laying out its blocks with `printpdf`.
+
+use printpdf::{
+    BuiltinFont, IndirectFontRef, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference,
+};
+use pulldown_cmark::{html, Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::Path;
+
+use crate::db::Database;
+
+/// Title used when the conversation has none
+const DEFAULT_TITLE: &str = "Conversation";
+
+/// Fonts covering non-Latin text (CJK in particular), tried in order when a
+/// PDF contains characters the built-in PDF fonts cannot encode
+const UNICODE_FONTS: &[&str] = &[
+    "/System/Library/Fonts/Supplemental/Arial Unicode.ttf",
+    "/Library/Fonts/Arial Unicode.ttf",
+    "C:\\Windows\\Fonts\\arialuni.ttf",
+    "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/google-noto-cjk/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+];
+
+/// Characters outside Latin-1 that the WinAnsi encoding of built-in fonts covers
+const WIN_ANSI_EXTRA: &str = "€‚ƒ„…†‡ˆ‰Š‹ŒŽ‘’“”•–—˜™š›œžŸ";
+
+// ============================================================================
+// Conversation
+// ============================================================================
+
+/// A conversation as shown in the chat view
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Conversation {
+    #[serde(default)]
+    pub title: Option<String>,
+    pub exchanges: Vec<Exchange>,
+}
+
+/// One question with its answer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Exchange {
+    pub question: String,
+    /// Answer as Markdown
+    pub answer: String,
+    /// Display names of the cited documents, as returned with the answer
+    #[serde(default)]
+    pub sources: Vec<String>,
+}
+
+/// A cited document, resolved to the local library when possible
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Citation {
+    pub name: String,
+    pub store: Option<String>,
+    pub path: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Markdown,
+    Html,
+    Pdf,
+}
+
+impl ExportFormat {
+    /// Format matching a file extension
+    pub fn from_path(path: &Path) -> Result<Self, String> {
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default()
+            .to_lowercase();
+
+        match extension.as_str() {
+            "md" | "markdown" => Ok(Self::Markdown),
+            "html" | "htm" => Ok(Self::Html),
+            "pdf" => Ok(Self::Pdf),
+            _ => Err(format!("Unsupported export format: {}", path.display())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportConversationResult {
+    pub path: String,
+    pub format: ExportFormat,
+    pub citations: usize,
+}
+
+/// Export a conversation to `path`
+///
+/// The format is taken from the file extension when not given.
+pub fn export_conversation(
+    db: &Database,
+    conversation: &Conversation,
+    format: Option<ExportFormat>,
+    path: &Path,
+) -> Result<ExportConversationResult, String> {
+    if conversation.exchanges.is_empty() {
+        return Err("Nothing to export".to_string());
+    }
+
+    let format = match format {
+        Some(format) => format,
+        None => ExportFormat::from_path(path)?,
+    };
+
+    let citations = conversation
+        .exchanges
+        .iter()
+        .map(|exchange| resolve_citations(db, &exchange.sources))
+        .collect::<Result<Vec<_>, _>>()?;
+    let markdown = render_markdown(conversation, &citations);
+
+    let result = match format {
+        ExportFormat::Markdown => fs::write(path, markdown).map_err(|e| e.to_string()),
+        ExportFormat::Html => {
+            fs::write(path, render_html(&title(conversation), &markdown)).map_err(|e| e.to_string())
+        }
+        ExportFormat::Pdf => write_pdf(&title(conversation), &markdown, path),
+    };
+    result.map_err(|e| format!("Failed to export conversation: {}", e))?;
+
+    Ok(ExportConversationResult {
+        path: path.to_string_lossy().to_string(),
+        format,
+        citations: citations.iter().map(Vec::len).sum(),
+    })
+}
+
+/// Look up cited documents by display name, like the chat view's source buttons
+pub fn resolve_citations(db: &Database, sources: &[String]) -> Result<Vec<Citation>, String> {
+    let mut citations: Vec<Citation> = Vec::new();
+
+    for name in sources {
+        if citations.iter().any(|c| &c.name == name) {
+            continue;
+        }
+
+        let document = db.get_document_by_display_name(name)?;
+        let store = match &document {
+            Some(document) => db.get_store(&document.store_id).ok().map(|s| s.title),
+            None => None,
+        };
+
+        citations.push(Citation {
+            name: name.clone(),
+            store,
+            path: document.map(|d| d.path),
+        });
+    }
+
+    Ok(citations)
+}
+
+fn title(conversation: &Conversation) -> String {
+    conversation
+        .title
+        .as_deref()
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .unwrap_or(DEFAULT_TITLE)
+        .to_string()
+}
+
+// ============================================================================
+// Markdown
+// ============================================================================
+
+/// Render the conversation with a "Sources" list after each answer
+pub fn render_markdown(conversation: &Conversation, citations: &[Vec<Citation>]) -> String {
+    let mut markdown = format!(
+        "# {}\n\n_Exported {}_\n",
+        escape_markdown(&title(conversation)),
+        chrono::Local::now().format("%Y-%m-%d %H:%M")
+    );
+
+    for (index, exchange) in conversation.exchanges.iter().enumerate() {
+        markdown.push_str(&format!(
+            "\n## {}\n\n{}\n",
+            escape_markdown(exchange.question.trim()),
+            exchange.answer.trim()
+        ));
+
+        let citations = citations.get(index).map(Vec::as_slice).unwrap_or_default();
+        if citations.is_empty() {
+            continue;
+        }
+
+        markdown.push_str("\n**Sources**\n\n");
+        for citation in citations {
+            markdown.push_str(&format!("- {}\n", citation_line(citation)));
+        }
+    }
+
+    markdown
+}
+
+fn citation_line(citation: &Citation) -> String {
+    let name = escape_markdown(&citation.name);
+    let Some(path) = &citation.path else {
+        return format!("{} (not in the library)", name);
+    };
+
+    let link = match url::Url::from_file_path(path) {
+        Ok(url) => format!("[{}](<{}>)", name, url),
+        Err(_) => name,
+    };
+    match &citation.store {
+        Some(store) => format!("{} ({}): `{}`", link, escape_markdown(store), path),
+        None => format!("{}: `{}`", link, path),
+    }
+}
+
+/// Escape characters that would turn plain text into Markdown markup
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(
+            c,
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '|'
+        ) {
+            escaped.push('\\');
+        }
+        escaped.push(if c == '\n' { ' ' } else { c });
+    }
+    escaped
+}
+
+fn markdown_options() -> Options {
+    Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS
+}
+
+// ============================================================================
+// HTML
+// ============================================================================
+
+const HTML_STYLE: &str = r#"
+body { max-width: 46rem; margin: 2.5rem auto; padding: 0 1.25rem; font: 15px/1.6 -apple-system, BlinkMacSystemFont, "Segoe UI", "Hiragino Sans", "Noto Sans CJK JP", sans-serif; color: #1f2937; }
+h1 { font-size: 1.6rem; margin-bottom: 0; }
+h1 + p { color: #6b7280; margin-top: 0.25rem; }
+h2 { font-size: 1.15rem; margin-top: 2.5rem; padding: 0.5rem 0.75rem; background: #f3f4f6; border-radius: 0.5rem; }
+table { border-collapse: collapse; margin: 1rem 0; }
+th, td { border: 1px solid #d1d5db; padding: 0.35rem 0.6rem; text-align: left; vertical-align: top; }
+th { background: #f9fafb; }
+pre, code { font-family: ui-monospace, Menlo, Consolas, monospace; font-size: 0.9em; }
+pre { background: #f3f4f6; padding: 0.75rem; border-radius: 0.5rem; overflow-x: auto; }
+a { color: #4f46e5; }
+"#;
+
+/// Render a standalone HTML page
+///
+/// Raw HTML in answers is escaped, so the page never runs model output.
+pub fn render_html(title: &str, markdown: &str) -> String {
+    let events = Parser::new_ext(markdown, markdown_options()).map(|event| match event {
+        Event::Html(raw) | Event::InlineHtml(raw) => Event::Text(raw),
+        // The chat view renders single newlines as line breaks
+        Event::SoftBreak => Event::HardBreak,
+        event => event,
+    });
+
+    let mut body = String::new();
+    html::push_html(&mut body, events);
+
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        escape_html(title),
+        HTML_STYLE,
+        body
+    )
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// ============================================================================
+// PDF
+// ============================================================================
+
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const MARGIN: f32 = 20.0;
+const BODY_SIZE: f32 = 10.5;
+const CODE_SIZE: f32 = 9.0;
+const LINE_SPACING: f32 = 1.4;
+const PT_TO_MM: f32 = 0.3528;
+const INDENT: f32 = 6.0;
+
+/// A block of text laid out as wrapped lines
+#[derive(Debug, Clone, PartialEq)]
+enum Block {
+    Heading(HeadingLevel, String),
+    Paragraph {
+        text: String,
+        indent: usize,
+        bold: bool,
+    },
+    Code(String),
+    Rule,
+}
+
+/// Flatten Markdown into blocks of plain text
+///
+/// PDF output favours a readable layout over fidelity: inline styles are
+/// dropped, list items get bullets or numbers, and table rows are written as
+/// cells separated by vertical bars with the header row in bold.
+fn markdown_blocks(markdown: &str) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut text = String::new();
+    let mut lists: Vec<Option<u64>> = Vec::new();
+    let mut item_prefix: Option<String> = None;
+    let mut cells: Vec<String> = Vec::new();
+    let mut in_table_head = false;
+    let mut quote_depth = 0;
+
+    let flush = |blocks: &mut Vec<Block>,
+                 text: &mut String,
+                 prefix: &mut Option<String>,
+                 indent: usize,
+                 bold: bool| {
+        let content = text.trim();
+        if content.is_empty() && prefix.is_none() {
+            return;
+        }
+        let content = match prefix.take() {
+            Some(prefix) => format!("{} {}", prefix, content),
+            None => content.to_string(),
+        };
+        blocks.push(Block::Paragraph {
+            text: content,
+            indent,
+            bold,
+        });
+        text.clear();
+    };
+
+    for event in Parser::new_ext(markdown, markdown_options()) {
+        let indent = lists.len() + quote_depth;
+        match event {
+            Event::Start(Tag::List(start)) => {
+                flush(&mut blocks, &mut text, &mut item_prefix, indent, false);
+                lists.push(start);
+            }
+            Event::End(TagEnd::List(_)) => {
+                lists.pop();
+            }
+            Event::Start(Tag::Item) => {
+                flush(&mut blocks, &mut text, &mut item_prefix, indent, false);
+                item_prefix = Some(match lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!("{}.", *number - 1)
+                    }
+                    _ => "•".to_string(),
+                });
+            }
+            Event::Start(Tag::BlockQuote(_)) => {
+                flush(&mut blocks, &mut text, &mut item_prefix, indent, false);
+                quote_depth += 1;
+            }
+            Event::End(TagEnd::BlockQuote(_)) => quote_depth -= 1,
+            Event::End(TagEnd::Paragraph) | Event::End(TagEnd::Item) => {
+                flush(&mut blocks, &mut text, &mut item_prefix, indent, false);
+            }
+            Event::End(TagEnd::Heading(level)) => {
+                blocks.push(Block::Heading(level, text.trim().to_string()));
+                text.clear();
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                blocks.push(Block::Code(text.trim_end().to_string()));
+                text.clear();
+            }
+            Event::Start(Tag::TableHead) => in_table_head = true,
+            Event::End(TagEnd::TableCell) => {
+                cells.push(text.trim().to_string());
+                text.clear();
+            }
+            Event::End(TagEnd::TableHead) | Event::End(TagEnd::TableRow) => {
+                blocks.push(Block::Paragraph {
+                    text: cells.join(" | "),
+                    indent,
+                    bold: in_table_head,
+                });
+                cells.clear();
+                in_table_head = false;
+            }
+            Event::Text(t) | Event::Code(t) | Event::Html(t) | Event::InlineHtml(t) => {
+                text.push_str(&t)
+            }
+            Event::SoftBreak | Event::HardBreak => text.push('\n'),
+            Event::TaskListMarker(done) => text.push_str(if done { "[x] " } else { "[ ] " }),
+            Event::Rule => blocks.push(Block::Rule),
+            _ => {}
+        }
+    }
+    flush(&mut blocks, &mut text, &mut item_prefix, 0, false);
+
+    blocks
+}
+
+/// Whether a built-in PDF font can show every character
+fn is_win_ansi(text: &str) -> bool {
+    text.chars()
+        .all(|c| c == '\n' || (' '..='\u{FF}').contains(&c) || WIN_ANSI_EXTRA.contains(c))
+}
+
+/// Whether a character takes a full em, as CJK characters do
+fn is_wide(c: char) -> bool {
+    matches!(c as u32, 0x1100..=0x115F | 0x2E80..=0xA4CF | 0xAC00..=0xD7A3 | 0xF900..=0xFAFF | 0xFE30..=0xFE4F | 0xFF00..=0xFF60 | 0xFFE0..=0xFFE6 | 0x20000..=0x3FFFD)
+}
+
+/// Approximate width of text in millimetres
+fn text_width(text: &str, size: f32) -> f32 {
+    let ems: f32 = text
+        .chars()
+        .map(|c| if is_wide(c) { 1.0 } else { 0.5 })
+        .sum();
+    ems * size * PT_TO_MM
+}
+
+/// Break text into lines no wider than `width`
+///
+/// Lines break at spaces, or between any two wide characters.
+fn wrap(text: &str, size: f32, width: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    for paragraph in text.split('\n') {
+        let mut line = String::new();
+        let mut tokens: Vec<String> = Vec::new();
+        for c in paragraph.chars() {
+            match tokens.last_mut() {
+                Some(last)
+                    if !is_wide(c)
+                        && !c.is_whitespace()
+                        && last.chars().all(|l| !is_wide(l) && !l.is_whitespace()) =>
+                {
+                    last.push(c)
+                }
+                _ => tokens.push(c.to_string()),
+            }
+        }
+
+        for token in tokens {
+            if !line.is_empty() && text_width(&line, size) + text_width(&token, size) > width {
+                lines.push(line.trim_end().to_string());
+                line.clear();
+            }
+            if line.is_empty() && token.trim().is_empty() {
+                continue;
+            }
+            // Split words longer than a line
+            for c in token.chars() {
+                if !line.is_empty()
+                    && text_width(&line, size) + text_width(&c.to_string(), size) > width
+                {
+                    lines.push(std::mem::take(&mut line));
+                }
+                line.push(c);
+            }
+        }
+        lines.push(line.trim_end().to_string());
+    }
+
+    lines
+}
+
+struct Fonts {
+    regular: IndirectFontRef,
+    bold: IndirectFontRef,
+    mono: IndirectFontRef,
+}
+
+impl Fonts {
+    /// Built-in fonts for Latin text, else the first Unicode font found
+    fn load(doc: &PdfDocumentReference, latin: bool) -> Result<Self, String> {
+        if !latin {
+            for path in UNICODE_FONTS {
+                let Ok(file) = File::open(path) else {
+                    continue;
+                };
+                match doc.add_external_font(file) {
+                    Ok(font) => {
+                        return Ok(Self {
+                            regular: font.clone(),
+                            bold: font.clone(),
+                            mono: font,
+                        })
+                    }
+                    Err(e) => eprintln!("Failed to load font {}: {}", path, e),
+                }
+            }
+            eprintln!("No Unicode font found, characters outside Latin-1 are left out of the PDF");
+        }
+
+        let builtin = |font| {
+            doc.add_builtin_font(font)
+                .map_err(|e| format!("Failed to load PDF font: {}", e))
+        };
+        Ok(Self {
+            regular: builtin(BuiltinFont::Helvetica)?,
+            bold: builtin(BuiltinFont::HelveticaBold)?,
+            mono: builtin(BuiltinFont::Courier)?,
+        })
+    }
+}
+
+/// Writes lines top to bottom, adding pages as they fill up
+struct PdfWriter {
+    doc: PdfDocumentReference,
+    layer: PdfLayerReference,
+    y: f32,
+}
+
+impl PdfWriter {
+    fn new(title: &str) -> Self {
+        let (doc, page, layer) =
+            PdfDocument::new(title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Layer 1");
+        let layer = doc.get_page(page).get_layer(layer);
+        Self {
+            doc,
+            layer,
+            y: PAGE_HEIGHT - MARGIN,
+        }
+    }
+
+    fn space(&mut self, height: f32) {
+        self.y -= height;
+    }
+
+    fn text(&mut self, text: &str, size: f32, font: &IndirectFontRef, indent: f32) {
+        let width = PAGE_WIDTH - 2.0 * MARGIN - indent;
+        let line_height = size * LINE_SPACING * PT_TO_MM;
+
+        for line in wrap(text, size, width) {
+            if self.y - line_height < MARGIN {
+                let (page, layer) = self
+                    .doc
+                    .add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Layer 1");
+                self.layer = self.doc.get_page(page).get_layer(layer);
+                self.y = PAGE_HEIGHT - MARGIN;
+            }
+            self.y -= line_height;
+            self.layer
+                .use_text(line, size, Mm(MARGIN + indent), Mm(self.y), font);
+        }
+    }
+}
+
+/// Lay out the Markdown as a PDF and write it to `path`
+fn write_pdf(title: &str, markdown: &str, path: &Path) -> Result<(), String> {
+    let mut writer = PdfWriter::new(title);
+    let fonts = Fonts::load(&writer.doc, is_win_ansi(markdown))?;
+
+    for block in markdown_blocks(markdown) {
+        match block {
+            Block::Heading(level, text) => {
+                let size = match level {
+                    HeadingLevel::H1 => 18.0,
+                    HeadingLevel::H2 => 13.5,
+                    _ => 12.0,
+                };
+                writer.space(size * 0.5 * PT_TO_MM);
+                writer.text(&text, size, &fonts.bold, 0.0);
+                writer.space(2.0);
+            }
+            Block::Paragraph { text, indent, bold } => {
+                let font = if bold { &fonts.bold } else { &fonts.regular };
+                writer.text(&text, BODY_SIZE, font, indent as f32 * INDENT);
+                writer.space(1.5);
+            }
+            Block::Code(text) => {
+                writer.text(&text, CODE_SIZE, &fonts.mono, INDENT);
+                writer.space(1.5);
+            }
+            Block::Rule => writer.space(4.0),
+        }
+    }
+
+    let file = File::create(path).map_err(|e| e.to_string())?;
+    writer
+        .doc
+        .save(&mut BufWriter::new(file))
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,83 @@
+//! Source archive extractor
+
+use std::io::Read;
+use std::path::Path;
+
+use super::text::is_text_file;
+use super::{extension, open_zip, Extracted, Extractor, MIME_TEXT_MARKDOWN, MAX_EXTRACT_BYTES};
+
+/// Archive entries larger than this are skipped
+const MAX_ENTRY_BYTES: u64 = 1024 * 1024;
+
+/// Zip archives of source code or text files, converted to one Markdown
+/// document with a fenced code block per file
+pub struct ZipArchiveExtractor;
+
+impl Extractor for ZipArchiveExtractor {
+    fn name(&self) -> &'static str {
+        "zip"
+    }
+
+    fn supports(&self, path: &Path) -> bool {
+        extension(path) == "zip"
+    }
+
+    fn extract(&self, path: &Path) -> Result<Extracted, String> {
+        let mut archive = open_zip(path)?;
+        let mut text = String::new();
+        let mut file_count = 0;
+
+        for index in 0..archive.len() {
+            let mut entry = archive
+                .by_index(index)
+                .map_err(|e| format!("Failed to read archive entry: {}", e))?;
+
+            // Skip unsafe paths, directories and hidden/metadata files
+            let Some(entry_path) = entry.enclosed_name() else {
+                continue;
+            };
+            let is_hidden = entry_path.components().any(|c| {
+                let name = c.as_os_str().to_string_lossy();
+                name.starts_with('.') || name == "__MACOSX"
+            });
+
+            if entry.is_dir()
+                || is_hidden
+                || entry.size() > MAX_ENTRY_BYTES
+                || !is_text_file(&entry_path)
+            {
+                continue;
+            }
+
+            let mut bytes = Vec::new();
+            if entry.read_to_end(&mut bytes).is_err() {
+                continue;
+            }
+
+            let content = String::from_utf8_lossy(&bytes);
+            let fence = if content.contains("```") { "````" } else { "```" };
+            text.push_str(&format!(
+                "## {}\n\n{}{}\n{}\n{}\n\n",
+                entry_path.display(),
+                fence,
+                extension(&entry_path),
+                content.trim_end(),
+                fence
+            ));
+            file_count += 1;
+
+            if text.len() as u64 > MAX_EXTRACT_BYTES {
+                break;
+            }
+        }
+
+        if file_count == 0 {
+            return Err("No text files found in archive".to_string());
+        }
+
+        Ok(Extracted {
+            text,
+            mime_type: MIME_TEXT_MARKDOWN,
+        })
+    }
+}
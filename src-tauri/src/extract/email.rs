@@ -0,0 +1,250 @@
+//! E-mail message (RFC 822 / .eml) extractor
+
+use std::path::Path;
+
+use base64::Engine;
+
+use super::text::strip_html_tags;
+use super::{extension, Extracted, Extractor, MIME_TEXT_MARKDOWN};
+
+/// Headers included in the extracted text
+const SUMMARY_HEADERS: &[&str] = &["From", "To", "Cc", "Date"];
+
+/// Saved e-mail messages, converted to Markdown with a header summary
+pub struct EmlExtractor;
+
+impl Extractor for EmlExtractor {
+    fn name(&self) -> &'static str {
+        "eml"
+    }
+
+    fn supports(&self, path: &Path) -> bool {
+        extension(path) == "eml"
+    }
+
+    fn extract(&self, path: &Path) -> Result<Extracted, String> {
+        let bytes = std::fs::read(path).map_err(|e| format!("Failed to read file: {}", e))?;
+        let raw = String::from_utf8_lossy(&bytes);
+        let message = Part::parse(&raw);
+
+        let mut text = String::new();
+        if let Some(subject) = message.header("Subject") {
+            text.push_str(&format!("# {}\n\n", subject));
+        }
+        for name in SUMMARY_HEADERS {
+            if let Some(value) = message.header(name) {
+                text.push_str(&format!("**{}:** {}  \n", name, value));
+            }
+        }
+        text.push('\n');
+        text.push_str(message.body_text().trim());
+
+        Ok(Extracted {
+            text,
+            mime_type: MIME_TEXT_MARKDOWN,
+        })
+    }
+}
+
+/// A message or MIME part
+struct Part<'a> {
+    headers: Vec<(String, String)>,
+    body: &'a str,
+}
+
+impl<'a> Part<'a> {
+    fn parse(raw: &'a str) -> Self {
+        let (head, body) = raw
+            .split_once("\r\n\r\n")
+            .or_else(|| raw.split_once("\n\n"))
+            .unwrap_or((raw, ""));
+
+        // Unfold continuation lines into the previous header
+        let mut headers: Vec<(String, String)> = Vec::new();
+        for line in head.lines() {
+            if line.starts_with([' ', '\t']) {
+                if let Some((_, value)) = headers.last_mut() {
+                    value.push(' ');
+                    value.push_str(line.trim());
+                }
+            } else if let Some((name, value)) = line.split_once(':') {
+                headers.push((name.trim().to_string(), value.trim().to_string()));
+            }
+        }
+
+        Self { headers, body }
+    }
+
+    /// Header value with RFC 2047 encoded words decoded
+    fn header(&self, name: &str) -> Option<String> {
+        self.raw_header(name).map(decode_encoded_words)
+    }
+
+    fn raw_header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Lowercased mime type and parameters of the Content-Type header
+    fn content_type(&self) -> (String, Option<String>) {
+        let value = self.raw_header("Content-Type").unwrap_or("text/plain");
+        let mut params = value.split(';');
+        let mime = params.next().unwrap_or_default().trim().to_lowercase();
+
+        let boundary = params.find_map(|param| {
+            let (key, value) = param.split_once('=')?;
+            key.trim()
+                .eq_ignore_ascii_case("boundary")
+                .then(|| value.trim().trim_matches('"').to_string())
+        });
+
+        (mime, boundary)
+    }
+
+    /// Decoded body, preferring the plain text alternative
+    fn body_text(&self) -> String {
+        let (mime, boundary) = self.content_type();
+
+        if mime.starts_with("multipart/") {
+            let Some(boundary) = boundary else {
+                return String::new();
+            };
+            let delimiter = format!("--{}", boundary);
+            let parts: Vec<Part> = self
+                .body
+                .split(delimiter.as_str())
+                .skip(1)
+                .filter(|p| !p.starts_with("--"))
+                .map(|p| Part::parse(p.trim_start_matches(['\r', '\n'])))
+                .collect();
+
+            let text_parts: Vec<String> = parts
+                .iter()
+                .filter(|p| p.content_type().0 == "text/plain" || p.content_type().0.starts_with("multipart/"))
+                .map(|p| p.body_text())
+                .filter(|t| !t.trim().is_empty())
+                .collect();
+
+            if !text_parts.is_empty() {
+                return text_parts.join("\n\n");
+            }
+
+            return parts
+                .iter()
+                .find(|p| p.content_type().0 == "text/html")
+                .map(|p| p.body_text())
+                .unwrap_or_default();
+        }
+
+        let decoded = self.decoded_body();
+        if mime == "text/html" {
+            strip_html_tags(&decoded)
+        } else if mime.starts_with("text/") {
+            decoded
+        } else {
+            // Attachments are skipped
+            String::new()
+        }
+    }
+
+    fn decoded_body(&self) -> String {
+        let encoding = self
+            .raw_header("Content-Transfer-Encoding")
+            .unwrap_or_default()
+            .to_lowercase();
+
+        match encoding.as_str() {
+            "base64" => {
+                let compact: String = self.body.split_whitespace().collect();
+                base64::engine::general_purpose::STANDARD
+                    .decode(compact)
+                    .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+                    .unwrap_or_default()
+            }
+            "quoted-printable" => decode_quoted_printable(self.body, false),
+            _ => self.body.to_string(),
+        }
+    }
+}
+
+/// Decode quoted-printable text (`underscore_as_space` for RFC 2047 "Q" words)
+fn decode_quoted_printable(input: &str, underscore_as_space: bool) -> String {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'=' if bytes.get(i + 1) == Some(&b'\r') && bytes.get(i + 2) == Some(&b'\n') => i += 3,
+            b'=' if bytes.get(i + 1) == Some(&b'\n') => i += 2,
+            b'=' if i + 2 < bytes.len() => {
+                match std::str::from_utf8(&bytes[i + 1..i + 3])
+                    .ok()
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                {
+                    Some(byte) => {
+                        decoded.push(byte);
+                        i += 3;
+                    }
+                    None => {
+                        decoded.push(b'=');
+                        i += 1;
+                    }
+                }
+            }
+            b'_' if underscore_as_space => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            byte => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Decode RFC 2047 encoded words (`=?charset?B|Q?text?=`), assuming UTF-8
+fn decode_encoded_words(value: &str) -> String {
+    let mut decoded = String::new();
+    let mut rest = value;
+
+    while let Some(start) = rest.find("=?") {
+        let word = &rest[start + 2..];
+        let mut fields = word.splitn(3, '?');
+        let (Some(_charset), Some(encoding), Some(tail)) =
+            (fields.next(), fields.next(), fields.next())
+        else {
+            break;
+        };
+        let Some(end) = tail.find("?=") else {
+            break;
+        };
+
+        // Whitespace between adjacent encoded words is not significant
+        let prefix = &rest[..start];
+        if !(prefix.trim().is_empty() && !decoded.is_empty()) {
+            decoded.push_str(prefix);
+        }
+
+        let text = &tail[..end];
+        let word_text = if encoding.eq_ignore_ascii_case("b") {
+            base64::engine::general_purpose::STANDARD
+                .decode(text)
+                .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+                .unwrap_or_else(|_| text.to_string())
+        } else {
+            decode_quoted_printable(text, true)
+        };
+        decoded.push_str(&word_text);
+
+        rest = &tail[end + 2..];
+    }
+    decoded.push_str(rest);
+
+    decoded
+}
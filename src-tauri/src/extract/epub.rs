@@ -0,0 +1,105 @@
+//! EPUB e-book extractor
+
+use std::path::Path;
+
+use super::text::strip_html_tags;
+use super::{extension, open_zip, read_zip_entry, Extracted, Extractor, MIME_TEXT_PLAIN};
+
+/// EPUB e-books, chapters concatenated in reading (spine) order
+pub struct EpubExtractor;
+
+impl Extractor for EpubExtractor {
+    fn name(&self) -> &'static str {
+        "epub"
+    }
+
+    fn supports(&self, path: &Path) -> bool {
+        extension(path) == "epub"
+    }
+
+    fn extract(&self, path: &Path) -> Result<Extracted, String> {
+        let mut archive = open_zip(path)?;
+
+        // META-INF/container.xml points at the package (OPF) document
+        let container = read_zip_entry(&mut archive, "META-INF/container.xml")?;
+        let opf_path = find_tags(&container, "rootfile")
+            .into_iter()
+            .find_map(|tag| attribute(tag, "full-path"))
+            .ok_or_else(|| "Missing rootfile in container.xml".to_string())?;
+        let opf = read_zip_entry(&mut archive, &opf_path)?;
+
+        // Chapter hrefs are relative to the OPF document
+        let base_dir = opf_path
+            .rsplit_once('/')
+            .map(|(dir, _)| format!("{}/", dir))
+            .unwrap_or_default();
+
+        let manifest: Vec<(String, String)> = find_tags(&opf, "item")
+            .into_iter()
+            .filter_map(|tag| Some((attribute(tag, "id")?, attribute(tag, "href")?)))
+            .collect();
+
+        let mut chapters = Vec::new();
+        for idref in find_tags(&opf, "itemref")
+            .into_iter()
+            .filter_map(|tag| attribute(tag, "idref"))
+        {
+            let Some((_, href)) = manifest.iter().find(|(id, _)| *id == idref) else {
+                continue;
+            };
+
+            let href = href.split('#').next().unwrap_or_default();
+            let entry = format!("{}{}", base_dir, urlencoding::decode(href).unwrap_or_default());
+
+            // Skip missing chapters instead of failing the whole book
+            if let Ok(html) = read_zip_entry(&mut archive, &entry) {
+                chapters.push(strip_html_tags(&html).trim().to_string());
+            }
+        }
+
+        if chapters.is_empty() {
+            return Err("No readable chapters found".to_string());
+        }
+
+        Ok(Extracted {
+            text: chapters.join("\n\n"),
+            mime_type: MIME_TEXT_PLAIN,
+        })
+    }
+}
+
+/// Find the contents of all opening tags with the given (unprefixed) name
+fn find_tags<'a>(xml: &'a str, name: &str) -> Vec<&'a str> {
+    let mut tags = Vec::new();
+    let mut rest = xml;
+
+    while let Some(start) = rest.find('<') {
+        let Some(end) = rest[start..].find('>').map(|i| start + i) else {
+            break;
+        };
+        let tag = &rest[start + 1..end];
+
+        let tag_name = tag.split_whitespace().next().unwrap_or_default();
+        let local_name = tag_name.rsplit(':').next().unwrap_or_default();
+        if local_name == name {
+            tags.push(tag);
+        }
+
+        rest = &rest[end + 1..];
+    }
+
+    tags
+}
+
+/// Read an attribute value from the contents of a tag
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let tag = tag.replace(['\n', '\r', '\t'], " ");
+    for quote in ['"', '\''] {
+        let pattern = format!(" {}={}", name, quote);
+        if let Some(start) = tag.find(&pattern).map(|i| i + pattern.len()) {
+            let end = tag[start..].find(quote)? + start;
+            return Some(tag[start..end].to_string());
+        }
+    }
+    None
+}
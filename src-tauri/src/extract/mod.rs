@@ -0,0 +1,215 @@
+//! Local text extraction
+//!
+//! Converts documents to plain text or Markdown locally. The extracted text is
+//! used for the offline full-text index, and as the upload content for formats
+//! that Gemini File Search does not accept natively.
+//!
+//! Extractors are registered in an [`ExtractorRegistry`]; the first extractor
+//! that supports a file wins, so specific formats are registered before the
+//! generic plain text fallback.
+
+mod archive;
+mod email;
+mod epub;
+mod office;
+mod rtf;
+mod text;
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::gemini::is_supported_mime_type;
+
+pub use archive::ZipArchiveExtractor;
+pub use email::EmlExtractor;
+pub use epub::EpubExtractor;
+pub use office::{DocxExtractor, OdtExtractor};
+pub use rtf::RtfExtractor;
+pub use text::{HtmlExtractor, PlainTextExtractor};
+
+/// Files larger than this are not extracted
+pub const MAX_EXTRACT_BYTES: u64 = 20 * 1024 * 1024;
+
+pub const MIME_TEXT_PLAIN: &str = "text/plain";
+pub const MIME_TEXT_MARKDOWN: &str = "text/markdown";
+
+/// Text extracted from a document
+#[derive(Debug, Clone)]
+pub struct Extracted {
+    pub text: String,
+    /// Mime type of the extracted text (plain text or Markdown)
+    pub mime_type: &'static str,
+}
+
+/// A converter from one or more file formats to text
+pub trait Extractor: Send + Sync {
+    /// Short identifier used in logs
+    fn name(&self) -> &'static str;
+
+    /// Whether this extractor handles the file at `path`
+    fn supports(&self, path: &Path) -> bool;
+
+    /// Extract text from the file at `path` (blocking)
+    fn extract(&self, path: &Path) -> Result<Extracted, String>;
+}
+
+/// Ordered collection of extractors
+pub struct ExtractorRegistry {
+    extractors: Vec<Box<dyn Extractor>>,
+}
+
+impl ExtractorRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self {
+            extractors: Vec::new(),
+        }
+    }
+
+    /// Register an extractor (checked after the ones already registered)
+    pub fn register<E: Extractor + 'static>(&mut self, extractor: E) -> &mut Self {
+        self.extractors.push(Box::new(extractor));
+        self
+    }
+
+    /// Find the first extractor supporting the file at `path`
+    pub fn find(&self, path: &Path) -> Option<&dyn Extractor> {
+        self.extractors
+            .iter()
+            .find(|e| e.supports(path))
+            .map(|e| e.as_ref())
+    }
+}
+
+impl Default for ExtractorRegistry {
+    /// Registry with all built-in extractors
+    fn default() -> Self {
+        let mut registry = Self::new();
+        registry
+            .register(DocxExtractor)
+            .register(OdtExtractor)
+            .register(EpubExtractor)
+            .register(EmlExtractor)
+            .register(RtfExtractor)
+            .register(ZipArchiveExtractor)
+            .register(HtmlExtractor)
+            .register(PlainTextExtractor);
+        registry
+    }
+}
+
+/// Get the global registry with the built-in extractors
+pub fn registry() -> &'static ExtractorRegistry {
+    static REGISTRY: OnceLock<ExtractorRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(ExtractorRegistry::default)
+}
+
+/// Extract text from a file for the local index
+///
+/// Returns `Ok(None)` for unsupported formats or files that are too large.
+pub async fn extract_text(path: &Path) -> Result<Option<String>, String> {
+    let path = path.to_path_buf();
+
+    tokio::task::spawn_blocking(move || {
+        let Some(extractor) = registry().find(&path) else {
+            return Ok(None);
+        };
+
+        if file_size(&path)? > MAX_EXTRACT_BYTES {
+            return Ok(None);
+        }
+
+        extractor.extract(&path).map(|e| Some(e.text))
+    })
+    .await
+    .map_err(|e| format!("Extraction task failed: {}", e))?
+}
+
+/// File content ready to be uploaded to Gemini
+#[derive(Debug)]
+pub struct PreparedUpload {
+    pub bytes: Vec<u8>,
+    pub mime_type: String,
+}
+
+/// Prepare a file for upload
+///
+/// Files Gemini accepts natively are uploaded as-is. Other formats are
+/// converted to text locally when an extractor supports them.
+pub async fn prepare_upload(path: &Path) -> Result<PreparedUpload, String> {
+    let path = path.to_path_buf();
+
+    tokio::task::spawn_blocking(move || {
+        let mime_type = mime_guess::from_path(&path)
+            .first_or_octet_stream()
+            .to_string();
+
+        if is_supported_mime_type(&mime_type) {
+            let bytes =
+                std::fs::read(&path).map_err(|e| format!("Failed to read file: {}", e))?;
+            return Ok(PreparedUpload { bytes, mime_type });
+        }
+
+        let extractor = registry()
+            .find(&path)
+            .ok_or_else(|| format!("Unsupported file type: {}", mime_type))?;
+
+        if file_size(&path)? > MAX_EXTRACT_BYTES {
+            return Err(format!(
+                "File is too large to convert ({} MB max)",
+                MAX_EXTRACT_BYTES / 1024 / 1024
+            ));
+        }
+
+        let extracted = extractor
+            .extract(&path)
+            .map_err(|e| format!("Failed to convert file ({}): {}", extractor.name(), e))?;
+
+        Ok(PreparedUpload {
+            bytes: extracted.text.into_bytes(),
+            mime_type: extracted.mime_type.to_string(),
+        })
+    })
+    .await
+    .map_err(|e| format!("Upload preparation task failed: {}", e))?
+}
+
+// ============================================================================
+// Helpers shared by extractors
+// ============================================================================
+
+/// Lowercased file extension
+fn extension(path: &Path) -> String {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default()
+}
+
+fn file_size(path: &Path) -> Result<u64, String> {
+    std::fs::metadata(path)
+        .map(|m| m.len())
+        .map_err(|e| format!("Failed to read file metadata: {}", e))
+}
+
+/// Open a zip-based document
+fn open_zip(path: &Path) -> Result<zip::ZipArchive<File>, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open file: {}", e))?;
+    zip::ZipArchive::new(file).map_err(|e| format!("Failed to read archive: {}", e))
+}
+
+/// Read a zip entry as (lossy) UTF-8 text
+fn read_zip_entry(archive: &mut zip::ZipArchive<File>, name: &str) -> Result<String, String> {
+    let mut entry = archive
+        .by_name(name)
+        .map_err(|e| format!("Missing {}: {}", name, e))?;
+
+    let mut bytes = Vec::new();
+    entry
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("Failed to read {}: {}", name, e))?;
+
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
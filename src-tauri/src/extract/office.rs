@@ -0,0 +1,92 @@
+//! Word processing document extractors (OOXML and OpenDocument)
+
+use std::path::Path;
+
+use super::text::decode_entities;
+use super::{extension, open_zip, read_zip_entry, Extracted, Extractor, MIME_TEXT_PLAIN};
+
+/// Word documents and their macro-enabled / template variants
+pub struct DocxExtractor;
+
+impl Extractor for DocxExtractor {
+    fn name(&self) -> &'static str {
+        "docx"
+    }
+
+    fn supports(&self, path: &Path) -> bool {
+        matches!(extension(path).as_str(), "docx" | "docm" | "dotx" | "dotm")
+    }
+
+    fn extract(&self, path: &Path) -> Result<Extracted, String> {
+        let mut archive = open_zip(path)?;
+        let xml = read_zip_entry(&mut archive, "word/document.xml")?;
+
+        Ok(Extracted {
+            text: xml_to_text(&xml, &["w:p", "w:br", "w:cr"], &["w:tab"]),
+            mime_type: MIME_TEXT_PLAIN,
+        })
+    }
+}
+
+/// OpenDocument text files
+pub struct OdtExtractor;
+
+impl Extractor for OdtExtractor {
+    fn name(&self) -> &'static str {
+        "odt"
+    }
+
+    fn supports(&self, path: &Path) -> bool {
+        matches!(extension(path).as_str(), "odt" | "ott")
+    }
+
+    fn extract(&self, path: &Path) -> Result<Extracted, String> {
+        let mut archive = open_zip(path)?;
+        let xml = read_zip_entry(&mut archive, "content.xml")?;
+
+        Ok(Extracted {
+            text: xml_to_text(&xml, &["text:p", "text:h", "text:line-break"], &["text:tab"]),
+            mime_type: MIME_TEXT_PLAIN,
+        })
+    }
+}
+
+/// Convert a document XML body to text
+///
+/// Closing (or self-closing) `line_tags` end a line and `tab_tags` become tabs.
+/// All other tags are dropped without separators, because word processors
+/// split a single word across several runs.
+fn xml_to_text(xml: &str, line_tags: &[&str], tab_tags: &[&str]) -> String {
+    let mut text = String::with_capacity(xml.len() / 4);
+    let mut rest = xml;
+
+    while let Some(start) = rest.find('<') {
+        text.push_str(&decode_entities(&rest[..start]));
+
+        let Some(end) = rest[start..].find('>').map(|i| start + i) else {
+            rest = "";
+            break;
+        };
+        let tag = &rest[start + 1..end];
+
+        let is_closing = tag.starts_with('/');
+        let is_self_closing = tag.ends_with('/');
+        let name = tag
+            .trim_start_matches('/')
+            .trim_end_matches('/')
+            .split_whitespace()
+            .next()
+            .unwrap_or_default();
+
+        if (is_closing || is_self_closing) && line_tags.contains(&name) {
+            text.push('\n');
+        } else if !is_closing && tab_tags.contains(&name) {
+            text.push('\t');
+        }
+
+        rest = &rest[end + 1..];
+    }
+    text.push_str(&decode_entities(rest));
+
+    text
+}
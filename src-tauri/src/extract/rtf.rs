@@ -0,0 +1,171 @@
+//! Rich Text Format extractor
+
+use std::path::Path;
+
+use super::{extension, Extracted, Extractor, MIME_TEXT_PLAIN};
+
+/// Destinations whose content is not document text
+const IGNORED_DESTINATIONS: &[&str] = &[
+    "fonttbl", "colortbl", "stylesheet", "info", "pict", "header", "footer", "object",
+    "listtable", "listoverridetable", "revtbl", "rsidtbl", "generator", "themedata",
+    "colorschememapping", "latentstyles", "datastore", "xmlnstbl",
+];
+
+/// RTF documents, reduced to their text
+pub struct RtfExtractor;
+
+impl Extractor for RtfExtractor {
+    fn name(&self) -> &'static str {
+        "rtf"
+    }
+
+    fn supports(&self, path: &Path) -> bool {
+        extension(path) == "rtf"
+    }
+
+    fn extract(&self, path: &Path) -> Result<Extracted, String> {
+        let bytes = std::fs::read(path).map_err(|e| format!("Failed to read file: {}", e))?;
+
+        if !bytes.starts_with(b"{\\rtf") {
+            return Err("Not an RTF document".to_string());
+        }
+
+        Ok(Extracted {
+            text: rtf_to_text(&bytes),
+            mime_type: MIME_TEXT_PLAIN,
+        })
+    }
+}
+
+/// Group state tracked while walking the RTF
+#[derive(Clone, Copy)]
+struct Group {
+    /// Text in this group is not part of the document
+    skip: bool,
+    /// Number of fallback characters to drop after a `\uN` escape
+    unicode_skip: usize,
+}
+
+fn rtf_to_text(rtf: &[u8]) -> String {
+    let mut text = String::new();
+    let mut stack: Vec<Group> = Vec::new();
+    let mut group = Group {
+        skip: false,
+        unicode_skip: 1,
+    };
+    let mut pending_skip = 0usize;
+    let mut i = 0;
+
+    while i < rtf.len() {
+        match rtf[i] {
+            b'{' => {
+                stack.push(group);
+                // `{\*\dest ...}` marks a destination readers may ignore
+                if rtf[i + 1..].starts_with(b"\\*") {
+                    group.skip = true;
+                }
+                i += 1;
+            }
+            b'}' => {
+                group = stack.pop().unwrap_or(group);
+                i += 1;
+            }
+            b'\\' => {
+                let (word, param, next) = read_control(rtf, i + 1);
+                i = next;
+
+                match word.as_str() {
+                    // Escaped literal characters
+                    "\\" | "{" | "}" => {
+                        if !group.skip {
+                            text.push_str(&word);
+                        }
+                    }
+                    "'" => {
+                        // \'hh: code page character, decoded as Windows-1252/Latin-1
+                        let hex = rtf.get(i..i + 2).and_then(|h| std::str::from_utf8(h).ok());
+                        if let Some(byte) = hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                            if pending_skip > 0 {
+                                pending_skip -= 1;
+                            } else if !group.skip {
+                                text.push(byte as char);
+                            }
+                        }
+                        i += 2;
+                    }
+                    "u" => {
+                        if let Some(code) = param {
+                            // Negative values encode code points above 32767
+                            let code = if code < 0 { code + 65536 } else { code };
+                            if !group.skip {
+                                text.push(char::from_u32(code as u32).unwrap_or('\u{FFFD}'));
+                            }
+                            pending_skip = group.unicode_skip;
+                        }
+                    }
+                    "uc" => group.unicode_skip = param.unwrap_or(1).max(0) as usize,
+                    "par" | "line" | "sect" | "page" | "row" => {
+                        if !group.skip {
+                            text.push('\n');
+                        }
+                    }
+                    "tab" | "cell" => {
+                        if !group.skip {
+                            text.push('\t');
+                        }
+                    }
+                    word if IGNORED_DESTINATIONS.contains(&word) => group.skip = true,
+                    _ => {}
+                }
+            }
+            b'\r' | b'\n' => i += 1,
+            byte => {
+                if pending_skip > 0 {
+                    pending_skip -= 1;
+                } else if !group.skip {
+                    text.push(byte as char);
+                }
+                i += 1;
+            }
+        }
+    }
+
+    text
+}
+
+/// Read a control word or symbol starting after the backslash at `start`
+/// Returns the word, its numeric parameter and the index after it
+fn read_control(rtf: &[u8], start: usize) -> (String, Option<i32>, usize) {
+    let Some(&first) = rtf.get(start) else {
+        return (String::new(), None, start);
+    };
+
+    // Control symbol: a single non-letter character
+    if !first.is_ascii_alphabetic() {
+        return ((first as char).to_string(), None, start + 1);
+    }
+
+    let mut i = start;
+    while i < rtf.len() && rtf[i].is_ascii_alphabetic() {
+        i += 1;
+    }
+    let word = String::from_utf8_lossy(&rtf[start..i]).into_owned();
+
+    let param_start = i;
+    if rtf.get(i) == Some(&b'-') {
+        i += 1;
+    }
+    while i < rtf.len() && rtf[i].is_ascii_digit() {
+        i += 1;
+    }
+    let param = std::str::from_utf8(&rtf[param_start..i])
+        .ok()
+        .and_then(|p| p.parse().ok());
+
+    // A single space delimits the control word and is not text
+    if rtf.get(i) == Some(&b' ') {
+        i += 1;
+    }
+
+    (word, param, i)
+}
@@ -0,0 +1,160 @@
+//! Plain text and HTML extractors
+
+use std::path::Path;
+
+use super::{extension, Extracted, Extractor, MIME_TEXT_MARKDOWN, MIME_TEXT_PLAIN};
+
+/// File extensions treated as plain text regardless of detected mime type
+pub(super) const TEXT_EXTENSIONS: &[&str] = &[
+    "txt", "md", "markdown", "rst", "csv", "tsv", "log", "json", "yaml", "yml", "toml", "ini",
+    "xml", "rs", "js", "ts", "jsx", "tsx", "vue", "py", "rb", "go", "java", "kt", "swift", "c",
+    "h", "cpp", "hpp", "cs", "php", "sh", "sql", "css", "scss",
+];
+
+/// Check whether a file name looks like a plain text file
+pub(super) fn is_text_file(path: &Path) -> bool {
+    if TEXT_EXTENSIONS.contains(&extension(path).as_str()) {
+        return true;
+    }
+
+    mime_guess::from_path(path)
+        .first()
+        .map(|mime| mime.type_() == mime_guess::mime::TEXT)
+        .unwrap_or(false)
+}
+
+/// Plain text, Markdown and source code files
+pub struct PlainTextExtractor;
+
+impl Extractor for PlainTextExtractor {
+    fn name(&self) -> &'static str {
+        "text"
+    }
+
+    fn supports(&self, path: &Path) -> bool {
+        is_text_file(path)
+    }
+
+    fn extract(&self, path: &Path) -> Result<Extracted, String> {
+        let bytes = std::fs::read(path).map_err(|e| format!("Failed to read file: {}", e))?;
+
+        let mime_type = match extension(path).as_str() {
+            "md" | "markdown" => MIME_TEXT_MARKDOWN,
+            _ => MIME_TEXT_PLAIN,
+        };
+
+        Ok(Extracted {
+            text: String::from_utf8_lossy(&bytes).into_owned(),
+            mime_type,
+        })
+    }
+}
+
+/// HTML pages, reduced to their visible text
+pub struct HtmlExtractor;
+
+impl Extractor for HtmlExtractor {
+    fn name(&self) -> &'static str {
+        "html"
+    }
+
+    fn supports(&self, path: &Path) -> bool {
+        matches!(extension(path).as_str(), "html" | "htm" | "xhtml")
+    }
+
+    fn extract(&self, path: &Path) -> Result<Extracted, String> {
+        let bytes = std::fs::read(path).map_err(|e| format!("Failed to read file: {}", e))?;
+
+        Ok(Extracted {
+            text: strip_html_tags(&String::from_utf8_lossy(&bytes)),
+            mime_type: MIME_TEXT_PLAIN,
+        })
+    }
+}
+
+/// Remove HTML tags, scripts and styles, keeping the visible text
+pub(super) fn strip_html_tags(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut rest = html;
+
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let head = rest.as_bytes()[..rest.len().min(8)].to_ascii_lowercase();
+        let closing = if head.starts_with(b"<script") {
+            Some("</script>")
+        } else if head.starts_with(b"<style") {
+            Some("</style>")
+        } else {
+            None
+        };
+
+        let skip_to = match closing {
+            Some(tag) => rest
+                .to_ascii_lowercase()
+                .find(tag)
+                .map(|i| i + tag.len())
+                .unwrap_or(rest.len()),
+            None => rest.find('>').map(|i| i + 1).unwrap_or(rest.len()),
+        };
+
+        // Block-level tags become line breaks, inline tags become spaces
+        let tag = &head[1..];
+        let is_block = [&b"p"[..], b"/p", b"br", b"div", b"/div", b"li", b"h", b"/h", b"tr"]
+            .iter()
+            .any(|prefix| tag.starts_with(prefix));
+        text.push(if is_block { '\n' } else { ' ' });
+
+        rest = &rest[skip_to..];
+    }
+    text.push_str(rest);
+
+    decode_entities(&text)
+}
+
+/// Decode the common named and numeric XML/HTML entities
+pub(super) fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let entity_end = rest.find(';').filter(|&end| end <= 10);
+        let replacement = entity_end.and_then(|end| match &rest[1..end] {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            entity if entity.starts_with("#x") || entity.starts_with("#X") => {
+                u32::from_str_radix(&entity[2..], 16).ok().and_then(char::from_u32)
+            }
+            entity if entity.starts_with('#') => {
+                entity[1..].parse().ok().and_then(char::from_u32)
+            }
+            _ => None,
+        });
+
+        match (replacement, entity_end) {
+            (Some(c), Some(end)) => {
+                decoded.push(c);
+                rest = &rest[end + 1..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+
+    decoded
+}
@@ -1,52 +1,95 @@
 //! Gemini API HTTP client
 
 use reqwest::{Client, StatusCode};
-use std::path::Path;
-use tauri::AppHandle;
-use tokio::fs::File;
-use tokio::io::AsyncReadExt;
+use std::path::PathBuf;
 
 use super::types::{
-    ChatQueryResult, Content, CreateStoreRequest, Document, FileSearchStore,
-    FileSearchTool, GenerateContentRequest, GenerateContentResponse,
-    GenerationConfig, Operation, Part, Tool, UploadMetadata,
+    BatchEmbedContentsRequest, BatchEmbedContentsResponse, ChatQueryResult, Content,
+    CreateStoreRequest, CustomMetadata, Document, EmbedContentRequest, FileSearchStore, FileSearchTool,
+    GenerateContentRequest, GenerateContentResponse, GenerationConfig, Operation, Part,
+    Tool, UploadMetadata,
 };
 use crate::settings;
 
-const BASE_URL: &str = "https://generativelanguage.googleapis.com";
+/// Default API endpoint, overridable with the `gemini_base_url` setting
+const DEFAULT_BASE_URL: &str = "https://generativelanguage.googleapis.com";
 const API_VERSION: &str = "v1beta";
+const CHAT_MODEL: &str = "gemini-3-flash-preview";
+
+/// Maximum requests per batchEmbedContents call
+const EMBED_BATCH_SIZE: usize = 100;
+
+/// Time a connectivity check waits for the endpoint to answer
+const CONNECTION_CHECK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
 
 /// Gemini API client
 pub struct GeminiClient {
     client: Client,
-    app_handle: AppHandle,
+    /// Client for connectivity checks, keeping no idle connections
+    probe_client: Client,
+    /// Data directory holding the settings with the API key
+    data_dir: PathBuf,
+    base_url: String,
 }
 
 impl GeminiClient {
-    /// Create a new Gemini API client
-    pub fn new(app_handle: AppHandle) -> Self {
+    /// Create a new Gemini API client for the configured endpoint
+    pub fn new(data_dir: PathBuf) -> Self {
+        let base_url = settings::get_gemini_base_url_sync(&data_dir)
+            .unwrap_or_else(|| DEFAULT_BASE_URL.to_string());
+
+        Self::with_base_url(data_dir, base_url)
+    }
+
+    /// Create a Gemini API client for another endpoint, e.g. a mock server
+    pub fn with_base_url(data_dir: PathBuf, base_url: impl Into<String>) -> Self {
         let client = Client::builder()
             .timeout(std::time::Duration::from_secs(600))
             .build()
             .expect("Failed to create HTTP client");
 
-        Self { client, app_handle }
+        // A pooled connection could outlive the network, so every check connects anew
+        let probe_client = Client::builder()
+            .timeout(CONNECTION_CHECK_TIMEOUT)
+            .pool_max_idle_per_host(0)
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self {
+            client,
+            probe_client,
+            data_dir,
+            base_url: base_url.into(),
+        }
     }
 
     /// Get API key from settings
     fn get_api_key(&self) -> Result<String, String> {
-        settings::get_api_key_sync(&self.app_handle)
+        settings::get_api_key_sync(&self.data_dir)
             .ok_or_else(|| "Gemini API Key not configured".to_string())
     }
 
     /// Build API URL with path
     fn api_url(&self, path: &str) -> String {
-        format!("{}/{}/{}", BASE_URL, API_VERSION, path)
+        format!("{}/{}/{}", self.base_url, API_VERSION, path)
     }
 
     /// Build upload URL with path
     fn upload_url(&self, path: &str) -> String {
-        format!("{}/upload/{}/{}", BASE_URL, API_VERSION, path)
+        format!("{}/upload/{}/{}", self.base_url, API_VERSION, path)
+    }
+
+    /// Check that the API endpoint can be reached
+    ///
+    /// Any HTTP response counts, only failing to connect or to get an answer
+    /// means the network is down.
+    pub async fn check_connection(&self) -> Result<(), String> {
+        self.probe_client
+            .head(&self.base_url)
+            .send()
+            .await
+            .map(|_| ())
+            .map_err(|e| format!("Failed to reach {}: {}", self.base_url, e))
     }
 
     // =========================================================================
@@ -103,32 +146,31 @@ impl GeminiClient {
     // Document Operations
     // =========================================================================
 
-    /// Upload a document to a FileSearchStore using resumable upload
+    /// Upload document content to a FileSearchStore using resumable upload
     pub async fn upload_document(
         &self,
         store_name: &str,
-        file_path: &str,
+        content: Vec<u8>,
+        mime_type: &str,
         display_name: Option<&str>,
+        custom_metadata: &[CustomMetadata],
     ) -> Result<Operation, String> {
         let api_key = self.get_api_key()?;
-        let path = Path::new(file_path);
+        let file_size = content.len() as u64;
 
-        // Get file size and mime type
-        let metadata = tokio::fs::metadata(path)
-            .await
-            .map_err(|e| format!("Failed to read file metadata: {}", e))?;
-        let file_size = metadata.len();
-        let mime_type = mime_guess::from_path(path)
-            .first_or_octet_stream()
-            .to_string();
+        let metadata = UploadMetadata {
+            display_name: display_name.map(|s| s.to_string()),
+            custom_metadata: (!custom_metadata.is_empty()).then(|| custom_metadata.to_vec()),
+            ..Default::default()
+        };
 
         // Step 1: Initiate resumable upload
         let upload_url = self
-            .initiate_resumable_upload(store_name, file_size, &mime_type, display_name, &api_key)
+            .initiate_resumable_upload(store_name, file_size, mime_type, &metadata, &api_key)
             .await?;
 
         // Step 2: Upload file bytes
-        self.upload_file_bytes(&upload_url, path, file_size).await
+        self.upload_file_bytes(&upload_url, content).await
     }
 
     /// Initiate a resumable upload
@@ -137,7 +179,7 @@ impl GeminiClient {
         store_name: &str,
         file_size: u64,
         mime_type: &str,
-        display_name: Option<&str>,
+        metadata: &UploadMetadata,
         api_key: &str,
     ) -> Result<String, String> {
         let url = format!(
@@ -146,11 +188,6 @@ impl GeminiClient {
             api_key
         );
 
-        let metadata = UploadMetadata {
-            display_name: display_name.map(|s| s.to_string()),
-            ..Default::default()
-        };
-
         let response = self
             .client
             .post(&url)
@@ -159,7 +196,7 @@ impl GeminiClient {
             .header("X-Goog-Upload-Header-Content-Length", file_size.to_string())
             .header("X-Goog-Upload-Header-Content-Type", mime_type)
             .header("Content-Type", "application/json")
-            .json(&metadata)
+            .json(metadata)
             .send()
             .await
             .map_err(|e| format!("Failed to initiate upload: {}", e))?;
@@ -181,25 +218,15 @@ impl GeminiClient {
     async fn upload_file_bytes(
         &self,
         upload_url: &str,
-        path: &Path,
-        file_size: u64,
+        content: Vec<u8>,
     ) -> Result<Operation, String> {
-        let mut file = File::open(path)
-            .await
-            .map_err(|e| format!("Failed to open file: {}", e))?;
-
-        let mut buffer = Vec::with_capacity(file_size as usize);
-        file.read_to_end(&mut buffer)
-            .await
-            .map_err(|e| format!("Failed to read file: {}", e))?;
-
         let response = self
             .client
             .post(upload_url)
-            .header("Content-Length", file_size.to_string())
+            .header("Content-Length", content.len().to_string())
             .header("X-Goog-Upload-Offset", "0")
             .header("X-Goog-Upload-Command", "upload, finalize")
-            .body(buffer)
+            .body(content)
             .send()
             .await
             .map_err(|e| format!("Failed to upload file: {}", e))?;
@@ -274,10 +301,9 @@ impl GeminiClient {
         query: &str,
     ) -> Result<ChatQueryResult, String> {
         let api_key = self.get_api_key()?;
-        let model = "gemini-3-flash-preview";
         let url = format!(
             "{}?key={}",
-            self.api_url(&format!("models/{}:generateContent", model)),
+            self.api_url(&format!("models/{}:generateContent", CHAT_MODEL)),
             api_key
         );
 
@@ -372,10 +398,9 @@ impl GeminiClient {
         locale: &str,
     ) -> Result<Vec<String>, String> {
         let api_key = self.get_api_key()?;
-        let model = "gemini-3-flash-preview";
         let url = format!(
             "{}?key={}",
-            self.api_url(&format!("models/{}:generateContent", model)),
+            self.api_url(&format!("models/{}:generateContent", CHAT_MODEL)),
             api_key
         );
 
@@ -441,6 +466,120 @@ impl GeminiClient {
         Ok(serde_json::from_str(cleaned).unwrap_or_else(|_| vec![]))
     }
 
+    /// Generate a plain answer without file search, e.g. over locally retrieved context
+    pub async fn generate_text(
+        &self,
+        system_prompt: String,
+        prompt: String,
+        temperature: f32,
+    ) -> Result<String, String> {
+        let api_key = self.get_api_key()?;
+        let url = format!(
+            "{}?key={}",
+            self.api_url(&format!("models/{}:generateContent", CHAT_MODEL)),
+            api_key
+        );
+
+        let request = GenerateContentRequest {
+            contents: vec![Content {
+                role: "user".to_string(),
+                parts: vec![Part { text: Some(prompt) }],
+            }],
+            system_instruction: Some(Content {
+                role: "user".to_string(),
+                parts: vec![Part {
+                    text: Some(system_prompt),
+                }],
+            }),
+            tools: None,
+            generation_config: Some(GenerationConfig {
+                temperature: Some(temperature),
+                max_output_tokens: Some(4096),
+            }),
+        };
+
+        let response = self
+            .client
+            .post(&url)
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to generate content: {}", e))?;
+
+        let result: GenerateContentResponse = self.handle_response(response).await?;
+
+        Ok(result
+            .candidates
+            .first()
+            .and_then(|c| c.content.parts.first())
+            .and_then(|p| p.text.clone())
+            .unwrap_or_default())
+    }
+
+    // =========================================================================
+    // Embedding Operations
+    // =========================================================================
+
+    /// Embed texts with embedContent, returning one vector per input in order
+    ///
+    /// `task_type` is e.g. `RETRIEVAL_DOCUMENT` or `RETRIEVAL_QUERY`.
+    pub async fn embed_contents(
+        &self,
+        model: &str,
+        texts: &[String],
+        task_type: &str,
+    ) -> Result<Vec<Vec<f32>>, String> {
+        let api_key = self.get_api_key()?;
+        let model = model.trim_start_matches("models/");
+        let url = format!(
+            "{}?key={}",
+            self.api_url(&format!("models/{}:batchEmbedContents", model)),
+            api_key
+        );
+
+        let mut embeddings = Vec::with_capacity(texts.len());
+
+        for batch in texts.chunks(EMBED_BATCH_SIZE) {
+            let request = BatchEmbedContentsRequest {
+                requests: batch
+                    .iter()
+                    .map(|text| EmbedContentRequest {
+                        model: format!("models/{}", model),
+                        content: Content {
+                            role: "user".to_string(),
+                            parts: vec![Part {
+                                text: Some(text.clone()),
+                            }],
+                        },
+                        task_type: Some(task_type.to_string()),
+                    })
+                    .collect(),
+            };
+
+            let response = self
+                .client
+                .post(&url)
+                .json(&request)
+                .send()
+                .await
+                .map_err(|e| format!("Failed to embed content: {}", e))?;
+
+            let result: BatchEmbedContentsResponse = self.handle_response(response).await?;
+
+            if result.embeddings.len() != batch.len() {
+                return Err(format!(
+                    "Expected {} embeddings, got {}",
+                    batch.len(),
+                    result.embeddings.len()
+                ));
+            }
+
+            embeddings.extend(result.embeddings.into_iter().map(|e| e.values));
+        }
+
+        Ok(embeddings)
+    }
+
     // =========================================================================
     // Helper Functions
     // =========================================================================
@@ -0,0 +1,46 @@
+//! File Search upload limits
+
+/// Maximum size of a single uploaded document
+pub const MAX_UPLOAD_BYTES: u64 = 100 * 1024 * 1024;
+
+/// Non-text mime types accepted by File Search (all `text/*` types are accepted)
+const SUPPORTED_APPLICATION_MIME_TYPES: &[&str] = &[
+    "application/pdf",
+    "application/json",
+    "application/xml",
+    "application/javascript",
+    "application/typescript",
+    "application/x-javascript",
+    "application/x-typescript",
+    "application/x-sh",
+    "application/x-python",
+    "application/x-php",
+    "application/x-tex",
+    "application/x-latex",
+    "application/sql",
+    "application/msword",
+    "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    "application/vnd.ms-excel",
+    "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+    "application/vnd.ms-powerpoint",
+    "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+    "application/vnd.oasis.opendocument.spreadsheet",
+    "application/vnd.oasis.opendocument.presentation",
+];
+
+/// Check whether File Search accepts uploads of the given mime type as-is
+pub fn is_supported_mime_type(mime_type: &str) -> bool {
+    let mime_type = mime_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_lowercase();
+
+    // RTF is served as text/rtf but is not parsed as text
+    if mime_type == "text/rtf" {
+        return false;
+    }
+
+    mime_type.starts_with("text/") || SUPPORTED_APPLICATION_MIME_TYPES.contains(&mime_type.as_str())
+}
@@ -4,6 +4,8 @@
 //! for managing FileSearchStores and Documents.
 
 mod client;
+mod limits;
 pub mod types;
 
 pub use client::GeminiClient;
+pub use limits::*;
@@ -304,6 +304,40 @@ pub struct UsageMetadata {
     pub total_token_count: i32,
 }
 
+// =========================================================================
+// Embedding Types
+// =========================================================================
+
+/// Request for batchEmbedContents
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchEmbedContentsRequest {
+    pub requests: Vec<EmbedContentRequest>,
+}
+
+/// A single embedContent request
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmbedContentRequest {
+    /// Model resource name, e.g. `models/gemini-embedding-001`
+    pub model: String,
+    pub content: Content,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task_type: Option<String>,
+}
+
+/// Response from batchEmbedContents
+#[derive(Debug, Clone, Deserialize)]
+pub struct BatchEmbedContentsResponse {
+    #[serde(default)]
+    pub embeddings: Vec<ContentEmbedding>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ContentEmbedding {
+    #[serde(default)]
+    pub values: Vec<f32>,
+}
+
 /// Chat query result for frontend
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -0,0 +1,318 @@
+//! Local HTTP/JSON API
+//!
+//! An opt-in server on 127.0.0.1 that lets editors, scripts and dashboards
+//! list stores, queue documents and ask questions without going through the
+//! webview. Every request must send the token from the settings as
+//! `Authorization: Bearer <token>`.
+
+use axum::extract::{Path as UrlPath, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::Deserialize;
+use serde_json::json;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::async_runtime::JoinHandle;
+use tokio::net::TcpListener;
+use tokio::sync::{oneshot, Mutex};
+
+use crate::backend::Backends;
+use crate::commands::chat::ChatResult;
+use crate::commands::documents::{queue_documents, FileInfo, UploadDocumentsResult};
+use crate::db::{Database, StoreWithStatus};
+use crate::settings::HttpApiSettings;
+use crate::sync::SyncState;
+
+/// How long a restart waits for open connections before dropping them
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+// ============================================================================
+// Request Types
+// ============================================================================
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct QueryRequest {
+    store_names: Vec<String>,
+    query: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SuggestRequest {
+    store_names: Vec<String>,
+    #[serde(default = "default_locale")]
+    locale: String,
+}
+
+fn default_locale() -> String {
+    "en".to_string()
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UploadRequest {
+    /// Paths of files on this machine
+    paths: Vec<PathBuf>,
+}
+
+/// Error answered as `{"error": "..."}`
+struct ApiError(StatusCode, String);
+
+impl ApiError {
+    fn bad_request(message: impl Into<String>) -> Self {
+        Self(StatusCode::BAD_REQUEST, message.into())
+    }
+}
+
+/// Errors of the database and backends reporting a missing store or document
+const NOT_FOUND_ERRORS: &[&str] = &["not found", "Query returned no rows", "NOT_FOUND", "(404"];
+
+/// Errors of the backends rejecting the request itself
+const BAD_REQUEST_ERRORS: &[&str] = &[
+    "No stores provided",
+    "Unknown search backend",
+    "Local-only stores",
+    "(400",
+];
+
+/// Failures of the search backend or the database
+///
+/// Errors are plain messages, so missing resources and invalid requests are
+/// told apart by their wording; anything else is an upstream failure.
+impl From<String> for ApiError {
+    fn from(message: String) -> Self {
+        let status = if NOT_FOUND_ERRORS.iter().any(|e| message.contains(e)) {
+            StatusCode::NOT_FOUND
+        } else if BAD_REQUEST_ERRORS.iter().any(|e| message.contains(e)) {
+            StatusCode::BAD_REQUEST
+        } else {
+            StatusCode::BAD_GATEWAY
+        };
+
+        Self(status, message)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(json!({ "error": self.1 }))).into_response()
+    }
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+#[derive(Clone)]
+struct ApiContext {
+    db: Arc<Database>,
+    backends: Arc<Backends>,
+    sync_state: Arc<SyncState>,
+    token: Arc<str>,
+}
+
+/// Compare tokens without leaking the matching prefix length through timing
+fn tokens_match(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+async fn require_token(State(ctx): State<ApiContext>, request: Request, next: Next) -> Response {
+    let authorized = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|token| tokens_match(token, &ctx.token));
+
+    if !authorized {
+        return ApiError(
+            StatusCode::UNAUTHORIZED,
+            "Invalid or missing token".to_string(),
+        )
+        .into_response();
+    }
+
+    next.run(request).await
+}
+
+async fn list_stores(
+    State(ctx): State<ApiContext>,
+) -> Result<Json<Vec<StoreWithStatus>>, ApiError> {
+    Ok(Json(ctx.db.call(|db| db.get_stores()).await?))
+}
+
+async fn query_stores(
+    State(ctx): State<ApiContext>,
+    Json(body): Json<QueryRequest>,
+) -> Result<Json<ChatResult>, ApiError> {
+    if body.store_names.is_empty() {
+        return Err(ApiError::bad_request("No stores provided"));
+    }
+
+    if body.query.trim().is_empty() {
+        return Err(ApiError::bad_request("Query cannot be empty"));
+    }
+
+    let result = ctx.backends.query(&body.store_names, &body.query).await?;
+
+    Ok(Json(ChatResult {
+        content: result.content,
+        sources: result.sources,
+    }))
+}
+
+async fn suggest_questions(
+    State(ctx): State<ApiContext>,
+    Json(body): Json<SuggestRequest>,
+) -> Result<Json<Vec<String>>, ApiError> {
+    if body.store_names.is_empty() {
+        return Ok(Json(vec![]));
+    }
+
+    Ok(Json(
+        ctx.backends
+            .suggest_questions(&body.store_names, &body.locale)
+            .await?,
+    ))
+}
+
+async fn upload_documents(
+    State(ctx): State<ApiContext>,
+    UrlPath(store_id): UrlPath<String>,
+    Json(body): Json<UploadRequest>,
+) -> Result<Json<UploadDocumentsResult>, ApiError> {
+    let id = store_id.clone();
+    ctx.db
+        .call(move |db| db.get_store(&id))
+        .await
+        .map_err(|_| {
+            ApiError(
+                StatusCode::NOT_FOUND,
+                format!("Store not found: {}", store_id),
+            )
+        })?;
+
+    let files = body.paths.into_iter().map(FileInfo::from_path).collect();
+    let result = ctx
+        .db
+        .call(move |db| queue_documents(db, &store_id, files))
+        .await?;
+
+    // Notify background sync to pick up the new documents
+    if !result.documents.is_empty() {
+        ctx.sync_state.notify();
+    }
+
+    Ok(Json(result))
+}
+
+fn router(ctx: ApiContext) -> Router {
+    Router::new()
+        .route("/v1/stores", get(list_stores))
+        .route("/v1/stores/:id/documents", post(upload_documents))
+        .route("/v1/query", post(query_stores))
+        .route("/v1/suggest-questions", post(suggest_questions))
+        .layer(middleware::from_fn_with_state(ctx.clone(), require_token))
+        .with_state(ctx)
+}
+
+// ============================================================================
+// Server
+// ============================================================================
+
+struct RunningServer {
+    shutdown: oneshot::Sender<()>,
+    task: JoinHandle<()>,
+}
+
+/// The running HTTP API server, if enabled
+pub struct HttpApiState {
+    db: Arc<Database>,
+    backends: Arc<Backends>,
+    sync_state: Arc<SyncState>,
+    server: Mutex<Option<RunningServer>>,
+}
+
+impl HttpApiState {
+    pub fn new(
+        db: Arc<Database>,
+        backends: Arc<Backends>,
+        sync_state: Arc<SyncState>,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            db,
+            backends,
+            sync_state,
+            server: Mutex::new(None),
+        })
+    }
+
+    /// Start, restart or stop the server to match the settings
+    ///
+    /// Returns the bound address while the server runs.
+    pub async fn apply(&self, settings: &HttpApiSettings) -> Result<Option<SocketAddr>, String> {
+        let mut running = self.server.lock().await;
+
+        // Stop the running server and wait for its listener to close, so the
+        // port can be bound again and the old token stops working
+        if let Some(mut server) = running.take() {
+            let _ = server.shutdown.send(());
+
+            if tokio::time::timeout(SHUTDOWN_TIMEOUT, &mut server.task)
+                .await
+                .is_err()
+            {
+                server.task.abort();
+                let _ = server.task.await;
+            }
+        }
+
+        let token = match (&settings.token, settings.enabled) {
+            (Some(token), true) if !token.is_empty() => token.clone(),
+            _ => return Ok(None),
+        };
+
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, settings.port))
+            .await
+            .map_err(|e| format!("Failed to start HTTP API on port {}: {}", settings.port, e))?;
+        let address = listener
+            .local_addr()
+            .map_err(|e| format!("Failed to start HTTP API: {}", e))?;
+
+        let app = router(ApiContext {
+            db: self.db.clone(),
+            backends: self.backends.clone(),
+            sync_state: self.sync_state.clone(),
+            token: token.into(),
+        });
+        let (sender, receiver) = oneshot::channel();
+
+        let task = tauri::async_runtime::spawn(async move {
+            let server = axum::serve(listener, app).with_graceful_shutdown(async {
+                let _ = receiver.await;
+            });
+
+            if let Err(e) = server.await {
+                eprintln!("HTTP API server failed: {}", e);
+            }
+        });
+
+        *running = Some(RunningServer {
+            shutdown: sender,
+            task,
+        });
+
+        Ok(Some(address))
+    }
+}
@@ -0,0 +1,340 @@
+//! Directory import
+//!
+//! Walks a directory tree and decides which files to import into a store.
+//! Exclude rules use `.gitignore` syntax and `.gitignore` files found in the
+//! tree are honoured as well; include rules are globs matched against the path
+//! relative to the imported directory. Hidden and system files are skipped.
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::{DirEntry, WalkBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use crate::db::Store;
+use crate::validation::validate_upload_file;
+
+/// Maximum number of files imported from one directory
+pub const MAX_IMPORT_FILES: usize = 5000;
+
+/// File and directory names created by the operating system
+const SYSTEM_FILE_NAMES: &[&str] = &[
+    "Thumbs.db",
+    "ehthumbs.db",
+    "desktop.ini",
+    "Icon\r",
+    "$RECYCLE.BIN",
+    "System Volume Information",
+    "__MACOSX",
+];
+
+/// Include and exclude rules, one pattern per line (`#` starts a comment)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportRules {
+    pub include_patterns: Option<String>,
+    pub exclude_patterns: Option<String>,
+}
+
+impl ImportRules {
+    /// Rules saved on a store
+    pub fn from_store(store: &Store) -> Self {
+        Self {
+            include_patterns: store.include_patterns.clone(),
+            exclude_patterns: store.exclude_patterns.clone(),
+        }
+    }
+}
+
+/// Why a file was left out of an import
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    Hidden,
+    System,
+    Excluded,
+    NotIncluded,
+    AlreadyImported,
+    Rejected,
+}
+
+/// A file or directory left out of an import
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedFile {
+    /// Path relative to the imported directory
+    pub name: String,
+    pub path: String,
+    pub reason: SkipReason,
+    pub message: String,
+}
+
+/// A file to be imported
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportFile {
+    /// Path relative to the imported directory, used as the document name
+    pub name: String,
+    pub path: String,
+    pub size: u64,
+}
+
+/// Files an import would add, shown to the user before committing
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportPreview {
+    pub files: Vec<ImportFile>,
+    pub file_count: usize,
+    pub total_size: u64,
+    pub skipped: Vec<SkippedFile>,
+    /// The directory has more than [`MAX_IMPORT_FILES`] importable files
+    pub truncated: bool,
+}
+
+/// Non-comment, non-empty lines of a pattern list
+fn pattern_lines(patterns: Option<&str>) -> impl Iterator<Item = &str> {
+    patterns
+        .unwrap_or_default()
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+}
+
+fn build_exclude_matcher(root: &Path, patterns: Option<&str>) -> Result<Gitignore, String> {
+    let mut builder = GitignoreBuilder::new(root);
+    for line in pattern_lines(patterns) {
+        builder
+            .add_line(None, line)
+            .map_err(|e| format!("Invalid exclude pattern '{}': {}", line, e))?;
+    }
+    builder
+        .build()
+        .map_err(|e| format!("Failed to build exclude rules: {}", e))
+}
+
+/// Build the include globs, or `None` when everything is included
+///
+/// Like `.gitignore`, a pattern without a slash matches at any depth and a
+/// leading slash anchors it to the imported directory.
+fn build_include_set(patterns: Option<&str>) -> Result<Option<GlobSet>, String> {
+    let mut builder = GlobSetBuilder::new();
+    let mut count = 0;
+
+    for line in pattern_lines(patterns) {
+        let pattern = match line.strip_prefix('/') {
+            Some(anchored) => anchored.to_string(),
+            None if !line.contains('/') => format!("**/{}", line),
+            None => line.to_string(),
+        };
+        let glob = Glob::new(&pattern)
+            .map_err(|e| format!("Invalid include pattern '{}': {}", line, e))?;
+        builder.add(glob);
+        count += 1;
+    }
+
+    if count == 0 {
+        return Ok(None);
+    }
+
+    builder
+        .build()
+        .map(Some)
+        .map_err(|e| format!("Failed to build include rules: {}", e))
+}
+
+/// Path relative to the root, with `/` separators
+fn relative_name(root: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn is_hidden(entry: &DirEntry) -> bool {
+    if entry.file_name().to_string_lossy().starts_with('.') {
+        return true;
+    }
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::MetadataExt;
+        const FILE_ATTRIBUTE_HIDDEN: u32 = 0x2;
+        if let Ok(metadata) = entry.metadata() {
+            return metadata.file_attributes() & FILE_ATTRIBUTE_HIDDEN != 0;
+        }
+    }
+
+    false
+}
+
+fn is_system(entry: &DirEntry) -> bool {
+    let name = entry.file_name().to_string_lossy();
+    // `~$` prefixed files are Office lock files
+    if SYSTEM_FILE_NAMES.contains(&name.as_ref()) || name.starts_with("~$") {
+        return true;
+    }
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::MetadataExt;
+        const FILE_ATTRIBUTE_SYSTEM: u32 = 0x4;
+        if let Ok(metadata) = entry.metadata() {
+            return metadata.file_attributes() & FILE_ATTRIBUTE_SYSTEM != 0;
+        }
+    }
+
+    false
+}
+
+/// Walk `root` and collect the files that would be imported with `rules`
+///
+/// Files whose path is in `existing_paths` are reported as already imported.
+/// This is blocking and should run on a blocking thread.
+pub fn scan_directory(
+    root: &Path,
+    rules: &ImportRules,
+    existing_paths: &HashSet<String>,
+) -> Result<ImportPreview, String> {
+    if !root.is_dir() {
+        return Err(format!("Not a directory: {}", root.display()));
+    }
+
+    let excludes = Arc::new(build_exclude_matcher(
+        root,
+        rules.exclude_patterns.as_deref(),
+    )?);
+    let includes = build_include_set(rules.include_patterns.as_deref())?;
+    let skipped = Arc::new(Mutex::new(Vec::new()));
+
+    let walker = {
+        let root = root.to_path_buf();
+        let skipped = skipped.clone();
+
+        WalkBuilder::new(&root)
+            .standard_filters(false)
+            .git_ignore(true)
+            .require_git(false)
+            .follow_links(false)
+            .sort_by_file_name(|a, b| a.cmp(b))
+            .filter_entry(move |entry| {
+                // The selected directory itself is always walked
+                if entry.depth() == 0 {
+                    return true;
+                }
+
+                let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+                let name = relative_name(&root, entry.path());
+                let skip = if is_hidden(entry) {
+                    Some((SkipReason::Hidden, "Hidden file".to_string()))
+                } else if is_system(entry) {
+                    Some((SkipReason::System, "System file".to_string()))
+                } else {
+                    match excludes.matched(entry.path(), is_dir) {
+                        ignore::Match::Ignore(glob) => Some((
+                            SkipReason::Excluded,
+                            format!("Matches exclude pattern '{}'", glob.original()),
+                        )),
+                        _ => None,
+                    }
+                };
+
+                match skip {
+                    Some((reason, message)) => {
+                        if let Ok(mut skipped) = skipped.lock() {
+                            skipped.push(SkippedFile {
+                                name: if is_dir { format!("{}/", name) } else { name },
+                                path: entry.path().to_string_lossy().to_string(),
+                                reason,
+                                message,
+                            });
+                        }
+                        false
+                    }
+                    None => true,
+                }
+            })
+            .build()
+    };
+
+    let mut files = Vec::new();
+    let mut total_size = 0;
+    let mut truncated = false;
+    let mut other_skipped = Vec::new();
+
+    for result in walker {
+        let entry = match result {
+            Ok(entry) => entry,
+            Err(e) => {
+                // Unreadable directories are skipped instead of failing the import
+                eprintln!("Skipping unreadable entry during import: {}", e);
+                continue;
+            }
+        };
+
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+
+        let name = relative_name(root, entry.path());
+        let path = entry.path().to_string_lossy().to_string();
+
+        if let Some(includes) = &includes {
+            if !includes.is_match(&name) {
+                other_skipped.push(SkippedFile {
+                    name,
+                    path,
+                    reason: SkipReason::NotIncluded,
+                    message: "Does not match any include pattern".to_string(),
+                });
+                continue;
+            }
+        }
+
+        if existing_paths.contains(&path) {
+            other_skipped.push(SkippedFile {
+                name,
+                path,
+                reason: SkipReason::AlreadyImported,
+                message: "Already in this store".to_string(),
+            });
+            continue;
+        }
+
+        let size = match validate_upload_file(entry.path()) {
+            Ok(size) => size,
+            Err((_, message)) => {
+                other_skipped.push(SkippedFile {
+                    name,
+                    path,
+                    reason: SkipReason::Rejected,
+                    message,
+                });
+                continue;
+            }
+        };
+
+        if files.len() >= MAX_IMPORT_FILES {
+            truncated = true;
+            break;
+        }
+
+        total_size += size;
+        files.push(ImportFile { name, path, size });
+    }
+
+    let mut skipped = std::mem::take(&mut *skipped.lock().map_err(|e| e.to_string())?);
+    skipped.extend(other_skipped);
+    skipped.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(ImportPreview {
+        file_count: files.len(),
+        files,
+        total_size,
+        skipped,
+        truncated,
+    })
+}
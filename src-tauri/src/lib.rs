@@ -3,34 +3,59 @@
 //! A Tauri-based desktop application for document management and search.
 
 // Module declarations
+// Core modules are public so integration tests and the CLI binary can drive them
+pub mod backend;
+pub mod backup;
+pub mod bundle;
+pub mod cli;
 mod commands;
 mod config;
-mod db;
-mod gemini;
-mod polling;
-mod settings;
+pub mod db;
+pub mod events;
+pub mod export;
+mod extract;
+pub mod gemini;
+pub mod http_api;
+mod import;
+pub mod mcp;
+pub mod polling;
+pub mod settings;
 mod shortcuts;
 mod state;
-mod sync;
+pub mod sync;
 mod tray;
+mod validation;
 mod window;
 
 // Use modules
 use std::sync::Arc;
 
 use tauri::{Manager, RunEvent};
+use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
 
 use commands::{
-    create_store, delete_document, delete_store, get_documents, get_documents_by_uids, get_store,
-    get_stores, hide_window, open_directory, open_document_file, open_file, query_stores,
-    set_always_on_top, set_prevent_auto_hide, set_window_movable, set_window_size, show_window,
-    suggest_questions, upload_documents,
+    copy_documents, create_backup, create_store, delete_document, delete_documents, delete_store,
+    export_conversation, export_store, get_document_timeline, get_documents, get_documents_by_uids,
+    get_http_api_settings, get_store, get_stores, get_sync_status, get_trash, get_trash_settings,
+    hide_window, import_directory, import_store, list_backups, list_documents, move_documents,
+    open_directory, open_document_file, open_file, pause_sync, preview_directory_import,
+    query_stores, regenerate_http_api_token, restore_backup, restore_document, restore_store,
+    resume_sync, retry_documents, reupload_documents, search_local, set_always_on_top,
+    set_http_api_settings, set_prevent_auto_hide, set_trash_settings, set_window_movable,
+    set_window_size, show_window, suggest_questions, tag_documents, update_store, upload_documents,
 };
+use backend::{Backends, LocalBackend};
+use backup::{open_database, start_backup_task};
 use config::AppConfig;
 use db::Database;
+use events::{EventSink, TauriEventSink};
 use gemini::GeminiClient;
+use http_api::HttpApiState;
 use polling::{start_polling_task, PollingState};
-use settings::{get_api_key, set_api_key, has_api_key, clear_api_key};
+use settings::{
+    clear_api_key, get_api_key, get_backup_settings, get_openai_settings, get_retrieval_settings,
+    has_api_key, set_api_key, set_backup_settings, set_openai_settings, set_retrieval_settings,
+};
 use shortcuts::{set_global_shortcut_enabled, setup_global_shortcut};
 use state::AppState;
 use sync::{start_sync_task, SyncState};
@@ -44,6 +69,7 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_store::Builder::new().build())
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+        .plugin(tauri_plugin_clipboard_manager::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_http::init())
@@ -64,21 +90,62 @@ pub fn run() {
             get_stores,
             get_store,
             create_store,
+            update_store,
             delete_store,
+            export_store,
+            import_store,
             // Document commands
             get_documents,
+            list_documents,
+            get_document_timeline,
             get_documents_by_uids,
             upload_documents,
             delete_document,
+            delete_documents,
+            retry_documents,
+            reupload_documents,
+            tag_documents,
+            move_documents,
+            copy_documents,
             open_document_file,
             // Settings commands
             get_api_key,
             set_api_key,
             has_api_key,
             clear_api_key,
+            get_openai_settings,
+            set_openai_settings,
+            get_retrieval_settings,
+            set_retrieval_settings,
             // Chat commands
             query_stores,
             suggest_questions,
+            export_conversation,
+            // Import commands
+            preview_directory_import,
+            import_directory,
+            // Search commands
+            search_local,
+            // HTTP API commands
+            get_http_api_settings,
+            set_http_api_settings,
+            regenerate_http_api_token,
+            // Backup commands
+            list_backups,
+            create_backup,
+            restore_backup,
+            get_backup_settings,
+            set_backup_settings,
+            // Trash commands
+            get_trash,
+            restore_store,
+            restore_document,
+            get_trash_settings,
+            set_trash_settings,
+            // Sync commands
+            get_sync_status,
+            pause_sync,
+            resume_sync,
             // Shortcut commands
             set_global_shortcut_enabled,
         ])
@@ -86,15 +153,43 @@ pub fn run() {
             // Load configuration
             let config = AppConfig::default();
 
-            // Initialize database
-            let db = Arc::new(
-                Database::new(app.handle()).expect("Failed to initialize database"),
-            );
+            // Settings and the database live in the app data directory
+            let data_dir = settings::app_data_dir(app.handle())?;
+
+            // Initialize database, replacing a damaged file with the newest sound backup
+            let (db, recovery) = open_database(&data_dir)?;
+            let db = Arc::new(db);
             app.manage(db.clone());
 
-            // Initialize Gemini API client
-            let gemini_client = Arc::new(GeminiClient::new(app.handle().clone()));
-            app.manage(gemini_client.clone());
+            if let Some(recovery) = recovery {
+                let message = match &recovery.restored_from {
+                    Some(backup) => format!(
+                        "The database was damaged and has been restored from the backup {}. Changes made since then are lost.",
+                        backup
+                    ),
+                    None => "The database was damaged and no usable backup was found, so the app starts with an empty database.".to_string(),
+                };
+                app.dialog()
+                    .message(format!(
+                        "{}\n\nThe damaged file was kept at {}.",
+                        message, recovery.damaged_path
+                    ))
+                    .title("Database recovered")
+                    .kind(MessageDialogKind::Warning)
+                    .show(|_| {});
+            }
+
+            // Take scheduled backups of the database
+            start_backup_task(db.clone(), data_dir.clone());
+
+            // Initialize search backends (Gemini File Search and local retrieval)
+            let gemini_client = Arc::new(GeminiClient::new(data_dir.clone()));
+            let backends = Backends::new(
+                db.clone(),
+                gemini_client.clone(),
+                Arc::new(LocalBackend::new(data_dir.clone(), db.clone(), gemini_client)),
+            );
+            app.manage(backends.clone());
 
             // Initialize polling state (for document status polling)
             let polling_state = PollingState::new();
@@ -102,18 +197,38 @@ pub fn run() {
 
             // Initialize sync state (for background sync)
             let sync_state = SyncState::new();
+            sync_state.set_trash_retention_days(
+                settings::get_trash_settings_sync(&data_dir).retention_days,
+            );
+            sync_state.set_paused(settings::get_sync_settings_sync(&data_dir).paused);
             app.manage(sync_state.clone());
 
+            // Start the local HTTP API if enabled in settings
+            let http_api = HttpApiState::new(db.clone(), backends.clone(), sync_state.clone());
+            app.manage(http_api.clone());
+
+            let http_api_settings = settings::get_http_api_settings_sync(&data_dir);
+            if http_api_settings.enabled {
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = http_api.apply(&http_api_settings).await {
+                        eprintln!("Failed to start HTTP API: {}", e);
+                    }
+                });
+            }
+
+            // Sync progress is forwarded to the frontend as events
+            let events: Arc<dyn EventSink> = Arc::new(TauriEventSink::new(app.handle().clone()));
+
             // Start background polling task (for checking document processing status)
             start_polling_task(
-                app.handle().clone(),
+                events.clone(),
                 db.clone(),
-                gemini_client.clone(),
+                backends.clone(),
                 polling_state.clone(),
             );
 
             // Start background sync task (for uploading stores/documents)
-            start_sync_task(app.handle().clone(), db, gemini_client, sync_state, polling_state);
+            start_sync_task(events, db, backends, sync_state, polling_state);
 
             // Create main window
             let window = create_main_window(app.handle(), &config)?;
@@ -133,6 +248,12 @@ pub fn run() {
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
         .run(|app, event| {
+            if let RunEvent::Exit = event {
+                // Hand the sync lease to the CLI right away instead of letting it expire
+                let db = app.state::<Arc<Database>>();
+                let _ = app.state::<Arc<SyncState>>().release_lease(&db);
+            }
+
             #[cfg(target_os = "macos")]
             {
                 if let RunEvent::Reopen { .. } = event {
@@ -0,0 +1,318 @@
+//! Model Context Protocol server
+//!
+//! Exposes stores as MCP tools so coding agents can ground their answers in
+//! Mizly documents. The server speaks newline-delimited JSON-RPC 2.0 over
+//! stdio and is started with `mizly-cli mcp`; it only reads, so it can run
+//! next to the app.
+
+use serde_json::{json, Value};
+use std::path::Path;
+use std::sync::Arc;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::backend::Backends;
+use crate::db::Database;
+use crate::extract;
+
+/// Protocol revision answered when the client asks for one we don't know
+pub const PROTOCOL_VERSION: &str = "2025-06-18";
+
+/// Protocol revisions the server is compatible with, newest first
+const SUPPORTED_VERSIONS: &[&str] = &[PROTOCOL_VERSION, "2025-03-26", "2024-11-05"];
+
+// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// Error answered in place of a result
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+// ============================================================================
+// Tools
+// ============================================================================
+
+fn tool_definitions() -> Value {
+    json!([
+        {
+            "name": "list_stores",
+            "description": "List the Mizly stores with their ids, titles, backends and document counts.",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "search_documents",
+            "description": "Ask a question answered from the documents of a Mizly store. Returns the answer and the documents it is grounded in.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "store": { "type": "string", "description": "Store id or title" },
+                    "query": { "type": "string", "description": "Question or search request" }
+                },
+                "required": ["store", "query"]
+            }
+        },
+        {
+            "name": "get_document",
+            "description": "Get a document's metadata and, when it can be read locally, its text.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "id": { "type": "string", "description": "Document id" }
+                },
+                "required": ["id"]
+            }
+        }
+    ])
+}
+
+/// Read a required string argument of a tool call
+fn string_argument<'a>(arguments: &'a Value, name: &str) -> Result<&'a str, String> {
+    arguments
+        .get(name)
+        .and_then(Value::as_str)
+        .filter(|value| !value.trim().is_empty())
+        .ok_or_else(|| format!("Missing argument: {}", name))
+}
+
+fn to_text(value: &Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_default()
+}
+
+// ============================================================================
+// Server
+// ============================================================================
+
+/// MCP server over one data directory
+pub struct McpServer {
+    db: Arc<Database>,
+    backends: Arc<Backends>,
+}
+
+impl McpServer {
+    pub fn new(db: Arc<Database>, backends: Arc<Backends>) -> Self {
+        Self { db, backends }
+    }
+
+    async fn list_stores(&self) -> Result<String, String> {
+        let stores: Vec<Value> = self
+            .db
+            .call(|db| db.get_stores())
+            .await?
+            .into_iter()
+            .map(|s| {
+                json!({
+                    "id": s.store.id,
+                    "title": s.store.title,
+                    "backend": s.store.backend,
+                    "syncStatus": s.store.sync_status,
+                    "documentCount": s.document_count,
+                    "processingCount": s.local_pending_count,
+                    "directoryPath": s.store.directory_path,
+                })
+            })
+            .collect();
+
+        Ok(to_text(&json!(stores)))
+    }
+
+    async fn search_documents(&self, arguments: &Value) -> Result<String, String> {
+        let store = string_argument(arguments, "store")?.to_string();
+        let store = self.db.call(move |db| db.find_store(&store)).await?;
+        let query = string_argument(arguments, "query")?;
+
+        let store_name = store
+            .gemini_name
+            .clone()
+            .ok_or_else(|| format!("Store {} isn't synced yet", store.title))?;
+        let store_names = [store_name];
+
+        let result = self.backends.query(&store_names, query).await?;
+
+        // Sources are display names; add ids so the agent can open them
+        let store_id = store.id.clone();
+        let documents: Vec<Value> = self
+            .db
+            .call(move |db| db.get_documents_by_store(&store_id))
+            .await?
+            .into_iter()
+            .filter(|d| result.sources.contains(&d.name))
+            .map(|d| json!({ "id": d.id, "name": d.name, "path": d.path }))
+            .collect();
+
+        Ok(to_text(&json!({
+            "answer": result.content,
+            "sources": result.sources,
+            "documents": documents,
+        })))
+    }
+
+    async fn get_document(&self, arguments: &Value) -> Result<String, String> {
+        let id = string_argument(arguments, "id")?.to_string();
+        let document = self.db.call(move |db| db.get_document(&id)).await?;
+
+        let text = match extract::extract_text(Path::new(&document.path)).await {
+            Ok(text) => text,
+            Err(e) => {
+                eprintln!(
+                    "Failed to extract text from document {}: {}",
+                    document.id, e
+                );
+                None
+            }
+        };
+
+        let mut value = serde_json::to_value(&document)
+            .map_err(|e| format!("Failed to serialize document: {}", e))?;
+        value["text"] = json!(text);
+
+        Ok(to_text(&value))
+    }
+
+    /// Run a tool; failures are reported to the agent as tool errors
+    async fn call_tool(&self, params: &Value) -> Result<Value, RpcError> {
+        let name = params
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or_else(|| RpcError::new(INVALID_PARAMS, "Missing tool name"))?;
+        let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
+
+        let result = match name {
+            "list_stores" => self.list_stores().await,
+            "search_documents" => self.search_documents(&arguments).await,
+            "get_document" => self.get_document(&arguments).await,
+            _ => {
+                return Err(RpcError::new(
+                    INVALID_PARAMS,
+                    format!("Unknown tool: {}", name),
+                ))
+            }
+        };
+
+        let (text, is_error) = match result {
+            Ok(text) => (text, false),
+            Err(e) => (e, true),
+        };
+
+        Ok(json!({
+            "content": [{ "type": "text", "text": text }],
+            "isError": is_error,
+        }))
+    }
+
+    fn initialize(&self, params: &Value) -> Value {
+        let requested = params.get("protocolVersion").and_then(Value::as_str);
+        let version = requested
+            .filter(|v| SUPPORTED_VERSIONS.contains(v))
+            .unwrap_or(PROTOCOL_VERSION);
+
+        json!({
+            "protocolVersion": version,
+            "capabilities": { "tools": {} },
+            "serverInfo": { "name": "mizly", "version": env!("CARGO_PKG_VERSION") },
+            "instructions": "Use list_stores to find a store, search_documents to ask it questions and get_document to read a source document.",
+        })
+    }
+
+    async fn dispatch(&self, method: &str, params: &Value) -> Result<Value, RpcError> {
+        match method {
+            "initialize" => Ok(self.initialize(params)),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+            "tools/call" => self.call_tool(params).await,
+            _ => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("Method not found: {}", method),
+            )),
+        }
+    }
+
+    /// Handle one JSON-RPC message
+    ///
+    /// Returns the response, or `None` for notifications.
+    pub async fn handle(&self, message: Value) -> Option<Value> {
+        let id = message.get("id").cloned();
+        let Some(method) = message.get("method").and_then(Value::as_str) else {
+            // Responses from the client are not expected, ignore them
+            return id.is_none().then(|| {
+                error_response(
+                    Value::Null,
+                    RpcError::new(INVALID_REQUEST, "Invalid request"),
+                )
+            });
+        };
+
+        let params = message.get("params").cloned().unwrap_or(json!({}));
+        let result = self.dispatch(method, &params).await;
+
+        // Notifications (no id) get no response
+        let id = id?;
+
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(e) => error_response(id, e),
+        })
+    }
+
+    /// Answer messages read line by line until the input is closed
+    pub async fn serve<R, W>(&self, reader: R, mut writer: W) -> Result<(), String>
+    where
+        R: AsyncBufRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        let mut lines = reader.lines();
+
+        while let Some(line) = lines
+            .next_line()
+            .await
+            .map_err(|e| format!("Failed to read message: {}", e))?
+        {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let response = match serde_json::from_str::<Value>(&line) {
+                Ok(message) => self.handle(message).await,
+                Err(e) => Some(error_response(
+                    Value::Null,
+                    RpcError::new(PARSE_ERROR, format!("Parse error: {}", e)),
+                )),
+            };
+
+            if let Some(response) = response {
+                let mut line = response.to_string();
+                line.push('\n');
+                writer
+                    .write_all(line.as_bytes())
+                    .await
+                    .map_err(|e| format!("Failed to write response: {}", e))?;
+                writer
+                    .flush()
+                    .await
+                    .map_err(|e| format!("Failed to write response: {}", e))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn error_response(id: Value, error: RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": error.code, "message": error.message },
+    })
+}
@@ -1,32 +1,19 @@
-//! Document status polling for Gemini operations
+//! Document status polling for backend upload operations
 
 use serde::Serialize;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
-use tauri::{AppHandle, Emitter};
 use tokio::sync::Notify;
 use tokio::time::sleep;
 
+use crate::backend::{Backends, OperationStatus};
 use crate::db::Database;
-use crate::gemini::GeminiClient;
+use crate::events::{self, EventSink};
 
 const POLLING_INTERVAL: Duration = Duration::from_secs(5);
 const IDLE_INTERVAL: Duration = Duration::from_secs(30);
 
-/// Map Gemini document state to internal status
-fn map_gemini_state_to_status(state: Option<&str>) -> String {
-    match state {
-        Some("STATE_ACTIVE") | Some("ACTIVE") => "completed".to_string(),
-        Some("STATE_PENDING") | Some("PENDING") => "processing".to_string(),
-        Some("STATE_FAILED") | Some("FAILED") => "failed".to_string(),
-        Some(s) if s.to_lowercase().contains("active") => "completed".to_string(),
-        Some(s) if s.to_lowercase().contains("pending") => "processing".to_string(),
-        Some(s) if s.to_lowercase().contains("fail") => "failed".to_string(),
-        _ => "completed".to_string(),
-    }
-}
-
 #[derive(Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DocumentStatusPayload {
@@ -39,6 +26,8 @@ pub struct DocumentStatusPayload {
 pub struct PollingState {
     notify: Notify,
     running: AtomicBool,
+    /// Set while background sync is paused or offline
+    paused: AtomicBool,
 }
 
 impl PollingState {
@@ -46,6 +35,7 @@ impl PollingState {
         Arc::new(Self {
             notify: Notify::new(),
             running: AtomicBool::new(false),
+            paused: AtomicBool::new(false),
         })
     }
 
@@ -61,12 +51,114 @@ impl PollingState {
     pub fn set_running(&self, running: bool) {
         self.running.store(running, Ordering::SeqCst);
     }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    /// Pause or resume polling, waking the task when it resumes
+    pub fn set_paused(&self, paused: bool) {
+        if self.paused.swap(paused, Ordering::SeqCst) && !paused {
+            self.notify();
+        }
+    }
+}
+
+/// Check the operation of every pending document once
+///
+/// Returns whether any document was still pending at the start of the check.
+pub async fn poll_pending_operations(
+    events: &dyn EventSink,
+    db: &Arc<Database>,
+    backends: &Backends,
+) -> Result<bool, String> {
+    let pending_docs = db
+        .call(|db| db.get_documents_with_pending_operations())
+        .await?;
+
+    if pending_docs.is_empty() {
+        return Ok(false);
+    }
+
+    // Check operation status for each pending document, trashed ones included
+    for doc in &pending_docs {
+        let operation_name = match &doc.operation_name {
+            Some(name) => name.clone(),
+            None => continue,
+        };
+
+        let backend = backends.for_resource(&operation_name);
+
+        match backend.get_operation(&operation_name).await {
+            Ok(OperationStatus::Running) => {
+                // Not done yet, continue polling
+            }
+            Ok(OperationStatus::Failed(message)) => {
+                let doc_id = doc.id.clone();
+                let _ = db
+                    .call(move |db| db.update_document_error(&doc_id, "failed", &message))
+                    .await;
+
+                let payload = DocumentStatusPayload {
+                    document_id: doc.id.clone(),
+                    gemini_name: None,
+                    store_id: doc.store_id.clone(),
+                    status: "failed".to_string(),
+                };
+                events::emit(events, "document-status-updated", &payload);
+            }
+            Ok(OperationStatus::Done {
+                document_name: Some(document_name),
+            }) => {
+                // Operation succeeded - get the document details from the backend
+                match backend.get_document(&document_name).await {
+                    Ok(remote_doc) => {
+                        let doc_id = doc.id.clone();
+                        let remote = remote_doc.clone();
+                        let _ = db
+                            .call(move |db| {
+                                db.update_document_from_gemini(
+                                    &doc_id,
+                                    &remote.name,
+                                    remote.mime_type.as_deref(),
+                                    &remote.status,
+                                )
+                            })
+                            .await;
+
+                        let payload = DocumentStatusPayload {
+                            document_id: doc.id.clone(),
+                            gemini_name: Some(remote_doc.name.clone()),
+                            store_id: doc.store_id.clone(),
+                            status: remote_doc.status.clone(),
+                        };
+                        events::emit(events, "document-status-updated", &payload);
+                    }
+                    Err(e) => {
+                        eprintln!(
+                            "Failed to get document info for {}: {}",
+                            document_name, e
+                        );
+                    }
+                }
+            }
+            Ok(OperationStatus::Done { document_name: None }) => {}
+            Err(e) => {
+                eprintln!(
+                    "Failed to get operation status for {}: {}",
+                    operation_name, e
+                );
+            }
+        }
+    }
+
+    Ok(true)
 }
 
 pub fn start_polling_task(
-    app_handle: AppHandle,
+    events: Arc<dyn EventSink>,
     db: Arc<Database>,
-    gemini_client: Arc<GeminiClient>,
+    backends: Arc<Backends>,
     polling_state: Arc<PollingState>,
 ) {
     if polling_state.is_running() {
@@ -77,93 +169,26 @@ pub fn start_polling_task(
 
     tauri::async_runtime::spawn(async move {
         loop {
-            // Get documents with pending operations
-            let pending_docs = match db.get_documents_with_pending_operations() {
-                Ok(docs) => docs,
-                Err(e) => {
-                    eprintln!("Failed to get documents with pending operations: {}", e);
-                    sleep(POLLING_INTERVAL).await;
-                    continue;
-                }
-            };
-
-            if pending_docs.is_empty() {
-                // No pending documents, wait longer or until notified
-                tokio::select! {
-                    _ = sleep(IDLE_INTERVAL) => {}
-                    _ = polling_state.notify.notified() => {}
-                }
+            if polling_state.is_paused() {
+                // Sync is paused or offline, wait until it resumes
+                polling_state.notify.notified().await;
                 continue;
             }
 
-            // Check operation status for each pending document
-            for doc in &pending_docs {
-                let operation_name = match &doc.operation_name {
-                    Some(name) => name.clone(),
-                    None => continue,
-                };
-
-                match gemini_client.get_operation(&operation_name).await {
-                    Ok(operation) => {
-                        if operation.done {
-                            // Operation completed
-                            if let Some(error) = operation.error {
-                                // Operation failed
-                                let _ = db.update_document_error(
-                                    &doc.id,
-                                    "failed",
-                                    &error.message,
-                                );
-
-                                let payload = DocumentStatusPayload {
-                                    document_id: doc.id.clone(),
-                                    gemini_name: None,
-                                    store_id: doc.store_id.clone(),
-                                    status: "failed".to_string(),
-                                };
-                                let _ = app_handle.emit("document-status-updated", &payload);
-                            } else if let Some(response) = operation.response {
-                                // Operation succeeded - get document info
-                                if let Some(document_name) = response.document_name {
-                                    // Get the document details from Gemini
-                                    match gemini_client.get_document(&document_name).await {
-                                        Ok(gemini_doc) => {
-                                            let status = map_gemini_state_to_status(gemini_doc.state.as_deref());
-
-                                            let _ = db.update_document_from_gemini(
-                                                &doc.id,
-                                                &gemini_doc.name,
-                                                gemini_doc.mime_type.as_deref(),
-                                                &status,
-                                            );
-
-                                            let payload = DocumentStatusPayload {
-                                                document_id: doc.id.clone(),
-                                                gemini_name: Some(gemini_doc.name.clone()),
-                                                store_id: doc.store_id.clone(),
-                                                status: status.clone(),
-                                            };
-                                            let _ =
-                                                app_handle.emit("document-status-updated", &payload);
-                                        }
-                                        Err(e) => {
-                                            eprintln!(
-                                                "Failed to get document info for {}: {}",
-                                                document_name, e
-                                            );
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                        // If not done, continue polling
-                    }
-                    Err(e) => {
-                        eprintln!(
-                            "Failed to get operation status for {}: {}",
-                            operation_name, e
-                        );
+            match poll_pending_operations(events.as_ref(), &db, &backends).await {
+                Ok(true) => {}
+                Ok(false) => {
+                    // No pending documents, wait longer or until notified
+                    tokio::select! {
+                        _ = sleep(IDLE_INTERVAL) => {}
+                        _ = polling_state.notify.notified() => {}
                     }
+                    continue;
+                }
+                Err(e) => {
+                    eprintln!("Failed to get documents with pending operations: {}", e);
+                    sleep(POLLING_INTERVAL).await;
+                    continue;
                 }
             }
 
@@ -1,28 +1,184 @@
 //! Settings management module
 //!
 //! Handles application settings including API key storage using tauri-plugin-store.
+//!
+//! Settings live in `settings.json` inside a data directory. The core
+//! functions take that directory so the sync engine can run outside the app;
+//! the commands resolve it from the app handle.
 
+use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
-use tauri::AppHandle;
-use tauri::Manager;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, Runtime};
+
+/// Bundle identifier from `tauri.conf.json`, naming the app data directory
+pub const APP_IDENTIFIER: &str = "ai.mizly.app";
 
 const SETTINGS_FILE: &str = "settings.json";
 const API_KEY_FIELD: &str = "gemini_api_key";
+const GEMINI_BASE_URL_FIELD: &str = "gemini_base_url";
+const OPENAI_FIELD: &str = "openai_backend";
+const RETRIEVAL_FIELD: &str = "retrieval";
+const HTTP_API_FIELD: &str = "http_api";
+const BACKUP_FIELD: &str = "backup";
+const TRASH_FIELD: &str = "trash";
+const SYNC_FIELD: &str = "sync";
+
+/// Connection settings of the OpenAI-compatible backend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OpenAiSettings {
+    /// Base URL including the API version, e.g. `http://localhost:11434/v1`
+    pub base_url: String,
+    /// Optional bearer token (local servers usually don't need one)
+    pub api_key: Option<String>,
+    pub chat_model: String,
+    pub embedding_model: String,
+}
+
+impl Default for OpenAiSettings {
+    /// Defaults for a local Ollama server
+    fn default() -> Self {
+        Self {
+            base_url: "http://localhost:11434/v1".to_string(),
+            api_key: None,
+            chat_model: "llama3.1".to_string(),
+            embedding_model: "nomic-embed-text".to_string(),
+        }
+    }
+}
+
+/// Where the local backend computes embeddings or generates answers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelProvider {
+    /// The OpenAI-compatible server from [`OpenAiSettings`]
+    #[default]
+    Local,
+    /// The Gemini API, using the configured API key
+    Gemini,
+}
 
-/// Get the settings file path
-fn get_settings_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
-    let app_data_dir = app_handle
+/// Settings of the local retrieval backend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RetrievalSettings {
+    pub embedding_provider: ModelProvider,
+    pub generation_provider: ModelProvider,
+    /// Embedding model used when `embedding_provider` is Gemini
+    pub gemini_embedding_model: String,
+    /// Chunks passed to the model as context for a query
+    pub top_k: usize,
+    /// Target chunk size in characters
+    pub chunk_chars: usize,
+    /// Characters repeated at the start of the next chunk when a paragraph is split
+    pub chunk_overlap_chars: usize,
+}
+
+impl Default for RetrievalSettings {
+    fn default() -> Self {
+        Self {
+            embedding_provider: ModelProvider::Local,
+            generation_provider: ModelProvider::Local,
+            gemini_embedding_model: "gemini-embedding-001".to_string(),
+            top_k: 8,
+            chunk_chars: 1200,
+            chunk_overlap_chars: 200,
+        }
+    }
+}
+
+/// Settings of the local HTTP API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HttpApiSettings {
+    pub enabled: bool,
+    /// Port on 127.0.0.1
+    pub port: u16,
+    /// Bearer token clients must send, generated when the API is first enabled
+    pub token: Option<String>,
+}
+
+impl Default for HttpApiSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 47615,
+            token: None,
+        }
+    }
+}
+
+/// Scheduled database backups
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BackupSettings {
+    /// Hours between backups, 0 disables scheduled backups
+    pub interval_hours: u32,
+    /// Number of backups kept, older ones are deleted
+    pub keep: u32,
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        Self {
+            interval_hours: 24,
+            keep: 7,
+        }
+    }
+}
+
+/// Grace period of deleted stores and documents
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TrashSettings {
+    /// Days deleted items stay restorable before they are purged, 0 purges them right away
+    pub retention_days: u32,
+}
+
+impl Default for TrashSettings {
+    fn default() -> Self {
+        Self { retention_days: 30 }
+    }
+}
+
+/// Background sync preferences kept across restarts
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SyncSettings {
+    /// Paused from the app, nothing is uploaded or polled until it is resumed
+    pub paused: bool,
+}
+
+/// Generate a random bearer token for the HTTP API
+pub fn generate_api_token() -> String {
+    format!(
+        "mzl_{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    )
+}
+
+/// Get the app data directory holding settings and the database
+pub fn app_data_dir<R: Runtime>(app_handle: &AppHandle<R>) -> Result<PathBuf, String> {
+    app_handle
         .path()
         .app_data_dir()
-        .map_err(|e| format!("Failed to get app data dir: {}", e))?;
+        .map_err(|e| format!("Failed to get app data dir: {}", e))
+}
 
-    Ok(app_data_dir.join(SETTINGS_FILE))
+/// Get the app data directory without an app handle, e.g. for the CLI
+///
+/// Matches the directory Tauri resolves for [`APP_IDENTIFIER`].
+pub fn default_data_dir() -> Result<PathBuf, String> {
+    dirs::data_dir()
+        .map(|dir| dir.join(APP_IDENTIFIER))
+        .ok_or_else(|| "Failed to get app data dir".to_string())
 }
 
 /// Read settings from file
-fn read_settings(app_handle: &AppHandle) -> Result<serde_json::Value, String> {
-    let path = get_settings_path(app_handle)?;
+fn read_settings(data_dir: &Path) -> Result<serde_json::Value, String> {
+    let path = data_dir.join(SETTINGS_FILE);
 
     if !path.exists() {
         return Ok(serde_json::json!({}));
@@ -35,81 +191,301 @@ fn read_settings(app_handle: &AppHandle) -> Result<serde_json::Value, String> {
 }
 
 /// Write settings to file
-fn write_settings(app_handle: &AppHandle, settings: &serde_json::Value) -> Result<(), String> {
-    let path = get_settings_path(app_handle)?;
-
-    // Ensure parent directory exists
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).map_err(|e| format!("Failed to create settings dir: {}", e))?;
-    }
+fn write_settings(data_dir: &Path, settings: &serde_json::Value) -> Result<(), String> {
+    fs::create_dir_all(data_dir).map_err(|e| format!("Failed to create settings dir: {}", e))?;
 
     let content = serde_json::to_string_pretty(settings)
         .map_err(|e| format!("Failed to serialize settings: {}", e))?;
 
-    fs::write(&path, content).map_err(|e| format!("Failed to write settings: {}", e))?;
+    fs::write(data_dir.join(SETTINGS_FILE), content)
+        .map_err(|e| format!("Failed to write settings: {}", e))?;
 
     Ok(())
 }
 
+/// Insert or replace one settings field
+fn write_field(data_dir: &Path, field: &str, value: serde_json::Value) -> Result<(), String> {
+    let mut settings = read_settings(data_dir)?;
+
+    if let Some(obj) = settings.as_object_mut() {
+        obj.insert(field.to_string(), value);
+    }
+
+    write_settings(data_dir, &settings)
+}
+
 /// Get API key synchronously (for use in non-async contexts)
-pub fn get_api_key_sync(app_handle: &AppHandle) -> Option<String> {
-    let settings = read_settings(app_handle).ok()?;
+pub fn get_api_key_sync(data_dir: &Path) -> Option<String> {
+    let settings = read_settings(data_dir).ok()?;
     settings
         .get(API_KEY_FIELD)
         .and_then(|v| v.as_str())
         .map(|s| s.to_string())
 }
 
-/// Get the Gemini API key
-#[tauri::command]
-pub fn get_api_key(app_handle: AppHandle) -> Result<Option<String>, String> {
-    let settings = read_settings(&app_handle)?;
-    Ok(settings
-        .get(API_KEY_FIELD)
+/// Get the Gemini API base URL override, e.g. for a proxy
+pub fn get_gemini_base_url_sync(data_dir: &Path) -> Option<String> {
+    let settings = read_settings(data_dir).ok()?;
+    settings
+        .get(GEMINI_BASE_URL_FIELD)
         .and_then(|v| v.as_str())
-        .map(|s| s.to_string()))
+        .filter(|s| !s.trim().is_empty())
+        .map(|s| s.trim_end_matches('/').to_string())
 }
 
-/// Set the Gemini API key
-#[tauri::command]
-pub fn set_api_key(app_handle: AppHandle, api_key: String) -> Result<(), String> {
+/// Validate and save the Gemini API key
+pub fn write_api_key(data_dir: &Path, api_key: &str) -> Result<(), String> {
     // Validate API key format (should start with "AIza")
     if !api_key.starts_with("AIza") {
         return Err("Invalid API key format. Gemini API keys should start with 'AIza'.".to_string());
     }
 
-    let mut settings = read_settings(&app_handle)?;
+    write_field(data_dir, API_KEY_FIELD, serde_json::json!(api_key))
+}
+
+/// Remove the Gemini API key
+pub fn remove_api_key(data_dir: &Path) -> Result<(), String> {
+    let mut settings = read_settings(data_dir)?;
 
     if let Some(obj) = settings.as_object_mut() {
-        obj.insert(API_KEY_FIELD.to_string(), serde_json::json!(api_key));
+        obj.remove(API_KEY_FIELD);
+    }
+
+    write_settings(data_dir, &settings)
+}
+
+/// Get the OpenAI-compatible backend settings synchronously
+pub fn get_openai_settings_sync(data_dir: &Path) -> OpenAiSettings {
+    read_settings(data_dir)
+        .ok()
+        .and_then(|settings| settings.get(OPENAI_FIELD).cloned())
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+/// Validate and save the OpenAI-compatible backend settings
+pub fn write_openai_settings(data_dir: &Path, settings: &OpenAiSettings) -> Result<(), String> {
+    url::Url::parse(&settings.base_url)
+        .map_err(|e| format!("Invalid base URL: {}", e))?;
+
+    if settings.chat_model.trim().is_empty() || settings.embedding_model.trim().is_empty() {
+        return Err("Chat and embedding models are required".to_string());
+    }
+
+    let value = serde_json::to_value(settings)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+
+    write_field(data_dir, OPENAI_FIELD, value)
+}
+
+/// Get the local retrieval settings synchronously
+pub fn get_retrieval_settings_sync(data_dir: &Path) -> RetrievalSettings {
+    read_settings(data_dir)
+        .ok()
+        .and_then(|settings| settings.get(RETRIEVAL_FIELD).cloned())
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+/// Validate and save the local retrieval settings
+pub fn write_retrieval_settings(
+    data_dir: &Path,
+    settings: &RetrievalSettings,
+) -> Result<(), String> {
+    if !(1..=50).contains(&settings.top_k) {
+        return Err("Top-k must be between 1 and 50".to_string());
+    }
+
+    if !(200..=8000).contains(&settings.chunk_chars) {
+        return Err("Chunk size must be between 200 and 8000 characters".to_string());
+    }
+
+    if settings.chunk_overlap_chars * 2 > settings.chunk_chars {
+        return Err("Chunk overlap must be at most half the chunk size".to_string());
     }
 
-    write_settings(&app_handle, &settings)?;
+    if settings.embedding_provider == ModelProvider::Gemini
+        && settings.gemini_embedding_model.trim().is_empty()
+    {
+        return Err("Gemini embedding model is required".to_string());
+    }
+
+    let value = serde_json::to_value(settings)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+
+    write_field(data_dir, RETRIEVAL_FIELD, value)
+}
+
+/// Get the HTTP API settings synchronously
+pub fn get_http_api_settings_sync(data_dir: &Path) -> HttpApiSettings {
+    read_settings(data_dir)
+        .ok()
+        .and_then(|settings| settings.get(HTTP_API_FIELD).cloned())
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+/// Check the HTTP API settings before starting the server with them
+pub fn validate_http_api_settings(settings: &HttpApiSettings) -> Result<(), String> {
+    if settings.port < 1024 {
+        return Err("Port must be between 1024 and 65535".to_string());
+    }
+
+    if settings.enabled && settings.token.as_deref().unwrap_or("").is_empty() {
+        return Err("A token is required to enable the HTTP API".to_string());
+    }
 
     Ok(())
 }
 
+/// Validate and save the HTTP API settings
+pub fn write_http_api_settings(data_dir: &Path, settings: &HttpApiSettings) -> Result<(), String> {
+    validate_http_api_settings(settings)?;
+
+    let value = serde_json::to_value(settings)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+
+    write_field(data_dir, HTTP_API_FIELD, value)
+}
+
+/// Get the backup settings synchronously
+pub fn get_backup_settings_sync(data_dir: &Path) -> BackupSettings {
+    read_settings(data_dir)
+        .ok()
+        .and_then(|settings| settings.get(BACKUP_FIELD).cloned())
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+/// Validate and save the backup settings
+pub fn write_backup_settings(data_dir: &Path, settings: &BackupSettings) -> Result<(), String> {
+    if settings.interval_hours > 24 * 30 {
+        return Err("Backup interval must be at most 720 hours".to_string());
+    }
+
+    if !(1..=100).contains(&settings.keep) {
+        return Err("Number of backups kept must be between 1 and 100".to_string());
+    }
+
+    let value = serde_json::to_value(settings)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+
+    write_field(data_dir, BACKUP_FIELD, value)
+}
+
+/// Get the trash settings synchronously
+pub fn get_trash_settings_sync(data_dir: &Path) -> TrashSettings {
+    read_settings(data_dir)
+        .ok()
+        .and_then(|settings| settings.get(TRASH_FIELD).cloned())
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+/// Validate and save the trash settings
+pub fn write_trash_settings(data_dir: &Path, settings: &TrashSettings) -> Result<(), String> {
+    if settings.retention_days > 365 {
+        return Err("Deleted items can be kept for at most 365 days".to_string());
+    }
+
+    let value = serde_json::to_value(settings)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+
+    write_field(data_dir, TRASH_FIELD, value)
+}
+
+/// Get the sync settings synchronously
+pub fn get_sync_settings_sync(data_dir: &Path) -> SyncSettings {
+    read_settings(data_dir)
+        .ok()
+        .and_then(|settings| settings.get(SYNC_FIELD).cloned())
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+/// Save the sync settings
+pub fn write_sync_settings(data_dir: &Path, settings: &SyncSettings) -> Result<(), String> {
+    let value = serde_json::to_value(settings)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+
+    write_field(data_dir, SYNC_FIELD, value)
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Get the Gemini API key
+#[tauri::command]
+pub fn get_api_key<R: Runtime>(app_handle: AppHandle<R>) -> Result<Option<String>, String> {
+    Ok(get_api_key_sync(&app_data_dir(&app_handle)?))
+}
+
+/// Set the Gemini API key
+#[tauri::command]
+pub fn set_api_key<R: Runtime>(app_handle: AppHandle<R>, api_key: String) -> Result<(), String> {
+    write_api_key(&app_data_dir(&app_handle)?, &api_key)
+}
+
 /// Check if API key is configured
 #[tauri::command]
-pub fn has_api_key(app_handle: AppHandle) -> Result<bool, String> {
-    let settings = read_settings(&app_handle)?;
-    Ok(settings
-        .get(API_KEY_FIELD)
-        .and_then(|v| v.as_str())
+pub fn has_api_key<R: Runtime>(app_handle: AppHandle<R>) -> Result<bool, String> {
+    Ok(get_api_key_sync(&app_data_dir(&app_handle)?)
         .map(|s| !s.is_empty())
         .unwrap_or(false))
 }
 
 /// Clear the API key
 #[tauri::command]
-pub fn clear_api_key(app_handle: AppHandle) -> Result<(), String> {
-    let mut settings = read_settings(&app_handle)?;
+pub fn clear_api_key<R: Runtime>(app_handle: AppHandle<R>) -> Result<(), String> {
+    remove_api_key(&app_data_dir(&app_handle)?)
+}
 
-    if let Some(obj) = settings.as_object_mut() {
-        obj.remove(API_KEY_FIELD);
-    }
+/// Get the OpenAI-compatible backend settings
+#[tauri::command]
+pub fn get_openai_settings<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<OpenAiSettings, String> {
+    Ok(get_openai_settings_sync(&app_data_dir(&app_handle)?))
+}
 
-    write_settings(&app_handle, &settings)?;
+/// Set the OpenAI-compatible backend settings
+#[tauri::command]
+pub fn set_openai_settings<R: Runtime>(
+    app_handle: AppHandle<R>,
+    settings: OpenAiSettings,
+) -> Result<(), String> {
+    write_openai_settings(&app_data_dir(&app_handle)?, &settings)
+}
 
-    Ok(())
+/// Get the local retrieval settings
+#[tauri::command]
+pub fn get_retrieval_settings<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<RetrievalSettings, String> {
+    Ok(get_retrieval_settings_sync(&app_data_dir(&app_handle)?))
+}
+
+/// Set the local retrieval settings
+#[tauri::command]
+pub fn set_retrieval_settings<R: Runtime>(
+    app_handle: AppHandle<R>,
+    settings: RetrievalSettings,
+) -> Result<(), String> {
+    write_retrieval_settings(&app_data_dir(&app_handle)?, &settings)
+}
+
+/// Get the backup settings
+#[tauri::command]
+pub fn get_backup_settings<R: Runtime>(app_handle: AppHandle<R>) -> Result<BackupSettings, String> {
+    Ok(get_backup_settings_sync(&app_data_dir(&app_handle)?))
+}
+
+/// Set the backup settings, used from the next scheduled check
+#[tauri::command]
+pub fn set_backup_settings<R: Runtime>(
+    app_handle: AppHandle<R>,
+    settings: BackupSettings,
+) -> Result<(), String> {
+    write_backup_settings(&app_data_dir(&app_handle)?, &settings)
 }
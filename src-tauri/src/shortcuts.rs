@@ -1,11 +1,24 @@
-use tauri::{command, AppHandle, Manager};
+use serde::Serialize;
+use tauri::{command, AppHandle, Emitter, Manager};
+use tauri_plugin_clipboard_manager::ClipboardExt;
 use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Modifiers, Shortcut, ShortcutState};
 
-use crate::window::toggle_window;
+use crate::state::AppState;
+use crate::window::{toggle_window, toggle_window_visibility};
+
+/// 剪貼簿提問事件的 payload
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AskClipboardPayload {
+    pub query: String,
+    /// 上次查詢使用的 stores（空陣列表示使用全部 stores）
+    pub store_names: Vec<String>,
+}
 
 /// 設置全域快捷鍵
 pub fn setup_global_shortcut(app: &AppHandle) -> tauri::Result<()> {
-    register_shortcut(app)
+    register_shortcut(app)?;
+    register_clipboard_shortcut(app)
 }
 
 /// 註冊快捷鍵
@@ -26,6 +39,51 @@ fn register_shortcut(app: &AppHandle) -> tauri::Result<()> {
     Ok(())
 }
 
+/// 註冊剪貼簿提問快捷鍵
+fn register_clipboard_shortcut(app: &AppHandle) -> tauri::Result<()> {
+    let shortcut = create_clipboard_shortcut();
+
+    app.global_shortcut()
+        .on_shortcut(shortcut, move |app, _shortcut_id, event| {
+            if event.state == ShortcutState::Released {
+                if let Err(e) = ask_about_clipboard(app) {
+                    eprintln!("Failed to ask about clipboard: {}", e);
+                }
+            }
+        })
+        .map_err(|e| tauri::Error::Anyhow(anyhow::anyhow!(e)))?;
+
+    Ok(())
+}
+
+/// 讀取剪貼簿文字，顯示主視窗並通知前端送出提問
+fn ask_about_clipboard(app: &AppHandle) -> Result<(), String> {
+    let text = app
+        .clipboard()
+        .read_text()
+        .map_err(|e| format!("Failed to read clipboard: {}", e))?;
+
+    let query = text.trim().to_string();
+    if query.is_empty() {
+        return Ok(());
+    }
+
+    let store_names = app
+        .try_state::<AppState>()
+        .and_then(|state| state.last_query_stores.lock().ok().map(|guard| guard.clone()))
+        .unwrap_or_default();
+
+    let window = app
+        .get_webview_window("main")
+        .ok_or_else(|| "Main window not found".to_string())?;
+
+    toggle_window_visibility(&window, true).map_err(|e| e.to_string())?;
+
+    window
+        .emit("ask-clipboard", AskClipboardPayload { query, store_names })
+        .map_err(|e| e.to_string())
+}
+
 /// 根據平台創建快捷鍵
 fn create_platform_shortcut() -> Shortcut {
     if cfg!(target_os = "macos") {
@@ -35,21 +93,36 @@ fn create_platform_shortcut() -> Shortcut {
     }
 }
 
+/// 根據平台創建剪貼簿提問快捷鍵
+fn create_clipboard_shortcut() -> Shortcut {
+    if cfg!(target_os = "macos") {
+        Shortcut::new(Some(Modifiers::SUPER | Modifiers::SHIFT), Code::KeyL)
+    } else {
+        Shortcut::new(Some(Modifiers::CONTROL | Modifiers::SHIFT), Code::KeyL)
+    }
+}
+
 /// 啟用或停用全域快捷鍵
 #[command]
 pub fn set_global_shortcut_enabled(app: AppHandle, enabled: bool) -> Result<(), String> {
     let shortcut = create_platform_shortcut();
+    let clipboard_shortcut = create_clipboard_shortcut();
 
     if enabled {
         // 先嘗試取消註冊（避免重複註冊）
         let _ = app.global_shortcut().unregister(shortcut);
+        let _ = app.global_shortcut().unregister(clipboard_shortcut);
         // 重新註冊
         register_shortcut(&app).map_err(|e| e.to_string())?;
+        register_clipboard_shortcut(&app).map_err(|e| e.to_string())?;
     } else {
         // 取消註冊
         app.global_shortcut()
             .unregister(shortcut)
             .map_err(|e| e.to_string())?;
+        app.global_shortcut()
+            .unregister(clipboard_shortcut)
+            .map_err(|e| e.to_string())?;
     }
 
     Ok(())
@@ -4,12 +4,15 @@ use std::sync::Mutex;
 pub struct AppState {
     /// 是否防止自動隱藏視窗
     pub prevent_auto_hide: Mutex<bool>,
+    /// 上次查詢使用的 store 名稱（供剪貼簿提問快捷鍵使用）
+    pub last_query_stores: Mutex<Vec<String>>,
 }
 
 impl AppState {
     pub fn new() -> Self {
         Self {
             prevent_auto_hide: Mutex::new(false),
+            last_query_stores: Mutex::new(Vec::new()),
         }
     }
 }
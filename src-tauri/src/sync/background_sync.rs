@@ -1,17 +1,23 @@
-//! Background synchronization task for uploading stores and documents to Gemini
+//! Background synchronization task for uploading stores and documents to their search backend
 
+use chrono::Utc;
 use serde::Serialize;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
-use std::time::Duration;
-use tauri::AppHandle;
-use tauri::Emitter;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tokio::sync::Notify;
 use tokio::time::sleep;
 
-use crate::db::Database;
-use crate::gemini::GeminiClient;
+use crate::backend::{BackendKind, Backends, UploadStatus};
+use crate::db::{
+    Database, DocumentEventKind, SyncQueue, DOCUMENT_EVENT_RETENTION_DAYS, MAX_EVENTS_PER_DOCUMENT,
+};
+use crate::events::{self, EventSink};
+use crate::extract;
+use crate::gemini::types::CustomMetadata;
 use crate::polling::PollingState;
+use crate::settings::TrashSettings;
 
 /// Interval between sync cycles when there's work to do
 const SYNC_INTERVAL: Duration = Duration::from_secs(2);
@@ -19,6 +25,18 @@ const SYNC_INTERVAL: Duration = Duration::from_secs(2);
 /// Interval between sync cycles when idle
 const IDLE_INTERVAL: Duration = Duration::from_secs(30);
 
+/// Interval between connectivity checks while offline
+const OFFLINE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Lease held by the process that uploads and deletes (the app or the CLI)
+const SYNC_LEASE: &str = "sync";
+
+/// Seconds a sync lease stays valid without renewal
+const SYNC_LEASE_TTL_SECS: i64 = 300;
+
+/// Interval between prunings of the document activity log
+const EVENT_PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
 // ============================================================================
 // Payload Types
 // ============================================================================
@@ -44,6 +62,48 @@ pub struct DocumentSyncPayload {
     pub status: String,
 }
 
+/// The last error of a sync cycle
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncError {
+    pub message: String,
+    /// When it happened, RFC 3339 in UTC
+    pub at: String,
+}
+
+/// Payload of `get_sync_status` and `sync-status` events
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncStatus {
+    /// Whether this process runs the sync task
+    pub running: bool,
+    /// Paused from the app, nothing is synced until it is resumed
+    pub paused: bool,
+    /// Gemini can't be reached, its stores sync again once it can; local
+    /// stores keep syncing
+    pub offline: bool,
+    /// Whether this process holds the sync lease; another process syncs otherwise
+    pub lease_held: bool,
+    #[serde(flatten)]
+    pub queue: SyncQueue,
+    /// When the last cycle finished, RFC 3339 in UTC
+    pub last_cycle_at: Option<String>,
+    pub last_cycle_duration_ms: Option<u64>,
+    pub last_error: Option<SyncError>,
+    /// When the sync task wakes up next, unless notified sooner; None while paused
+    pub next_wakeup_at: Option<String>,
+}
+
+/// What the sync task did last, kept for the sync status
+#[derive(Default)]
+struct SyncActivity {
+    lease_held: bool,
+    last_cycle_at: Option<String>,
+    last_cycle_duration_ms: Option<u64>,
+    last_error: Option<SyncError>,
+    next_wakeup_at: Option<String>,
+}
+
 // ============================================================================
 // Sync State
 // ============================================================================
@@ -52,6 +112,16 @@ pub struct DocumentSyncPayload {
 pub struct SyncState {
     notify: Notify,
     running: AtomicBool,
+    /// Identifies this process as the holder of the sync lease
+    lease_owner: String,
+    /// Days deleted items stay in the trash before they are purged
+    trash_retention_days: AtomicU32,
+    /// When the document activity log was last pruned
+    last_event_prune: Mutex<Option<Instant>>,
+    paused: AtomicBool,
+    /// Set while connectivity checks fail
+    offline: AtomicBool,
+    activity: Mutex<SyncActivity>,
 }
 
 impl SyncState {
@@ -59,6 +129,110 @@ impl SyncState {
         Arc::new(Self {
             notify: Notify::new(),
             running: AtomicBool::new(false),
+            lease_owner: format!("{}:{}", std::process::id(), uuid::Uuid::new_v4()),
+            trash_retention_days: AtomicU32::new(TrashSettings::default().retention_days),
+            last_event_prune: Mutex::new(None),
+            paused: AtomicBool::new(false),
+            offline: AtomicBool::new(false),
+            activity: Mutex::new(SyncActivity::default()),
+        })
+    }
+
+    /// Days deleted items stay in the trash
+    pub fn trash_retention_days(&self) -> u32 {
+        self.trash_retention_days.load(Ordering::SeqCst)
+    }
+
+    /// Set the trash grace period, from the settings
+    pub fn set_trash_retention_days(&self, days: u32) {
+        self.trash_retention_days.store(days, Ordering::SeqCst);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    /// Pause or resume sync, from the settings or the app
+    ///
+    /// Callers notify the sync task so it takes the change into account.
+    pub fn set_paused(&self, paused: bool) {
+        self.paused.store(paused, Ordering::SeqCst);
+    }
+
+    /// Whether the last connectivity check failed
+    pub fn is_offline(&self) -> bool {
+        self.offline.load(Ordering::SeqCst)
+    }
+
+    /// Record the outcome of a connectivity check, returning whether it changed
+    fn set_offline(&self, offline: bool) -> bool {
+        self.offline.swap(offline, Ordering::SeqCst) != offline
+    }
+
+    /// Whether work in `kind` waits until the remote backend can be reached
+    fn waits_for_connection(&self, kind: BackendKind) -> bool {
+        kind == BackendKind::Gemini && self.is_offline()
+    }
+
+    /// Whether the document activity log is due for pruning, marking it pruned
+    fn take_event_prune(&self) -> bool {
+        let mut last = self.last_event_prune.lock().unwrap();
+        if last.is_some_and(|at| at.elapsed() < EVENT_PRUNE_INTERVAL) {
+            return false;
+        }
+        *last = Some(Instant::now());
+        true
+    }
+
+    /// Take or renew the sync lease, returning false while another process syncs
+    pub fn acquire_lease(&self, db: &Database) -> Result<bool, String> {
+        let acquired = db.try_acquire_lease(SYNC_LEASE, &self.lease_owner, SYNC_LEASE_TTL_SECS);
+        self.activity.lock().unwrap().lease_held = matches!(acquired, Ok(true));
+        acquired
+    }
+
+    /// Release the sync lease so another process can take over immediately
+    pub fn release_lease(&self, db: &Database) -> Result<(), String> {
+        self.activity.lock().unwrap().lease_held = false;
+        db.release_lease(SYNC_LEASE, &self.lease_owner)
+    }
+
+    /// Log a sync error and keep it as the last error of the sync status
+    pub fn report_error(&self, message: String) {
+        eprintln!("{}", message);
+        self.activity.lock().unwrap().last_error = Some(SyncError {
+            message,
+            at: Utc::now().to_rfc3339(),
+        });
+    }
+
+    fn finish_cycle(&self, duration: Duration) {
+        let mut activity = self.activity.lock().unwrap();
+        activity.last_cycle_at = Some(Utc::now().to_rfc3339());
+        activity.last_cycle_duration_ms = Some(duration.as_millis() as u64);
+    }
+
+    fn schedule_wakeup(&self, interval: Option<Duration>) {
+        let at = interval
+            .map(|interval| Utc::now() + chrono::Duration::from_std(interval).unwrap_or_default());
+        self.activity.lock().unwrap().next_wakeup_at = at.map(|at| at.to_rfc3339());
+    }
+
+    /// Queue depth per stage and what the sync task did last
+    pub fn status(&self, db: &Database) -> Result<SyncStatus, String> {
+        let queue = db.get_sync_queue(self.trash_retention_days())?;
+        let activity = self.activity.lock().unwrap();
+
+        Ok(SyncStatus {
+            running: self.is_running(),
+            paused: self.is_paused(),
+            offline: self.is_offline(),
+            lease_held: activity.lease_held,
+            queue,
+            last_cycle_at: activity.last_cycle_at.clone(),
+            last_cycle_duration_ms: activity.last_cycle_duration_ms,
+            last_error: activity.last_error.clone(),
+            next_wakeup_at: activity.next_wakeup_at.clone(),
         })
     }
 
@@ -82,13 +256,14 @@ impl SyncState {
 // Sync Operations
 // ============================================================================
 
-/// Sync pending stores (create stores in Gemini)
+/// Sync pending stores (create stores in their backend)
 async fn sync_pending_stores(
-    app_handle: &AppHandle,
-    db: &Database,
-    gemini_client: &Arc<GeminiClient>,
+    events: &dyn EventSink,
+    db: &Arc<Database>,
+    backends: &Backends,
+    sync_state: &SyncState,
 ) -> bool {
-    let stores = match db.get_pending_sync_stores() {
+    let mut stores = match db.call(|db| db.get_pending_sync_stores()).await {
         Ok(s) => s,
         Err(e) => {
             eprintln!("Failed to get pending stores: {}", e);
@@ -96,44 +271,60 @@ async fn sync_pending_stores(
         }
     };
 
+    stores.retain(|store| {
+        !BackendKind::parse(&store.backend).is_ok_and(|kind| sync_state.waits_for_connection(kind))
+    });
+
     if stores.is_empty() {
         return false;
     }
 
     for store in stores {
-        match gemini_client.create_store(&store.title).await {
+        let result = match backends.for_store(&store) {
+            Ok(backend) => backend.create_store(&store.title).await,
+            Err(e) => Err(e),
+        };
+
+        match result {
             Ok(response) => {
                 // Update store with gemini_name and mark as synced
-                if let Err(e) = db.update_store_from_gemini(
-                    &store.id,
-                    &response.name,
-                    response.create_time.as_deref(),
-                    response.update_time.as_deref(),
-                    response.active_documents_count,
-                    response.pending_documents_count,
-                    response.failed_documents_count,
-                    response.size_bytes,
-                ) {
+                let store_id = store.id.clone();
+                let gemini_name = response.name.clone();
+                let update = db
+                    .call(move |db| {
+                        db.update_store_from_gemini(
+                            &store_id,
+                            &response.name,
+                            response.create_time.as_deref(),
+                            response.update_time.as_deref(),
+                            response.active_documents_count,
+                            response.pending_documents_count,
+                            response.failed_documents_count,
+                            response.size_bytes,
+                        )
+                    })
+                    .await;
+                if let Err(e) = update {
                     eprintln!("Failed to update store from gemini: {}", e);
                     continue;
                 }
 
-                // Emit success event to frontend
+                // Emit success event
                 let payload = StoreSyncPayload {
                     store_id: store.id.clone(),
                     sync_status: "synced".to_string(),
-                    gemini_name: Some(response.name),
+                    gemini_name: Some(gemini_name),
                 };
-
-                if let Err(e) = app_handle.emit("store-sync-updated", &payload) {
-                    eprintln!("Failed to emit store sync event: {}", e);
-                }
+                events::emit(events, "store-sync-updated", &payload);
             }
             Err(e) => {
-                eprintln!("Failed to create store for store {}: {}", store.id, e);
+                sync_state.report_error(format!("Failed to create store {}: {}", store.id, e));
 
                 // Mark as failed
-                let _ = db.update_store_sync_status(&store.id, "failed", None);
+                let store_id = store.id.clone();
+                let _ = db
+                    .call(move |db| db.update_store_sync_status(&store_id, "failed", None))
+                    .await;
 
                 // Emit failure event
                 let payload = StoreSyncPayload {
@@ -141,7 +332,7 @@ async fn sync_pending_stores(
                     sync_status: "failed".to_string(),
                     gemini_name: None,
                 };
-                let _ = app_handle.emit("store-sync-updated", &payload);
+                events::emit(events, "store-sync-updated", &payload);
             }
         }
     }
@@ -149,14 +340,15 @@ async fn sync_pending_stores(
     true
 }
 
-/// Upload pending documents to Gemini
+/// Upload pending documents to their store's backend
 async fn sync_pending_documents(
-    app_handle: &AppHandle,
-    db: &Database,
-    gemini_client: &Arc<GeminiClient>,
+    events: &dyn EventSink,
+    db: &Arc<Database>,
+    backends: &Backends,
+    sync_state: &SyncState,
     polling_state: &Arc<PollingState>,
 ) -> bool {
-    let documents = match db.get_pending_upload_documents() {
+    let documents = match db.call(|db| db.get_pending_upload_documents()).await {
         Ok(d) => d,
         Err(e) => {
             eprintln!("Failed to get pending upload documents: {}", e);
@@ -168,9 +360,17 @@ async fn sync_pending_documents(
         return false;
     }
 
+    let mut has_work = false;
+
     for doc in documents {
+        // Leave the rest of the queue to the cycle after a resume
+        if sync_state.is_paused() {
+            break;
+        }
+
         // Get store to get gemini_name
-        let store = match db.get_store(&doc.store_id) {
+        let store_id = doc.store_id.clone();
+        let store = match db.call(move |db| db.get_store(&store_id)).await {
             Ok(s) => s,
             Err(e) => {
                 eprintln!("Failed to get store for document: {}", e);
@@ -178,46 +378,146 @@ async fn sync_pending_documents(
             }
         };
 
+        let backend = match backends.for_store(&store) {
+            Ok(b) => b,
+            Err(e) => {
+                eprintln!("Failed to get backend for document: {}", e);
+                continue;
+            }
+        };
+
         let store_gemini_name = match store.gemini_name {
             Some(name) => name,
             None => continue, // Store not synced yet
         };
 
-        // Upload document using resumable upload
-        match gemini_client
-            .upload_document(&store_gemini_name, &doc.path, Some(&doc.name))
+        // Uploads to an unreachable backend wait, the others go ahead
+        if sync_state.waits_for_connection(BackendKind::from_resource_name(&store_gemini_name)) {
+            continue;
+        }
+        has_work = true;
+
+        // Metadata is kept as JSON, e.g. from an imported bundle
+        let custom_metadata: Vec<CustomMetadata> = match doc.custom_metadata.as_deref() {
+            Some(json) => serde_json::from_str(json).unwrap_or_else(|e| {
+                eprintln!("Ignoring invalid custom metadata of document {}: {}", doc.id, e);
+                vec![]
+            }),
+            None => vec![],
+        };
+
+        let doc_id = doc.id.clone();
+        if let Err(e) = db
+            .call(move |db| {
+                db.record_document_event(&doc_id, DocumentEventKind::UploadStarted, None)
+            })
             .await
         {
-            Ok(operation) => {
-                // Update document with operation info
-                if let Err(e) = db.update_document_operation(&doc.id, &operation.name) {
-                    eprintln!("Failed to update document operation: {}", e);
-                    continue;
-                }
+            eprintln!("Failed to record upload start of {}: {}", doc.id, e);
+        }
 
-                // Emit success event
-                let payload = DocumentSyncPayload {
-                    document_id: doc.id.clone(),
-                    store_id: doc.store_id.clone(),
-                    sync_status: "synced".to_string(),
-                    gemini_name: None, // Will be set after operation completes
-                    operation_name: Some(operation.name.clone()),
-                    status: "processing".to_string(),
+        let result = backend
+            .upload_document(
+                &store_gemini_name,
+                Path::new(&doc.path),
+                &doc.name,
+                &custom_metadata,
+            )
+            .await;
+
+        match result {
+            Ok(uploaded) => {
+                let doc_id = doc.id.clone();
+                let upload_mime_type = uploaded.upload_mime_type.clone();
+                let (update, payload) = match uploaded.status {
+                    UploadStatus::Pending { operation_name } => (
+                        // Update document with operation info
+                        {
+                            let operation_name = operation_name.clone();
+                            db.call(move |db| {
+                                db.update_document_operation(
+                                    &doc_id,
+                                    &operation_name,
+                                    &upload_mime_type,
+                                )
+                            })
+                            .await
+                        },
+                        DocumentSyncPayload {
+                            document_id: doc.id.clone(),
+                            store_id: doc.store_id.clone(),
+                            sync_status: "synced".to_string(),
+                            gemini_name: None, // Will be set after operation completes
+                            operation_name: Some(operation_name),
+                            status: "processing".to_string(),
+                        },
+                    ),
+                    UploadStatus::Completed {
+                        document_name,
+                        mime_type,
+                    } => (
+                        {
+                            let document_name = document_name.clone();
+                            db.call(move |db| {
+                                db.update_document_uploaded(
+                                    &doc_id,
+                                    &document_name,
+                                    mime_type.as_deref(),
+                                    &upload_mime_type,
+                                )
+                            })
+                            .await
+                        },
+                        DocumentSyncPayload {
+                            document_id: doc.id.clone(),
+                            store_id: doc.store_id.clone(),
+                            sync_status: "synced".to_string(),
+                            gemini_name: Some(document_name),
+                            operation_name: None,
+                            status: "completed".to_string(),
+                        },
+                    ),
                 };
 
-                if let Err(e) = app_handle.emit("document-sync-updated", &payload) {
-                    eprintln!("Failed to emit document sync event: {}", e);
+                if let Err(e) = update {
+                    eprintln!("Failed to update uploaded document: {}", e);
+                    continue;
                 }
 
+                // A re-upload replaces the previous remote copy
+                if let Some(previous) = &doc.gemini_name {
+                    if let Err(e) = backends
+                        .for_resource(previous)
+                        .delete_document(previous)
+                        .await
+                    {
+                        if !(e.contains("404") || e.contains("NOT_FOUND")) {
+                            eprintln!("Failed to delete replaced document {}: {}", previous, e);
+                        }
+                    }
+                }
+
+                let is_pending = payload.operation_name.is_some();
+
+                events::emit(events, "document-sync-updated", &payload);
+
                 // Notify polling task to check document status
-                polling_state.notify();
+                if is_pending {
+                    polling_state.notify();
+                }
             }
             Err(e) => {
-                eprintln!("Failed to upload document {}: {}", doc.id, e);
+                sync_state.report_error(format!("Failed to upload document {}: {}", doc.id, e));
 
                 // Mark as failed
-                let _ = db.update_document_sync_status(&doc.id, "failed");
-                let _ = db.update_document_error(&doc.id, "failed", &e);
+                let doc_id = doc.id.clone();
+                let error = e.clone();
+                let _ = db
+                    .call(move |db| {
+                        db.update_document_sync_status(&doc_id, "failed")?;
+                        db.update_document_error(&doc_id, "failed", &error)
+                    })
+                    .await;
 
                 // Emit failure event
                 let payload = DocumentSyncPayload {
@@ -228,92 +528,288 @@ async fn sync_pending_documents(
                     operation_name: None,
                     status: "failed".to_string(),
                 };
-                let _ = app_handle.emit("document-sync-updated", &payload);
+                events::emit(events, "document-sync-updated", &payload);
             }
         }
     }
 
-    true
+    has_work
 }
 
-/// Process soft-deleted stores (delete from Gemini then hard delete locally)
-async fn process_pending_store_deletions(db: &Database, gemini_client: &Arc<GeminiClient>) -> bool {
-    let stores = match db.get_soft_deleted_stores() {
+/// Move the sources of moved documents to the trash once their copy is uploaded
+async fn finish_document_moves(events: &dyn EventSink, db: &Arc<Database>) -> bool {
+    let finished = match db.call(|db| db.finish_document_moves()).await {
+        Ok(f) => f,
+        Err(e) => {
+            eprintln!("Failed to finish document moves: {}", e);
+            return false;
+        }
+    };
+
+    for finished in &finished {
+        events::emit(events, "document-moved", finished);
+    }
+
+    // Keep checking soon while copies are uploaded, polling completes them
+    let in_progress = db
+        .call(|db| db.has_document_moves_in_progress())
+        .await
+        .unwrap_or(false);
+
+    !finished.is_empty() || in_progress
+}
+
+/// Purge stores whose time in the trash is up (delete from their backend then hard delete locally)
+async fn process_pending_store_deletions(
+    db: &Arc<Database>,
+    backends: &Backends,
+    sync_state: &SyncState,
+    retention_days: u32,
+) -> bool {
+    let mut stores = match db
+        .call(move |db| db.get_soft_deleted_stores(retention_days))
+        .await
+    {
         Ok(s) => s,
         Err(_) => return false,
     };
 
+    // Purged once their backend can be reached, the trash keeps them until then
+    stores.retain(|store| {
+        !store.gemini_name.as_deref().is_some_and(|name| {
+            sync_state.waits_for_connection(BackendKind::from_resource_name(name))
+        })
+    });
+
     if stores.is_empty() {
         return false;
     }
 
     for store in stores {
-        // Only call Gemini API if store has a gemini_name
-        if let Some(ref gemini_name) = store.gemini_name {
-            match gemini_client.delete_store(gemini_name, true).await {
-                Ok(()) => {
-                    // API deletion successful, hard delete locally
-                    let _ = db.hard_delete_store(&store.id);
-                }
+        // Only call the backend if store has a gemini_name
+        let delete = if let Some(ref gemini_name) = store.gemini_name {
+            match backends.for_resource(gemini_name).delete_store(gemini_name, true).await {
+                // API deletion successful, hard delete locally
+                Ok(()) => true,
+                // Check if it's a 404 (already deleted)
+                Err(e) if e.contains("404") || e.contains("NOT_FOUND") => true,
+                // Otherwise keep the soft-deleted record for retry
                 Err(e) => {
-                    // Check if it's a 404 (already deleted)
-                    if e.contains("404") || e.contains("NOT_FOUND") {
-                        let _ = db.hard_delete_store(&store.id);
-                    }
-                    // Otherwise keep the soft-deleted record for retry
+                    sync_state.report_error(format!("Failed to delete store {}: {}", store.id, e));
+                    false
                 }
             }
         } else {
             // No gemini_name means it was never synced, just hard delete
-            let _ = db.hard_delete_store(&store.id);
+            true
+        };
+
+        if delete {
+            let _ = db.call(move |db| db.hard_delete_store(&store.id)).await;
         }
     }
 
     true
 }
 
-/// Process soft-deleted documents (delete from Gemini then hard delete locally)
+/// Purge documents whose time in the trash is up (delete from their backend then hard delete locally)
 async fn process_pending_document_deletions(
-    db: &Database,
-    gemini_client: &Arc<GeminiClient>,
+    db: &Arc<Database>,
+    backends: &Backends,
+    sync_state: &SyncState,
+    retention_days: u32,
 ) -> bool {
-    let documents = match db.get_soft_deleted_documents() {
+    let mut documents = match db
+        .call(move |db| db.get_soft_deleted_documents(retention_days))
+        .await
+    {
         Ok(d) => d,
         Err(_) => return false,
     };
 
+    documents.retain(|doc| {
+        !doc.gemini_name.as_deref().is_some_and(|name| {
+            sync_state.waits_for_connection(BackendKind::from_resource_name(name))
+        })
+    });
+
     if documents.is_empty() {
         return false;
     }
 
     for doc in documents {
-        match gemini_client.delete_document(&doc.gemini_name).await {
-            Ok(()) => {
+        // Only call the backend if the document was uploaded
+        let delete = if let Some(ref gemini_name) = doc.gemini_name {
+            match backends
+                .for_resource(gemini_name)
+                .delete_document(gemini_name)
+                .await
+            {
                 // API deletion successful, hard delete locally
-                let _ = db.hard_delete_document(&doc.id);
-            }
-            Err(e) => {
+                Ok(()) => true,
                 // Check if it's a 404 (already deleted)
-                if e.contains("404") || e.contains("NOT_FOUND") {
-                    let _ = db.hard_delete_document(&doc.id);
-                }
+                Err(e) if e.contains("404") || e.contains("NOT_FOUND") => true,
                 // Otherwise keep the soft-deleted record for retry
+                Err(e) => {
+                    sync_state.report_error(format!("Failed to delete document {}: {}", doc.id, e));
+                    false
+                }
+            }
+        } else {
+            // Never uploaded, just hard delete
+            true
+        };
+
+        if delete {
+            let _ = db.call(move |db| db.hard_delete_document(&doc.id)).await;
+        }
+    }
+
+    true
+}
+
+/// Extract text from unindexed documents into the local full-text index
+async fn index_pending_documents(db: &Arc<Database>) -> bool {
+    let documents = match db.call(|db| db.get_pending_index_documents()).await {
+        Ok(d) => d,
+        Err(e) => {
+            eprintln!("Failed to get pending index documents: {}", e);
+            return false;
+        }
+    };
+
+    if documents.is_empty() {
+        return false;
+    }
+
+    for doc in documents {
+        // Index by name only if the content can't be extracted
+        let content = match extract::extract_text(Path::new(&doc.path)).await {
+            Ok(content) => content,
+            Err(e) => {
+                eprintln!("Failed to extract text from document {}: {}", doc.id, e);
+                None
             }
+        };
+
+        let doc_id = doc.id.clone();
+        let index = db
+            .call(move |db| {
+                db.index_document(&doc.id, &doc.store_id, &doc.name, content.as_deref())
+            })
+            .await;
+        if let Err(e) = index {
+            eprintln!("Failed to index document {}: {}", doc_id, e);
         }
     }
 
     true
 }
 
+/// Prune the document activity log, at most once per [`EVENT_PRUNE_INTERVAL`]
+async fn prune_document_events(db: &Arc<Database>, sync_state: &SyncState) {
+    if !sync_state.take_event_prune() {
+        return;
+    }
+
+    if let Err(e) = db
+        .call(|db| db.prune_document_events(DOCUMENT_EVENT_RETENTION_DAYS, MAX_EVENTS_PER_DOCUMENT))
+        .await
+    {
+        eprintln!("Failed to prune document events: {}", e);
+    }
+}
+
 // ============================================================================
 // Main Sync Task
 // ============================================================================
 
+/// Run every sync step once, returning whether any step found work
+pub async fn run_sync_cycle(
+    events: &dyn EventSink,
+    db: &Arc<Database>,
+    backends: &Backends,
+    sync_state: &SyncState,
+    polling_state: &Arc<PollingState>,
+) -> bool {
+    let started = Instant::now();
+    let mut has_work = false;
+    let retention_days = sync_state.trash_retention_days();
+
+    // Step 1: Sync pending stores
+    has_work |= sync_pending_stores(events, db, backends, sync_state).await;
+
+    // Step 2: Upload pending documents
+    has_work |= sync_pending_documents(events, db, backends, sync_state, polling_state).await;
+
+    // Step 3: Trash the sources of moves whose copy completed
+    has_work |= finish_document_moves(events, db).await;
+
+    // Step 4: Purge stores past their time in the trash
+    has_work |= process_pending_store_deletions(db, backends, sync_state, retention_days).await;
+
+    // Step 5: Purge documents past their time in the trash
+    has_work |= process_pending_document_deletions(db, backends, sync_state, retention_days).await;
+
+    // Step 6: Index new documents for local search
+    has_work |= index_pending_documents(db).await;
+
+    // Step 7: Prune old entries of the document activity log
+    prune_document_events(db, sync_state).await;
+
+    sync_state.finish_cycle(started.elapsed());
+    has_work
+}
+
+/// Check the network before a cycle with remote work, and while offline
+///
+/// Returns false while the remote backend can't be reached: the cycle then
+/// leaves its work for later instead of failing every upload, and the check
+/// runs again every [`OFFLINE_INTERVAL`]. Local stores keep syncing.
+async fn check_connection(db: &Arc<Database>, backends: &Backends, sync_state: &SyncState) -> bool {
+    if !sync_state.is_offline() {
+        let retention_days = sync_state.trash_retention_days();
+        match db.call(move |db| db.get_sync_queue(retention_days)).await {
+            Ok(queue) if queue.is_empty() => return true,
+            Ok(_) => {}
+            Err(e) => eprintln!("Failed to count sync queue: {}", e),
+        }
+    }
+
+    match backends.check_connection().await {
+        Ok(()) => {
+            if sync_state.set_offline(false) {
+                eprintln!("Back online, resuming sync");
+            }
+            true
+        }
+        Err(e) => {
+            if sync_state.set_offline(true) {
+                sync_state.report_error(format!("Offline, pausing Gemini sync: {}", e));
+            }
+            false
+        }
+    }
+}
+
+/// Emit the sync status, for the dashboard
+async fn emit_sync_status(events: &dyn EventSink, db: &Arc<Database>, sync_state: &Arc<SyncState>) {
+    let state = sync_state.clone();
+    match db.call(move |db| state.status(db)).await {
+        Ok(status) => events::emit(events, "sync-status", &status),
+        Err(e) => eprintln!("Failed to get sync status: {}", e),
+    }
+}
+
 /// Start the background sync task
+///
+/// Cycles only run while this process holds the sync lease, so a running app
+/// and the CLI never upload the same document twice. While sync is paused or
+/// offline the polling task pauses too.
 pub fn start_sync_task(
-    app_handle: AppHandle,
+    events: Arc<dyn EventSink>,
     db: Arc<Database>,
-    gemini_client: Arc<GeminiClient>,
+    backends: Arc<Backends>,
     sync_state: Arc<SyncState>,
     polling_state: Arc<PollingState>,
 ) {
@@ -325,31 +821,55 @@ pub fn start_sync_task(
 
     tauri::async_runtime::spawn(async move {
         loop {
-            // Run all sync operations
-            let mut has_work = false;
-
-            // Step 1: Sync pending stores
-            has_work |= sync_pending_stores(&app_handle, &db, &gemini_client).await;
-
-            // Step 2: Upload pending documents
-            has_work |= sync_pending_documents(&app_handle, &db, &gemini_client, &polling_state).await;
+            let interval = if sync_state.is_paused() {
+                // Another process may sync until this one is resumed
+                let state = sync_state.clone();
+                if let Err(e) = db.call(move |db| state.release_lease(db)).await {
+                    eprintln!("Failed to release sync lease: {}", e);
+                }
+                None
+            } else {
+                let online = check_connection(&db, &backends, &sync_state).await;
+                let lease = {
+                    let sync_state = sync_state.clone();
+                    db.call(move |db| sync_state.acquire_lease(db)).await
+                };
+                let has_work = match lease {
+                    Ok(true) => {
+                        run_sync_cycle(events.as_ref(), &db, &backends, &sync_state, &polling_state)
+                            .await
+                    }
+                    Ok(false) => false,
+                    Err(e) => {
+                        eprintln!("Failed to acquire sync lease: {}", e);
+                        false
+                    }
+                };
 
-            // Step 3: Process soft-deleted stores
-            has_work |= process_pending_store_deletions(&db, &gemini_client).await;
+                // Wait before next sync cycle
+                Some(if has_work {
+                    SYNC_INTERVAL
+                } else if !online {
+                    OFFLINE_INTERVAL
+                } else {
+                    IDLE_INTERVAL
+                })
+            };
 
-            // Step 4: Process soft-deleted documents
-            has_work |= process_pending_document_deletions(&db, &gemini_client).await;
+            polling_state.set_paused(sync_state.is_paused() || sync_state.is_offline());
 
-            // Wait before next sync cycle
-            let interval = if has_work {
-                SYNC_INTERVAL
-            } else {
-                IDLE_INTERVAL
-            };
+            sync_state.schedule_wakeup(interval);
+            emit_sync_status(events.as_ref(), &db, &sync_state).await;
 
-            tokio::select! {
-                _ = sleep(interval) => {}
-                _ = sync_state.notify.notified() => {}
+            // Only a resume wakes a paused task
+            match interval {
+                Some(interval) => {
+                    tokio::select! {
+                        _ = sleep(interval) => {}
+                        _ = sync_state.notify.notified() => {}
+                    }
+                }
+                None => sync_state.notify.notified().await,
             }
         }
     });
@@ -1,10 +1,14 @@
+use std::sync::Arc;
+
 use tauri::{
     image::Image,
-    menu::{Menu, MenuItem, MenuEvent},
+    menu::{Menu, MenuItem, MenuEvent, PredefinedMenuItem},
     tray::{MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent},
-    AppHandle, Manager, Result,
+    AppHandle, Listener, Manager, Result,
 };
 
+use crate::commands::sync::set_sync_paused;
+use crate::sync::SyncState;
 use crate::window::{toggle_window, toggle_window_visibility};
 
 // 編譯時嵌入 tray icon（依平台選擇不同圖標）
@@ -16,9 +20,49 @@ const TRAY_ICON: &[u8] = include_bytes!("../icons/tray-icon-win.png"); // 彩色
 
 /// 創建系統托盤
 pub fn setup_system_tray(app: &AppHandle) -> Result<()> {
+    let sync_state = app.state::<Arc<SyncState>>();
+    let (paused, offline) = (sync_state.is_paused(), sync_state.is_offline());
+
     let show_item = MenuItem::with_id(app, "show", "顯示搜尋", true, None::<&str>)?;
+    // 同步狀態僅供顯示，不可點擊
+    let sync_status_item = MenuItem::with_id(
+        app,
+        "sync_status",
+        sync_status_text(paused, offline),
+        false,
+        None::<&str>,
+    )?;
+    let sync_toggle_item = MenuItem::with_id(
+        app,
+        "toggle_sync",
+        sync_toggle_text(paused),
+        true,
+        None::<&str>,
+    )?;
     let quit_item = MenuItem::with_id(app, "quit", "退出", true, None::<&str>)?;
-    let menu = Menu::with_items(app, &[&show_item, &quit_item])?;
+    let menu = Menu::with_items(
+        app,
+        &[
+            &show_item,
+            &PredefinedMenuItem::separator(app)?,
+            &sync_status_item,
+            &sync_toggle_item,
+            &PredefinedMenuItem::separator(app)?,
+            &quit_item,
+        ],
+    )?;
+
+    // 同步任務每次循環後發出 sync-status，據此更新選單文字
+    app.listen("sync-status", move |event| {
+        let Ok(status) = serde_json::from_str::<serde_json::Value>(event.payload()) else {
+            return;
+        };
+        let paused = status["paused"].as_bool().unwrap_or(false);
+        let offline = status["offline"].as_bool().unwrap_or(false);
+
+        let _ = sync_status_item.set_text(sync_status_text(paused, offline));
+        let _ = sync_toggle_item.set_text(sync_toggle_text(paused));
+    });
 
     // 從嵌入的 bytes 載入 icon
     let icon = Image::from_bytes(TRAY_ICON).expect("Failed to load tray icon");
@@ -35,6 +79,26 @@ pub fn setup_system_tray(app: &AppHandle) -> Result<()> {
     Ok(())
 }
 
+/// 同步狀態選單項目的文字
+fn sync_status_text(paused: bool, offline: bool) -> &'static str {
+    if paused {
+        "同步已暫停"
+    } else if offline {
+        "離線中，Gemini 同步等待連線"
+    } else {
+        "同步運作中"
+    }
+}
+
+/// 暫停/繼續同步選單項目的文字
+fn sync_toggle_text(paused: bool) -> &'static str {
+    if paused {
+        "繼續同步"
+    } else {
+        "暫停同步"
+    }
+}
+
 /// 處理托盤選單事件
 fn handle_tray_menu_event(app: &AppHandle, event: MenuEvent) {
     match event.id.as_ref() {
@@ -46,6 +110,12 @@ fn handle_tray_menu_event(app: &AppHandle, event: MenuEvent) {
                 let _ = toggle_window_visibility(&window, true);
             }
         }
+        "toggle_sync" => {
+            let paused = app.state::<Arc<SyncState>>().is_paused();
+            if let Err(e) = set_sync_paused(app, !paused) {
+                eprintln!("Failed to toggle sync: {}", e);
+            }
+        }
         _ => {}
     }
 }
@@ -0,0 +1,93 @@
+//! Upload validation
+//!
+//! Checks files before they are queued for upload, so problems are reported
+//! immediately instead of surfacing as failed uploads in the sync loop.
+
+use serde::Serialize;
+use std::fs::File;
+use std::io::ErrorKind;
+use std::path::Path;
+
+use crate::extract::{self, MAX_EXTRACT_BYTES};
+use crate::gemini::{is_supported_mime_type, MAX_UPLOAD_BYTES};
+
+/// Why a file was not accepted for upload
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RejectionReason {
+    NotFound,
+    NotAFile,
+    Unreadable,
+    Empty,
+    TooLarge,
+    UnsupportedType,
+}
+
+/// A file rejected at enqueue time
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RejectedFile {
+    pub name: String,
+    pub path: String,
+    pub reason: RejectionReason,
+    pub message: String,
+}
+
+/// Validate a file for upload
+///
+/// Returns the file size if the file can be uploaded, either as-is or after
+/// local conversion.
+pub fn validate_upload_file(path: &Path) -> Result<u64, (RejectionReason, String)> {
+    let metadata = std::fs::metadata(path).map_err(|e| match e.kind() {
+        ErrorKind::NotFound => (RejectionReason::NotFound, "File not found".to_string()),
+        _ => (
+            RejectionReason::Unreadable,
+            format!("Failed to read file metadata: {}", e),
+        ),
+    })?;
+
+    if !metadata.is_file() {
+        return Err((RejectionReason::NotAFile, "Not a regular file".to_string()));
+    }
+
+    File::open(path).map_err(|e| {
+        (
+            RejectionReason::Unreadable,
+            format!("File is not readable: {}", e),
+        )
+    })?;
+
+    let size = metadata.len();
+    if size == 0 {
+        return Err((RejectionReason::Empty, "File is empty".to_string()));
+    }
+
+    let mime_type = mime_guess::from_path(path)
+        .first_or_octet_stream()
+        .to_string();
+
+    // Natively supported files are uploaded as-is, others must be converted locally
+    let max_size = if is_supported_mime_type(&mime_type) {
+        MAX_UPLOAD_BYTES
+    } else if extract::registry().find(path).is_some() {
+        MAX_EXTRACT_BYTES
+    } else {
+        return Err((
+            RejectionReason::UnsupportedType,
+            format!("Unsupported file type: {}", mime_type),
+        ));
+    };
+
+    if size > max_size {
+        return Err((
+            RejectionReason::TooLarge,
+            format!(
+                "File is too large ({} MB, {} MB max)",
+                size / 1024 / 1024,
+                max_size / 1024 / 1024
+            ),
+        ));
+    }
+
+    Ok(size)
+}
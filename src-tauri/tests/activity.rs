@@ -0,0 +1,129 @@
+//! Per-document activity log: recorded transitions and pruning
+
+mod common;
+
+use std::time::Duration;
+
+use common::mock_gemini::Endpoint;
+use common::{is_status, wait_for, TestApp};
+use mizly_lib::db::{Database, DocumentEvent, DocumentEventKind};
+
+const TIMEOUT: Duration = Duration::from_secs(20);
+
+fn kinds(events: &[DocumentEvent]) -> Vec<DocumentEventKind> {
+    events.iter().map(|e| e.kind).collect()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn timeline_follows_a_failed_upload_to_the_trash() {
+    use DocumentEventKind::*;
+
+    let test = TestApp::new().await;
+    test.mock.fail_next(Endpoint::StartUpload, 503, 1);
+    let (_, document_id) = test.create_store_with_document("notes.txt");
+    test.start_tasks();
+
+    wait_for(TIMEOUT, "document to fail", || {
+        is_status(&test.db, &document_id, "failed")
+    })
+    .await;
+
+    let events = test.db.get_document_timeline(&document_id).unwrap();
+    assert_eq!(kinds(&events), [Queued, UploadStarted, Failed]);
+    assert!(events[2].message.as_deref().unwrap().contains("503"));
+
+    test.db
+        .retry_documents(std::slice::from_ref(&document_id))
+        .unwrap();
+    test.sync_state.notify();
+
+    wait_for(TIMEOUT, "document to complete", || {
+        is_status(&test.db, &document_id, "completed")
+    })
+    .await;
+
+    test.db.soft_delete_document(&document_id).unwrap();
+    test.db.restore_document(&document_id).unwrap();
+
+    let events = test.db.get_document_timeline(&document_id).unwrap();
+    assert_eq!(
+        kinds(&events[3..]),
+        [
+            Queued,
+            UploadStarted,
+            UploadFinished,
+            OperationDone,
+            Deleted,
+            Restored
+        ]
+    );
+    assert_eq!(events[3].message.as_deref(), Some("Retried"));
+    assert!(events[5]
+        .message
+        .as_deref()
+        .unwrap()
+        .contains("operations/"));
+    assert!(events.windows(2).all(|pair| pair[0].id < pair[1].id));
+}
+
+#[test]
+fn old_and_excess_events_are_pruned() {
+    let dir = tempfile::tempdir().unwrap();
+    let db = Database::new(dir.path()).unwrap();
+    db.create_store("animals", "Animals", None, "gemini", false)
+        .unwrap();
+    for id in ["fox", "owl"] {
+        db.create_document(id, "animals", id, "/data/animals", None, None, None)
+            .unwrap();
+    }
+    for _ in 0..4 {
+        db.record_document_event("fox", DocumentEventKind::UploadStarted, None)
+            .unwrap();
+    }
+
+    // The owl's creation is past the retention period
+    let conn = db.writer().unwrap();
+    conn.execute(
+        "UPDATE document_events SET created_at = datetime('now', '-100 days') WHERE document_id = 'owl'",
+        [],
+    )
+    .unwrap();
+    drop(conn);
+
+    // Five fox events, three kept; the owl's expired one
+    assert_eq!(db.prune_document_events(90, 3).unwrap(), 3);
+    let events = db.get_document_timeline("fox").unwrap();
+    assert_eq!(events.len(), 3);
+    assert!(events
+        .iter()
+        .all(|e| e.kind == DocumentEventKind::UploadStarted));
+    assert!(db.get_document_timeline("owl").unwrap().is_empty());
+
+    // Purged documents keep their timeline, ending with the purge
+    db.hard_delete_document("fox").unwrap();
+    let events = db.get_document_timeline("fox").unwrap();
+    assert_eq!(events.len(), 4);
+    assert_eq!(events[3].kind, DocumentEventKind::Purged);
+    assert_eq!(db.prune_document_events(90, 3).unwrap(), 1);
+    assert_eq!(
+        db.get_document_timeline("fox").unwrap().last().unwrap().kind,
+        DocumentEventKind::Purged
+    );
+
+    // So do the documents of a purged store
+    db.hard_delete_store("animals").unwrap();
+    let events = db.get_document_timeline("owl").unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].kind, DocumentEventKind::Purged);
+
+    // Until they age out like any other event
+    let conn = db.writer().unwrap();
+    conn.execute(
+        "UPDATE document_events SET created_at = datetime('now', '-100 days')",
+        [],
+    )
+    .unwrap();
+    drop(conn);
+    assert_eq!(db.prune_document_events(90, 3).unwrap(), 4);
+    assert!(db.get_document_timeline("fox").unwrap().is_empty());
+}
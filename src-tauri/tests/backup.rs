@@ -0,0 +1,101 @@
+//! Database backups, restores and recovery from a damaged file
+
+mod common;
+
+use std::fs;
+
+use common::TestApp;
+use mizly_lib::backup::{create_backup, list_backups, open_database, restore_backup};
+use mizly_lib::db::{check_file_integrity, Database, DATABASE_FILE};
+
+fn journal_mode(db: &Database) -> String {
+    let conn = db.writer().unwrap();
+    conn.query_row("PRAGMA journal_mode", [], |row| row.get(0))
+        .unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn backups_are_pruned_to_the_newest() {
+    let test = TestApp::new().await;
+    assert_eq!(journal_mode(&test.db), "wal");
+    test.create_store_with_document("fox.txt");
+
+    let mut names = Vec::new();
+    for _ in 0..4 {
+        names.push(create_backup(&test.db, test.dir.path(), 2).unwrap().name);
+    }
+
+    let backups = list_backups(test.dir.path()).unwrap();
+    let kept: Vec<_> = backups.iter().map(|b| b.name.clone()).collect();
+    assert_eq!(kept, [names[3].clone(), names[2].clone()]);
+
+    // Backups are complete, self-contained databases
+    let path = std::path::Path::new(&backups[0].path);
+    assert!(check_file_integrity(path).unwrap().is_empty());
+    let copy = Database::open(path).unwrap();
+    assert_eq!(copy.get_stores().unwrap().len(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn restore_replaces_contents_and_keeps_the_previous_state() {
+    let test = TestApp::new().await;
+    let (store_id, _) = test.create_store_with_document("fox.txt");
+    let backup = create_backup(&test.db, test.dir.path(), 5).unwrap();
+
+    test.db.hard_delete_store(&store_id).unwrap();
+    test.create_store_with_document("wolf.txt");
+
+    let result = restore_backup(&test.db, test.dir.path(), &backup.name).unwrap();
+    assert_eq!(result.restored.name, backup.name);
+
+    let stores = test.db.get_stores().unwrap();
+    assert_eq!(stores.len(), 1);
+    assert_eq!(stores[0].store.id, store_id);
+    assert_eq!(journal_mode(&test.db), "wal");
+
+    // The state before the restore can be restored in turn
+    let previous = Database::open(std::path::Path::new(&result.previous.path)).unwrap();
+    assert_ne!(previous.get_stores().unwrap()[0].store.id, store_id);
+
+    let error = restore_backup(&test.db, test.dir.path(), "stores-missing.db").unwrap_err();
+    assert_eq!(error, "Backup not found: stores-missing.db");
+}
+
+#[test]
+fn damaged_database_is_replaced_with_the_newest_sound_backup() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let (db, recovery) = open_database(dir.path()).unwrap();
+    assert!(recovery.is_none());
+    db.create_store("animals", "Animals", None, "gemini", false)
+        .unwrap();
+    let backup = create_backup(&db, dir.path(), 5).unwrap();
+    drop(db);
+
+    // A damaged newer backup is skipped
+    let damaged_backup = dir.path().join("backups/stores-99999999-000000-000.db");
+    fs::write(&damaged_backup, "not a database").unwrap();
+    fs::write(dir.path().join(DATABASE_FILE), "garbage that is not SQLite").unwrap();
+
+    let (db, recovery) = open_database(dir.path()).unwrap();
+    let recovery = recovery.unwrap();
+    assert_eq!(
+        recovery.restored_from.as_deref(),
+        Some(backup.name.as_str())
+    );
+    assert_eq!(db.get_stores().unwrap()[0].store.title, "Animals");
+    assert_eq!(
+        fs::read_to_string(&recovery.damaged_path).unwrap(),
+        "garbage that is not SQLite"
+    );
+}
+
+#[test]
+fn damaged_database_without_backups_starts_empty() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join(DATABASE_FILE), "garbage that is not SQLite").unwrap();
+
+    let (db, recovery) = open_database(dir.path()).unwrap();
+    assert_eq!(recovery.unwrap().restored_from, None);
+    assert!(db.get_stores().unwrap().is_empty());
+}
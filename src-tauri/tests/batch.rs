@@ -0,0 +1,173 @@
+//! Batch document operations: delete, retry, re-upload and tag
+
+mod common;
+
+use std::time::Duration;
+
+use common::mock_gemini::Endpoint;
+use common::{is_status, wait_for, TestApp};
+use mizly_lib::db::TagChange;
+use serde_json::json;
+
+const TIMEOUT: Duration = Duration::from_secs(20);
+
+fn tags(add: &[&str], remove: &[&str]) -> TagChange {
+    TagChange {
+        add: add.iter().map(|t| t.to_string()).collect(),
+        remove: remove.iter().map(|t| t.to_string()).collect(),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn deletes_report_each_document() {
+    let test = TestApp::new().await;
+    let (store_id, first_id) = test.create_store_with_document("first.txt");
+    let path = test.write_file("second.txt", "Second");
+    test.db
+        .create_document(
+            "second",
+            &store_id,
+            "second.txt",
+            path.to_str().unwrap(),
+            Some("text/plain"),
+            Some(6),
+            None,
+        )
+        .unwrap();
+
+    let ids = [first_id, "missing".to_string(), "second".to_string()];
+    let report = test.db.delete_documents(&ids).unwrap();
+    assert_eq!((report.succeeded, report.failed), (2, 1));
+
+    let missing = &report.items[1];
+    assert_eq!(missing.id, "missing");
+    assert!(!missing.ok);
+    assert_eq!(missing.message.as_deref(), Some("Document not found"));
+    assert_eq!(report.items[2].name.as_deref(), Some("second.txt"));
+
+    assert!(test
+        .db
+        .get_documents_by_store(&store_id)
+        .unwrap()
+        .is_empty());
+    assert_eq!(test.db.get_trash(30).unwrap().documents.len(), 2);
+
+    // Deleted documents are not found a second time
+    let report = test.db.delete_documents(&ids[..1]).unwrap();
+    assert_eq!(report.failed, 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn retried_and_reuploaded_documents_are_uploaded_again() {
+    let test = TestApp::new().await;
+    test.mock.fail_next(Endpoint::StartUpload, 503, 1);
+    let (_, document_id) = test.create_store_with_document("notes.txt");
+    test.start_tasks();
+
+    wait_for(TIMEOUT, "document to fail", || {
+        is_status(&test.db, &document_id, "failed")
+    })
+    .await;
+
+    let ids = [document_id.clone()];
+    let report = test.db.reupload_documents(&ids).unwrap();
+    assert!(report.items[0].ok);
+    let report = test.db.retry_documents(&ids).unwrap();
+    assert_eq!(
+        report.items[0].message.as_deref(),
+        Some("Only failed documents can be retried")
+    );
+    test.sync_state.notify();
+
+    wait_for(TIMEOUT, "document to complete", || {
+        is_status(&test.db, &document_id, "completed")
+    })
+    .await;
+    let first_name = test.db.get_document(&document_id).unwrap().gemini_name;
+
+    // A re-upload replaces the remote copy
+    test.write_file("notes.txt", "The quick brown fox jumps over the lazy cat.");
+    let report = test.db.reupload_documents(&ids).unwrap();
+    assert_eq!(report.succeeded, 1);
+    let report = test.db.reupload_documents(&ids).unwrap();
+    assert_eq!(
+        report.items[0].message.as_deref(),
+        Some("Already queued for upload")
+    );
+    test.sync_state.notify();
+
+    wait_for(TIMEOUT, "re-upload to complete", || {
+        test.db
+            .get_document(&document_id)
+            .map(|d| d.status == "completed" && d.gemini_name != first_name)
+            .unwrap_or(false)
+    })
+    .await;
+
+    wait_for(TIMEOUT, "previous copy to be deleted", || {
+        test.mock.documents().len() == 1
+    })
+    .await;
+    let document = test.db.get_document(&document_id).unwrap();
+    assert_eq!(test.mock.documents()[0].name, document.gemini_name.unwrap());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn tags_are_sent_as_custom_metadata() {
+    let test = TestApp::new().await;
+    let (_, document_id) = test.create_store_with_document("notes.txt");
+    let ids = [document_id.clone()];
+
+    // Documents not uploaded yet keep their place in the queue
+    let report = test
+        .db
+        .tag_documents(&ids, &tags(&[" animals ", "fox", "fox"], &[]))
+        .unwrap();
+    assert_eq!(report.items[0].message, None);
+
+    test.start_tasks();
+    wait_for(TIMEOUT, "document to complete", || {
+        is_status(&test.db, &document_id, "completed")
+    })
+    .await;
+    assert_eq!(
+        test.mock.documents()[0].custom_metadata,
+        json!([{ "key": "tags", "stringListValue": { "values": ["animals", "fox"] } }])
+    );
+
+    let report = test.db.tag_documents(&ids, &tags(&["fox"], &[])).unwrap();
+    assert_eq!(report.items[0].message.as_deref(), Some("Tags unchanged"));
+
+    // Uploaded documents are re-uploaded with their new tags
+    let report = test
+        .db
+        .tag_documents(&ids, &tags(&["dogs"], &["fox"]))
+        .unwrap();
+    assert_eq!(
+        report.items[0].message.as_deref(),
+        Some("Queued for re-upload with the new tags")
+    );
+    test.sync_state.notify();
+
+    wait_for(TIMEOUT, "re-upload with the new tags", || {
+        test.mock.documents().iter().any(|d| {
+            d.custom_metadata
+                == json!([{ "key": "tags", "stringListValue": { "values": ["animals", "dogs"] } }])
+        })
+    })
+    .await;
+    wait_for(TIMEOUT, "re-upload to complete", || {
+        is_status(&test.db, &document_id, "completed")
+    })
+    .await;
+
+    // Removing the last tags drops the entry
+    test.db
+        .tag_documents(&ids, &tags(&[], &["animals", "dogs"]))
+        .unwrap();
+    let document = test.db.get_document(&document_id).unwrap();
+    assert_eq!(document.custom_metadata, None);
+
+    let error = test.db.tag_documents(&ids, &tags(&[" "], &[])).unwrap_err();
+    assert_eq!(error, "No tags to add or remove");
+}
@@ -0,0 +1,295 @@
+//! Store bundle export and import against the mock Gemini server
+
+mod common;
+
+use std::path::Path;
+use std::time::Duration;
+
+use serde_json::json;
+
+use common::{wait_for, TestApp};
+use mizly_lib::bundle::{export_store, import_store};
+use mizly_lib::db::StoreUpdate;
+
+const TIMEOUT: Duration = Duration::from_secs(20);
+const CONTENT: &str = "The quick brown fox jumps over the lazy dog.";
+
+/// Create a store over `docs/` with one document in a subdirectory
+fn create_store(test: &TestApp) -> (String, String) {
+    let directory = test.dir.path().join("docs");
+    let path = directory.join("animals/fox.txt");
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, CONTENT).unwrap();
+
+    let store_id = uuid::Uuid::new_v4().to_string();
+    let document_id = uuid::Uuid::new_v4().to_string();
+    test.db
+        .create_store(
+            &store_id,
+            "Animals",
+            Some(directory.to_str().unwrap()),
+            "gemini",
+            false,
+        )
+        .unwrap();
+    test.db
+        .update_store_import_rules(&store_id, Some("**/*.txt"), None)
+        .unwrap();
+    test.db
+        .create_document(
+            &document_id,
+            &store_id,
+            "fox.txt",
+            path.to_str().unwrap(),
+            Some("text/plain"),
+            Some(CONTENT.len() as i64),
+            None,
+        )
+        .unwrap();
+
+    (store_id, document_id)
+}
+
+async fn wait_for_completed(test: &TestApp, document_id: &str) {
+    wait_for(TIMEOUT, "document to complete", || {
+        test.db
+            .get_document(document_id)
+            .map(|d| d.status == "completed")
+            .unwrap_or(false)
+    })
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn moves_a_store_with_its_files_and_metadata() {
+    let source = TestApp::new().await;
+    let (store_id, document_id) = create_store(&source);
+    let metadata = json!([{ "key": "team", "stringValue": "research" }]);
+    source
+        .db
+        .update_document_custom_metadata(&document_id, Some(&metadata.to_string()))
+        .unwrap();
+    let store = source.db.get_store(&store_id).unwrap();
+    source
+        .db
+        .update_store(
+            &store_id,
+            &StoreUpdate {
+                title: store.title,
+                directory_path: store.directory_path,
+                description: Some("Field notes".to_string()),
+                color: Some("#f97316".to_string()),
+                icon: Some("🦊".to_string()),
+            },
+        )
+        .unwrap();
+    source.start_tasks();
+    wait_for_completed(&source, &document_id).await;
+    assert_eq!(source.mock.documents()[0].custom_metadata, metadata);
+
+    // Metadata not kept locally is read back from Gemini
+    source
+        .db
+        .update_document_custom_metadata(&document_id, None)
+        .unwrap();
+
+    let bundle_path = source.dir.path().join("animals.zip");
+    let exported = export_store(&source.db, &source.backends, &store_id, &bundle_path, true)
+        .await
+        .unwrap();
+    assert_eq!(exported.documents, 1);
+    assert_eq!(exported.files_included, 1);
+    assert!(exported.missing.is_empty());
+
+    // Another machine, without the original directory
+    let target = TestApp::new().await;
+    let imported = import_store(&target.db, target.dir.path(), &bundle_path, None, false).unwrap();
+    assert_eq!(imported.store.title, "Animals");
+    assert_eq!(imported.store.include_patterns.as_deref(), Some("**/*.txt"));
+    assert_eq!(imported.store.description.as_deref(), Some("Field notes"));
+    assert_eq!(imported.store.color.as_deref(), Some("#f97316"));
+    assert_eq!(imported.store.icon.as_deref(), Some("🦊"));
+    assert_eq!(imported.documents.len(), 1);
+    assert!(imported.rejected.is_empty());
+    assert!(imported.changed.is_empty());
+
+    let document = &imported.documents[0];
+    let extracted = Path::new(imported.store.directory_path.as_deref().unwrap())
+        .join("animals")
+        .join("fox.txt");
+    assert_eq!(Path::new(&document.path), extracted);
+    assert_eq!(std::fs::read_to_string(&extracted).unwrap(), CONTENT);
+
+    // The sync loop uploads the imported document with its metadata
+    target.start_tasks();
+    wait_for_completed(&target, &document.id).await;
+    assert_eq!(target.mock.documents()[0].custom_metadata, metadata);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn resolves_paths_against_another_directory() {
+    let source = TestApp::new().await;
+    let (store_id, _) = create_store(&source);
+    source.write_file("outside.txt", "Not in the store directory.");
+    let outside = source.dir.path().join("outside.txt");
+    source
+        .db
+        .create_document(
+            "outside",
+            &store_id,
+            "outside.txt",
+            outside.to_str().unwrap(),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+
+    let bundle_path = source.dir.path().join("animals.zip");
+    let exported = export_store(&source.db, &source.backends, &store_id, &bundle_path, false)
+        .await
+        .unwrap();
+    assert_eq!(exported.files_included, 0);
+
+    // The directory was copied elsewhere and the file edited since
+    let target = TestApp::new().await;
+    let copy = target.dir.path().join("copy");
+    std::fs::create_dir_all(copy.join("animals")).unwrap();
+    std::fs::write(copy.join("animals/fox.txt"), "The fox sleeps.").unwrap();
+
+    let imported = import_store(
+        &target.db,
+        target.dir.path(),
+        &bundle_path,
+        Some(&copy),
+        false,
+    )
+    .unwrap();
+    assert_eq!(
+        imported.store.directory_path.as_deref(),
+        Some(copy.to_str().unwrap())
+    );
+    assert_eq!(imported.documents.len(), 1);
+    assert_eq!(
+        Path::new(&imported.documents[0].path),
+        copy.join("animals/fox.txt")
+    );
+    assert_eq!(imported.changed, ["fox.txt"]);
+
+    // Absolute paths outside the store directory are only used when allowed
+    assert_eq!(imported.rejected.len(), 1);
+    assert_eq!(imported.rejected[0].path, outside.to_str().unwrap());
+    assert!(imported.rejected[0]
+        .message
+        .starts_with("Outside the store directory"));
+
+    let imported = import_store(
+        &target.db,
+        target.dir.path(),
+        &bundle_path,
+        Some(&copy),
+        true,
+    )
+    .unwrap();
+    assert_eq!(imported.documents.len(), 2);
+    assert_eq!(imported.documents[1].path, outside.to_str().unwrap());
+    assert!(imported.rejected.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn keeps_existing_files_when_extracting() {
+    let source = TestApp::new().await;
+    let (store_id, _) = create_store(&source);
+    let bundle_path = source.dir.path().join("animals.zip");
+    export_store(&source.db, &source.backends, &store_id, &bundle_path, true)
+        .await
+        .unwrap();
+
+    // The target directory already has a different file at the document's path
+    let target = TestApp::new().await;
+    let directory = target.dir.path().join("docs");
+    std::fs::create_dir_all(directory.join("animals")).unwrap();
+    std::fs::write(directory.join("animals/fox.txt"), "The fox sleeps.").unwrap();
+
+    let imported = import_store(
+        &target.db,
+        target.dir.path(),
+        &bundle_path,
+        Some(&directory),
+        false,
+    )
+    .unwrap();
+
+    assert_eq!(
+        std::fs::read_to_string(directory.join("animals/fox.txt")).unwrap(),
+        "The fox sleeps."
+    );
+    assert_eq!(imported.changed, ["fox.txt"]);
+    assert_eq!(imported.documents.len(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn rejects_invalid_bundles() {
+    let test = TestApp::new().await;
+
+    let not_a_bundle = test.write_file("notes.zip", "not a zip");
+    assert!(import_store(&test.db, test.dir.path(), &not_a_bundle, None, false).is_err());
+
+    // Paths must stay inside the target directory
+    let path = test.dir.path().join("evil.zip");
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(&path).unwrap());
+    zip.start_file("manifest.json", zip::write::SimpleFileOptions::default())
+        .unwrap();
+    let manifest = json!({
+        "format": "mizly-store-bundle",
+        "version": 1,
+        "exportedAt": "2026-01-01T00:00:00Z",
+        "store": { "title": "Evil", "backend": "gemini", "localOnly": false },
+        "documents": [{ "name": "passwd", "path": "../../etc/passwd", "relative": true }],
+    });
+    std::io::Write::write_all(&mut zip, manifest.to_string().as_bytes()).unwrap();
+    zip.finish().unwrap();
+
+    let error = import_store(
+        &test.db,
+        test.dir.path(),
+        &path,
+        Some(test.dir.path()),
+        false,
+    )
+    .unwrap_err();
+    assert_eq!(error, "Invalid path in bundle: ../../etc/passwd");
+    assert!(test.db.get_stores().unwrap().is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn imports_nothing_from_a_bundle_with_an_invalid_store() {
+    let test = TestApp::new().await;
+
+    let path = test.dir.path().join("red.zip");
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(&path).unwrap());
+    let options = zip::write::SimpleFileOptions::default();
+    zip.start_file("manifest.json", options).unwrap();
+    let manifest = json!({
+        "format": "mizly-store-bundle",
+        "version": 1,
+        "exportedAt": "2026-01-01T00:00:00Z",
+        "store": { "title": "Animals", "backend": "gemini", "localOnly": false, "color": "red" },
+        "documents": [{
+            "name": "fox.txt",
+            "path": "fox.txt",
+            "relative": true,
+            "file": "files/fox.txt",
+        }],
+    });
+    std::io::Write::write_all(&mut zip, manifest.to_string().as_bytes()).unwrap();
+    zip.start_file("files/fox.txt", options).unwrap();
+    std::io::Write::write_all(&mut zip, CONTENT.as_bytes()).unwrap();
+    zip.finish().unwrap();
+
+    let error = import_store(&test.db, test.dir.path(), &path, None, false).unwrap_err();
+
+    assert_eq!(error, "Invalid color red, expected #rrggbb");
+    assert!(test.db.get_stores().unwrap().is_empty());
+    assert!(!test.dir.path().join("imports").exists());
+}
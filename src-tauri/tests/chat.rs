@@ -0,0 +1,141 @@
+//! Grounded queries against the mock Gemini server
+
+mod common;
+
+use std::time::Duration;
+
+use common::mock_gemini::Endpoint;
+use common::{wait_for, TestApp};
+use mizly_lib::backend::BackendKind;
+use mizly_lib::settings::{self, ModelProvider, RetrievalSettings};
+
+const TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Sync one document and return the remote name of its store
+async fn synced_store(test: &TestApp, file_name: &str) -> String {
+    let (store_id, document_id) = test.create_store_with_document(file_name);
+    test.start_tasks();
+
+    wait_for(TIMEOUT, "document to complete", || {
+        test.db
+            .get_document(&document_id)
+            .map(|d| d.status == "completed")
+            .unwrap_or(false)
+    })
+    .await;
+
+    test.db.get_store(&store_id).unwrap().gemini_name.unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn query_is_grounded_in_store_documents() {
+    let test = TestApp::new().await;
+    test.mock.set_answer("The fox jumps over the dog.");
+    let store_name = synced_store(&test, "fox.txt").await;
+
+    let result = test
+        .backends
+        .get(BackendKind::Gemini)
+        .query(&[store_name], "What does the fox do?")
+        .await
+        .unwrap();
+
+    assert_eq!(result.content, "The fox jumps over the dog.");
+    assert_eq!(result.sources, vec!["fox.txt".to_string()]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn query_reports_api_errors() {
+    let test = TestApp::new().await;
+    let store_name = synced_store(&test, "fox.txt").await;
+    test.mock.fail_next(Endpoint::GenerateContent, 429, 1);
+
+    let store_names = vec![store_name];
+    let backend = test.backends.get(BackendKind::Gemini);
+    let error = backend
+        .query(&store_names, "What does the fox do?")
+        .await
+        .unwrap_err();
+    assert!(error.contains("429"), "unexpected error: {}", error);
+
+    // The failure is not sticky
+    assert!(backend.query(&store_names, "Again?").await.is_ok());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn query_of_unknown_store_fails() {
+    let test = TestApp::new().await;
+
+    let error = test
+        .backends
+        .get(BackendKind::Gemini)
+        .query(&["fileSearchStores/missing".to_string()], "Anything?")
+        .await
+        .unwrap_err();
+
+    assert!(error.contains("404"), "unexpected error: {}", error);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn query_merges_answers_from_local_and_gemini_stores() {
+    let test = TestApp::new().await;
+    test.mock.set_answer("The fox jumps over the dog.");
+
+    // The local store embeds and answers with Gemini, so no local model is needed
+    let retrieval = RetrievalSettings {
+        embedding_provider: ModelProvider::Gemini,
+        generation_provider: ModelProvider::Gemini,
+        ..Default::default()
+    };
+    settings::write_retrieval_settings(test.dir.path(), &retrieval).unwrap();
+
+    let local_store_id = uuid::Uuid::new_v4().to_string();
+    test.db
+        .create_store(&local_store_id, "Local", None, "local", false)
+        .unwrap();
+    let path = test.write_file("dog.txt", "The lazy dog sleeps.");
+    test.db
+        .create_document(
+            "dog",
+            &local_store_id,
+            "dog.txt",
+            path.to_str().unwrap(),
+            Some("text/plain"),
+            Some(20),
+            None,
+        )
+        .unwrap();
+
+    let gemini_name = synced_store(&test, "fox.txt").await;
+    wait_for(TIMEOUT, "local document to complete", || {
+        test.db
+            .get_document("dog")
+            .map(|d| d.status == "completed")
+            .unwrap_or(false)
+    })
+    .await;
+    let local_name = test
+        .db
+        .get_store(&local_store_id)
+        .unwrap()
+        .gemini_name
+        .unwrap();
+
+    let result = test
+        .backends
+        .query(&[gemini_name, local_name], "What do the animals do?")
+        .await
+        .unwrap();
+
+    assert_eq!(
+        result.content,
+        "The fox jumps over the dog.\n\nThe fox jumps over the dog."
+    );
+    assert_eq!(
+        result.sources,
+        vec!["fox.txt".to_string(), "dog.txt".to_string()]
+    );
+
+    let error = test.backends.query(&[], "Anything?").await.unwrap_err();
+    assert_eq!(error, "No stores provided");
+}
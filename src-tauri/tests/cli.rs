@@ -0,0 +1,163 @@
+//! The `mizly-cli` binary against the mock Gemini server
+
+mod common;
+
+use serde_json::{json, Value};
+use tokio::process::Command;
+
+use common::TestApp;
+use mizly_lib::settings::{self, TrashSettings};
+use mizly_lib::sync::SyncState;
+
+/// Point the test's data directory at the mock server
+fn configure(test: &TestApp) {
+    let settings = json!({
+        "gemini_api_key": "AIzaTestKey",
+        "gemini_base_url": test.mock.base_url,
+    });
+    std::fs::write(test.dir.path().join("settings.json"), settings.to_string())
+        .expect("Failed to write settings");
+}
+
+/// Run the CLI on the test's data directory, returning success and its JSON output
+async fn cli(test: &TestApp, args: &[&str]) -> (bool, Value) {
+    let output = Command::new(env!("CARGO_BIN_EXE_mizly-cli"))
+        .env("MIZLY_DATA_DIR", test.dir.path())
+        .args(args)
+        .output()
+        .await
+        .expect("Failed to run CLI");
+
+    let stream = if output.status.success() {
+        &output.stdout
+    } else {
+        &output.stderr
+    };
+    let value = serde_json::from_slice(stream).expect("CLI printed invalid JSON");
+
+    (output.status.success(), value)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn queues_syncs_and_answers() {
+    let test = TestApp::new().await;
+    configure(&test);
+    test.mock.set_answer("The fox jumps.");
+    let path = test.write_file("fox.txt", "The quick brown fox jumps over the lazy dog.");
+
+    let (ok, store) = cli(&test, &["stores", "create", "Animals"]).await;
+    assert!(ok, "{}", store);
+    assert_eq!(store["syncStatus"], "pending");
+
+    let (ok, queued) = cli(
+        &test,
+        &["docs", "add", "--store", "Animals", path.to_str().unwrap()],
+    )
+    .await;
+    assert!(ok, "{}", queued);
+    assert_eq!(queued["documents"].as_array().unwrap().len(), 1);
+
+    let (ok, status) = cli(&test, &["sync", "--status"]).await;
+    assert!(ok, "{}", status);
+    assert_eq!(status["storesPending"], 1);
+    assert_eq!(status["uploadsPending"], 1);
+    assert_eq!(status["paused"], false);
+
+    let (ok, synced) = cli(&test, &["sync", "--once"]).await;
+    assert!(ok, "{}", synced);
+    assert_eq!(synced["synced"], true);
+    assert_eq!(synced["processing"], 0);
+
+    let (_, documents) = cli(&test, &["docs", "status", "--store", "Animals"]).await;
+    assert_eq!(documents[0]["status"], "completed");
+    assert_eq!(test.mock.documents().len(), 1);
+
+    // Copies are queued in the target store, a second copy is rejected
+    cli(&test, &["stores", "create", "Plants"]).await;
+    let document_id = documents[0]["id"].as_str().unwrap();
+    let (ok, copied) = cli(&test, &["docs", "copy", "--to", "Plants", document_id]).await;
+    assert!(ok, "{}", copied);
+    assert_eq!(copied["documents"][0]["status"], "pending");
+    let (_, copied) = cli(&test, &["docs", "copy", "--to", "Plants", document_id]).await;
+    assert_eq!(
+        copied["rejected"][0]["message"],
+        "Already in the target store"
+    );
+
+    let (ok, answer) = cli(
+        &test,
+        &["ask", "--store", "Animals", "What does the fox do?"],
+    )
+    .await;
+    assert!(ok, "{}", answer);
+    assert_eq!(answer["content"], "The fox jumps.");
+    assert_eq!(answer["sources"], json!(["fox.txt"]));
+
+    // Edits are local, the store stays synced
+    let (ok, edited) = cli(
+        &test,
+        &[
+            "stores", "update", "Animals", "--title", "Wildlife", "--icon", "🦊",
+        ],
+    )
+    .await;
+    assert!(ok, "{}", edited);
+    assert_eq!(edited["title"], "Wildlife");
+    assert_eq!(edited["icon"], "🦊");
+    assert_eq!(edited["syncStatus"], "synced");
+
+    // Deleted stores wait in the trash for the grace period
+    let (ok, _) = cli(&test, &["stores", "delete", "Wildlife"]).await;
+    assert!(ok);
+    cli(&test, &["sync", "--once"]).await;
+    assert_eq!(test.mock.stores().len(), 2);
+
+    let (_, trash) = cli(&test, &["trash"]).await;
+    assert_eq!(trash["stores"][0]["title"], "Wildlife");
+    assert_eq!(trash["stores"][0]["documentCount"], 1);
+
+    settings::write_trash_settings(test.dir.path(), &TrashSettings { retention_days: 0 }).unwrap();
+    cli(&test, &["sync", "--once"]).await;
+    assert_eq!(test.mock.stores().len(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn sync_defers_to_the_lease_holder() {
+    let test = TestApp::new().await;
+    configure(&test);
+    let (store_id, _) = test.create_store_with_document("notes.txt");
+
+    // Another process (e.g. the app) is syncing
+    let app_sync = SyncState::new();
+    assert!(app_sync.acquire_lease(&test.db).unwrap());
+
+    let (ok, synced) = cli(&test, &["sync", "--once"]).await;
+    assert!(ok, "{}", synced);
+    assert_eq!(synced["synced"], false);
+    assert!(test.mock.stores().is_empty());
+
+    // Once released, the CLI syncs
+    app_sync.release_lease(&test.db).unwrap();
+    let (_, synced) = cli(&test, &["sync", "--once"]).await;
+    assert_eq!(synced["synced"], true);
+    assert_eq!(test.db.get_store(&store_id).unwrap().sync_status, "synced");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn reports_errors_as_json() {
+    let test = TestApp::new().await;
+
+    let (ok, error) = cli(&test, &["docs", "add", "--store", "Missing", "notes.txt"]).await;
+    assert!(!ok);
+    assert_eq!(error["error"], "Store not found: Missing");
+
+    // Batches report per document instead of failing
+    let (ok, report) = cli(&test, &["docs", "rm", "missing"]).await;
+    assert!(ok, "{}", report);
+    assert_eq!(report["failed"], 1);
+    assert_eq!(report["items"][0]["message"], "Document not found");
+
+    let (ok, error) = cli(&test, &["docs", "tag", "missing"]).await;
+    assert!(!ok);
+    assert_eq!(error["error"], "No tags to add or remove");
+}
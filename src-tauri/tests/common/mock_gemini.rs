@@ -0,0 +1,495 @@
+//! In-process mock of the Gemini File Search API
+//!
+//! Implements the endpoints used by `GeminiClient`: stores, resumable uploads,
+//! upload operations, documents, `generateContent` and `batchEmbedContents`.
+//! Failures can be injected per endpoint, upload operations can be made to
+//! stay running for a number of polls or to finish with an error, and the
+//! server can be taken offline.
+
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{HeaderMap, Method, StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use axum::{Json, Router};
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+/// Endpoints that support failure injection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Endpoint {
+    CreateStore,
+    DeleteStore,
+    StartUpload,
+    UploadBytes,
+    GetOperation,
+    GetDocument,
+    DeleteDocument,
+    GenerateContent,
+}
+
+#[derive(Debug, Clone)]
+pub struct MockDocument {
+    pub name: String,
+    pub store_name: String,
+    pub display_name: String,
+    pub mime_type: String,
+    pub size: usize,
+    pub active: bool,
+    /// `customMetadata` sent with the upload, `Null` when absent
+    pub custom_metadata: Value,
+}
+
+#[derive(Debug, Clone)]
+struct MockOperation {
+    document_name: String,
+    polls_remaining: usize,
+    error: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct PendingUpload {
+    store_name: String,
+    display_name: String,
+    mime_type: String,
+    custom_metadata: Value,
+}
+
+#[derive(Default)]
+struct MockState {
+    next_id: usize,
+    stores: HashMap<String, String>,
+    documents: HashMap<String, MockDocument>,
+    operations: HashMap<String, MockOperation>,
+    uploads: HashMap<String, PendingUpload>,
+    failures: HashMap<Endpoint, VecDeque<StatusCode>>,
+    operation_polls: usize,
+    operation_error: Option<String>,
+    answer: String,
+    requests: Vec<String>,
+}
+
+impl MockState {
+    fn next_id(&mut self, prefix: &str) -> String {
+        self.next_id += 1;
+        format!("{}-{}", prefix, self.next_id)
+    }
+
+    fn injected_failure(&mut self, endpoint: Endpoint) -> Option<Response> {
+        let status = self.failures.get_mut(&endpoint)?.pop_front()?;
+        Some(error(status, "Injected failure"))
+    }
+}
+
+/// A running mock server
+pub struct MockGemini {
+    pub base_url: String,
+    state: Arc<Mutex<MockState>>,
+    addr: SocketAddr,
+    app: Router,
+    /// Accept loop, None while offline
+    server: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl MockGemini {
+    /// Start the server on a random local port
+    pub async fn start() -> Self {
+        let state = Arc::new(Mutex::new(MockState {
+            answer: "Mock answer".to_string(),
+            ..Default::default()
+        }));
+
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("Failed to bind mock server");
+        let addr = listener.local_addr().unwrap();
+        let base_url = format!("http://{}", addr);
+
+        let app = Router::new()
+            .fallback(handle)
+            .with_state((state.clone(), base_url.clone()));
+        let server = serve(listener, app.clone());
+
+        Self {
+            base_url,
+            state,
+            addr,
+            app,
+            server: Mutex::new(Some(server)),
+        }
+    }
+
+    /// Refuse new connections as if the network were down, or accept them again
+    ///
+    /// Connections already open keep working.
+    pub async fn set_offline(&self, offline: bool) {
+        let server = self.server.lock().unwrap().take();
+        if let Some(server) = server {
+            server.abort();
+            let _ = server.await;
+        }
+
+        if !offline {
+            let listener = TcpListener::bind(self.addr)
+                .await
+                .expect("Failed to bind mock server");
+            *self.server.lock().unwrap() = Some(serve(listener, self.app.clone()));
+        }
+    }
+
+    /// Respond to the next `count` requests to `endpoint` with `status`
+    pub fn fail_next(&self, endpoint: Endpoint, status: u16, count: usize) {
+        let status = StatusCode::from_u16(status).unwrap();
+        let mut state = self.state.lock().unwrap();
+        let queue = state.failures.entry(endpoint).or_default();
+        queue.extend(std::iter::repeat_n(status, count));
+    }
+
+    /// Keep new upload operations running for `polls` status requests
+    pub fn set_operation_polls(&self, polls: usize) {
+        self.state.lock().unwrap().operation_polls = polls;
+    }
+
+    /// Finish new upload operations with an error
+    pub fn fail_operations(&self, message: &str) {
+        self.state.lock().unwrap().operation_error = Some(message.to_string());
+    }
+
+    /// Text returned by `generateContent`
+    pub fn set_answer(&self, answer: &str) {
+        self.state.lock().unwrap().answer = answer.to_string();
+    }
+
+    pub fn stores(&self) -> Vec<String> {
+        self.state.lock().unwrap().stores.keys().cloned().collect()
+    }
+
+    pub fn documents(&self) -> Vec<MockDocument> {
+        self.state.lock().unwrap().documents.values().cloned().collect()
+    }
+
+    /// Requests received so far, as `METHOD /path`
+    pub fn requests(&self) -> Vec<String> {
+        self.state.lock().unwrap().requests.clone()
+    }
+}
+
+fn serve(listener: TcpListener, app: Router) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.expect("Mock server failed");
+    })
+}
+
+fn error(status: StatusCode, message: &str) -> Response {
+    let body = json!({
+        "error": {
+            "code": status.as_u16(),
+            "message": message,
+            "status": status.canonical_reason().unwrap_or("ERROR"),
+        }
+    });
+    (status, Json(body)).into_response()
+}
+
+fn not_found(name: &str) -> Response {
+    error(StatusCode::NOT_FOUND, &format!("NOT_FOUND: {}", name))
+}
+
+fn has_api_key(uri: &Uri) -> bool {
+    uri.query()
+        .map(|q| q.split('&').any(|p| p.starts_with("key=") && p.len() > 4))
+        .unwrap_or(false)
+}
+
+async fn handle(
+    State((state, base_url)): State<(Arc<Mutex<MockState>>, String)>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let mut state = state.lock().unwrap();
+    let path = uri.path().to_string();
+    state.requests.push(format!("{} {}", method, path));
+
+    // Upload sessions are authenticated by their URL
+    if let Some(upload_id) = path.strip_prefix("/upload-session/") {
+        return upload_bytes(&mut state, upload_id, &body);
+    }
+
+    if !has_api_key(&uri) {
+        return error(StatusCode::FORBIDDEN, "API key required");
+    }
+
+    if let Some(rest) = path.strip_prefix("/upload/v1beta/") {
+        let store_name = match rest.strip_suffix(":uploadToFileSearchStore") {
+            Some(name) => name,
+            None => return not_found(rest),
+        };
+        return start_upload(&mut state, &base_url, store_name, &headers, &body);
+    }
+
+    let name = match path.strip_prefix("/v1beta/") {
+        Some(name) => name.to_string(),
+        None => return not_found(&path),
+    };
+
+    if let Some(model) = name.strip_prefix("models/") {
+        if method == Method::POST && model.ends_with(":generateContent") {
+            return generate_content(&mut state, &body);
+        }
+        if method == Method::POST && model.ends_with(":batchEmbedContents") {
+            return embed_contents(&body);
+        }
+        return not_found(&name);
+    }
+
+    let segments: Vec<&str> = name.split('/').collect();
+    match (method, segments.as_slice()) {
+        (Method::POST, ["fileSearchStores"]) => create_store(&mut state, &body),
+        (Method::DELETE, ["fileSearchStores", _]) => delete_store(&mut state, &name),
+        (Method::GET, ["fileSearchStores", _, "upload", "operations", _]) => {
+            get_operation(&mut state, &name)
+        }
+        (Method::GET, ["fileSearchStores", _, "documents", _]) => get_document(&mut state, &name),
+        (Method::DELETE, ["fileSearchStores", _, "documents", _]) => {
+            delete_document(&mut state, &name)
+        }
+        _ => not_found(&name),
+    }
+}
+
+fn create_store(state: &mut MockState, body: &[u8]) -> Response {
+    if let Some(response) = state.injected_failure(Endpoint::CreateStore) {
+        return response;
+    }
+
+    let request: Value = serde_json::from_slice(body).unwrap_or_default();
+    let display_name = request["displayName"].as_str().unwrap_or_default().to_string();
+    let name = format!("fileSearchStores/{}", state.next_id("store"));
+    state.stores.insert(name.clone(), display_name.clone());
+
+    Json(json!({
+        "name": name,
+        "displayName": display_name,
+        "createTime": "2025-01-01T00:00:00Z",
+        "updateTime": "2025-01-01T00:00:00Z",
+        "activeDocumentsCount": "0",
+        "sizeBytes": "0",
+    }))
+    .into_response()
+}
+
+fn delete_store(state: &mut MockState, name: &str) -> Response {
+    if let Some(response) = state.injected_failure(Endpoint::DeleteStore) {
+        return response;
+    }
+
+    if state.stores.remove(name).is_none() {
+        return not_found(name);
+    }
+    state.documents.retain(|_, doc| doc.store_name != name);
+
+    Json(json!({})).into_response()
+}
+
+fn start_upload(
+    state: &mut MockState,
+    base_url: &str,
+    store_name: &str,
+    headers: &HeaderMap,
+    body: &[u8],
+) -> Response {
+    if let Some(response) = state.injected_failure(Endpoint::StartUpload) {
+        return response;
+    }
+
+    if !state.stores.contains_key(store_name) {
+        return not_found(store_name);
+    }
+
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string()
+    };
+
+    if header("x-goog-upload-protocol") != "resumable" || header("x-goog-upload-command") != "start" {
+        return error(StatusCode::BAD_REQUEST, "Expected a resumable upload start");
+    }
+
+    let metadata: Value = serde_json::from_slice(body).unwrap_or_default();
+    let upload_id = state.next_id("upload");
+    state.uploads.insert(
+        upload_id.clone(),
+        PendingUpload {
+            store_name: store_name.to_string(),
+            display_name: metadata["displayName"].as_str().unwrap_or_default().to_string(),
+            mime_type: header("x-goog-upload-header-content-type"),
+            custom_metadata: metadata["customMetadata"].clone(),
+        },
+    );
+
+    let upload_url = format!("{}/upload-session/{}", base_url, upload_id);
+    (StatusCode::OK, [("x-goog-upload-url", upload_url)], "").into_response()
+}
+
+fn upload_bytes(state: &mut MockState, upload_id: &str, body: &[u8]) -> Response {
+    if let Some(response) = state.injected_failure(Endpoint::UploadBytes) {
+        return response;
+    }
+
+    let upload = match state.uploads.remove(upload_id) {
+        Some(upload) => upload,
+        None => return not_found(upload_id),
+    };
+
+    let document_name = format!("{}/documents/{}", upload.store_name, state.next_id("doc"));
+    state.documents.insert(
+        document_name.clone(),
+        MockDocument {
+            name: document_name.clone(),
+            store_name: upload.store_name.clone(),
+            display_name: upload.display_name,
+            mime_type: upload.mime_type,
+            size: body.len(),
+            active: false,
+            custom_metadata: upload.custom_metadata,
+        },
+    );
+
+    let operation_name = format!("{}/upload/operations/{}", upload.store_name, state.next_id("op"));
+    let operation = MockOperation {
+        document_name,
+        polls_remaining: state.operation_polls,
+        error: state.operation_error.clone(),
+    };
+    state.operations.insert(operation_name.clone(), operation);
+
+    Json(json!({ "name": operation_name, "done": false })).into_response()
+}
+
+fn get_operation(state: &mut MockState, name: &str) -> Response {
+    if let Some(response) = state.injected_failure(Endpoint::GetOperation) {
+        return response;
+    }
+
+    let operation = match state.operations.get_mut(name) {
+        Some(operation) => operation,
+        None => return not_found(name),
+    };
+
+    if operation.polls_remaining > 0 {
+        operation.polls_remaining -= 1;
+        return Json(json!({ "name": name, "done": false })).into_response();
+    }
+
+    if let Some(message) = operation.error.clone() {
+        return Json(json!({
+            "name": name,
+            "done": true,
+            "error": { "code": 13, "message": message },
+        }))
+        .into_response();
+    }
+
+    let document_name = operation.document_name.clone();
+    if let Some(document) = state.documents.get_mut(&document_name) {
+        document.active = true;
+    }
+
+    Json(json!({
+        "name": name,
+        "done": true,
+        "response": {
+            "@type": "type.googleapis.com/google.ai.generativelanguage.v1main.UploadToFileSearchStoreResponse",
+            "documentName": document_name,
+        },
+    }))
+    .into_response()
+}
+
+fn get_document(state: &mut MockState, name: &str) -> Response {
+    if let Some(response) = state.injected_failure(Endpoint::GetDocument) {
+        return response;
+    }
+
+    match state.documents.get(name) {
+        Some(document) => Json(json!({
+            "name": document.name,
+            "displayName": document.display_name,
+            "mimeType": document.mime_type,
+            "sizeBytes": document.size.to_string(),
+            "state": if document.active { "STATE_ACTIVE" } else { "STATE_PENDING" },
+            "customMetadata": document.custom_metadata,
+        }))
+        .into_response(),
+        None => not_found(name),
+    }
+}
+
+fn delete_document(state: &mut MockState, name: &str) -> Response {
+    if let Some(response) = state.injected_failure(Endpoint::DeleteDocument) {
+        return response;
+    }
+
+    match state.documents.remove(name) {
+        Some(_) => Json(json!({})).into_response(),
+        None => not_found(name),
+    }
+}
+
+/// The same embedding for every text, so any chunk matches any query
+fn embed_contents(body: &[u8]) -> Response {
+    let request: Value = serde_json::from_slice(body).unwrap_or_default();
+    let count = request["requests"].as_array().map_or(0, Vec::len);
+    let embeddings: Vec<Value> = (0..count)
+        .map(|_| json!({ "values": [1.0, 1.0, 1.0] }))
+        .collect();
+
+    Json(json!({ "embeddings": embeddings })).into_response()
+}
+
+fn generate_content(state: &mut MockState, body: &[u8]) -> Response {
+    if let Some(response) = state.injected_failure(Endpoint::GenerateContent) {
+        return response;
+    }
+
+    let request: Value = serde_json::from_slice(body).unwrap_or_default();
+    let store_names: Vec<String> = request["tools"][0]["file_search"]["file_search_store_names"]
+        .as_array()
+        .map(|names| {
+            names
+                .iter()
+                .filter_map(|n| n.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if let Some(missing) = store_names.iter().find(|n| !state.stores.contains_key(*n)) {
+        return not_found(missing);
+    }
+
+    // Ground the answer in every active document of the queried stores
+    let grounding_chunks: Vec<Value> = state
+        .documents
+        .values()
+        .filter(|doc| doc.active && store_names.contains(&doc.store_name))
+        .map(|doc| json!({ "retrievedContext": { "title": doc.display_name, "text": "..." } }))
+        .collect();
+
+    Json(json!({
+        "candidates": [{
+            "content": { "role": "model", "parts": [{ "text": state.answer }] },
+            "finishReason": "STOP",
+            "groundingMetadata": { "groundingChunks": grounding_chunks },
+        }],
+        "usageMetadata": { "promptTokenCount": 1, "candidatesTokenCount": 1, "totalTokenCount": 2 },
+    }))
+    .into_response()
+}
@@ -0,0 +1,155 @@
+//! Shared setup for integration tests
+
+#![allow(dead_code)]
+
+pub mod mock_gemini;
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use mizly_lib::backend::{Backends, LocalBackend};
+use mizly_lib::db::Database;
+use mizly_lib::events::EventSink;
+use mizly_lib::gemini::GeminiClient;
+use mizly_lib::polling::{start_polling_task, PollingState};
+use mizly_lib::settings;
+use mizly_lib::sync::{start_sync_task, SyncState};
+use serde_json::Value;
+use tempfile::TempDir;
+
+use mock_gemini::MockGemini;
+
+/// Event sink that keeps every event for inspection
+#[derive(Default)]
+pub struct RecordingSink {
+    events: Mutex<Vec<(String, Value)>>,
+}
+
+impl RecordingSink {
+    /// Payloads of the events with a name, in emission order
+    pub fn payloads(&self, event: &str) -> Vec<Value> {
+        self.events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(name, _)| name == event)
+            .map(|(_, payload)| payload.clone())
+            .collect()
+    }
+}
+
+impl EventSink for RecordingSink {
+    fn emit(&self, event: &str, payload: Value) {
+        self.events.lock().unwrap().push((event.to_string(), payload));
+    }
+}
+
+/// Sync engine wired to a mock Gemini server, with its own data directory
+pub struct TestApp {
+    pub db: Arc<Database>,
+    pub backends: Arc<Backends>,
+    pub sync_state: Arc<SyncState>,
+    pub polling_state: Arc<PollingState>,
+    pub events: Arc<RecordingSink>,
+    pub mock: MockGemini,
+    pub dir: TempDir,
+}
+
+impl TestApp {
+    pub async fn new() -> Self {
+        let mock = MockGemini::start().await;
+        let dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let data_dir = dir.path().to_path_buf();
+
+        settings::write_api_key(&data_dir, "AIzaTestKey").expect("Failed to set API key");
+        let db = Arc::new(Database::new(&data_dir).expect("Failed to open database"));
+
+        let gemini_client = Arc::new(GeminiClient::with_base_url(
+            data_dir.clone(),
+            mock.base_url.clone(),
+        ));
+        let backends = Backends::new(
+            db.clone(),
+            gemini_client.clone(),
+            Arc::new(LocalBackend::new(data_dir, db.clone(), gemini_client)),
+        );
+
+        Self {
+            db,
+            backends,
+            sync_state: SyncState::new(),
+            polling_state: PollingState::new(),
+            events: Arc::new(RecordingSink::default()),
+            mock,
+            dir,
+        }
+    }
+
+    /// Start the background sync and polling tasks
+    pub fn start_tasks(&self) {
+        start_polling_task(
+            self.events.clone(),
+            self.db.clone(),
+            self.backends.clone(),
+            self.polling_state.clone(),
+        );
+        start_sync_task(
+            self.events.clone(),
+            self.db.clone(),
+            self.backends.clone(),
+            self.sync_state.clone(),
+            self.polling_state.clone(),
+        );
+    }
+
+    /// Write a file to upload
+    pub fn write_file(&self, name: &str, content: &str) -> PathBuf {
+        let path = self.dir.path().join(name);
+        std::fs::write(&path, content).expect("Failed to write file");
+        path
+    }
+
+    /// Create a Gemini store with one queued document, returning their ids
+    pub fn create_store_with_document(&self, file_name: &str) -> (String, String) {
+        let path = self.write_file(file_name, "The quick brown fox jumps over the lazy dog.");
+        let store_id = uuid::Uuid::new_v4().to_string();
+        let document_id = uuid::Uuid::new_v4().to_string();
+
+        self.db
+            .create_store(&store_id, "Test store", None, "gemini", false)
+            .expect("Failed to create store");
+        self.db
+            .create_document(
+                &document_id,
+                &store_id,
+                file_name,
+                path.to_str().unwrap(),
+                Some("text/plain"),
+                Some(44),
+                None,
+            )
+            .expect("Failed to create document");
+
+        (store_id, document_id)
+    }
+}
+
+/// Wait until `condition` holds, panicking after `timeout`
+pub async fn wait_for<F: FnMut() -> bool>(timeout: Duration, what: &str, mut condition: F) {
+    let deadline = tokio::time::Instant::now() + timeout;
+
+    while !condition() {
+        if tokio::time::Instant::now() > deadline {
+            panic!("Timed out waiting for {}", what);
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+}
+
+/// Whether the document exists (outside the trash) with `status`
+pub fn is_status(db: &Database, document_id: &str, status: &str) -> bool {
+    db.get_document(document_id)
+        .map(|d| d.status == status)
+        .unwrap_or(false)
+}
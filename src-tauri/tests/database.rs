@@ -0,0 +1,69 @@
+//! Connection pool: readers alongside the writer, and work off the async executor
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use mizly_lib::db::Database;
+
+fn open() -> (tempfile::TempDir, Arc<Database>) {
+    let dir = tempfile::tempdir().unwrap();
+    let db = Arc::new(Database::new(dir.path()).unwrap());
+    (dir, db)
+}
+
+#[test]
+fn reads_do_not_wait_for_the_writer() {
+    let (_dir, db) = open();
+    db.create_store("animals", "Animals", None, "gemini", false)
+        .unwrap();
+
+    // An open write transaction holds the writer
+    let writer = db.writer().unwrap();
+    writer.execute_batch("BEGIN IMMEDIATE").unwrap();
+    writer
+        .execute(
+            "INSERT INTO stores (id, title, sync_status) VALUES ('plants', 'Plants', 'pending')",
+            [],
+        )
+        .unwrap();
+
+    // Readers see the last committed state meanwhile
+    let reader = {
+        let db = db.clone();
+        std::thread::spawn(move || db.get_stores().unwrap().len())
+    };
+    assert_eq!(reader.join().unwrap(), 1);
+
+    writer.execute_batch("COMMIT").unwrap();
+    drop(writer);
+    assert_eq!(db.get_stores().unwrap().len(), 2);
+}
+
+// The writer is held across awaits on purpose, to keep `call` waiting
+#[allow(clippy::await_holding_lock)]
+#[tokio::test(flavor = "current_thread")]
+async fn calls_leave_the_executor_free() {
+    let (_dir, db) = open();
+    let writer = db.writer().unwrap();
+
+    // Waits for the writer on the blocking pool, not on the only executor thread
+    let create = tokio::spawn({
+        let db = db.clone();
+        async move {
+            db.call(|db| db.create_store("animals", "Animals", None, "gemini", false))
+                .await
+        }
+    });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(!create.is_finished());
+
+    drop(writer);
+    let store = create.await.unwrap().unwrap();
+
+    let found = db.call(move |db| db.get_store(&store.id)).await.unwrap();
+    assert_eq!(found.title, "Animals");
+
+    // Errors come back as they are
+    let error = db.call(|db| db.get_store("missing")).await.unwrap_err();
+    assert!(error.starts_with("Failed to get store"), "{}", error);
+}
@@ -0,0 +1,221 @@
+//! Document listing: cursor pages, sort orders, filters and status counts
+
+use mizly_lib::db::{Database, DocumentListQuery, DocumentSort};
+
+/// Name, size, mime type and status of the sample documents
+const DOCUMENTS: [(&str, Option<i64>, &str, &str); 7] = [
+    ("b-owl.txt", Some(300), "text/plain", "completed"),
+    ("A-fox.md", Some(100), "text/markdown", "completed"),
+    ("c-bear.pdf", Some(200), "application/pdf", "failed"),
+    ("50%_off.txt", None, "text/plain", "pending"),
+    ("d-wolf.pdf", Some(100), "application/pdf", "completed"),
+    ("e-lynx.txt", Some(400), "text/plain", "failed"),
+    ("f-hare.txt", Some(100), "text/plain", "pending"),
+];
+
+fn open() -> (tempfile::TempDir, Database) {
+    let dir = tempfile::tempdir().unwrap();
+    let db = Database::new(dir.path()).unwrap();
+    db.create_store("animals", "Animals", None, "gemini", false)
+        .unwrap();
+
+    for (i, (name, size, mime_type, status)) in DOCUMENTS.iter().enumerate() {
+        let id = format!("doc-{}", i);
+        let path = format!("/data/{}", name);
+        db.create_document(&id, "animals", name, &path, Some(mime_type), *size, None)
+            .unwrap();
+        if *status != "pending" {
+            db.update_document_error(&id, status, "").unwrap();
+        }
+    }
+
+    // Two documents share a creation time, their ids break the tie
+    let conn = db.writer().unwrap();
+    conn.execute(
+        "UPDATE documents SET created_at = datetime('2025-12-01', '+' || (CAST(substr(id, 5) AS INTEGER) % 6) || ' minutes')",
+        [],
+    )
+    .unwrap();
+    // Rows from old versions may have no status, which lists as pending
+    conn.execute("UPDATE documents SET status = NULL WHERE id = 'doc-3'", [])
+        .unwrap();
+    drop(conn);
+
+    db.soft_delete_document("doc-6").unwrap();
+    (dir, db)
+}
+
+/// Names of every page of a listing, two documents at a time
+fn list_all(db: &Database, mut query: DocumentListQuery) -> Vec<String> {
+    query.limit = Some(2);
+    let mut names = Vec::new();
+
+    loop {
+        let page = db.list_documents("animals", &query).unwrap();
+        assert!(page.documents.len() <= 2);
+        names.extend(page.documents.into_iter().map(|d| d.name));
+
+        match page.next_cursor {
+            Some(cursor) => query.cursor = Some(cursor),
+            None => return names,
+        }
+    }
+}
+
+fn sorted(sort: DocumentSort, descending: bool) -> DocumentListQuery {
+    DocumentListQuery {
+        sort,
+        descending,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn pages_follow_the_sort_order() {
+    let (_dir, db) = open();
+
+    assert_eq!(
+        list_all(&db, sorted(DocumentSort::Name, false)),
+        [
+            "50%_off.txt",
+            "A-fox.md",
+            "b-owl.txt",
+            "c-bear.pdf",
+            "d-wolf.pdf",
+            "e-lynx.txt"
+        ]
+    );
+    assert_eq!(
+        list_all(&db, sorted(DocumentSort::Name, true)),
+        [
+            "e-lynx.txt",
+            "d-wolf.pdf",
+            "c-bear.pdf",
+            "b-owl.txt",
+            "A-fox.md",
+            "50%_off.txt"
+        ]
+    );
+
+    // Sizes tie, and documents without a size come first
+    assert_eq!(
+        list_all(&db, sorted(DocumentSort::Size, false)),
+        [
+            "50%_off.txt",
+            "A-fox.md",
+            "d-wolf.pdf",
+            "c-bear.pdf",
+            "b-owl.txt",
+            "e-lynx.txt"
+        ]
+    );
+
+    // doc-0 and doc-6 were created at the same minute, doc-6 is deleted
+    assert_eq!(
+        list_all(&db, sorted(DocumentSort::Created, true)),
+        [
+            "e-lynx.txt",
+            "d-wolf.pdf",
+            "50%_off.txt",
+            "c-bear.pdf",
+            "A-fox.md",
+            "b-owl.txt"
+        ]
+    );
+    assert_eq!(
+        list_all(&db, sorted(DocumentSort::Status, false)),
+        [
+            "b-owl.txt",
+            "A-fox.md",
+            "d-wolf.pdf",
+            "c-bear.pdf",
+            "e-lynx.txt",
+            "50%_off.txt"
+        ]
+    );
+}
+
+#[test]
+fn filters_narrow_the_listing_and_counts() {
+    let (_dir, db) = open();
+
+    let page = db
+        .list_documents("animals", &DocumentListQuery::default())
+        .unwrap();
+    assert_eq!(page.total, 6);
+    assert_eq!(page.next_cursor, None);
+    assert_eq!(page.status_counts["completed"], 3);
+    assert_eq!(page.status_counts["failed"], 2);
+    assert_eq!(page.status_counts["pending"], 1);
+
+    // Counts per status leave out the status filter
+    let page = db
+        .list_documents(
+            "animals",
+            &DocumentListQuery {
+                status: Some("failed".to_string()),
+                mime_type: Some("text/".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    let names: Vec<_> = page.documents.iter().map(|d| d.name.as_str()).collect();
+    assert_eq!(names, ["e-lynx.txt"]);
+    assert_eq!(page.total, 1);
+    assert_eq!(page.status_counts["completed"], 2);
+    assert!(!page.status_counts.contains_key("deleted"));
+
+    let query = |name: &str| DocumentListQuery {
+        sort: DocumentSort::Name,
+        name: Some(name.to_string()),
+        ..Default::default()
+    };
+    let names = |query: DocumentListQuery| list_all(&db, query);
+
+    // Case-insensitive, wildcards match themselves
+    assert_eq!(names(query("FOX")), ["A-fox.md"]);
+    assert_eq!(names(query("%_")), ["50%_off.txt"]);
+    assert!(names(query("_x")).is_empty());
+
+    let pdfs = DocumentListQuery {
+        mime_type: Some("application/pdf".to_string()),
+        sync_status: Some("pending".to_string()),
+        ..query(".pdf")
+    };
+    assert_eq!(names(pdfs), ["c-bear.pdf", "d-wolf.pdf"]);
+}
+
+#[test]
+fn cursors_only_continue_their_listing() {
+    let (_dir, db) = open();
+
+    let mut query = DocumentListQuery {
+        limit: Some(1),
+        ..Default::default()
+    };
+    let cursor = db
+        .list_documents("animals", &query)
+        .unwrap()
+        .next_cursor
+        .unwrap();
+
+    query.sort = DocumentSort::Size;
+    query.cursor = Some(cursor);
+    let error = db.list_documents("animals", &query).unwrap_err();
+    assert_eq!(error, "Invalid cursor");
+
+    query.cursor = Some("not a cursor".to_string());
+    assert!(db.list_documents("animals", &query).is_err());
+
+    // Page sizes are kept within bounds
+    let page = db
+        .list_documents(
+            "animals",
+            &DocumentListQuery {
+                limit: Some(0),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    assert_eq!(page.documents.len(), 1);
+}
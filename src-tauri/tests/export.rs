@@ -0,0 +1,101 @@
+//! Conversation export to Markdown, HTML and PDF
+
+mod common;
+
+use std::fs;
+
+use common::TestApp;
+use mizly_lib::export::{export_conversation, Conversation, Exchange, ExportFormat};
+
+const ANSWER: &str = "Foxes are **quick**.\n\n| Animal | Speed |\n|---|---|\n| Fox | 50 km/h |\n\n<script>alert(1)</script>";
+
+fn conversation(sources: &[&str]) -> Conversation {
+    Conversation {
+        title: Some("Animals".to_string()),
+        exchanges: vec![Exchange {
+            question: "How fast is a fox?".to_string(),
+            answer: ANSWER.to_string(),
+            sources: sources.iter().map(|s| s.to_string()).collect(),
+        }],
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn markdown_lists_resolved_citations() {
+    let test = TestApp::new().await;
+    let (_, document_id) = test.create_store_with_document("fox.txt");
+    let document = test.db.get_document(&document_id).unwrap();
+
+    let path = test.dir.path().join("animals.md");
+    let result = export_conversation(
+        &test.db,
+        &conversation(&["fox.txt", "fox.txt", "wolf.pdf"]),
+        None,
+        &path,
+    )
+    .unwrap();
+    assert_eq!(result.format, ExportFormat::Markdown);
+    assert_eq!(result.citations, 2);
+
+    let markdown = fs::read_to_string(&path).unwrap();
+    assert!(markdown.starts_with("# Animals\n"));
+    assert!(markdown.contains("## How fast is a fox?\n\nFoxes are **quick**."));
+    assert!(markdown.contains("| Fox | 50 km/h |"));
+    let url = url::Url::from_file_path(&document.path).unwrap();
+    assert!(markdown.contains(&format!(
+        "- [fox.txt](<{}>) (Test store): `{}`",
+        url, document.path
+    )));
+    assert!(markdown.contains("- wolf.pdf (not in the library)"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn html_is_standalone_and_escapes_raw_html() {
+    let test = TestApp::new().await;
+    let path = test.dir.path().join("animals.html");
+    export_conversation(&test.db, &conversation(&[]), None, &path).unwrap();
+
+    let html = fs::read_to_string(&path).unwrap();
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<title>Animals</title>"));
+    assert!(html.contains("<strong>quick</strong>"));
+    assert!(html.contains("<td>50 km/h</td>"));
+    assert!(html.contains("&lt;script&gt;"));
+    assert!(!html.contains("<script>"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn pdf_is_written_for_latin_and_cjk_text() {
+    let test = TestApp::new().await;
+
+    let path = test.dir.path().join("animals.pdf");
+    export_conversation(&test.db, &conversation(&[]), None, &path).unwrap();
+    assert!(fs::read(&path).unwrap().starts_with(b"%PDF"));
+
+    // Long answers span pages; CJK text uses a system font when one is found
+    let mut japanese = conversation(&[]);
+    japanese.exchanges[0].answer = "キツネはとても速く走ります。".repeat(400);
+    let path = test.dir.path().join("export");
+    export_conversation(&test.db, &japanese, Some(ExportFormat::Pdf), &path).unwrap();
+    assert!(fs::read(&path).unwrap().starts_with(b"%PDF"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn rejects_empty_conversations_and_unknown_formats() {
+    let test = TestApp::new().await;
+
+    let empty = Conversation {
+        title: None,
+        exchanges: vec![],
+    };
+    let path = test.dir.path().join("empty.md");
+    assert_eq!(
+        export_conversation(&test.db, &empty, None, &path).unwrap_err(),
+        "Nothing to export"
+    );
+
+    let path = test.dir.path().join("animals.docx");
+    let error = export_conversation(&test.db, &conversation(&[]), None, &path).unwrap_err();
+    assert!(error.starts_with("Unsupported export format"), "{}", error);
+    assert!(!path.exists());
+}
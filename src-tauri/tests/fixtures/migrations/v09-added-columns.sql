@@ -0,0 +1,30 @@
+-- Database from before gemini_name existed, upgraded by release 0.1.3:
+-- the columns were added with ALTER TABLE, so gemini_name is not UNIQUE
+-- and two documents share one
+
+CREATE TABLE schema_migrations (version INTEGER PRIMARY KEY, applied_at DATETIME DEFAULT CURRENT_TIMESTAMP);
+CREATE TABLE stores (id TEXT PRIMARY KEY, title TEXT NOT NULL, directory_path TEXT, created_at DATETIME DEFAULT CURRENT_TIMESTAMP, updated_at DATETIME DEFAULT CURRENT_TIMESTAMP, gemini_name TEXT, sync_status TEXT DEFAULT 'pending', create_time TEXT, update_time TEXT, active_documents_count INTEGER DEFAULT 0, pending_documents_count INTEGER DEFAULT 0, failed_documents_count INTEGER DEFAULT 0, size_bytes INTEGER DEFAULT 0, deleted_at DATETIME DEFAULT NULL);
+CREATE TABLE documents (id TEXT PRIMARY KEY, store_id TEXT NOT NULL, name TEXT NOT NULL, path TEXT NOT NULL, content_type TEXT, size INTEGER, hash TEXT, status TEXT DEFAULT 'pending', created_at DATETIME DEFAULT CURRENT_TIMESTAMP, updated_at DATETIME DEFAULT CURRENT_TIMESTAMP, gemini_name TEXT, operation_name TEXT, mime_type TEXT, sync_status TEXT DEFAULT 'pending', error_message TEXT, deleted_at DATETIME DEFAULT NULL, FOREIGN KEY (store_id) REFERENCES stores(id) ON DELETE CASCADE);
+CREATE INDEX idx_documents_status ON documents(status);
+CREATE INDEX idx_documents_store ON documents(store_id);
+CREATE INDEX idx_documents_deleted ON documents(deleted_at);
+CREATE INDEX idx_stores_deleted ON stores(deleted_at);
+CREATE INDEX idx_documents_operation ON documents(operation_name);
+CREATE INDEX idx_stores_gemini_name ON stores(gemini_name);
+CREATE INDEX idx_documents_gemini_name ON documents(gemini_name);
+
+INSERT INTO schema_migrations (version, applied_at) VALUES (1, '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, applied_at) VALUES (2, '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, applied_at) VALUES (3, '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, applied_at) VALUES (4, '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, applied_at) VALUES (5, '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, applied_at) VALUES (6, '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, applied_at) VALUES (7, '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, applied_at) VALUES (8, '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, applied_at) VALUES (9, '2025-12-01 09:00:00');
+INSERT INTO stores (id, title, directory_path, created_at, updated_at, gemini_name, sync_status, create_time, update_time, active_documents_count, pending_documents_count, failed_documents_count, size_bytes, deleted_at) VALUES ('store-animals', 'Animals', '/data/animals', '2025-12-01 09:00:00', '2025-12-01 09:00:00', 'fileSearchStores/animals-1', 'pending', NULL, NULL, 0, 0, 0, 0, NULL);
+INSERT INTO stores (id, title, directory_path, created_at, updated_at, gemini_name, sync_status, create_time, update_time, active_documents_count, pending_documents_count, failed_documents_count, size_bytes, deleted_at) VALUES ('store-drafts', 'Drafts', NULL, '2025-12-01 09:00:00', '2025-12-01 09:00:00', NULL, 'pending', NULL, NULL, 0, 0, 0, 0, NULL);
+INSERT INTO documents (id, store_id, name, path, content_type, size, hash, status, created_at, updated_at, gemini_name, operation_name, mime_type, sync_status, error_message, deleted_at) VALUES ('doc-fox', 'store-animals', 'fox.txt', '/data/animals/fox.txt', NULL, 12, 'h-fox', 'active', '2025-12-01 09:00:00', '2025-12-01 09:00:00', 'fileSearchStores/animals-1/documents/fox', NULL, NULL, 'pending', NULL, NULL);
+INSERT INTO documents (id, store_id, name, path, content_type, size, hash, status, created_at, updated_at, gemini_name, operation_name, mime_type, sync_status, error_message, deleted_at) VALUES ('doc-owl', 'store-animals', 'owl.txt', '/data/animals/owl.txt', NULL, 9, 'h-owl', 'pending', '2025-12-01 09:00:00', '2025-12-01 09:00:00', NULL, NULL, NULL, 'pending', NULL, NULL);
+INSERT INTO documents (id, store_id, name, path, content_type, size, hash, status, created_at, updated_at, gemini_name, operation_name, mime_type, sync_status, error_message, deleted_at) VALUES ('doc-note', 'store-drafts', 'note.md', '/tmp/note.md', NULL, 5, 'h-note', 'pending', '2025-12-01 09:00:00', '2025-12-01 09:00:00', NULL, NULL, NULL, 'pending', NULL, '2025-12-01 10:00:00');
+INSERT INTO documents (id, store_id, name, path, content_type, size, hash, status, created_at, updated_at, gemini_name, operation_name, mime_type, sync_status, error_message, deleted_at) VALUES ('doc-fox-copy', 'store-animals', 'fox.txt', '/data/animals/fox.txt', NULL, 12, 'h-fox', 'active', '2025-12-01 09:00:00', '2025-12-01 09:00:00', 'fileSearchStores/animals-1/documents/fox', NULL, NULL, 'pending', NULL, NULL);
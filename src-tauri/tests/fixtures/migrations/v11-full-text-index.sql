@@ -0,0 +1,76 @@
+-- Adds the FTS5 index and documents.indexed_at (migrations 1-11)
+-- Written by the migrations of that version, with sample rows
+
+CREATE TABLE schema_migrations (
+            version INTEGER PRIMARY KEY,
+            applied_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+CREATE TABLE stores (
+        id TEXT PRIMARY KEY,
+        gemini_name TEXT UNIQUE,
+        title TEXT NOT NULL,
+        directory_path TEXT,
+        sync_status TEXT DEFAULT 'pending',
+        create_time TEXT,
+        update_time TEXT,
+        active_documents_count INTEGER DEFAULT 0,
+        pending_documents_count INTEGER DEFAULT 0,
+        failed_documents_count INTEGER DEFAULT 0,
+        size_bytes INTEGER DEFAULT 0,
+        deleted_at DATETIME DEFAULT NULL,
+        created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+        updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+    );
+CREATE TABLE documents (
+        id TEXT PRIMARY KEY,
+        store_id TEXT NOT NULL,
+        gemini_name TEXT UNIQUE,
+        operation_name TEXT,
+        name TEXT NOT NULL,
+        path TEXT NOT NULL,
+        content_type TEXT,
+        mime_type TEXT,
+        size INTEGER,
+        hash TEXT,
+        status TEXT DEFAULT 'pending',
+        sync_status TEXT DEFAULT 'pending',
+        error_message TEXT,
+        indexed_at DATETIME DEFAULT NULL,
+        deleted_at DATETIME DEFAULT NULL,
+        created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+        updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+        FOREIGN KEY (store_id) REFERENCES stores(id) ON DELETE CASCADE
+    );
+CREATE INDEX idx_documents_status ON documents(status);
+CREATE INDEX idx_documents_store ON documents(store_id);
+CREATE INDEX idx_documents_deleted ON documents(deleted_at);
+CREATE INDEX idx_stores_deleted ON stores(deleted_at);
+CREATE INDEX idx_documents_operation ON documents(operation_name);
+CREATE INDEX idx_stores_gemini_name ON stores(gemini_name);
+CREATE INDEX idx_documents_gemini_name ON documents(gemini_name);
+CREATE VIRTUAL TABLE documents_fts USING fts5(
+        document_id UNINDEXED,
+        store_id UNINDEXED,
+        name,
+        content,
+        tokenize = 'trigram'
+    );
+CREATE INDEX idx_documents_indexed ON documents(indexed_at);
+
+INSERT INTO schema_migrations (version, applied_at) VALUES (1, '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, applied_at) VALUES (2, '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, applied_at) VALUES (3, '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, applied_at) VALUES (4, '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, applied_at) VALUES (5, '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, applied_at) VALUES (6, '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, applied_at) VALUES (7, '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, applied_at) VALUES (8, '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, applied_at) VALUES (9, '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, applied_at) VALUES (10, '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, applied_at) VALUES (11, '2025-12-01 09:00:00');
+INSERT INTO stores (id, gemini_name, title, directory_path, sync_status, create_time, update_time, active_documents_count, pending_documents_count, failed_documents_count, size_bytes, deleted_at, created_at, updated_at) VALUES ('store-animals', 'fileSearchStores/animals-1', 'Animals', '/data/animals', 'pending', NULL, NULL, 0, 0, 0, 0, NULL, '2025-12-01 09:00:00', '2025-12-01 09:00:00');
+INSERT INTO stores (id, gemini_name, title, directory_path, sync_status, create_time, update_time, active_documents_count, pending_documents_count, failed_documents_count, size_bytes, deleted_at, created_at, updated_at) VALUES ('store-drafts', NULL, 'Drafts', NULL, 'pending', NULL, NULL, 0, 0, 0, 0, NULL, '2025-12-01 09:00:00', '2025-12-01 09:00:00');
+INSERT INTO documents (id, store_id, gemini_name, operation_name, name, path, content_type, mime_type, size, hash, status, sync_status, error_message, indexed_at, deleted_at, created_at, updated_at) VALUES ('doc-fox', 'store-animals', 'fileSearchStores/animals-1/documents/fox', NULL, 'fox.txt', '/data/animals/fox.txt', NULL, NULL, 12, 'h-fox', 'active', 'pending', NULL, NULL, NULL, '2025-12-01 09:00:00', '2025-12-01 09:00:00');
+INSERT INTO documents (id, store_id, gemini_name, operation_name, name, path, content_type, mime_type, size, hash, status, sync_status, error_message, indexed_at, deleted_at, created_at, updated_at) VALUES ('doc-owl', 'store-animals', NULL, NULL, 'owl.txt', '/data/animals/owl.txt', NULL, NULL, 9, 'h-owl', 'pending', 'pending', NULL, NULL, NULL, '2025-12-01 09:00:00', '2025-12-01 09:00:00');
+INSERT INTO documents (id, store_id, gemini_name, operation_name, name, path, content_type, mime_type, size, hash, status, sync_status, error_message, indexed_at, deleted_at, created_at, updated_at) VALUES ('doc-note', 'store-drafts', NULL, NULL, 'note.md', '/tmp/note.md', NULL, NULL, 5, 'h-note', 'pending', 'pending', NULL, NULL, '2025-12-01 10:00:00', '2025-12-01 09:00:00', '2025-12-01 09:00:00');
+INSERT INTO documents_fts (document_id, store_id, name, content) VALUES ('doc-fox', 'store-animals', 'fox.txt', 'The quick brown fox');
@@ -0,0 +1,97 @@
+-- Adds stores.local_only and document_chunks.embedding_model
+-- Written by the migrations of that version, with sample rows
+
+CREATE TABLE schema_migrations (
+            version INTEGER PRIMARY KEY,
+            applied_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+CREATE TABLE stores (
+        id TEXT PRIMARY KEY,
+        gemini_name TEXT UNIQUE,
+        title TEXT NOT NULL,
+        directory_path TEXT,
+        sync_status TEXT DEFAULT 'pending',
+        create_time TEXT,
+        update_time TEXT,
+        active_documents_count INTEGER DEFAULT 0,
+        pending_documents_count INTEGER DEFAULT 0,
+        failed_documents_count INTEGER DEFAULT 0,
+        size_bytes INTEGER DEFAULT 0,
+        include_patterns TEXT,
+        exclude_patterns TEXT,
+        backend TEXT DEFAULT 'gemini',
+        local_only INTEGER DEFAULT 0,
+        deleted_at DATETIME DEFAULT NULL,
+        created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+        updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+    );
+CREATE TABLE documents (
+        id TEXT PRIMARY KEY,
+        store_id TEXT NOT NULL,
+        gemini_name TEXT UNIQUE,
+        operation_name TEXT,
+        name TEXT NOT NULL,
+        path TEXT NOT NULL,
+        content_type TEXT,
+        mime_type TEXT,
+        size INTEGER,
+        hash TEXT,
+        status TEXT DEFAULT 'pending',
+        sync_status TEXT DEFAULT 'pending',
+        error_message TEXT,
+        indexed_at DATETIME DEFAULT NULL,
+        upload_mime_type TEXT,
+        deleted_at DATETIME DEFAULT NULL,
+        created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+        updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+        FOREIGN KEY (store_id) REFERENCES stores(id) ON DELETE CASCADE
+    );
+CREATE INDEX idx_documents_status ON documents(status);
+CREATE INDEX idx_documents_store ON documents(store_id);
+CREATE INDEX idx_documents_deleted ON documents(deleted_at);
+CREATE INDEX idx_stores_deleted ON stores(deleted_at);
+CREATE INDEX idx_documents_operation ON documents(operation_name);
+CREATE INDEX idx_stores_gemini_name ON stores(gemini_name);
+CREATE INDEX idx_documents_gemini_name ON documents(gemini_name);
+CREATE VIRTUAL TABLE documents_fts USING fts5(
+        document_id UNINDEXED,
+        store_id UNINDEXED,
+        name,
+        content,
+        tokenize = 'trigram'
+    );
+CREATE INDEX idx_documents_indexed ON documents(indexed_at);
+CREATE TABLE document_chunks (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        store_name TEXT NOT NULL,
+        document_name TEXT NOT NULL,
+        display_name TEXT NOT NULL,
+        chunk_index INTEGER NOT NULL,
+        content TEXT NOT NULL,
+        embedding BLOB NOT NULL,
+        embedding_model TEXT
+    );
+CREATE INDEX idx_document_chunks_store ON document_chunks(store_name);
+CREATE INDEX idx_document_chunks_document ON document_chunks(document_name);
+
+INSERT INTO schema_migrations (version, applied_at) VALUES (1, '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, applied_at) VALUES (2, '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, applied_at) VALUES (3, '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, applied_at) VALUES (4, '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, applied_at) VALUES (5, '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, applied_at) VALUES (6, '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, applied_at) VALUES (7, '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, applied_at) VALUES (8, '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, applied_at) VALUES (9, '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, applied_at) VALUES (10, '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, applied_at) VALUES (11, '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, applied_at) VALUES (12, '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, applied_at) VALUES (13, '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, applied_at) VALUES (14, '2025-12-01 09:00:00');
+INSERT INTO stores (id, gemini_name, title, directory_path, sync_status, create_time, update_time, active_documents_count, pending_documents_count, failed_documents_count, size_bytes, include_patterns, exclude_patterns, backend, local_only, deleted_at, created_at, updated_at) VALUES ('store-animals', 'fileSearchStores/animals-1', 'Animals', '/data/animals', 'pending', NULL, NULL, 0, 0, 0, 0, NULL, NULL, 'gemini', 0, NULL, '2025-12-01 09:00:00', '2025-12-01 09:00:00');
+INSERT INTO stores (id, gemini_name, title, directory_path, sync_status, create_time, update_time, active_documents_count, pending_documents_count, failed_documents_count, size_bytes, include_patterns, exclude_patterns, backend, local_only, deleted_at, created_at, updated_at) VALUES ('store-drafts', NULL, 'Drafts', NULL, 'pending', NULL, NULL, 0, 0, 0, 0, NULL, NULL, 'gemini', 0, NULL, '2025-12-01 09:00:00', '2025-12-01 09:00:00');
+INSERT INTO documents (id, store_id, gemini_name, operation_name, name, path, content_type, mime_type, size, hash, status, sync_status, error_message, indexed_at, upload_mime_type, deleted_at, created_at, updated_at) VALUES ('doc-fox', 'store-animals', 'fileSearchStores/animals-1/documents/fox', NULL, 'fox.txt', '/data/animals/fox.txt', NULL, NULL, 12, 'h-fox', 'active', 'pending', NULL, NULL, NULL, NULL, '2025-12-01 09:00:00', '2025-12-01 09:00:00');
+INSERT INTO documents (id, store_id, gemini_name, operation_name, name, path, content_type, mime_type, size, hash, status, sync_status, error_message, indexed_at, upload_mime_type, deleted_at, created_at, updated_at) VALUES ('doc-owl', 'store-animals', NULL, NULL, 'owl.txt', '/data/animals/owl.txt', NULL, NULL, 9, 'h-owl', 'pending', 'pending', NULL, NULL, NULL, NULL, '2025-12-01 09:00:00', '2025-12-01 09:00:00');
+INSERT INTO documents (id, store_id, gemini_name, operation_name, name, path, content_type, mime_type, size, hash, status, sync_status, error_message, indexed_at, upload_mime_type, deleted_at, created_at, updated_at) VALUES ('doc-note', 'store-drafts', NULL, NULL, 'note.md', '/tmp/note.md', NULL, NULL, 5, 'h-note', 'pending', 'pending', NULL, NULL, NULL, '2025-12-01 10:00:00', '2025-12-01 09:00:00', '2025-12-01 09:00:00');
+INSERT INTO documents_fts (document_id, store_id, name, content) VALUES ('doc-fox', 'store-animals', 'fox.txt', 'The quick brown fox');
+INSERT INTO document_chunks (id, store_name, document_name, display_name, chunk_index, content, embedding, embedding_model) VALUES (1, 'fileSearchStores/animals-1', 'fileSearchStores/animals-1/documents/fox', 'fox.txt', 0, 'The quick brown fox', X'0000803F', NULL);
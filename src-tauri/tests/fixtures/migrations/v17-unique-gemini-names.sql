@@ -0,0 +1,108 @@
+-- Rebuilds stores and documents with UNIQUE gemini_name (migrations 1-17)
+-- Written by the migrations of that version, with sample rows
+
+CREATE TABLE schema_migrations (
+                version INTEGER PRIMARY KEY,
+                name TEXT,
+                checksum TEXT,
+                applied_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            );
+CREATE VIRTUAL TABLE documents_fts USING fts5(
+        document_id UNINDEXED,
+        store_id UNINDEXED,
+        name,
+        content,
+        tokenize = 'trigram'
+    );
+CREATE TABLE document_chunks (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        store_name TEXT NOT NULL,
+        document_name TEXT NOT NULL,
+        display_name TEXT NOT NULL,
+        chunk_index INTEGER NOT NULL,
+        content TEXT NOT NULL,
+        embedding BLOB NOT NULL,
+        embedding_model TEXT
+    );
+CREATE INDEX idx_document_chunks_store ON document_chunks(store_name);
+CREATE INDEX idx_document_chunks_document ON document_chunks(document_name);
+CREATE TABLE sync_leases (
+        name TEXT PRIMARY KEY,
+        owner TEXT NOT NULL,
+        expires_at INTEGER NOT NULL
+    );
+CREATE TABLE "stores" (
+        id TEXT PRIMARY KEY,
+        gemini_name TEXT UNIQUE,
+        title TEXT NOT NULL,
+        directory_path TEXT,
+        sync_status TEXT DEFAULT 'pending',
+        create_time TEXT,
+        update_time TEXT,
+        active_documents_count INTEGER DEFAULT 0,
+        pending_documents_count INTEGER DEFAULT 0,
+        failed_documents_count INTEGER DEFAULT 0,
+        size_bytes INTEGER DEFAULT 0,
+        include_patterns TEXT,
+        exclude_patterns TEXT,
+        backend TEXT DEFAULT 'gemini',
+        local_only INTEGER DEFAULT 0,
+        deleted_at DATETIME DEFAULT NULL,
+        created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+        updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+    );
+CREATE INDEX idx_stores_deleted ON stores(deleted_at);
+CREATE INDEX idx_stores_gemini_name ON stores(gemini_name);
+CREATE TABLE "documents" (
+        id TEXT PRIMARY KEY,
+        store_id TEXT NOT NULL,
+        gemini_name TEXT UNIQUE,
+        operation_name TEXT,
+        name TEXT NOT NULL,
+        path TEXT NOT NULL,
+        content_type TEXT,
+        mime_type TEXT,
+        size INTEGER,
+        hash TEXT,
+        status TEXT DEFAULT 'pending',
+        sync_status TEXT DEFAULT 'pending',
+        error_message TEXT,
+        indexed_at DATETIME DEFAULT NULL,
+        upload_mime_type TEXT,
+        custom_metadata TEXT,
+        deleted_at DATETIME DEFAULT NULL,
+        created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+        updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+        FOREIGN KEY (store_id) REFERENCES stores(id) ON DELETE CASCADE
+    );
+CREATE INDEX idx_documents_status ON documents(status);
+CREATE INDEX idx_documents_store ON documents(store_id);
+CREATE INDEX idx_documents_deleted ON documents(deleted_at);
+CREATE INDEX idx_documents_operation ON documents(operation_name);
+CREATE INDEX idx_documents_gemini_name ON documents(gemini_name);
+CREATE INDEX idx_documents_indexed ON documents(indexed_at);
+
+INSERT INTO schema_migrations (version, name, checksum, applied_at) VALUES (1, 'create_stores', '683f25411a17c032779022e01d26394d253aefd36b50ffa8c702f8a1a4cc3138', '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, name, checksum, applied_at) VALUES (2, 'create_documents', '2b27e05ef91881cb9f04f065dc12e6bbe9a9f8ed4c817679bff2271afc5c188d', '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, name, checksum, applied_at) VALUES (3, 'index_documents_status', 'd44fea12b3ec4d4a7e31951aab2baf85cc3b2403c42cf4dba48f898442bf69b1', '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, name, checksum, applied_at) VALUES (4, 'index_documents_store', '60354cc31bd8be1468e267ce4f6bc8b39f59003c060a60f9aa490e338d68de2b', '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, name, checksum, applied_at) VALUES (5, 'index_documents_deleted', '4c3188e49972f6ff7f1002874ef3476e7c0de8bee59294e57797becc9e02d551', '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, name, checksum, applied_at) VALUES (6, 'index_stores_deleted', '172a3176c10af6942bc56386a7191cf2e1942891299868715138df6f19aa72eb', '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, name, checksum, applied_at) VALUES (7, 'index_documents_operation', '1b4f59b7de9818bf3359dc61a5817a10dd3e404c9ab34031adb0da301a957ccf', '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, name, checksum, applied_at) VALUES (8, 'index_stores_gemini_name', '348e5cbf161b4b21ca90e8f9e856d71c81816f9df996c7ce588afe2508f2c62f', '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, name, checksum, applied_at) VALUES (9, 'index_documents_gemini_name', '7c14aea328b1683be6fcf14ea74152368af2d17a7411fe270c55f5c137cccc95', '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, name, checksum, applied_at) VALUES (10, 'create_documents_fts', 'b81080480bd6832527082c37feafbb0ec8d4aaca62da380fd2d21513130d6c14', '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, name, checksum, applied_at) VALUES (11, 'index_documents_indexed', '98373691e43633d5fbc351aca0a496bf9404cdc5574818463497b15bf7bf6ad4', '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, name, checksum, applied_at) VALUES (12, 'create_document_chunks', '186b37c173c26a8075e4a8a8d148944858bbacb6fffb006d8a62c3db8200f9ab', '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, name, checksum, applied_at) VALUES (13, 'index_document_chunks_store', '3cb52634b9a8812342c1505ef5b0b187d08ace63f1fb6e2e8049f3611245edef', '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, name, checksum, applied_at) VALUES (14, 'index_document_chunks_document', 'cf2b0f9c690c04be1263ec07e3d6518a6b575ef6605e8a1627757b5a3f4dfdef', '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, name, checksum, applied_at) VALUES (15, 'create_sync_leases', '279fc989065fd1919429324fc6e3c9082be63d09913ea904d352fb5c932ef373', '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, name, checksum, applied_at) VALUES (16, 'rebuild_stores_unique_gemini_name', 'f4eab3e9561a4c3ae7a587167fb16722eb7529876347edd9df69d434e2d4d42e', '2025-12-01 09:00:00');
+INSERT INTO schema_migrations (version, name, checksum, applied_at) VALUES (17, 'rebuild_documents_unique_gemini_name', '446671406d7433ec7ddbdc276fa299460f597b2655b5513f1e4e19499a9e5adc', '2025-12-01 09:00:00');
+INSERT INTO stores (id, gemini_name, title, directory_path, sync_status, create_time, update_time, active_documents_count, pending_documents_count, failed_documents_count, size_bytes, include_patterns, exclude_patterns, backend, local_only, deleted_at, created_at, updated_at) VALUES ('store-animals', 'fileSearchStores/animals-1', 'Animals', '/data/animals', 'pending', NULL, NULL, 0, 0, 0, 0, NULL, NULL, 'gemini', 0, NULL, '2025-12-01 09:00:00', '2025-12-01 09:00:00');
+INSERT INTO stores (id, gemini_name, title, directory_path, sync_status, create_time, update_time, active_documents_count, pending_documents_count, failed_documents_count, size_bytes, include_patterns, exclude_patterns, backend, local_only, deleted_at, created_at, updated_at) VALUES ('store-drafts', NULL, 'Drafts', NULL, 'pending', NULL, NULL, 0, 0, 0, 0, NULL, NULL, 'gemini', 0, NULL, '2025-12-01 09:00:00', '2025-12-01 09:00:00');
+INSERT INTO documents (id, store_id, gemini_name, operation_name, name, path, content_type, mime_type, size, hash, status, sync_status, error_message, indexed_at, upload_mime_type, custom_metadata, deleted_at, created_at, updated_at) VALUES ('doc-fox', 'store-animals', 'fileSearchStores/animals-1/documents/fox', NULL, 'fox.txt', '/data/animals/fox.txt', NULL, NULL, 12, 'h-fox', 'active', 'pending', NULL, NULL, NULL, NULL, NULL, '2025-12-01 09:00:00', '2025-12-01 09:00:00');
+INSERT INTO documents (id, store_id, gemini_name, operation_name, name, path, content_type, mime_type, size, hash, status, sync_status, error_message, indexed_at, upload_mime_type, custom_metadata, deleted_at, created_at, updated_at) VALUES ('doc-owl', 'store-animals', NULL, NULL, 'owl.txt', '/data/animals/owl.txt', NULL, NULL, 9, 'h-owl', 'pending', 'pending', NULL, NULL, NULL, NULL, NULL, '2025-12-01 09:00:00', '2025-12-01 09:00:00');
+INSERT INTO documents (id, store_id, gemini_name, operation_name, name, path, content_type, mime_type, size, hash, status, sync_status, error_message, indexed_at, upload_mime_type, custom_metadata, deleted_at, created_at, updated_at) VALUES ('doc-note', 'store-drafts', NULL, NULL, 'note.md', '/tmp/note.md', NULL, NULL, 5, 'h-note', 'pending', 'pending', NULL, NULL, NULL, NULL, '2025-12-01 10:00:00', '2025-12-01 09:00:00', '2025-12-01 09:00:00');
+INSERT INTO documents_fts (document_id, store_id, name, content) VALUES ('doc-fox', 'store-animals', 'fox.txt', 'The quick brown fox');
+INSERT INTO document_chunks (id, store_name, document_name, display_name, chunk_index, content, embedding, embedding_model) VALUES (1, 'fileSearchStores/animals-1', 'fileSearchStores/animals-1/documents/fox', 'fox.txt', 0, 'The quick brown fox', X'0000803F', NULL);
@@ -0,0 +1,222 @@
+//! The local HTTP API against the mock Gemini server
+
+mod common;
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+
+use common::mock_gemini::Endpoint;
+use common::{wait_for, TestApp};
+use mizly_lib::http_api::HttpApiState;
+use mizly_lib::settings::{self, HttpApiSettings, ModelProvider, RetrievalSettings};
+
+const TIMEOUT: Duration = Duration::from_secs(20);
+const TOKEN: &str = "mzl_test_token";
+
+/// Start the API on a free port
+async fn start_api(test: &TestApp) -> (Arc<HttpApiState>, SocketAddr) {
+    let api = HttpApiState::new(
+        test.db.clone(),
+        test.backends.clone(),
+        test.sync_state.clone(),
+    );
+    let settings = HttpApiSettings {
+        enabled: true,
+        port: 0,
+        token: Some(TOKEN.to_string()),
+    };
+    let address = api.apply(&settings).await.unwrap().unwrap();
+
+    (api, address)
+}
+
+async fn get(address: SocketAddr, path: &str, token: &str) -> (StatusCode, Value) {
+    let response = reqwest::Client::new()
+        .get(format!("http://{}{}", address, path))
+        .bearer_auth(token)
+        .send()
+        .await
+        .unwrap();
+
+    (response.status(), response.json().await.unwrap())
+}
+
+async fn post(address: SocketAddr, path: &str, body: Value) -> (StatusCode, Value) {
+    let response = reqwest::Client::new()
+        .post(format!("http://{}{}", address, path))
+        .bearer_auth(TOKEN)
+        .json(&body)
+        .send()
+        .await
+        .unwrap();
+
+    (response.status(), response.json().await.unwrap())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn rejects_missing_or_wrong_token() {
+    let test = TestApp::new().await;
+    let (_api, address) = start_api(&test).await;
+
+    let response = reqwest::get(format!("http://{}/v1/stores", address))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let (status, body) = get(address, "/v1/stores", "mzl_wrong_token").await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["error"], "Invalid or missing token");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn uploads_lists_and_answers() {
+    let test = TestApp::new().await;
+    test.mock.set_answer("The fox jumps.");
+    let (_api, address) = start_api(&test).await;
+    test.start_tasks();
+
+    let store_id = uuid::Uuid::new_v4().to_string();
+    test.db
+        .create_store(&store_id, "Animals", None, "gemini", false)
+        .unwrap();
+    let path = test.write_file("fox.txt", "The quick brown fox jumps over the lazy dog.");
+
+    let (status, queued) = post(
+        address,
+        &format!("/v1/stores/{}/documents", store_id),
+        json!({ "paths": [path, "/missing.txt"] }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(queued["documents"].as_array().unwrap().len(), 1);
+    assert_eq!(queued["rejected"][0]["reason"], "not_found");
+
+    let document_id = queued["documents"][0]["id"].as_str().unwrap().to_string();
+    wait_for(TIMEOUT, "document to complete", || {
+        test.db
+            .get_document(&document_id)
+            .map(|d| d.status == "completed")
+            .unwrap_or(false)
+    })
+    .await;
+
+    let (status, stores) = get(address, "/v1/stores", TOKEN).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(stores[0]["title"], "Animals");
+
+    let store_name = stores[0]["geminiName"].clone();
+    let (status, answer) = post(
+        address,
+        "/v1/query",
+        json!({ "storeNames": [store_name], "query": "What does the fox do?" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(answer["content"], "The fox jumps.");
+    assert_eq!(answer["sources"], json!(["fox.txt"]));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn reports_request_errors() {
+    let test = TestApp::new().await;
+    let (_api, address) = start_api(&test).await;
+
+    let (status, body) = post(
+        address,
+        "/v1/query",
+        json!({ "storeNames": [], "query": "?" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "No stores provided");
+
+    let (status, _) = post(
+        address,
+        "/v1/stores/missing/documents",
+        json!({ "paths": [] }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    // Errors of the backends keep their meaning
+    let (status, _) = post(
+        address,
+        "/v1/query",
+        json!({ "storeNames": ["fileSearchStores/missing"], "query": "?" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let local = test
+        .db
+        .create_store("local", "Local", None, "local", true)
+        .unwrap();
+    test.db
+        .update_store_from_gemini(&local.id, "localStores/private", None, None, 0, 0, 0, 0)
+        .unwrap();
+    let retrieval = RetrievalSettings {
+        embedding_provider: ModelProvider::Gemini,
+        ..Default::default()
+    };
+    settings::write_retrieval_settings(test.dir.path(), &retrieval).unwrap();
+    let (status, body) = post(
+        address,
+        "/v1/query",
+        json!({ "storeNames": ["localStores/private"], "query": "?" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body["error"]
+        .as_str()
+        .unwrap()
+        .starts_with("Local-only stores"));
+
+    test.mock.fail_next(Endpoint::GenerateContent, 500, 1);
+    let (status, _) = post(
+        address,
+        "/v1/query",
+        json!({ "storeNames": ["fileSearchStores/missing"], "query": "?" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_GATEWAY);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn disabling_stops_the_server() {
+    let test = TestApp::new().await;
+    let (api, address) = start_api(&test).await;
+
+    let stopped = api.apply(&HttpApiSettings::default()).await.unwrap();
+    assert_eq!(stopped, None);
+
+    wait_for(TIMEOUT, "server to stop", || {
+        std::net::TcpStream::connect(address).is_err()
+    })
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn restarting_replaces_the_token() {
+    let test = TestApp::new().await;
+    let (api, address) = start_api(&test).await;
+    let (status, _) = get(address, "/v1/stores", TOKEN).await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Same port, new token
+    let settings = HttpApiSettings {
+        enabled: true,
+        port: address.port(),
+        token: Some("mzl_new_token".to_string()),
+    };
+    let restarted = api.apply(&settings).await.unwrap();
+    assert_eq!(restarted, Some(address));
+
+    let (status, _) = get(address, "/v1/stores", TOKEN).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _) = get(address, "/v1/stores", "mzl_new_token").await;
+    assert_eq!(status, StatusCode::OK);
+}
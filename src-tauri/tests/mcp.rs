@@ -0,0 +1,161 @@
+//! The MCP server against the mock Gemini server
+
+mod common;
+
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::Command;
+
+use common::{wait_for, TestApp};
+use mizly_lib::mcp::{McpServer, PROTOCOL_VERSION};
+
+const TIMEOUT: Duration = Duration::from_secs(20);
+
+fn server(test: &TestApp) -> McpServer {
+    McpServer::new(test.db.clone(), test.backends.clone())
+}
+
+/// Call a tool, returning its text parsed as JSON and whether it failed
+async fn call_tool(server: &McpServer, name: &str, arguments: Value) -> (Value, bool) {
+    let response = server
+        .handle(json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "tools/call",
+            "params": { "name": name, "arguments": arguments },
+        }))
+        .await
+        .unwrap();
+
+    let result = &response["result"];
+    let text = result["content"][0]["text"].as_str().unwrap();
+    let value = serde_json::from_str(text).unwrap_or_else(|_| json!(text));
+
+    (value, result["isError"].as_bool().unwrap())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn lists_searches_and_reads_documents() {
+    let test = TestApp::new().await;
+    test.mock.set_answer("The fox jumps.");
+    let (store_id, document_id) = test.create_store_with_document("fox.txt");
+    test.start_tasks();
+
+    wait_for(TIMEOUT, "document to complete", || {
+        test.db
+            .get_document(&document_id)
+            .map(|d| d.status == "completed")
+            .unwrap_or(false)
+    })
+    .await;
+
+    let server = server(&test);
+
+    let (stores, failed) = call_tool(&server, "list_stores", json!({})).await;
+    assert!(!failed);
+    assert_eq!(stores[0]["id"], store_id);
+    assert_eq!(stores[0]["documentCount"], 1);
+
+    let (search, failed) = call_tool(
+        &server,
+        "search_documents",
+        json!({ "store": "Test store", "query": "What does the fox do?" }),
+    )
+    .await;
+    assert!(!failed, "{}", search);
+    assert_eq!(search["answer"], "The fox jumps.");
+    assert_eq!(search["documents"][0]["id"], document_id);
+
+    let (document, failed) = call_tool(&server, "get_document", json!({ "id": document_id })).await;
+    assert!(!failed);
+    assert_eq!(document["name"], "fox.txt");
+    assert_eq!(
+        document["text"],
+        "The quick brown fox jumps over the lazy dog."
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn reports_tool_and_protocol_errors() {
+    let test = TestApp::new().await;
+    let server = server(&test);
+
+    let (error, failed) = call_tool(
+        &server,
+        "search_documents",
+        json!({ "store": "Missing", "query": "?" }),
+    )
+    .await;
+    assert!(failed);
+    assert_eq!(error, "Store not found: Missing");
+
+    let (error, failed) = call_tool(&server, "get_document", json!({})).await;
+    assert!(failed);
+    assert_eq!(error, "Missing argument: id");
+
+    let response = server
+        .handle(json!({ "jsonrpc": "2.0", "id": 2, "method": "resources/list" }))
+        .await
+        .unwrap();
+    assert_eq!(response["error"]["code"], -32601);
+
+    // Notifications are not answered
+    let response = server
+        .handle(json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }))
+        .await;
+    assert!(response.is_none());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn cli_serves_over_stdio() {
+    let test = TestApp::new().await;
+    test.create_store_with_document("notes.txt");
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_mizly-cli"))
+        .env("MIZLY_DATA_DIR", test.dir.path())
+        .arg("mcp")
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .expect("Failed to run CLI");
+
+    let mut stdin = child.stdin.take().unwrap();
+    let messages = [
+        json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize",
+                "params": { "protocolVersion": PROTOCOL_VERSION, "capabilities": {},
+                            "clientInfo": { "name": "test", "version": "1" } } }),
+        json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+        json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/list" }),
+    ];
+    for message in messages {
+        stdin
+            .write_all(format!("{}\n", message).as_bytes())
+            .await
+            .unwrap();
+    }
+    stdin.write_all(b"not json\n").await.unwrap();
+    drop(stdin);
+
+    let mut lines = BufReader::new(child.stdout.take().unwrap()).lines();
+    let mut responses = vec![];
+    while let Some(line) = lines.next_line().await.unwrap() {
+        responses.push(serde_json::from_str::<Value>(&line).unwrap());
+    }
+    assert!(child.wait().await.unwrap().success());
+
+    assert_eq!(responses.len(), 3);
+    assert_eq!(responses[0]["result"]["protocolVersion"], PROTOCOL_VERSION);
+    assert_eq!(responses[0]["result"]["serverInfo"]["name"], "mizly");
+
+    let tools: Vec<&str> = responses[1]["result"]["tools"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|tool| tool["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(tools, ["list_stores", "search_documents", "get_document"]);
+
+    assert_eq!(responses[2]["error"]["code"], -32700);
+}
@@ -0,0 +1,304 @@
+//! Schema migrations: upgrades from every historical schema, checksums and rollbacks
+//!
+//! `tests/fixtures/migrations` holds one database per schema the app has
+//! shipped with, written by the migrations of that version with sample rows.
+//! Add a fixture whenever a migration changes the schema.
+
+use std::fs;
+use std::path::PathBuf;
+
+use mizly_lib::db::migrations::{
+    applied_migrations, migrate, rollback_migrations, run_migrations, Migration, MIGRATIONS,
+};
+use mizly_lib::db::{Database, DATABASE_FILE};
+use rusqlite::Connection;
+
+fn fixtures() -> Vec<(String, String)> {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/migrations");
+    let mut fixtures: Vec<_> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .map(|path| {
+            let name = path.file_name().unwrap().to_string_lossy().to_string();
+            (name, fs::read_to_string(path).unwrap())
+        })
+        .collect();
+    fixtures.sort();
+    fixtures
+}
+
+fn fixture(name: &str) -> Connection {
+    let (_, sql) = fixtures().into_iter().find(|(n, _)| n == name).unwrap();
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch(&sql).unwrap();
+    conn
+}
+
+/// Tables with their columns, and indexes with their columns and uniqueness
+///
+/// Automatic indexes are compared by columns, their names follow the table's history.
+/// `schema_migrations` is left out, legacy databases have its columns in another order.
+fn schema(conn: &Connection) -> Vec<String> {
+    let tables: Vec<String> = conn
+        .prepare(
+            "SELECT name FROM sqlite_master WHERE type = 'table'
+             AND name NOT LIKE 'sqlite_%' AND name NOT LIKE 'documents_fts_%'
+             AND name != 'schema_migrations' ORDER BY name",
+        )
+        .unwrap()
+        .query_map([], |row| row.get(0))
+        .unwrap()
+        .map(|r| r.unwrap())
+        .collect();
+
+    let mut schema = Vec::new();
+    for table in tables {
+        let columns: Vec<String> = conn
+            .prepare(&format!("PRAGMA table_info({})", table))
+            .unwrap()
+            .query_map([], |row| {
+                Ok(format!(
+                    "{} {} notnull={} default={:?} pk={}",
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, i64>(3)?,
+                    row.get::<_, Option<String>>(4)?,
+                    row.get::<_, i64>(5)?
+                ))
+            })
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect();
+        schema.push(format!("table {} ({})", table, columns.join(", ")));
+
+        let indexes: Vec<(String, i64, String)> = conn
+            .prepare(&format!("PRAGMA index_list({})", table))
+            .unwrap()
+            .query_map([], |row| Ok((row.get(1)?, row.get(2)?, row.get(3)?)))
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect();
+        let mut indexes: Vec<String> = indexes
+            .into_iter()
+            .map(|(name, unique, origin)| {
+                // Expressions, such as `IFNULL(size, 0)`, have no column name
+                let columns: Vec<String> = conn
+                    .prepare(&format!("PRAGMA index_info({})", name))
+                    .unwrap()
+                    .query_map([], |row| row.get::<_, Option<String>>(2))
+                    .unwrap()
+                    .map(|r| r.unwrap().unwrap_or_else(|| "<expression>".to_string()))
+                    .collect();
+                let name = if origin == "c" { name } else { origin };
+                format!(
+                    "index {} on {}({}) unique={}",
+                    name,
+                    table,
+                    columns.join(", "),
+                    unique
+                )
+            })
+            .collect();
+        indexes.sort();
+        schema.extend(indexes);
+    }
+    schema
+}
+
+fn current_schema() -> Vec<String> {
+    let conn = Connection::open_in_memory().unwrap();
+    run_migrations(&conn).unwrap();
+    schema(&conn)
+}
+
+fn gemini_name(conn: &Connection, table: &str, id: &str) -> Option<String> {
+    conn.query_row(
+        &format!("SELECT gemini_name FROM {} WHERE id = ?1", table),
+        [id],
+        |row| row.get(0),
+    )
+    .unwrap()
+}
+
+#[test]
+fn every_historical_schema_upgrades_to_the_current_one() {
+    let current = current_schema();
+    let fixtures = fixtures();
+    assert!(fixtures.len() >= 9, "fixtures are missing");
+
+    for (name, sql) in fixtures {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(DATABASE_FILE);
+        Connection::open(&path)
+            .unwrap()
+            .execute_batch(&sql)
+            .unwrap();
+
+        let db = Database::open(&path).unwrap_or_else(|e| panic!("{}: {}", name, e));
+        assert_eq!(db.get_stores().unwrap().len(), 2, "{}", name);
+
+        let conn = db.writer().unwrap();
+        assert_eq!(schema(&conn), current, "{}", name);
+
+        let applied = applied_migrations(&conn).unwrap();
+        assert_eq!(applied.len(), MIGRATIONS.len(), "{}", name);
+        for (record, migration) in applied.iter().zip(MIGRATIONS) {
+            assert_eq!(record.name, migration.name, "{}", name);
+            assert_eq!(record.checksum, migration.checksum(), "{}", name);
+        }
+
+        // Rows survive the table rebuilds
+        assert_eq!(
+            gemini_name(&conn, "documents", "doc-fox").as_deref(),
+            Some("fileSearchStores/animals-1/documents/fox"),
+            "{}",
+            name
+        );
+        let deleted_at: Option<String> = conn
+            .query_row(
+                "SELECT deleted_at FROM documents WHERE id = 'doc-note'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(
+            deleted_at.as_deref(),
+            Some("2025-12-01 10:00:00"),
+            "{}",
+            name
+        );
+
+        // gemini_name is UNIQUE again
+        let duplicate = conn.execute(
+            "UPDATE stores SET gemini_name = 'fileSearchStores/animals-1' WHERE id = 'store-drafts'",
+            [],
+        );
+        assert!(duplicate.is_err(), "{}", name);
+
+        // Foreign keys are back on
+        drop(conn);
+        db.hard_delete_store("store-animals").unwrap();
+        assert!(db.get_document("doc-fox").is_err(), "{}", name);
+    }
+}
+
+#[test]
+fn duplicate_remote_names_are_queued_again() {
+    let conn = fixture("v09-added-columns.sql");
+    run_migrations(&conn).unwrap();
+
+    assert!(gemini_name(&conn, "documents", "doc-fox").is_some());
+    let (gemini_name, status, sync_status): (Option<String>, String, String) = conn
+        .query_row(
+            "SELECT gemini_name, status, sync_status FROM documents WHERE id = 'doc-fox-copy'",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .unwrap();
+    assert_eq!(gemini_name, None);
+    assert_eq!(
+        (status.as_str(), sync_status.as_str()),
+        ("pending", "pending")
+    );
+}
+
+#[test]
+fn changed_or_unknown_migrations_are_reported() {
+    let conn = Connection::open_in_memory().unwrap();
+    run_migrations(&conn).unwrap();
+    // Running again is a no-op
+    run_migrations(&conn).unwrap();
+
+    conn.execute(
+        "UPDATE schema_migrations SET checksum = 'edited' WHERE version = 3",
+        [],
+    )
+    .unwrap();
+    assert_eq!(
+        run_migrations(&conn).unwrap_err(),
+        "Migration 3 (index_documents_status) was changed after it was applied"
+    );
+
+    let conn = Connection::open_in_memory().unwrap();
+    run_migrations(&conn).unwrap();
+    conn.execute(
+        "INSERT INTO schema_migrations (version, name, checksum) VALUES (999, 'future', 'x')",
+        [],
+    )
+    .unwrap();
+    assert_eq!(
+        run_migrations(&conn).unwrap_err(),
+        "Database schema version 999 is newer than this version of Mizly"
+    );
+}
+
+#[test]
+fn rollback_restores_the_previous_schema() {
+    let conn = fixture("v15-custom-metadata.sql");
+    let before = schema(&conn);
+    run_migrations(&conn).unwrap();
+
+    let undone = rollback_migrations(&conn, 15).unwrap();
+    let versions: Vec<_> = undone.iter().map(|m| m.version).collect();
+    assert_eq!(versions, [22, 21, 20, 19, 18, 17, 16]);
+
+    // The rebuilds are undone without UNIQUE, the looser of the historical shapes
+    let without_unique = |schema: Vec<String>| -> Vec<String> {
+        schema
+            .into_iter()
+            .filter(|entry| !entry.contains("(gemini_name) unique=1"))
+            .collect()
+    };
+    assert_eq!(without_unique(schema(&conn)), without_unique(before));
+    assert_eq!(
+        gemini_name(&conn, "stores", "store-animals").as_deref(),
+        Some("fileSearchStores/animals-1")
+    );
+
+    // All the way down, then up again
+    rollback_migrations(&conn, 0).unwrap();
+    assert!(applied_migrations(&conn).unwrap().is_empty());
+    run_migrations(&conn).unwrap();
+    assert_eq!(schema(&conn), current_schema());
+}
+
+#[test]
+fn failed_migration_leaves_no_trace() {
+    let migrations = [
+        Migration {
+            version: 1,
+            name: "create_notes",
+            up: "CREATE TABLE notes (id TEXT PRIMARY KEY);",
+            down: "DROP TABLE notes;",
+        },
+        Migration {
+            version: 2,
+            name: "create_tags",
+            up: "CREATE TABLE tags (id TEXT PRIMARY KEY); INSERT INTO missing VALUES (1);",
+            down: "DROP TABLE tags;",
+        },
+    ];
+
+    let conn = Connection::open_in_memory().unwrap();
+    let error = migrate(&conn, &migrations).unwrap_err();
+    assert!(
+        error.starts_with("Failed to run migration 2 (create_tags)"),
+        "{}",
+        error
+    );
+
+    let versions: Vec<_> = applied_migrations(&conn)
+        .unwrap()
+        .into_iter()
+        .map(|m| m.version)
+        .collect();
+    assert_eq!(versions, [1]);
+    let tags: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE name = 'tags'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(tags, 0);
+}
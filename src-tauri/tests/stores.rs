@@ -0,0 +1,80 @@
+//! Editing stores: title, directory, description, color and icon
+
+use mizly_lib::db::{Database, StoreUpdate};
+
+fn open() -> (tempfile::TempDir, Database) {
+    let dir = tempfile::tempdir().unwrap();
+    let db = Database::new(dir.path()).unwrap();
+    db.create_store("animals", "Animals", Some("/data/animals"), "gemini", false)
+        .unwrap();
+    (dir, db)
+}
+
+fn update(title: &str) -> StoreUpdate {
+    StoreUpdate {
+        title: title.to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn edits_are_saved_and_listed() {
+    let (_dir, db) = open();
+
+    let store = db
+        .update_store(
+            "animals",
+            &StoreUpdate {
+                title: "  Wildlife ".to_string(),
+                directory_path: Some("/data/wildlife".to_string()),
+                description: Some("Field notes".to_string()),
+                color: Some("#3B82F6".to_string()),
+                icon: Some("🦊".to_string()),
+            },
+        )
+        .unwrap();
+    assert_eq!(store.title, "Wildlife");
+    assert_eq!(store.directory_path.as_deref(), Some("/data/wildlife"));
+    assert_eq!(store.description.as_deref(), Some("Field notes"));
+    assert_eq!(store.color.as_deref(), Some("#3b82f6"));
+    assert_eq!(store.icon.as_deref(), Some("🦊"));
+
+    let listed = &db.get_stores().unwrap()[0];
+    assert_eq!(listed.store.title, "Wildlife");
+    assert_eq!(listed.store.icon.as_deref(), Some("🦊"));
+    assert_eq!(listed.document_count, 0);
+
+    // Blank values clear the optional fields
+    let mut blank = update("Wildlife");
+    blank.description = Some(" ".to_string());
+    let store = db.update_store("animals", &blank).unwrap();
+    assert_eq!(store.directory_path, None);
+    assert_eq!(store.description, None);
+    assert_eq!(store.color, None);
+    assert_eq!(store.icon, None);
+}
+
+#[test]
+fn invalid_edits_are_rejected() {
+    let (_dir, db) = open();
+
+    let error = db.update_store("animals", &update(" ")).unwrap_err();
+    assert_eq!(error, "Store title can't be empty");
+
+    let mut invalid = update("Animals");
+    invalid.color = Some("blue".to_string());
+    let error = db.update_store("animals", &invalid).unwrap_err();
+    assert!(error.starts_with("Invalid color"), "{}", error);
+
+    let mut invalid = update("Animals");
+    invalid.icon = Some("a long label".to_string());
+    assert!(db.update_store("animals", &invalid).is_err());
+
+    // Nothing was saved
+    assert_eq!(db.get_store("animals").unwrap().title, "Animals");
+
+    // Stores in the trash can't be edited
+    db.soft_delete_store("animals").unwrap();
+    let error = db.update_store("animals", &update("Wildlife")).unwrap_err();
+    assert_eq!(error, "Store not found: animals");
+}
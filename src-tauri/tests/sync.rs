@@ -0,0 +1,219 @@
+//! Background sync and polling against the mock Gemini server
+
+mod common;
+
+use std::time::Duration;
+
+use common::mock_gemini::Endpoint;
+use common::{wait_for, TestApp};
+
+const TIMEOUT: Duration = Duration::from_secs(20);
+
+#[tokio::test(flavor = "multi_thread")]
+async fn uploads_store_and_document_until_completed() {
+    let test = TestApp::new().await;
+    test.mock.set_operation_polls(1);
+    let (store_id, document_id) = test.create_store_with_document("notes.txt");
+
+    test.start_tasks();
+
+    wait_for(TIMEOUT, "document to complete", || {
+        test.db
+            .get_document(&document_id)
+            .map(|d| d.status == "completed")
+            .unwrap_or(false)
+    })
+    .await;
+
+    let store = test.db.get_store(&store_id).unwrap();
+    assert_eq!(store.sync_status, "synced");
+    assert_eq!(test.mock.stores(), vec![store.gemini_name.clone().unwrap()]);
+
+    let document = test.db.get_document(&document_id).unwrap();
+    let remote = test.mock.documents();
+    assert_eq!(remote.len(), 1);
+    assert_eq!(document.gemini_name.as_deref(), Some(remote[0].name.as_str()));
+    assert_eq!(document.sync_status, "synced");
+    assert_eq!(document.operation_name, None);
+    assert_eq!(document.upload_mime_type.as_deref(), Some("text/plain"));
+    assert_eq!(remote[0].display_name, "notes.txt");
+    assert_eq!(remote[0].mime_type, "text/plain");
+
+    // Progress is reported through the event sink
+    let store_events = test.events.payloads("store-sync-updated");
+    assert_eq!(store_events.len(), 1);
+    assert_eq!(store_events[0]["storeId"], store_id.as_str());
+    assert_eq!(store_events[0]["syncStatus"], "synced");
+
+    let status_events = test.events.payloads("document-status-updated");
+    assert_eq!(status_events.last().unwrap()["status"], "completed");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn failed_store_creation_marks_store_failed() {
+    let test = TestApp::new().await;
+    test.mock.fail_next(Endpoint::CreateStore, 500, 1);
+    let (store_id, document_id) = test.create_store_with_document("notes.txt");
+
+    test.start_tasks();
+
+    wait_for(TIMEOUT, "store to fail", || {
+        test.db
+            .get_store(&store_id)
+            .map(|s| s.sync_status == "failed")
+            .unwrap_or(false)
+    })
+    .await;
+
+    // Documents of an unsynced store are never uploaded
+    let document = test.db.get_document(&document_id).unwrap();
+    assert_eq!(document.sync_status, "pending");
+    assert!(test.mock.stores().is_empty());
+    assert!(test.mock.documents().is_empty());
+
+    // The event follows the status update
+    wait_for(TIMEOUT, "store sync event", || {
+        !test.events.payloads("store-sync-updated").is_empty()
+    })
+    .await;
+    assert_eq!(test.events.payloads("store-sync-updated")[0]["syncStatus"], "failed");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn failed_upload_marks_document_failed() {
+    let test = TestApp::new().await;
+    test.mock.fail_next(Endpoint::StartUpload, 503, 1);
+    let (_, document_id) = test.create_store_with_document("notes.txt");
+
+    test.start_tasks();
+
+    wait_for(TIMEOUT, "document to fail", || {
+        test.db
+            .get_document(&document_id)
+            .map(|d| d.status == "failed")
+            .unwrap_or(false)
+    })
+    .await;
+
+    let document = test.db.get_document(&document_id).unwrap();
+    assert_eq!(document.sync_status, "failed");
+    assert!(document
+        .error_message
+        .unwrap_or_default()
+        .contains("Injected failure"));
+    assert!(test.mock.documents().is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn missing_file_marks_document_failed() {
+    let test = TestApp::new().await;
+    let (_, document_id) = test.create_store_with_document("notes.txt");
+    std::fs::remove_file(test.dir.path().join("notes.txt")).unwrap();
+
+    test.start_tasks();
+
+    wait_for(TIMEOUT, "document to fail", || {
+        test.db
+            .get_document(&document_id)
+            .map(|d| d.status == "failed")
+            .unwrap_or(false)
+    })
+    .await;
+
+    assert!(!test
+        .mock
+        .requests()
+        .iter()
+        .any(|r| r.starts_with("POST /upload/")));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn failed_operation_marks_document_failed() {
+    let test = TestApp::new().await;
+    test.mock.fail_operations("Unsupported document");
+    let (_, document_id) = test.create_store_with_document("notes.txt");
+
+    test.start_tasks();
+
+    wait_for(TIMEOUT, "document to fail", || {
+        test.db
+            .get_document(&document_id)
+            .map(|d| d.status == "failed")
+            .unwrap_or(false)
+    })
+    .await;
+
+    let document = test.db.get_document(&document_id).unwrap();
+    assert_eq!(document.error_message.as_deref(), Some("Unsupported document"));
+    assert_eq!(document.gemini_name, None);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn polling_retries_after_operation_errors() {
+    let test = TestApp::new().await;
+    test.mock.fail_next(Endpoint::GetOperation, 500, 1);
+    let (_, document_id) = test.create_store_with_document("notes.txt");
+
+    test.start_tasks();
+
+    wait_for(TIMEOUT, "document to complete", || {
+        test.db
+            .get_document(&document_id)
+            .map(|d| d.status == "completed")
+            .unwrap_or(false)
+    })
+    .await;
+
+    let operation_requests = test
+        .mock
+        .requests()
+        .iter()
+        .filter(|r| r.contains("/operations/"))
+        .count();
+    assert!(operation_requests >= 2);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn deletes_documents_and_stores_remotely() {
+    let test = TestApp::new().await;
+    // Purge right away instead of keeping deleted items in the trash
+    test.sync_state.set_trash_retention_days(0);
+    let (store_id, document_id) = test.create_store_with_document("notes.txt");
+
+    test.start_tasks();
+
+    wait_for(TIMEOUT, "document to complete", || {
+        test.db
+            .get_document(&document_id)
+            .map(|d| d.status == "completed")
+            .unwrap_or(false)
+    })
+    .await;
+
+    // A failed remote deletion keeps the record for a retry
+    test.mock.fail_next(Endpoint::DeleteDocument, 500, 1);
+    test.db.soft_delete_document(&document_id).unwrap();
+    test.sync_state.notify();
+
+    wait_for(TIMEOUT, "document to be deleted", || {
+        test.mock.documents().is_empty()
+            && test.db.get_soft_deleted_documents(0).unwrap().is_empty()
+    })
+    .await;
+
+    let document_deletions = test
+        .mock
+        .requests()
+        .iter()
+        .filter(|r| r.starts_with("DELETE") && r.contains("/documents/"))
+        .count();
+    assert_eq!(document_deletions, 2);
+
+    test.db.soft_delete_store(&store_id).unwrap();
+    test.sync_state.notify();
+
+    wait_for(TIMEOUT, "store to be deleted", || {
+        test.mock.stores().is_empty() && test.db.get_soft_deleted_stores(0).unwrap().is_empty()
+    })
+    .await;
+}
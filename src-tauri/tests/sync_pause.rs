@@ -0,0 +1,100 @@
+//! Pausing background sync, and waiting offline until the backend can be reached
+
+mod common;
+
+use std::time::Duration;
+
+use common::{is_status, wait_for, TestApp};
+use mizly_lib::settings::{self, SyncSettings};
+
+const TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Whether the sync task has emitted a status with `field` set
+fn emitted(test: &TestApp, field: &str) -> bool {
+    test.events
+        .payloads("sync-status")
+        .iter()
+        .any(|status| status[field] == true)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn paused_sync_waits_for_a_resume() {
+    let test = TestApp::new().await;
+
+    // The pause is kept across restarts
+    settings::write_sync_settings(test.dir.path(), &SyncSettings { paused: true }).unwrap();
+    let paused = settings::get_sync_settings_sync(test.dir.path()).paused;
+    test.sync_state.set_paused(paused);
+
+    let (_, document_id) = test.create_store_with_document("notes.txt");
+    test.start_tasks();
+
+    wait_for(TIMEOUT, "a paused status", || emitted(&test, "paused")).await;
+
+    let status = test.sync_state.status(&test.db).unwrap();
+    assert!(!status.lease_held);
+    assert_eq!(status.next_wakeup_at, None);
+    assert_eq!(status.queue.uploads_pending, 1);
+    assert!(test.polling_state.is_paused());
+    assert!(test.mock.requests().is_empty());
+
+    test.sync_state.set_paused(false);
+    test.sync_state.notify();
+
+    wait_for(TIMEOUT, "document to complete", || {
+        is_status(&test.db, &document_id, "completed")
+    })
+    .await;
+    assert!(!test.polling_state.is_paused());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn sync_waits_offline_until_the_backend_is_reachable() {
+    let test = TestApp::new().await;
+    test.mock.set_offline(true).await;
+    let (_, document_id) = test.create_store_with_document("notes.txt");
+    let local = test
+        .db
+        .create_store("local", "Local", None, "local", true)
+        .unwrap();
+    test.start_tasks();
+
+    wait_for(TIMEOUT, "an offline status", || emitted(&test, "offline")).await;
+
+    // Local work goes on: the local store is created and the keyword index built
+    wait_for(TIMEOUT, "local sync", || {
+        let local_synced = test
+            .db
+            .get_store(&local.id)
+            .is_ok_and(|store| store.sync_status == "synced");
+        let indexed = test
+            .db
+            .search_local("fox", &[], 10)
+            .is_ok_and(|results| !results.is_empty());
+        local_synced && indexed
+    })
+    .await;
+
+    // Gemini work was not attempted, so nothing failed
+    let status = test.sync_state.status(&test.db).unwrap();
+    assert_eq!(status.queue.stores_pending, 1);
+    assert_eq!(status.queue.uploads_pending, 1);
+    assert!(status.next_wakeup_at.is_some());
+    assert!(status
+        .last_error
+        .unwrap()
+        .message
+        .starts_with("Offline, pausing Gemini sync"));
+    assert!(test.polling_state.is_paused());
+
+    // Reconnection is found by the next check, which a notification brings forward
+    test.mock.set_offline(false).await;
+    test.sync_state.notify();
+
+    wait_for(TIMEOUT, "document to complete", || {
+        is_status(&test.db, &document_id, "completed")
+    })
+    .await;
+    assert!(!test.sync_state.is_offline());
+    assert!(!test.polling_state.is_paused());
+}
@@ -0,0 +1,147 @@
+//! Sync status: queue depth per stage, last cycle, last error and wakeups
+
+mod common;
+
+use std::time::Duration;
+
+use common::mock_gemini::Endpoint;
+use common::{wait_for, TestApp};
+use mizly_lib::db::{DocumentEventKind, SyncQueue};
+use mizly_lib::sync::run_sync_cycle;
+
+const TIMEOUT: Duration = Duration::from_secs(20);
+
+#[tokio::test(flavor = "multi_thread")]
+async fn status_counts_the_work_of_each_stage() {
+    let test = TestApp::new().await;
+    let (store_id, document_id) = test.create_store_with_document("notes.txt");
+    let path = test.write_file("old.txt", "Old notes");
+    test.db
+        .create_document(
+            "old",
+            &store_id,
+            "old.txt",
+            path.to_str().unwrap(),
+            Some("text/plain"),
+            Some(9),
+            None,
+        )
+        .unwrap();
+    test.db.soft_delete_document("old").unwrap();
+
+    // Nothing is purged before the grace period ends
+    let status = test.sync_state.status(&test.db).unwrap();
+    assert_eq!(
+        status.queue,
+        SyncQueue {
+            stores_pending: 1,
+            uploads_pending: 1,
+            uploads_in_flight: 0,
+            operations_polling: 0,
+            deletions_pending: 0,
+        }
+    );
+    assert!(!status.lease_held);
+    assert_eq!(status.last_cycle_at, None);
+
+    test.sync_state.set_trash_retention_days(0);
+    assert_eq!(
+        test.sync_state
+            .status(&test.db)
+            .unwrap()
+            .queue
+            .deletions_pending,
+        1
+    );
+
+    // An upload is in flight from its start until the backend accepts it
+    test.db
+        .record_document_event(&document_id, DocumentEventKind::UploadStarted, None)
+        .unwrap();
+    let queue = test.sync_state.status(&test.db).unwrap().queue;
+    assert_eq!((queue.uploads_pending, queue.uploads_in_flight), (0, 1));
+
+    assert!(test.sync_state.acquire_lease(&test.db).unwrap());
+    run_sync_cycle(
+        test.events.as_ref(),
+        &test.db,
+        &test.backends,
+        &test.sync_state,
+        &test.polling_state,
+    )
+    .await;
+
+    // The upload's operation waits for the polling task, not started here
+    let status = test.sync_state.status(&test.db).unwrap();
+    assert_eq!(
+        status.queue,
+        SyncQueue {
+            operations_polling: 1,
+            ..Default::default()
+        }
+    );
+    assert!(status.lease_held);
+    assert!(status.last_cycle_at.is_some());
+    assert!(status.last_cycle_duration_ms.is_some());
+    assert!(status.last_error.is_none());
+
+    test.sync_state.release_lease(&test.db).unwrap();
+    assert!(!test.sync_state.status(&test.db).unwrap().lease_held);
+
+    // Documents deleted along with their store are purged with it
+    test.db
+        .create_document(
+            "new",
+            &store_id,
+            "new.txt",
+            path.to_str().unwrap(),
+            Some("text/plain"),
+            Some(9),
+            None,
+        )
+        .unwrap();
+    test.db.soft_delete_store(&store_id).unwrap();
+    assert_eq!(
+        test.sync_state
+            .status(&test.db)
+            .unwrap()
+            .queue
+            .deletions_pending,
+        1
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn sync_task_emits_its_status_with_the_last_error() {
+    let test = TestApp::new().await;
+    test.mock.fail_next(Endpoint::StartUpload, 503, 1);
+    test.create_store_with_document("notes.txt");
+    test.start_tasks();
+
+    wait_for(TIMEOUT, "a status with the upload error", || {
+        test.events
+            .payloads("sync-status")
+            .iter()
+            .any(|status| !status["lastError"].is_null())
+    })
+    .await;
+
+    let statuses = test.events.payloads("sync-status");
+    let status = statuses
+        .iter()
+        .find(|status| !status["lastError"].is_null())
+        .unwrap();
+    assert!(status["lastError"]["message"]
+        .as_str()
+        .unwrap()
+        .starts_with("Failed to upload document"));
+    assert_eq!(status["running"], true);
+    assert_eq!(status["leaseHeld"], true);
+    assert_eq!(status["uploadsPending"], 0);
+    assert!(status["nextWakeupAt"].is_string());
+    assert!(status["lastCycleAt"].is_string());
+
+    // The error stays until another one replaces it
+    let status = test.sync_state.status(&test.db).unwrap();
+    assert!(status.last_error.is_some());
+}
@@ -0,0 +1,200 @@
+//! Moving and copying documents between stores
+
+mod common;
+
+use std::time::Duration;
+
+use common::{is_status, wait_for, TestApp};
+use mizly_lib::db::Database;
+
+const TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Create an empty Gemini store to transfer documents to
+fn create_target(db: &Database) -> String {
+    let store_id = uuid::Uuid::new_v4().to_string();
+    db.create_store(&store_id, "Target store", None, "gemini", false)
+        .unwrap();
+    store_id
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn moved_documents_leave_their_source_once_uploaded() {
+    let test = TestApp::new().await;
+    let (source_store_id, document_id) = test.create_store_with_document("notes.txt");
+    let target_store_id = create_target(&test.db);
+    test.start_tasks();
+
+    wait_for(TIMEOUT, "document to complete", || {
+        is_status(&test.db, &document_id, "completed")
+    })
+    .await;
+
+    let result = test
+        .db
+        .move_documents(std::slice::from_ref(&document_id), &target_store_id)
+        .unwrap();
+    assert!(result.rejected.is_empty());
+    let moved = &result.documents[0];
+    assert_eq!(moved.store_id, target_store_id);
+    assert_eq!(moved.status, "pending");
+
+    // The source stays while the copy is uploaded
+    assert!(test
+        .db
+        .get_document(&document_id)
+        .unwrap()
+        .deleted_at
+        .is_none());
+    test.sync_state.notify();
+
+    wait_for(TIMEOUT, "source to be trashed", || {
+        test.db.get_document(&document_id).is_err()
+    })
+    .await;
+
+    let trash = test.db.get_trash(30).unwrap();
+    assert_eq!(trash.documents[0].document.id, document_id);
+
+    assert_eq!(test.db.get_document(&moved.id).unwrap().status, "completed");
+    assert_eq!(
+        test.db
+            .get_documents_by_store(&source_store_id)
+            .unwrap()
+            .len(),
+        0
+    );
+    assert_eq!(test.mock.documents().len(), 2);
+
+    let events = test.events.payloads("document-moved");
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0]["documentId"], moved.id.as_str());
+    assert_eq!(events[0]["sourceStoreId"], source_store_id.as_str());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn copied_documents_keep_their_source() {
+    let test = TestApp::new().await;
+    let (source_store_id, document_id) = test.create_store_with_document("notes.txt");
+    let target_store_id = create_target(&test.db);
+    test.start_tasks();
+
+    let result = test
+        .db
+        .copy_documents(std::slice::from_ref(&document_id), &target_store_id)
+        .unwrap();
+    let copy_id = result.documents[0].id.clone();
+    test.sync_state.notify();
+
+    wait_for(TIMEOUT, "both documents to complete", || {
+        is_status(&test.db, &document_id, "completed") && is_status(&test.db, &copy_id, "completed")
+    })
+    .await;
+
+    assert_eq!(
+        test.db
+            .get_documents_by_store(&source_store_id)
+            .unwrap()
+            .len(),
+        1
+    );
+    assert_eq!(
+        test.db
+            .get_documents_by_store(&target_store_id)
+            .unwrap()
+            .len(),
+        1
+    );
+    assert!(test.events.payloads("document-moved").is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn failed_copies_keep_the_moved_source() {
+    let test = TestApp::new().await;
+    let (_, document_id) = test.create_store_with_document("notes.txt");
+    let target_store_id = create_target(&test.db);
+    test.mock.fail_operations("Unsupported document");
+    test.start_tasks();
+
+    let result = test
+        .db
+        .move_documents(std::slice::from_ref(&document_id), &target_store_id)
+        .unwrap();
+    let copy_id = result.documents[0].id.clone();
+    test.sync_state.notify();
+
+    wait_for(TIMEOUT, "copy to fail", || {
+        is_status(&test.db, &copy_id, "failed")
+    })
+    .await;
+
+    assert!(test
+        .db
+        .get_document(&document_id)
+        .unwrap()
+        .deleted_at
+        .is_none());
+
+    // A second move waits for the first one
+    let result = test
+        .db
+        .move_documents(std::slice::from_ref(&document_id), &create_target(&test.db))
+        .unwrap();
+    assert!(result.documents.is_empty());
+    assert_eq!(result.rejected[0].message, "Already being moved");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn invalid_transfers_are_rejected_per_document() {
+    let test = TestApp::new().await;
+    let (source_store_id, document_id) = test.create_store_with_document("notes.txt");
+    let target_store_id = create_target(&test.db);
+
+    let local_store_id = uuid::Uuid::new_v4().to_string();
+    test.db
+        .create_store(&local_store_id, "Private", None, "local", true)
+        .unwrap();
+    let path = test.write_file("private.txt", "Kept on this machine");
+    test.db
+        .create_document(
+            "private",
+            &local_store_id,
+            "private.txt",
+            path.to_str().unwrap(),
+            Some("text/plain"),
+            Some(20),
+            None,
+        )
+        .unwrap();
+
+    let ids = [
+        document_id.clone(),
+        "missing".to_string(),
+        "private".to_string(),
+    ];
+    let result = test.db.copy_documents(&ids, &target_store_id).unwrap();
+    assert_eq!(result.documents.len(), 1);
+
+    let messages: Vec<_> = result.rejected.iter().map(|r| r.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        [
+            "Document not found",
+            "Documents of local-only stores stay on this machine"
+        ]
+    );
+
+    // The same file is only copied once, and not into its own store
+    let result = test.db.copy_documents(&ids[..1], &target_store_id).unwrap();
+    assert_eq!(result.rejected[0].message, "Already in the target store");
+    let result = test.db.copy_documents(&ids[..1], &source_store_id).unwrap();
+    assert_eq!(result.rejected[0].message, "Already in the target store");
+
+    // Copies are read from the local file
+    let target_store_id = create_target(&test.db);
+    std::fs::remove_file(test.dir.path().join("notes.txt")).unwrap();
+    let result = test.db.copy_documents(&ids[..1], &target_store_id).unwrap();
+    assert!(result.rejected[0].message.starts_with("File not found"));
+
+    let error = test.db.copy_documents(&ids, "missing").unwrap_err();
+    assert!(error.starts_with("Failed to get store"), "{}", error);
+}
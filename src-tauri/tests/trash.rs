@@ -0,0 +1,257 @@
+//! Trash: deleted stores and documents stay restorable for the grace period
+
+mod common;
+
+use std::time::Duration;
+
+use common::{wait_for, TestApp};
+use mizly_lib::db::NewChunk;
+use mizly_lib::polling::poll_pending_operations;
+use mizly_lib::sync::run_sync_cycle;
+
+const TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Run one sync pass, as the background task would
+async fn sync(test: &TestApp) {
+    run_sync_cycle(
+        test.events.as_ref(),
+        &test.db,
+        &test.backends,
+        &test.sync_state,
+        &test.polling_state,
+    )
+    .await;
+}
+
+/// Move the deletion of every trashed item `days` into the past
+fn age_trash(test: &TestApp, days: u32) {
+    let conn = test.db.writer().unwrap();
+    let modifier = format!("-{} days", days);
+    for table in ["stores", "documents"] {
+        conn.execute(
+            &format!(
+                "UPDATE {} SET deleted_at = datetime(deleted_at, ?1) WHERE deleted_at IS NOT NULL",
+                table
+            ),
+            [&modifier],
+        )
+        .unwrap();
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn deleted_items_are_restored_within_the_grace_period() {
+    let test = TestApp::new().await;
+    test.sync_state.set_trash_retention_days(30);
+    let (store_id, document_id) = test.create_store_with_document("notes.txt");
+    test.start_tasks();
+
+    wait_for(TIMEOUT, "document to complete", || {
+        test.db
+            .get_document(&document_id)
+            .map(|d| d.status == "completed")
+            .unwrap_or(false)
+    })
+    .await;
+
+    // A deleted document is kept remotely
+    test.db.soft_delete_document(&document_id).unwrap();
+    sync(&test).await;
+    assert_eq!(test.mock.documents().len(), 1);
+
+    let trash = test.db.get_trash(30).unwrap();
+    assert_eq!(trash.documents.len(), 1);
+    assert_eq!(trash.documents[0].store_title, "Test store");
+
+    let document = test.db.restore_document(&document_id).unwrap();
+    assert!(document.deleted_at.is_none());
+    assert!(test.db.get_trash(30).unwrap().documents.is_empty());
+
+    // Deleting the store takes its documents along
+    test.db.soft_delete_store(&store_id).unwrap();
+    sync(&test).await;
+    assert_eq!(test.mock.stores().len(), 1);
+
+    let trash = test.db.get_trash(30).unwrap();
+    assert_eq!(trash.stores.len(), 1);
+    assert_eq!(trash.stores[0].document_count, 1);
+    assert!(trash.documents.is_empty());
+
+    let error = test.db.restore_document(&document_id).unwrap_err();
+    assert_eq!(error, "Store Test store is in the trash, restore it first");
+
+    test.db.restore_store(&store_id).unwrap();
+    let documents = test.db.get_documents_by_store(&store_id).unwrap();
+    assert_eq!(documents.len(), 1);
+    assert_eq!(documents[0].status, "completed");
+
+    let error = test.db.restore_store(&store_id).unwrap_err();
+    assert_eq!(error, format!("Store not found in the trash: {}", store_id));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn expired_items_are_purged_remotely() {
+    let test = TestApp::new().await;
+    test.sync_state.set_trash_retention_days(7);
+    let (store_id, document_id) = test.create_store_with_document("notes.txt");
+    let drafts = test
+        .db
+        .create_store("drafts", "Drafts", None, "gemini", false)
+        .unwrap();
+    let path = test.write_file("draft.txt", "Not uploaded yet");
+    test.db
+        .create_document(
+            "draft",
+            &drafts.id,
+            "draft.txt",
+            path.to_str().unwrap(),
+            Some("text/plain"),
+            Some(16),
+            None,
+        )
+        .unwrap();
+    // Deleted before sync could upload it
+    test.db.soft_delete_document("draft").unwrap();
+    test.start_tasks();
+
+    wait_for(TIMEOUT, "document to complete", || {
+        test.db
+            .get_document(&document_id)
+            .map(|d| d.status == "completed")
+            .unwrap_or(false)
+    })
+    .await;
+
+    test.db.soft_delete_document(&document_id).unwrap();
+    age_trash(&test, 6);
+    sync(&test).await;
+    assert_eq!(test.mock.documents().len(), 1);
+    assert_eq!(test.db.get_trash(7).unwrap().documents.len(), 2);
+
+    // A day later both are purged, the unsynced one only locally
+    age_trash(&test, 1);
+    sync(&test).await;
+    assert!(test.mock.documents().is_empty());
+    assert!(test.db.get_trash(7).unwrap().documents.is_empty());
+
+    let remote_name = test.db.get_store(&store_id).unwrap().gemini_name.unwrap();
+    test.db.soft_delete_store(&store_id).unwrap();
+    age_trash(&test, 7);
+    sync(&test).await;
+    assert!(!test.mock.stores().contains(&remote_name));
+    assert!(test.db.get_trash(7).unwrap().stores.is_empty());
+}
+#[tokio::test]
+async fn local_search_leaves_out_trashed_documents() {
+    let test = TestApp::new().await;
+    let store = test
+        .db
+        .create_store("local", "Local", None, "local", true)
+        .unwrap();
+    let path = test.write_file("notes.txt", "Notes");
+    test.db
+        .create_document(
+            "notes",
+            &store.id,
+            "notes.txt",
+            path.to_str().unwrap(),
+            Some("text/plain"),
+            Some(5),
+            None,
+        )
+        .unwrap();
+
+    let (store_name, document_name) = ("localStores/a", "localStores/a/documents/b");
+    let chunks = [NewChunk {
+        content: "Notes".to_string(),
+        embedding: vec![1.0, 0.0],
+    }];
+    test.db
+        .replace_document_chunks(store_name, document_name, "notes.txt", "model", &chunks)
+        .unwrap();
+    test.db
+        .update_document_uploaded("notes", document_name, None, "text/plain")
+        .unwrap();
+
+    let store_names = [store_name.to_string()];
+    let search = || {
+        test.db
+            .search_chunks(&store_names, "model", &[1.0, 0.0], 8)
+            .unwrap()
+    };
+    assert_eq!(search().len(), 1);
+
+    test.db.soft_delete_document("notes").unwrap();
+    assert!(search().is_empty());
+    assert!(test.db.sample_chunks(&store_names, 8).unwrap().is_empty());
+
+    test.db.restore_document("notes").unwrap();
+    assert_eq!(search().len(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn trashed_documents_are_left_out_of_query_sources() {
+    let test = TestApp::new().await;
+    test.sync_state.set_trash_retention_days(30);
+    let (store_id, document_id) = test.create_store_with_document("notes.txt");
+    test.start_tasks();
+
+    wait_for(TIMEOUT, "document to complete", || {
+        test.db
+            .get_document(&document_id)
+            .map(|d| d.status == "completed")
+            .unwrap_or(false)
+    })
+    .await;
+
+    let store_names = [test.db.get_store(&store_id).unwrap().gemini_name.unwrap()];
+    let sources = || async {
+        test.backends
+            .query(&store_names, "What are the notes about?")
+            .await
+            .unwrap()
+            .sources
+    };
+    assert_eq!(sources().await, ["notes.txt"]);
+
+    // The document stays in Gemini until purged, but isn't cited
+    test.db.soft_delete_document(&document_id).unwrap();
+    assert_eq!(test.mock.documents().len(), 1);
+    assert!(sources().await.is_empty());
+
+    test.db.restore_document(&document_id).unwrap();
+    assert_eq!(sources().await, ["notes.txt"]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn documents_trashed_while_processing_are_purged_remotely() {
+    let test = TestApp::new().await;
+    test.sync_state.set_trash_retention_days(0);
+    test.mock.set_operation_polls(1);
+    let (_, document_id) = test.create_store_with_document("notes.txt");
+
+    // Uploaded, but Gemini is still processing it
+    sync(&test).await;
+    assert!(test
+        .db
+        .get_document(&document_id)
+        .unwrap()
+        .operation_name
+        .is_some());
+
+    // The purge waits for the operation to name the remote document
+    test.db.soft_delete_document(&document_id).unwrap();
+    sync(&test).await;
+    assert_eq!(test.db.get_trash(0).unwrap().documents.len(), 1);
+
+    for _ in 0..2 {
+        poll_pending_operations(test.events.as_ref(), &test.db, &test.backends)
+            .await
+            .unwrap();
+    }
+    assert_eq!(test.mock.documents().len(), 1);
+
+    sync(&test).await;
+    assert!(test.mock.documents().is_empty());
+    assert!(test.db.get_trash(0).unwrap().documents.is_empty());
+}
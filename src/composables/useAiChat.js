@@ -79,6 +79,11 @@ export function useAiChat(options = {}) {
     }
   }
 
+  // Sanitize a local search snippet, keeping only the match highlights
+  const renderSnippet = (snippet) => {
+    return DOMPurify.sanitize(snippet || '', { ALLOWED_TAGS: ['mark'] })
+  }
+
   // Send chat message
   const sendMessage = async (content) => {
     appStore.clearSearchQuery()
@@ -200,6 +205,7 @@ export function useAiChat(options = {}) {
     selectSuggestQuestion,
     exitChat,
     openDocument,
+    renderSnippet,
     navigateSuggestUp,
     navigateSuggestDown,
     handleSuggestEnter
@@ -0,0 +1,85 @@
+import { ref, computed } from 'vue'
+import { useI18n } from 'vue-i18n'
+import { invoke } from '@tauri-apps/api/core'
+import { save } from '@tauri-apps/plugin-dialog'
+import { FILE_LIMITS } from '@/utils/constants.js'
+import { useChatStore } from '@/stores/chat'
+
+// The first filter's format is the default; the chosen extension picks the format
+const EXPORT_FILTERS = [
+  { name: 'Markdown', extensions: ['md'] },
+  { name: 'HTML', extensions: ['html'] },
+  { name: 'PDF', extensions: ['pdf'] }
+]
+
+/**
+ * Conversation export composable
+ * Saves the current chat with its citations as Markdown, HTML or PDF
+ *
+ * @returns {Object} Export state and methods
+ */
+export function useConversationExport() {
+  const { t } = useI18n()
+  const chatStore = useChatStore()
+
+  const isExporting = ref(false)
+  // Result or error of the last export
+  const exportStatus = ref('')
+
+  const canExport = computed(() => chatStore.exchanges.length > 0 && !chatStore.isStreaming)
+
+  const showStatus = (message) => {
+    exportStatus.value = message
+    setTimeout(() => {
+      exportStatus.value = ''
+    }, 5000)
+  }
+
+  const exportConversation = async () => {
+    if (!canExport.value || isExporting.value) return null
+
+    const title = chatStore.exchanges[0].question.slice(0, 80)
+
+    try {
+      // Tell backend to prevent auto-hide while the dialog is open
+      await invoke('set_prevent_auto_hide', { prevent: true })
+
+      let path
+      try {
+        path = await save({
+          defaultPath: `${title.replace(/[\\/:*?"<>|]/g, ' ').trim()}.md`,
+          filters: EXPORT_FILTERS
+        })
+      } finally {
+        setTimeout(async () => {
+          try {
+            await invoke('set_prevent_auto_hide', { prevent: false })
+          } catch {
+            // Ignore error if window is already closed
+          }
+        }, FILE_LIMITS.DIALOG_CLOSE_DELAY)
+      }
+      if (!path) return null
+
+      isExporting.value = true
+      const result = await invoke('export_conversation', {
+        conversation: { title, exchanges: chatStore.exchanges },
+        path
+      })
+      showStatus(t('aiChat.exported', { path: result.path }))
+      return result
+    } catch (e) {
+      showStatus(t('aiChat.exportFailed', { message: String(e) }))
+      return null
+    } finally {
+      isExporting.value = false
+    }
+  }
+
+  return {
+    canExport,
+    isExporting,
+    exportStatus,
+    exportConversation
+  }
+}
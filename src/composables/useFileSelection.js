@@ -1,13 +1,12 @@
 import { ref } from 'vue'
 import { invoke } from '@tauri-apps/api/core'
 import { open } from '@tauri-apps/plugin-dialog'
-import { readFile, stat, readDir } from '@tauri-apps/plugin-fs'
 import { FILE_LIMITS } from '@/utils/constants.js'
-import { formatFileSize, getContentType, calculateFileHash } from '@/utils/helpers.js'
+import { formatFileSize, getContentType } from '@/utils/helpers.js'
 
 /**
  * File selection composable
- * Handles local folder file selection
+ * Handles local folder file selection with include/exclude rules
  *
  * @returns {Object} File selection state and methods
  */
@@ -17,6 +16,11 @@ export function useFileSelection() {
   const isSelectingFile = ref(false)
   const selectedDirectoryPath = ref('')
   const selectedDirectoryFullPath = ref('')
+  const importRules = ref({ includePatterns: '', excludePatterns: '' })
+  const skippedFiles = ref([])
+  const removedFiles = ref([])
+  const truncated = ref(false)
+  const previewError = ref('')
 
   // Helper function to get directory name for display
   const getDirectoryDisplayName = (fullPath) => {
@@ -25,104 +29,55 @@ export function useFileSelection() {
     return parts[parts.length - 1] || fullPath
   }
 
-  // Helper function to process a file entry
-  const processFileEntry = async (dirPath, entry, parentDirSelected = true) => {
-    const fullPath = `${dirPath}/${entry.name}`
-    let fileSize = entry.size
-
-    // Try to get file size with stat if not available from readDir
-    if (fileSize === undefined) {
-      try {
-        const fileInfo = await stat(fullPath)
-        fileSize = fileInfo.size
-      } catch {
-        return null // Skip files we can't access
-      }
-    }
-
-    // Skip files with no size info
-    if (!fileSize || fileSize <= 0) {
-      return null
-    }
-
-    const contentType = getContentType(entry.name)
-    let hash = null
-
-    // Only calculate hash for small files in parent directory
-    if (fileSize < FILE_LIMITS.MAX_HASH_SIZE && parentDirSelected) {
-      try {
-        hash = await calculateFileHash(fullPath, fileSize, readFile)
-      } catch {
-        // Skip hash calculation if file is not accessible
-      }
-    }
+  // Convert a previewed file from the backend to a list item
+  const toSelectedFile = (file) => {
+    const segments = file.name.split('/')
+    const displayName = segments.pop()
 
     return {
-      name: entry.name,
-      displayName: entry.name,
-      subdirectoryName: '',
-      path: fullPath,
-      size: fileSize,
-      sizeText: formatFileSize(fileSize),
-      isSubdirectoryFile: !parentDirSelected,
-      contentType,
-      hash
+      name: file.name,
+      displayName,
+      subdirectoryName: segments.join('/'),
+      path: file.path,
+      size: file.size,
+      sizeText: formatFileSize(file.size),
+      isSubdirectoryFile: segments.length > 0,
+      contentType: getContentType(displayName)
     }
   }
 
-  // Process files in a subdirectory
-  const processSubdirectory = async (parentDir, dirEntry) => {
-    const subDirPath = `${parentDir}/${dirEntry.name}`
-
-    try {
-      const subEntries = await readDir(subDirPath)
-
-      for (const subEntry of subEntries) {
-        // Skip hidden files and nested directories
-        if (subEntry.name.startsWith('.') || subEntry.isDirectory) continue
-
-        const file = await processFileEntry(subDirPath, subEntry, false)
-        if (file) {
-          file.name = `${dirEntry.name}/${subEntry.name}`
-          file.subdirectoryName = dirEntry.name
-          file.displayName = subEntry.name
-          selectedFiles.value.push(file)
-        }
-      }
-    } catch {
-      // Silently skip subdirectories we can't read
-    }
-  }
-
-  // Process the selected directory and its subdirectories
+  // Walk the selected directory in the backend with the current rules
   const processSelectedDirectory = async (selectedDir) => {
     selectedFiles.value = []
+    skippedFiles.value = []
+    removedFiles.value = []
     selectedDirectoryPath.value = getDirectoryDisplayName(selectedDir)
     selectedDirectoryFullPath.value = selectedDir
 
+    await refreshPreview()
+  }
+
+  // Re-run the preview, e.g. after the import rules changed
+  const refreshPreview = async () => {
+    if (!selectedDirectoryFullPath.value) return
+
+    previewError.value = ''
     try {
-      const entries = await readDir(selectedDir)
-
-      for (const entry of entries) {
-        // Skip hidden files/folders
-        if (entry.name.startsWith('.')) continue
-
-        if (entry.isDirectory) {
-          // Process subdirectory (1 level deep)
-          await processSubdirectory(selectedDir, entry)
-        } else {
-          // Process file in main directory
-          const file = await processFileEntry(selectedDir, entry, true)
-          if (file) {
-            selectedFiles.value.push(file)
-          }
-        }
-      }
+      const preview = await invoke('preview_directory_import', {
+        path: selectedDirectoryFullPath.value,
+        rules: importRules.value
+      })
 
-      // Sort files alphabetically
-      selectedFiles.value.sort((a, b) => a.name.localeCompare(b.name))
-    } catch {
-      // Failed to read directory - likely a permissions issue
+      selectedFiles.value = preview.files
+        .filter((file) => !removedFiles.value.includes(file.name))
+        .map(toSelectedFile)
+      skippedFiles.value = preview.skipped
+      truncated.value = preview.truncated
+    } catch (e) {
+      // Invalid patterns or an unreadable directory
+      previewError.value = String(e)
+      selectedFiles.value = []
+      skippedFiles.value = []
     }
   }
 
@@ -166,7 +121,10 @@ export function useFileSelection() {
 
   // Remove file from list
   const removeFile = (index) => {
-    selectedFiles.value.splice(index, 1)
+    const [file] = selectedFiles.value.splice(index, 1)
+    if (file) {
+      removedFiles.value.push(file.name)
+    }
   }
 
   // Clear all selections
@@ -174,6 +132,11 @@ export function useFileSelection() {
     selectedFiles.value = []
     selectedDirectoryPath.value = ''
     selectedDirectoryFullPath.value = ''
+    importRules.value = { includePatterns: '', excludePatterns: '' }
+    skippedFiles.value = []
+    removedFiles.value = []
+    truncated.value = false
+    previewError.value = ''
   }
 
   return {
@@ -182,8 +145,14 @@ export function useFileSelection() {
     isSelectingFile,
     selectedDirectoryPath,
     selectedDirectoryFullPath,
+    importRules,
+    skippedFiles,
+    removedFiles,
+    truncated,
+    previewError,
     // Methods
     openFileSelector,
+    refreshPreview,
     removeFile,
     clearSelection
   }
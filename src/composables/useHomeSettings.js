@@ -1,7 +1,7 @@
 import { computed, markRaw, ref } from 'vue'
 import { useRouter } from 'vue-router'
 import { useI18n } from 'vue-i18n'
-import { ThemeIcon, GlobeIcon, KeyIcon, DownloadIcon } from '@/utils/icons.js'
+import { ThemeIcon, GlobeIcon, KeyIcon, DownloadIcon, ServerIcon, PlugIcon, DatabaseIcon, TrashIcon, LayersIcon } from '@/utils/icons.js'
 import { highlightSearchMatch } from '@/utils/helpers.js'
 import { useAppStore } from '@/stores/app'
 import { useUpdater } from '@/composables/useUpdater'
@@ -26,7 +26,12 @@ export function useHomeSettings() {
     { id: 1, title: t('settings.changeTheme'), icon: markRaw(ThemeIcon), action: 'changeTheme' },
     { id: 2, title: t('settings.language'), icon: markRaw(GlobeIcon), action: 'changeLanguage' },
     { id: 3, title: t('settings.apiKey'), icon: markRaw(KeyIcon), action: 'configureApiKey' },
-    { id: 4, title: t('settings.checkUpdate'), icon: markRaw(DownloadIcon), action: 'checkUpdate' }
+    { id: 4, title: t('settings.localBackend'), icon: markRaw(ServerIcon), action: 'configureLocalBackend' },
+    { id: 5, title: t('settings.httpApi'), icon: markRaw(PlugIcon), action: 'configureHttpApi' },
+    { id: 6, title: t('settings.backups'), icon: markRaw(DatabaseIcon), action: 'configureBackups' },
+    { id: 7, title: t('settings.trash'), icon: markRaw(TrashIcon), action: 'openTrash' },
+    { id: 8, title: t('settings.syncStatus'), icon: markRaw(LayersIcon), action: 'openSyncStatus' },
+    { id: 9, title: t('settings.checkUpdate'), icon: markRaw(DownloadIcon), action: 'checkUpdate' }
   ])
 
   // All items (no user profile items anymore)
@@ -90,6 +95,26 @@ export function useHomeSettings() {
       router.push('/settings/api-key')
       return
     }
+    if (result.action === 'configureLocalBackend') {
+      router.push('/settings/local-backend')
+      return
+    }
+    if (result.action === 'configureHttpApi') {
+      router.push('/settings/http-api')
+      return
+    }
+    if (result.action === 'configureBackups') {
+      router.push('/settings/backups')
+      return
+    }
+    if (result.action === 'openTrash') {
+      router.push('/settings/trash')
+      return
+    }
+    if (result.action === 'openSyncStatus') {
+      router.push('/settings/sync')
+      return
+    }
     if (result.action === 'checkUpdate') {
       handleCheckUpdate()
       return
@@ -0,0 +1,95 @@
+import { ref } from 'vue'
+import { useI18n } from 'vue-i18n'
+import { invoke } from '@tauri-apps/api/core'
+import { ask, open, save } from '@tauri-apps/plugin-dialog'
+import { FILE_LIMITS } from '@/utils/constants.js'
+import { useStoresStore } from '@/stores/stores'
+
+const BUNDLE_FILTERS = [{ name: 'Mizly store bundle', extensions: ['zip'] }]
+
+/**
+ * Store bundle composable
+ * Exports stores to portable bundles and imports them back
+ *
+ * @returns {Object} Bundle status and methods
+ */
+export function useStoreBundles() {
+  const { t } = useI18n()
+  const storesStore = useStoresStore()
+
+  // Result or error of the last export/import
+  const bundleStatus = ref('')
+
+  const showStatus = (message) => {
+    bundleStatus.value = message
+    setTimeout(() => {
+      bundleStatus.value = ''
+    }, 5000)
+  }
+
+  // Keep the window open while a native dialog is shown
+  const withDialog = async (showDialog) => {
+    await invoke('set_prevent_auto_hide', { prevent: true })
+
+    try {
+      return await showDialog()
+    } finally {
+      setTimeout(async () => {
+        try {
+          await invoke('set_prevent_auto_hide', { prevent: false })
+        } catch {
+          // Ignore error if window is already closed
+        }
+      }, FILE_LIMITS.DIALOG_CLOSE_DELAY)
+    }
+  }
+
+  const exportStore = async (item) => {
+    try {
+      const path = await withDialog(() =>
+        save({ defaultPath: `${item.title}.zip`, filters: BUNDLE_FILTERS })
+      )
+      if (!path) return null
+
+      const includeFiles = await withDialog(() =>
+        ask(t('bundles.includeFilesHint'), {
+          title: t('bundles.includeFiles'),
+          okLabel: t('bundles.withFiles'),
+          cancelLabel: t('bundles.listOnly')
+        })
+      )
+
+      const result = await invoke('export_store', { id: item.id, path, includeFiles })
+      showStatus(t('bundles.exported', { count: result.documents, missing: result.missing.length }))
+      return result
+    } catch (e) {
+      showStatus(t('bundles.failed', { message: String(e) }))
+      return null
+    }
+  }
+
+  const importStore = async () => {
+    try {
+      const path = await withDialog(() => open({ multiple: false, filters: BUNDLE_FILTERS }))
+      if (!path) return null
+
+      const result = await invoke('import_store', { path })
+      await storesStore.loadStores()
+      showStatus(t('bundles.imported', {
+        title: result.store.title,
+        count: result.documents.length,
+        rejected: result.rejected.length
+      }))
+      return result
+    } catch (e) {
+      showStatus(t('bundles.failed', { message: String(e) }))
+      return null
+    }
+  }
+
+  return {
+    bundleStatus,
+    exportStore,
+    importStore
+  }
+}
@@ -41,19 +41,29 @@ export function useStoreDetail() {
       return
     }
 
-    // Load documents
-    documents.value = await storesStore.loadStoreDocuments(storeId)
+    // Load the first page of documents, filtered by the search query
+    documents.value = await storesStore.setDocumentQuery(storeId, {
+      name: appStore.searchQuery || null
+    })
   }
 
-  // Filtered documents based on search query
-  const filteredDocuments = computed(() => {
-    if (!appStore.searchQuery) return documents.value
+  // Documents are filtered by name in the backend, page by page
+  const filteredDocuments = computed(() => documents.value)
 
-    const query = appStore.searchQuery.toLowerCase()
-    return documents.value.filter((doc) =>
-      doc.name.toLowerCase().includes(query) || doc.path.toLowerCase().includes(query)
-    )
-  })
+  // Sort, filters, total and counts per status of the listing
+  const listing = computed(() =>
+    selectedStore.value ? storesStore.documentListings[selectedStore.value.id] : null
+  )
+  const hasMoreDocuments = computed(() => Boolean(listing.value?.nextCursor))
+
+  // Change the sort or status filter and reload the first page
+  const setDocumentQuery = async (query) => {
+    documents.value = await storesStore.setDocumentQuery(selectedStore.value.id, query)
+  }
+
+  const loadMoreDocuments = async () => {
+    documents.value = await storesStore.loadMoreStoreDocuments(selectedStore.value.id)
+  }
 
   // Open directory in Finder
   const openDirectory = async () => {
@@ -88,6 +98,32 @@ export function useStoreDetail() {
     }
   }
 
+  // Run a batch command (delete_documents, retry_documents, ...) and reload the documents
+  const runBatch = async (command, ids, args = {}) => {
+    const report = await invoke(command, { ids, ...args })
+    documents.value = await storesStore.loadStoreDocuments(selectedStore.value.id)
+    return report
+  }
+
+  // Stores documents can be moved or copied to
+  const targetStores = computed(() =>
+    storesStore.stores.filter((s) => s.id !== selectedStore.value?.id)
+  )
+
+  // Move or copy a document, returning the documents that were rejected
+  const transferDocument = async (doc, mode, targetStoreId) => {
+    const result = await storesStore.transferDocuments(mode, [doc.id], targetStoreId)
+    return result.rejected
+  }
+
+  // Activity of a document, oldest first
+  const getDocumentTimeline = (doc) => invoke('get_document_timeline', { id: doc.id })
+
+  // Open the form to edit the store
+  const editStore = () => {
+    router.push({ name: 'stores-edit', params: { id: selectedStore.value.id } })
+  }
+
   // Navigate back to stores list
   const goBack = () => {
     appStore.clearSearchQuery()
@@ -104,6 +140,16 @@ export function useStoreDetail() {
     storesStore.cleanupStatusListener()
   })
 
+  // Follow reloads of the store's documents, e.g. when a move finishes
+  watch(() => storesStore.storeDocuments[selectedStore.value?.id], (docs) => {
+    if (docs) documents.value = docs
+  })
+
+  // Search by name as the query changes
+  watch(() => appStore.searchQuery, (query) => {
+    if (selectedStore.value) setDocumentQuery({ name: query || null })
+  })
+
   // Reload when route changes
   watch(() => route.params.id, () => {
     loadStoreData()
@@ -114,12 +160,21 @@ export function useStoreDetail() {
     documents,
     selectedStore,
     filteredDocuments,
+    targetStores,
+    listing,
+    hasMoreDocuments,
     searchQuery: computed(() => appStore.searchQuery),
     // Methods
     loadStoreData,
+    setDocumentQuery,
+    loadMoreDocuments,
     openDirectory,
     openFile,
     deleteDocument,
+    runBatch,
+    transferDocument,
+    getDocumentTimeline,
+    editStore,
     goBack
   }
 }
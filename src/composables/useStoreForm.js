@@ -13,6 +13,10 @@ import { useStoresStore } from '@/stores/stores'
  * @param {Object} options - Configuration options
  * @param {import('vue').Ref<Array>} options.selectedFiles - Selected files ref
  * @param {import('vue').Ref<string>} options.selectedDirectoryFullPath - Full path of selected directory
+ * @param {import('vue').Ref<Object>} options.importRules - Include/exclude patterns for the directory import
+ * @param {import('vue').Ref<Array>} options.removedFiles - Relative paths of files removed from the list
+ * @param {import('vue').Ref<string>} options.backend - Search backend to create the store in
+ * @param {import('vue').Ref<boolean>} options.localOnly - Never upload the store's documents
  * @param {Function} options.clearSelection - Function to clear file selection
  * @returns {Object} Form state and methods
  */
@@ -20,6 +24,10 @@ export function useStoreForm(options = {}) {
   const {
     selectedFiles,
     selectedDirectoryFullPath,
+    importRules,
+    removedFiles,
+    backend,
+    localOnly,
     clearSelection
   } = options
 
@@ -71,6 +79,22 @@ export function useStoreForm(options = {}) {
     return isValid
   }
 
+  // Escape glob characters so a path is matched literally
+  const escapePattern = (path) => path.replace(/[\\*?[\]!#]/g, '\\$&')
+
+  // Saved rules, with files removed from the list excluded by their exact path
+  const buildImportRules = () => {
+    const removed = (removedFiles?.value || []).map((name) => `/${escapePattern(name)}`)
+    const excludePatterns = [importRules?.value.excludePatterns, ...removed]
+      .filter(Boolean)
+      .join('\n')
+
+    return {
+      includePatterns: importRules?.value.includePatterns || null,
+      excludePatterns: excludePatterns || null
+    }
+  }
+
   // Handle store creation
   const createStore = async () => {
     if (!validate()) {
@@ -83,6 +107,9 @@ export function useStoreForm(options = {}) {
     const storeData = {
       name,
       directoryPath: selectedDirectoryFullPath.value,
+      backend: backend?.value,
+      localOnly: localOnly?.value || false,
+      importRules: buildImportRules(),
       files: selectedFiles.value.map(file => ({
         filename: file.name.split('/').pop(), // Get actual filename without subdirectory path
         displayName: file.name, // Keep full relative path for display
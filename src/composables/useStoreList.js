@@ -1,10 +1,11 @@
 import { computed, markRaw, onMounted, onUnmounted } from 'vue'
 import { useRouter } from 'vue-router'
 import { useI18n } from 'vue-i18n'
-import { FolderPlusIcon } from '@/utils/icons.js'
+import { FolderOpenIcon, FolderPlusIcon } from '@/utils/icons.js'
 import { highlightSearchMatch } from '@/utils/helpers.js'
 import { useAppStore } from '@/stores/app'
 import { useStoresStore } from '@/stores/stores'
+import { useStoreBundles } from '@/composables/useStoreBundles'
 
 /**
  * Store list composable
@@ -17,6 +18,7 @@ export function useStoreList() {
   const router = useRouter()
   const appStore = useAppStore()
   const storesStore = useStoresStore()
+  const { bundleStatus, exportStore, importStore } = useStoreBundles()
 
   // "New Store" button item
   const newStoreButton = computed(() => ({
@@ -26,6 +28,14 @@ export function useStoreList() {
     action: 'create'
   }))
 
+  // "Import Store" button item
+  const importStoreButton = computed(() => ({
+    id: 'import-store',
+    title: t('stores.importStore'),
+    icon: markRaw(FolderOpenIcon),
+    action: 'import'
+  }))
+
   // Filtered stores based on search query
   const filteredStores = computed(() => {
     if (!appStore.searchQuery) return storesStore.stores || []
@@ -36,9 +46,9 @@ export function useStoreList() {
     )
   })
 
-  // Display items = filtered stores + new store button
+  // Display items = filtered stores + new and import store buttons
   const displayItems = computed(() => {
-    return [...filteredStores.value, newStoreButton.value]
+    return [...filteredStores.value, newStoreButton.value, importStoreButton.value]
   })
 
   // Navigate to store or create new
@@ -47,6 +57,10 @@ export function useStoreList() {
       router.push('/stores/new')
       return
     }
+    if (item.action === 'import') {
+      importStore()
+      return
+    }
     router.push(`/stores/${item.id}`)
   }
 
@@ -60,7 +74,7 @@ export function useStoreList() {
 
   // Delete a store
   const deleteStore = async (item) => {
-    if (item.action === 'create') return false
+    if (item.action) return false
     await storesStore.deleteStore(item)
     return true
   }
@@ -70,7 +84,7 @@ export function useStoreList() {
     const items = displayItems.value
     if (items.length > 0 && index >= 0 && index < items.length) {
       const item = items[index]
-      if (item.action !== 'create') {
+      if (!item.action) {
         await deleteStore(item)
         return true
       }
@@ -100,11 +114,15 @@ export function useStoreList() {
     // State
     displayItems,
     searchQuery: computed(() => appStore.searchQuery),
+    bundleStatus,
+    rejectedFiles: computed(() => storesStore.rejectedFiles),
     // Methods
     selectItem,
     handleSelectAtIndex,
     deleteStore,
     handleDeleteAtIndex,
+    exportStore,
+    clearRejectedFiles: storesStore.clearRejectedFiles,
     createNew,
     highlightMatch,
     // App store methods for tab navigation
@@ -22,6 +22,12 @@ const routes = [
     component: () => import('@/views/stores/show.vue'),
     props: true
   },
+  {
+    path: '/stores/:id/edit',
+    name: 'stores-edit',
+    component: () => import('@/views/stores/edit.vue'),
+    props: true
+  },
   {
     path: '/chats',
     name: 'chats',
@@ -36,6 +42,31 @@ const routes = [
     path: '/settings/api-key',
     name: 'settings-api-key',
     component: () => import('@/views/settings/api-key/index.vue')
+  },
+  {
+    path: '/settings/local-backend',
+    name: 'settings-local-backend',
+    component: () => import('@/views/settings/local-backend/index.vue')
+  },
+  {
+    path: '/settings/http-api',
+    name: 'settings-http-api',
+    component: () => import('@/views/settings/http-api/index.vue')
+  },
+  {
+    path: '/settings/backups',
+    name: 'settings-backups',
+    component: () => import('@/views/settings/backups/index.vue')
+  },
+  {
+    path: '/settings/trash',
+    name: 'settings-trash',
+    component: () => import('@/views/settings/trash/index.vue')
+  },
+  {
+    path: '/settings/sync',
+    name: 'settings-sync',
+    component: () => import('@/views/settings/sync/index.vue')
   }
 ]
 
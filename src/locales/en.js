@@ -43,6 +43,11 @@ export default {
     changeTheme: 'Change Theme',
     language: 'Language',
     apiKey: 'API Key',
+    localBackend: 'Local AI Server',
+    httpApi: 'Local HTTP API',
+    backups: 'Database Backups',
+    trash: 'Trash',
+    syncStatus: 'Sync Status',
     checkUpdate: 'Check for Updates'
   },
 
@@ -76,6 +81,115 @@ export default {
     cleared: 'API key cleared'
   },
 
+  // Local AI server settings
+  localBackend: {
+    title: 'LOCAL SERVER',
+    searchPlaceholder: 'Configure local AI server...',
+    heading: 'OpenAI-compatible server',
+    hint: 'Used by stores in the local index, e.g. with Ollama or llama.cpp. Documents are chunked and embedded on this machine.',
+    baseUrl: 'Base URL',
+    chatModel: 'Chat model',
+    embeddingModel: 'Embedding model',
+    apiKey: 'API key (optional)',
+    save: 'Save',
+    saved: 'Settings saved',
+    retrievalTitle: 'Local index',
+    embeddingProvider: 'Embeddings',
+    generationProvider: 'Answers',
+    providerLocal: 'Local server',
+    providerGemini: 'Gemini',
+    geminiEmbeddingModel: 'Gemini embedding model',
+    topK: 'Retrieved chunks',
+    chunkChars: 'Chunk size (characters)',
+    chunkOverlapChars: 'Chunk overlap (characters)',
+    retrievalHint: 'Changing the embedding model only affects documents indexed afterwards; re-upload documents to re-index them.'
+  },
+
+  // Local HTTP API settings
+  httpApi: {
+    title: 'HTTP API',
+    searchPlaceholder: 'Configure local HTTP API...',
+    heading: 'Local HTTP API',
+    hint: 'Lets editors and scripts on this machine list stores, add documents and ask questions. Only reachable from 127.0.0.1.',
+    enabled: 'Enable HTTP API',
+    port: 'Port',
+    token: 'Token',
+    tokenHint: 'Send it as "Authorization: Bearer <token>". Anyone with the token can read your stores.',
+    copy: 'Copy',
+    copied: 'Copied',
+    regenerate: 'Regenerate',
+    regenerated: 'New token generated, the old one no longer works',
+    example: 'Example',
+    save: 'Save',
+    saved: 'Settings saved'
+  },
+
+  // Database backups
+  backups: {
+    title: 'Backups',
+    searchPlaceholder: 'Manage database backups...',
+    heading: 'Database Backups',
+    hint: 'Copies of the stores database, taken on a schedule and before every restore. Kept in the backups folder of the app data directory.',
+    intervalHours: 'Hours between backups (0 turns scheduled backups off)',
+    keep: 'Backups to keep',
+    save: 'Save',
+    saved: 'Settings saved',
+    backUpNow: 'Back Up Now',
+    created: 'Backup created',
+    noBackups: 'No backups yet',
+    restore: 'Restore',
+    confirmRestore: 'Replace all stores and documents with this backup? The current state is backed up first.',
+    restored: 'Restored {name}. The previous state was saved as {previous}.'
+  },
+
+  // Trash
+  trash: {
+    title: 'Trash',
+    searchPlaceholder: 'Restore deleted stores and documents...',
+    heading: 'Trash',
+    hint: 'Deleted stores and documents are kept for the grace period and can be restored until then. Afterwards they are also deleted from their search backend.',
+    retentionDays: 'Days to keep deleted items (0 deletes them right away)',
+    save: 'Save',
+    saved: 'Settings saved',
+    stores: 'Stores',
+    documents: 'Documents',
+    empty: 'The trash is empty',
+    restore: 'Restore',
+    restored: 'Restored {name}',
+    documentCount: '{count} documents',
+    purgeAt: 'deleted for good {date}'
+  },
+
+  // Sync status
+  sync: {
+    title: 'Sync',
+    searchPlaceholder: 'Uploads, processing and deletions...',
+    heading: 'Sync status',
+    running: 'Syncing from this app',
+    elsewhere: 'Another Mizly process (e.g. the CLI) is syncing',
+    stopped: 'Sync is not running',
+    paused: 'Paused, nothing is uploaded until you resume',
+    offline: 'Offline, Gemini sync resumes once the connection is back. Local stores keep syncing',
+    pause: 'Pause',
+    resume: 'Resume',
+    queue: 'Queue',
+    stages: {
+      storesPending: 'Stores to create',
+      uploadsPending: 'Uploads waiting',
+      uploadsInFlight: 'Uploads in progress',
+      operationsPolling: 'Processing by the backend',
+      deletionsPending: 'Deletions waiting'
+    },
+    activity: 'Activity',
+    lastCycle: 'Last cycle',
+    lastCycleAt: '{date} ({ms} ms)',
+    nextWakeup: 'Next check',
+    onResume: 'When resumed',
+    lastError: 'Last error',
+    noError: 'None',
+    never: 'Not yet'
+  },
+
   // Stores
   stores: {
     title: 'Stores',
@@ -85,7 +199,96 @@ export default {
     enterName: 'Enter store name',
     noStores: 'No stores found. Create one!',
     noFiles: 'No files in this store',
-    addFilesHint: 'Add files to organize them in this store'
+    addFilesHint: 'Add files to organize them in this store',
+    importStore: 'Import Store',
+    export: 'Export',
+    edit: 'Edit',
+    rejectedFiles: '{count} files were not added',
+    rejectedFile: '{name}: {message}',
+    dismiss: 'Dismiss'
+  },
+
+  // Store editing
+  storeEdit: {
+    searchPlaceholder: 'Edit store...',
+    title: 'Title',
+    titleHint: 'Search backends keep the name the store was created with where they can\'t rename it.',
+    directory: 'Folder',
+    chooseFolder: 'Choose...',
+    description: 'Description',
+    color: 'Color',
+    noColor: 'No color',
+    icon: 'Icon',
+    iconPlaceholder: 'e.g. 🦊',
+    save: 'Save'
+  },
+
+  // Moving and copying documents between stores
+  transfers: {
+    move: 'Move',
+    copy: 'Copy',
+    target: 'To store',
+    noTargets: 'Create another store first',
+    moving: 'Moving to {store}, the file leaves this store once it is uploaded there',
+    copied: 'Copied to {store}',
+    rejected: '{name}: {message}'
+  },
+
+  // Actions on several documents at once
+  batch: {
+    selected: '{count} selected',
+    selectAll: 'Select all',
+    clear: 'Clear',
+    retry: 'Retry',
+    reupload: 'Re-upload',
+    delete: 'Delete',
+    tagPlaceholder: 'tag, tag...',
+    addTag: 'Add tags',
+    removeTag: 'Remove tags',
+    done: '{succeeded} done, {failed} skipped'
+  },
+
+  // Document listing
+  listing: {
+    sort: {
+      created: 'Date added',
+      name: 'Name',
+      size: 'Size',
+      status: 'Status'
+    },
+    ascending: 'Ascending',
+    descending: 'Descending',
+    all: 'All',
+    status: '{status} ({count})',
+    shown: '{count} of {total}',
+    loadMore: 'Load more'
+  },
+
+  // Document activity
+  activity: {
+    history: 'History',
+    empty: 'No activity recorded',
+    kinds: {
+      queued: 'Queued',
+      upload_started: 'Upload started',
+      upload_finished: 'Uploaded',
+      operation_done: 'Ready',
+      failed: 'Failed',
+      deleted: 'Deleted',
+      restored: 'Restored',
+      purged: 'Purged'
+    }
+  },
+
+  // Store bundles
+  bundles: {
+    includeFiles: 'Include files?',
+    includeFilesHint: 'Add the document files to the bundle so it works on machines without the original folder. Otherwise only the store definition and document list are exported.',
+    withFiles: 'With files',
+    listOnly: 'List only',
+    exported: 'Exported {count} documents ({missing} unreadable)',
+    imported: 'Imported {title}: {count} documents queued, {rejected} not found',
+    failed: 'Bundle failed: {message}'
   },
 
   // Files
@@ -94,7 +297,16 @@ export default {
     clickToChange: 'Click to change folder',
     noFilesSelected: 'No Files Selected',
     selectFolderHint: 'Press ⌘O or click "Select Folder" to choose a directory',
-    subdirectoryInfo: 'Files from subdirectories (1 level deep) will be included',
+    subdirectoryInfo: 'Files from all subdirectories will be included, except hidden and system files',
+    includePatterns: 'Include (globs, one per line)',
+    excludePatterns: 'Exclude (.gitignore syntax)',
+    importSummary: '{count} files · {size} · {skipped} skipped',
+    importTruncated: 'Only the first 5000 files will be imported',
+    backend: 'Backend',
+    backendGemini: 'Gemini File Search',
+    backendLocal: 'Local index',
+    localOnly: 'Local only',
+    localOnlyHint: 'Index and answer on this machine only; documents are never uploaded',
     noSizeInfo: 'No size info'
   },
 
@@ -114,7 +326,11 @@ export default {
     typeQuestion: 'Type your question and press Enter to send',
     sources: 'Sources',
     somethingWentWrong: 'Something went wrong',
-    suggestQuestions: 'Suggested Questions'
+    suggestQuestions: 'Suggested Questions',
+    localResults: 'Matching Documents',
+    export: 'Export',
+    exported: 'Saved to {path}',
+    exportFailed: 'Export failed: {message}'
   },
 
   // Empty states
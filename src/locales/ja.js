@@ -43,6 +43,11 @@ export default {
     changeTheme: 'ダークモード',
     language: '言語を変更',
     apiKey: 'APIキー',
+    localBackend: 'ローカル AI サーバー',
+    httpApi: 'ローカル HTTP API',
+    backups: 'データベースのバックアップ',
+    trash: 'ゴミ箱',
+    syncStatus: '同期ステータス',
     checkUpdate: 'アップデートを確認'
   },
 
@@ -76,6 +81,115 @@ export default {
     cleared: 'APIキーをクリアしました'
   },
 
+  // Local AI server settings
+  localBackend: {
+    title: 'ローカルサーバー',
+    searchPlaceholder: 'ローカル AI サーバーを設定...',
+    heading: 'OpenAI 互換サーバー',
+    hint: 'ローカルインデックスのストアで使用されます（Ollama、llama.cpp など）。ドキュメントはこのマシン上で分割・埋め込みされます。',
+    baseUrl: 'ベース URL',
+    chatModel: 'チャットモデル',
+    embeddingModel: '埋め込みモデル',
+    apiKey: 'API キー（任意）',
+    save: '保存',
+    saved: '設定を保存しました',
+    retrievalTitle: 'ローカルインデックス',
+    embeddingProvider: '埋め込み',
+    generationProvider: '回答',
+    providerLocal: 'ローカルサーバー',
+    providerGemini: 'Gemini',
+    geminiEmbeddingModel: 'Gemini 埋め込みモデル',
+    topK: '取得するチャンク数',
+    chunkChars: 'チャンクサイズ（文字）',
+    chunkOverlapChars: 'チャンクの重なり（文字）',
+    retrievalHint: '埋め込みモデルの変更は、その後にインデックス化されたドキュメントにのみ適用されます。再インデックスするにはドキュメントを再アップロードしてください。'
+  },
+
+  // Local HTTP API settings
+  httpApi: {
+    title: 'HTTP API',
+    searchPlaceholder: 'ローカル HTTP API を設定...',
+    heading: 'ローカル HTTP API',
+    hint: 'このマシン上のエディタやスクリプトから、ストアの一覧、ドキュメントの追加、質問ができます。127.0.0.1 からのみ接続できます。',
+    enabled: 'HTTP API を有効にする',
+    port: 'ポート',
+    token: 'トークン',
+    tokenHint: '"Authorization: Bearer <token>" として送信してください。トークンを持つ人は誰でもストアを読み取れます。',
+    copy: 'コピー',
+    copied: 'コピーしました',
+    regenerate: '再生成',
+    regenerated: '新しいトークンを生成しました。古いトークンは使用できません',
+    example: '例',
+    save: '保存',
+    saved: '設定を保存しました'
+  },
+
+  // Database backups
+  backups: {
+    title: 'バックアップ',
+    searchPlaceholder: 'データベースのバックアップを管理...',
+    heading: 'データベースのバックアップ',
+    hint: 'ストアのデータベースのコピーです。定期的に、また復元の前に作成されます。アプリのデータディレクトリの backups フォルダに保存されます。',
+    intervalHours: 'バックアップの間隔（時間、0 で定期バックアップを無効化）',
+    keep: '保持するバックアップ数',
+    save: '保存',
+    saved: '設定を保存しました',
+    backUpNow: '今すぐバックアップ',
+    created: 'バックアップを作成しました',
+    noBackups: 'バックアップはまだありません',
+    restore: '復元',
+    confirmRestore: 'すべてのストアとドキュメントをこのバックアップで置き換えますか？現在の状態は先にバックアップされます。',
+    restored: '{name} を復元しました。以前の状態は {previous} として保存されています。'
+  },
+
+  // Trash
+  trash: {
+    title: 'ゴミ箱',
+    searchPlaceholder: '削除したストアとドキュメントを復元...',
+    heading: 'ゴミ箱',
+    hint: '削除したストアとドキュメントは猶予期間中は保持され、復元できます。期間が過ぎると検索バックエンドからも削除されます。',
+    retentionDays: '削除した項目を保持する日数（0 ですぐに削除）',
+    save: '保存',
+    saved: '設定を保存しました',
+    stores: 'ストア',
+    documents: 'ドキュメント',
+    empty: 'ゴミ箱は空です',
+    restore: '復元',
+    restored: '{name} を復元しました',
+    documentCount: '{count} 件のドキュメント',
+    purgeAt: '{date} に完全に削除'
+  },
+
+  // Sync status
+  sync: {
+    title: '同期',
+    searchPlaceholder: 'アップロード、処理、削除...',
+    heading: '同期ステータス',
+    running: 'このアプリで同期中',
+    elsewhere: '別の Mizly プロセス（CLI など）が同期中',
+    stopped: '同期は実行されていません',
+    paused: '一時停止中です。再開するまでアップロードされません',
+    offline: 'オフラインです。接続が戻るとGeminiの同期を再開します。ローカルストアは同期を続けます',
+    pause: '一時停止',
+    resume: '再開',
+    queue: 'キュー',
+    stages: {
+      storesPending: '作成待ちのストア',
+      uploadsPending: 'アップロード待ち',
+      uploadsInFlight: 'アップロード中',
+      operationsPolling: 'バックエンドで処理中',
+      deletionsPending: '削除待ち'
+    },
+    activity: 'アクティビティ',
+    lastCycle: '前回の同期',
+    lastCycleAt: '{date}（{ms} ms）',
+    nextWakeup: '次回のチェック',
+    onResume: '再開時',
+    lastError: '最後のエラー',
+    noError: 'なし',
+    never: 'まだありません'
+  },
+
   // Stores
   stores: {
     title: 'ストア',
@@ -85,7 +199,96 @@ export default {
     enterName: 'ストア名を入力',
     noStores: 'ストアがありません。作成してください！',
     noFiles: 'このストアにファイルがありません',
-    addFilesHint: 'ファイルを追加して整理しましょう'
+    addFilesHint: 'ファイルを追加して整理しましょう',
+    importStore: 'ストアをインポート',
+    export: 'エクスポート',
+    edit: '編集',
+    rejectedFiles: '{count} 件のファイルを追加できませんでした',
+    rejectedFile: '{name}: {message}',
+    dismiss: '閉じる'
+  },
+
+  // Store editing
+  storeEdit: {
+    searchPlaceholder: 'ストアを編集...',
+    title: 'タイトル',
+    titleHint: '名前を変更できない検索バックエンドでは、作成時の名前のままになります。',
+    directory: 'フォルダ',
+    chooseFolder: '選択...',
+    description: '説明',
+    color: '色',
+    noColor: '色なし',
+    icon: 'アイコン',
+    iconPlaceholder: '例: 🦊',
+    save: '保存'
+  },
+
+  // Moving and copying documents between stores
+  transfers: {
+    move: '移動',
+    copy: 'コピー',
+    target: '対象ストア',
+    noTargets: '先に別のストアを作成してください',
+    moving: '{store} に移動中です。アップロード完了後にこのストアから削除されます',
+    copied: '{store} にコピーしました',
+    rejected: '{name}: {message}'
+  },
+
+  // Actions on several documents at once
+  batch: {
+    selected: '{count} 件選択中',
+    selectAll: 'すべて選択',
+    clear: '選択解除',
+    retry: '再試行',
+    reupload: '再アップロード',
+    delete: '削除',
+    tagPlaceholder: 'タグ, タグ...',
+    addTag: 'タグを追加',
+    removeTag: 'タグを削除',
+    done: '{succeeded} 件完了、{failed} 件スキップ'
+  },
+
+  // Document listing
+  listing: {
+    sort: {
+      created: '追加日',
+      name: '名前',
+      size: 'サイズ',
+      status: 'ステータス'
+    },
+    ascending: '昇順',
+    descending: '降順',
+    all: 'すべて',
+    status: '{status} ({count})',
+    shown: '{total} 件中 {count} 件',
+    loadMore: 'さらに読み込む'
+  },
+
+  // Document activity
+  activity: {
+    history: '履歴',
+    empty: '記録されたアクティビティはありません',
+    kinds: {
+      queued: 'キューに追加',
+      upload_started: 'アップロード開始',
+      upload_finished: 'アップロード完了',
+      operation_done: '準備完了',
+      failed: '失敗',
+      deleted: '削除',
+      restored: '復元',
+      purged: '完全に削除'
+    }
+  },
+
+  // Store bundles
+  bundles: {
+    includeFiles: 'ファイルを含めますか？',
+    includeFilesHint: 'ドキュメントファイルをバンドルに含めると、元のフォルダがないマシンでも使えます。含めない場合は、ストアの定義とドキュメント一覧のみをエクスポートします。',
+    withFiles: 'ファイルを含める',
+    listOnly: '一覧のみ',
+    exported: '{count} 件のドキュメントをエクスポートしました（読み取れないもの {missing} 件）',
+    imported: '{title} をインポートしました：{count} 件をキューに追加、{rejected} 件が見つかりません',
+    failed: 'バンドルの処理に失敗しました：{message}'
   },
 
   // Files
@@ -94,7 +297,16 @@ export default {
     clickToChange: 'クリックしてフォルダを変更',
     noFilesSelected: 'ファイルが選択されていません',
     selectFolderHint: '⌘O を押すか「フォルダを選択」をクリックしてディレクトリを選択',
-    subdirectoryInfo: 'サブディレクトリ（1階層）のファイルも含まれます',
+    subdirectoryInfo: '隠しファイルとシステムファイルを除く、すべてのサブディレクトリのファイルが含まれます',
+    includePatterns: '含める（glob、1行に1つ）',
+    excludePatterns: '除外（.gitignore 形式）',
+    importSummary: '{count} ファイル · {size} · {skipped} 件スキップ',
+    importTruncated: '最初の 5000 ファイルのみインポートされます',
+    backend: 'バックエンド',
+    backendGemini: 'Gemini File Search',
+    backendLocal: 'ローカルインデックス',
+    localOnly: 'ローカルのみ',
+    localOnlyHint: 'このマシン上でのみインデックス化・回答し、ドキュメントはアップロードされません',
     noSizeInfo: 'サイズ情報なし'
   },
 
@@ -114,7 +326,11 @@ export default {
     typeQuestion: '質問を入力してEnterで送信',
     sources: '出典',
     somethingWentWrong: 'エラーが発生しました',
-    suggestQuestions: 'おすすめの質問'
+    suggestQuestions: 'おすすめの質問',
+    localResults: '一致するドキュメント',
+    export: 'エクスポート',
+    exported: '{path} に保存しました',
+    exportFailed: 'エクスポートに失敗しました：{message}'
   },
 
   // Empty states
@@ -43,6 +43,11 @@ export default {
     changeTheme: '深色模式',
     language: '切換語言',
     apiKey: 'API 金鑰',
+    localBackend: '本機 AI 伺服器',
+    httpApi: '本機 HTTP API',
+    backups: '資料庫備份',
+    trash: '垃圾桶',
+    syncStatus: '同步狀態',
     checkUpdate: '檢查更新'
   },
 
@@ -76,6 +81,115 @@ export default {
     cleared: 'API 金鑰已清除'
   },
 
+  // Local AI server settings
+  localBackend: {
+    title: '本機伺服器',
+    searchPlaceholder: '設定本機 AI 伺服器...',
+    heading: 'OpenAI 相容伺服器',
+    hint: '供使用本機索引的資料庫使用，例如 Ollama 或 llama.cpp。文件會在本機切分並產生向量。',
+    baseUrl: '基礎 URL',
+    chatModel: '對話模型',
+    embeddingModel: '向量模型',
+    apiKey: 'API 金鑰（選填）',
+    save: '儲存',
+    saved: '設定已儲存',
+    retrievalTitle: '本機索引',
+    embeddingProvider: '向量',
+    generationProvider: '回答',
+    providerLocal: '本機伺服器',
+    providerGemini: 'Gemini',
+    geminiEmbeddingModel: 'Gemini 向量模型',
+    topK: '擷取的區塊數',
+    chunkChars: '區塊大小（字元）',
+    chunkOverlapChars: '區塊重疊（字元）',
+    retrievalHint: '變更向量模型只會影響之後建立索引的文件；請重新上傳文件以重新建立索引。'
+  },
+
+  // Local HTTP API settings
+  httpApi: {
+    title: 'HTTP API',
+    searchPlaceholder: '設定本機 HTTP API...',
+    heading: '本機 HTTP API',
+    hint: '讓本機上的編輯器與腳本列出知識庫、新增文件並提問。僅能從 127.0.0.1 連線。',
+    enabled: '啟用 HTTP API',
+    port: '連接埠',
+    token: '權杖',
+    tokenHint: '以 "Authorization: Bearer <token>" 傳送。持有權杖的人都能讀取你的知識庫。',
+    copy: '複製',
+    copied: '已複製',
+    regenerate: '重新產生',
+    regenerated: '已產生新權杖，舊權杖已失效',
+    example: '範例',
+    save: '儲存',
+    saved: '設定已儲存'
+  },
+
+  // Database backups
+  backups: {
+    title: '備份',
+    searchPlaceholder: '管理資料庫備份...',
+    heading: '資料庫備份',
+    hint: '資料集資料庫的副本，會定期建立，並在每次還原前建立。存放在應用程式資料目錄的 backups 資料夾中。',
+    intervalHours: '備份間隔（小時，0 表示關閉定期備份）',
+    keep: '保留的備份數量',
+    save: '儲存',
+    saved: '設定已儲存',
+    backUpNow: '立即備份',
+    created: '已建立備份',
+    noBackups: '尚無備份',
+    restore: '還原',
+    confirmRestore: '要以此備份取代所有資料集與文件嗎？系統會先備份目前的狀態。',
+    restored: '已還原 {name}。先前的狀態已儲存為 {previous}。'
+  },
+
+  // Trash
+  trash: {
+    title: '垃圾桶',
+    searchPlaceholder: '還原已刪除的資料集與文件...',
+    heading: '垃圾桶',
+    hint: '已刪除的資料集與文件會在寬限期內保留，期間內可以還原。期限過後也會從搜尋後端刪除。',
+    retentionDays: '已刪除項目的保留天數（0 表示立即刪除）',
+    save: '儲存',
+    saved: '設定已儲存',
+    stores: '資料集',
+    documents: '文件',
+    empty: '垃圾桶是空的',
+    restore: '還原',
+    restored: '已還原 {name}',
+    documentCount: '{count} 份文件',
+    purgeAt: '將於 {date} 永久刪除'
+  },
+
+  // Sync status
+  sync: {
+    title: '同步',
+    searchPlaceholder: '上傳、處理與刪除...',
+    heading: '同步狀態',
+    running: '由此應用程式同步中',
+    elsewhere: '另一個 Mizly 程序（例如 CLI）正在同步',
+    stopped: '同步未執行',
+    paused: '已暫停，繼續同步前不會上傳',
+    offline: '離線中，連線恢復後會繼續 Gemini 同步。本機資料集會持續同步',
+    pause: '暫停',
+    resume: '繼續',
+    queue: '佇列',
+    stages: {
+      storesPending: '待建立的資料集',
+      uploadsPending: '等待上傳',
+      uploadsInFlight: '上傳中',
+      operationsPolling: '後端處理中',
+      deletionsPending: '等待刪除'
+    },
+    activity: '活動',
+    lastCycle: '上次同步',
+    lastCycleAt: '{date}（{ms} ms）',
+    nextWakeup: '下次檢查',
+    onResume: '繼續同步時',
+    lastError: '最後的錯誤',
+    noError: '無',
+    never: '尚未執行'
+  },
+
   // Stores
   stores: {
     title: '資料集',
@@ -85,7 +199,96 @@ export default {
     enterName: '輸入資料集名稱',
     noStores: '尚無資料集。立即建立一個！',
     noFiles: '此資料集中沒有檔案',
-    addFilesHint: '將檔案加入此資料集以進行管理'
+    addFilesHint: '將檔案加入此資料集以進行管理',
+    importStore: '匯入資料集',
+    export: '匯出',
+    edit: '編輯',
+    rejectedFiles: '{count} 個檔案未加入',
+    rejectedFile: '{name}：{message}',
+    dismiss: '關閉'
+  },
+
+  // Store editing
+  storeEdit: {
+    searchPlaceholder: '編輯資料集...',
+    title: '標題',
+    titleHint: '無法重新命名的搜尋後端會保留資料集建立時的名稱。',
+    directory: '資料夾',
+    chooseFolder: '選擇...',
+    description: '說明',
+    color: '顏色',
+    noColor: '無顏色',
+    icon: '圖示',
+    iconPlaceholder: '例如 🦊',
+    save: '儲存'
+  },
+
+  // Moving and copying documents between stores
+  transfers: {
+    move: '移動',
+    copy: '複製',
+    target: '目標資料集',
+    noTargets: '請先建立另一個資料集',
+    moving: '正在移動到 {store}，上傳完成後會從此資料集移除',
+    copied: '已複製到 {store}',
+    rejected: '{name}: {message}'
+  },
+
+  // Actions on several documents at once
+  batch: {
+    selected: '已選取 {count} 個',
+    selectAll: '全選',
+    clear: '取消選取',
+    retry: '重試',
+    reupload: '重新上傳',
+    delete: '刪除',
+    tagPlaceholder: '標籤, 標籤...',
+    addTag: '新增標籤',
+    removeTag: '移除標籤',
+    done: '{succeeded} 個完成，{failed} 個略過'
+  },
+
+  // Document listing
+  listing: {
+    sort: {
+      created: '加入日期',
+      name: '名稱',
+      size: '大小',
+      status: '狀態'
+    },
+    ascending: '遞增',
+    descending: '遞減',
+    all: '全部',
+    status: '{status} ({count})',
+    shown: '{count} / {total}',
+    loadMore: '載入更多'
+  },
+
+  // Document activity
+  activity: {
+    history: '紀錄',
+    empty: '沒有活動紀錄',
+    kinds: {
+      queued: '已排入佇列',
+      upload_started: '開始上傳',
+      upload_finished: '已上傳',
+      operation_done: '已就緒',
+      failed: '失敗',
+      deleted: '已刪除',
+      restored: '已還原',
+      purged: '已永久刪除'
+    }
+  },
+
+  // Store bundles
+  bundles: {
+    includeFiles: '要包含檔案嗎？',
+    includeFilesHint: '將文件檔案加入封裝，即可在沒有原始資料夾的電腦上使用。否則只會匯出資料集設定與文件清單。',
+    withFiles: '包含檔案',
+    listOnly: '僅清單',
+    exported: '已匯出 {count} 份文件（{missing} 份無法讀取）',
+    imported: '已匯入 {title}：{count} 份文件已排入佇列，{rejected} 份找不到',
+    failed: '封裝處理失敗：{message}'
   },
 
   // Files
@@ -94,7 +297,16 @@ export default {
     clickToChange: '點擊以變更資料夾',
     noFilesSelected: '尚未選擇檔案',
     selectFolderHint: '按 ⌘O 或點擊「選擇資料夾」來選擇目錄',
-    subdirectoryInfo: '將包含子目錄（深度一層）中的檔案',
+    subdirectoryInfo: '將包含所有子目錄中的檔案，隱藏檔與系統檔除外',
+    includePatterns: '包含（glob，每行一個）',
+    excludePatterns: '排除（.gitignore 語法）',
+    importSummary: '{count} 個檔案 · {size} · 略過 {skipped} 個',
+    importTruncated: '僅會匯入前 5000 個檔案',
+    backend: '後端',
+    backendGemini: 'Gemini File Search',
+    backendLocal: '本機索引',
+    localOnly: '僅限本機',
+    localOnlyHint: '只在這台電腦上建立索引與回答，文件永遠不會上傳',
     noSizeInfo: '無大小資訊'
   },
 
@@ -114,7 +326,11 @@ export default {
     typeQuestion: '輸入問題並按 Enter 送出',
     sources: '來源',
     somethingWentWrong: '發生錯誤',
-    suggestQuestions: '推薦問題'
+    suggestQuestions: '推薦問題',
+    localResults: '相符的文件',
+    export: '匯出',
+    exported: '已儲存至 {path}',
+    exportFailed: '匯出失敗：{message}'
   },
 
   // Empty states
@@ -13,6 +13,9 @@ export const useChatStore = defineStore('chat', () => {
   const isStreaming = ref(false)
   const sources = ref([])
   const currentQuestion = ref('')
+  const localResults = ref([])
+  // Answered questions of this chat, for export
+  const exchanges = ref([])
 
   let typewriterQueue = []
   let isTyping = false
@@ -26,6 +29,8 @@ export const useChatStore = defineStore('chat', () => {
     isStreaming.value = false
     sources.value = []
     currentQuestion.value = ''
+    localResults.value = []
+    exchanges.value = []
     typewriterQueue = []
     isTyping = false
     isCancelled = false
@@ -50,13 +55,26 @@ export const useChatStore = defineStore('chat', () => {
     requestAnimationFrame(processTypewriterQueue)
   }
 
-  const sendMessage = async (content) => {
+  // Instant keyword results from the local index, shown before the AI answer arrives
+  const fetchLocalResults = async (content, storeIds) => {
+    try {
+      const results = await invoke('search_local', { query: content, storeIds })
+      if (currentQuestion.value === content) {
+        localResults.value = results
+      }
+    } catch (err) {
+      console.warn('Local search failed:', err)
+    }
+  }
+
+  const sendMessage = async (content, preferredStoreNames = []) => {
     if (!content.trim()) return
 
     // Reset state but keep current question
     response.value = ''
     error.value = null
     sources.value = []
+    localResults.value = []
     typewriterQueue = []
     isTyping = false
     isCancelled = false
@@ -64,12 +82,19 @@ export const useChatStore = defineStore('chat', () => {
     currentQuestion.value = content
     isLoading.value = true
 
+    const storesStore = useStoresStore()
+    const localStoreIds = storesStore.stores
+      .filter((s) => !preferredStoreNames.length || preferredStoreNames.includes(s.geminiName))
+      .map((s) => s.id)
+    fetchLocalResults(content, localStoreIds)
+
     try {
-      // Get all store gemini names
-      const storesStore = useStoresStore()
-      const storeNames = storesStore.stores
-        .filter((s) => s.geminiName)
-        .map((s) => s.geminiName)
+      // Use the preferred stores if given, otherwise all store gemini names
+      const storeNames = preferredStoreNames.length
+        ? preferredStoreNames
+        : storesStore.stores
+          .filter((s) => s.geminiName)
+          .map((s) => s.geminiName)
 
       if (storeNames.length === 0) {
         throw new Error('No stores available. Please add documents first.')
@@ -89,6 +114,12 @@ export const useChatStore = defineStore('chat', () => {
       if (result.sources?.length) {
         sources.value = result.sources
       }
+
+      exchanges.value.push({
+        question: content,
+        answer: result.content,
+        sources: result.sources || []
+      })
     } catch (err) {
       if (isCancelled) return
       error.value = err.message || err || 'Failed to send message'
@@ -159,6 +190,8 @@ export const useChatStore = defineStore('chat', () => {
     isStreaming,
     sources,
     currentQuestion,
+    localResults,
+    exchanges,
     suggestQuestions,
     isSuggestLoading,
     suggestError,
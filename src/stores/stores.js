@@ -18,9 +18,13 @@ export const useStoresStore = defineStore('stores', () => {
   // State
   const stores = ref([])
   const storeDocuments = ref({})
+  const documentListings = ref({})
+  // Files rejected by the backend validation in the last upload
+  const rejectedFiles = ref([])
   let unlistenStatusUpdate = null
   let unlistenStoreSync = null
   let unlistenDocumentSync = null
+  let unlistenDocumentMoved = null
 
   // Helpers
   const findStore = (storeId) =>
@@ -50,6 +54,10 @@ export const useStoresStore = defineStore('stores', () => {
             geminiName: s.geminiName,
             subtitle: formatItemCount(s.documentCount || 0),
             icon: markRaw(FolderIcon),
+            // Text icon (e.g. an emoji) and color chosen for the store
+            iconText: s.icon,
+            color: s.color,
+            description: s.description,
             directoryPath: s.directoryPath,
             status: s.status,
             syncStatus: s.syncStatus || 'pending',
@@ -66,6 +74,34 @@ export const useStoresStore = defineStore('stores', () => {
     }
   }
 
+  // Queue individually selected files for upload
+  const uploadFiles = async (storeId, files) => {
+    const fileInfos = files.map((file) => ({
+      name: file.displayName || file.filename || file.name,
+      path: file.path || '',
+      contentType: file.contentType || null,
+      size: file.size ? Number(file.size) : null,
+      hash: file.hash || null
+    }))
+
+    return invoke('upload_documents', { storeId, files: fileInfos })
+  }
+
+  // Import a directory, walked in the backend with the given rules
+  const importDirectory = async (storeId, path, rules) => {
+    const result = await invoke('import_directory', {
+      storeId,
+      path,
+      rules: rules || null
+    })
+
+    // Hidden, excluded and already imported files are skipped on purpose
+    return {
+      documents: result.documents,
+      rejected: result.skipped.filter((file) => file.reason === 'rejected')
+    }
+  }
+
   const addStore = async (storeData) => {
     const name =
       typeof storeData === 'string' ? storeData : storeData.name
@@ -75,41 +111,44 @@ export const useStoresStore = defineStore('stores', () => {
       typeof storeData === 'object'
         ? storeData.directoryPath
         : undefined
+    const backend =
+      typeof storeData === 'object' ? storeData.backend : undefined
+    const localOnly =
+      typeof storeData === 'object' ? storeData.localOnly : false
+
+    let queuedCount = 0
+    rejectedFiles.value = []
 
     try {
       const newStore = await invoke('create_store', {
         title: name,
-        directoryPath
+        directoryPath,
+        backend,
+        localOnly
       })
 
       if (files.length > 0 && newStore.id) {
-        const fileInfos = files.map((file) => ({
-          name: file.displayName || file.filename || file.name,
-          path: file.path || '',
-          contentType: file.contentType || null,
-          size: file.size ? Number(file.size) : null,
-          hash: file.hash || null
-        }))
-
-        const documents = await invoke('upload_documents', {
-          storeId: newStore.id,
-          files: fileInfos
-        })
-
-        storeDocuments.value[newStore.id] = documents
+        const result = directoryPath
+          ? await importDirectory(newStore.id, directoryPath, storeData.importRules)
+          : await uploadFiles(newStore.id, files)
+
+        storeDocuments.value[newStore.id] = result.documents
+        queuedCount = result.documents.length
+        rejectedFiles.value = result.rejected
+
       }
 
       const storeItem = {
         id: newStore.id,
         title: newStore.title,
         geminiName: newStore.geminiName,
-        subtitle: formatItemCount(files.length),
+        subtitle: formatItemCount(queuedCount),
         icon: markRaw(FolderIcon),
         directoryPath: newStore.directoryPath,
-        status: files.length > 0 ? 'processing' : 'completed',
+        status: queuedCount > 0 ? 'processing' : 'completed',
         syncStatus: newStore.syncStatus || 'pending',
-        documentCount: files.length,
-        pendingCount: files.length,
+        documentCount: queuedCount,
+        pendingCount: queuedCount,
         failedCount: 0
       }
 
@@ -130,6 +169,7 @@ export const useStoresStore = defineStore('stores', () => {
       if (index !== -1) {
         stores.value.splice(index, 1)
         delete storeDocuments.value[item.id]
+        delete documentListings.value[item.id]
       }
       return true
     } catch (e) {
@@ -138,24 +178,74 @@ export const useStoresStore = defineStore('stores', () => {
     }
   }
 
-  const loadStoreDocuments = async (storeId) => {
-    try {
-      const documents = await invoke('get_documents', { storeId })
-      storeDocuments.value[storeId] = documents || []
-
-      const store = findStore(storeId)
-      if (store) {
-        store.subtitle = formatItemCount(documents.length)
-        store.documentCount = documents.length
-        store.pendingCount = documents.filter(
-          (d) => !isTerminalStatus(d.status)
-        ).length
-        store.failedCount = documents.filter(
-          (d) => d.status === 'failed'
-        ).length
-        recalculateStoreStatus(store)
+  // Save a store's title, folder, description, color and icon; errors are thrown to the form
+  const updateStore = async (storeId, { title, directoryPath, description, color, icon }) => {
+    const updated = await invoke('update_store', {
+      id: storeId,
+      update: { title, directoryPath, description, color, icon }
+    })
+    await loadStores()
+    return updated
+  }
+
+  // Move or copy documents to another store; the copies are uploaded by sync
+  const transferDocuments = async (mode, documentIds, targetStoreId) => {
+    const command = mode === 'move' ? 'move_documents' : 'copy_documents'
+    const result = await invoke(command, { ids: documentIds, targetStoreId })
+    await loadStores()
+    if (storeDocuments.value[targetStoreId]) {
+      await loadStoreDocuments(targetStoreId)
+    }
+    return result
+  }
+
+  // Listing of a store's documents: query, cursor of the next page and counts per status
+  const getListing = (storeId) => {
+    if (!documentListings.value[storeId]) {
+      documentListings.value[storeId] = {
+        query: { sort: 'created', descending: false, status: null, name: null },
+        nextCursor: null,
+        total: 0,
+        statusCounts: {}
       }
+    }
+    return documentListings.value[storeId]
+  }
+
+  // Store counts follow the listing's counts per status, unless a name filter narrows them
+  const applyStatusCounts = (storeId) => {
+    const store = findStore(storeId)
+    const listing = documentListings.value[storeId]
+    if (!store || !listing || listing.query.name) return
+
+    const counts = Object.entries(listing.statusCounts)
+    store.documentCount = counts.reduce((sum, [, count]) => sum + count, 0)
+    store.subtitle = formatItemCount(store.documentCount)
+    store.pendingCount = counts
+      .filter(([status]) => !isTerminalStatus(status))
+      .reduce((sum, [, count]) => sum + count, 0)
+    store.failedCount = listing.statusCounts.failed || 0
+    recalculateStoreStatus(store)
+  }
+
+  const fetchDocumentPage = async (storeId, cursor) => {
+    const listing = getListing(storeId)
+    const page = await invoke('list_documents', {
+      storeId,
+      query: { ...listing.query, cursor }
+    })
+    listing.nextCursor = page.nextCursor
+    listing.total = page.total
+    listing.statusCounts = page.statusCounts
+    applyStatusCounts(storeId)
+    return page.documents
+  }
 
+  // Load the first page of a store's documents
+  const loadStoreDocuments = async (storeId) => {
+    try {
+      const documents = await fetchDocumentPage(storeId, null)
+      storeDocuments.value[storeId] = documents
       return documents
     } catch (e) {
       console.error('Failed to load store documents:', e)
@@ -164,22 +254,40 @@ export const useStoresStore = defineStore('stores', () => {
     }
   }
 
+  // Append the next page of a store's documents
+  const loadMoreStoreDocuments = async (storeId) => {
+    const listing = getListing(storeId)
+    if (!listing.nextCursor) return getStoreDocuments(storeId)
+
+    try {
+      const documents = await fetchDocumentPage(storeId, listing.nextCursor)
+      storeDocuments.value[storeId] = [...getStoreDocuments(storeId), ...documents]
+    } catch (e) {
+      console.error('Failed to load more store documents:', e)
+    }
+    return getStoreDocuments(storeId)
+  }
+
+  // Change the sort or filters of a store's listing and reload its first page
+  const setDocumentQuery = async (storeId, query) => {
+    const listing = getListing(storeId)
+    listing.query = { ...listing.query, ...query }
+    return loadStoreDocuments(storeId)
+  }
+
   const getStoreDocuments = (storeId) => {
     return storeDocuments.value[storeId] || []
   }
 
-  const updateStoreFromDocuments = (storeId) => {
-    const store = findStore(storeId)
-    const documents = storeDocuments.value[storeId]
-    if (store && documents) {
-      store.pendingCount = documents.filter(
-        (d) => !isTerminalStatus(d.status)
-      ).length
-      store.failedCount = documents.filter(
-        (d) => d.status === 'failed'
-      ).length
-      recalculateStoreStatus(store)
-    }
+  // Keep the counts per status in step with a document's new status
+  const moveStatusCount = (storeId, from, to) => {
+    const listing = documentListings.value[storeId]
+    if (!listing || from === to) return
+
+    const counts = listing.statusCounts
+    if (counts[from]) counts[from] -= 1
+    counts[to] = (counts[to] || 0) + 1
+    applyStatusCounts(storeId)
   }
 
   const updateStoreCountsDirectly = (storeId, status) => {
@@ -198,12 +306,12 @@ export const useStoresStore = defineStore('stores', () => {
     if (documents) {
       const doc = documents.find((d) => d.id === documentId)
       if (doc) {
+        moveStatusCount(storeId, doc.status, newStatus)
         doc.status = newStatus
         if (geminiName) {
           doc.geminiName = geminiName
         }
       }
-      updateStoreFromDocuments(storeId)
     } else if (isTerminalStatus(newStatus)) {
       updateStoreCountsDirectly(storeId, newStatus)
     }
@@ -230,13 +338,13 @@ export const useStoresStore = defineStore('stores', () => {
     if (documents) {
       const doc = documents.find((d) => d.id === documentId)
       if (doc) {
+        moveStatusCount(storeId, doc.status, status)
         doc.syncStatus = syncStatus
         doc.status = status
         if (geminiName) {
           doc.geminiName = geminiName
         }
       }
-      updateStoreFromDocuments(storeId)
     } else if (isTerminalStatus(status)) {
       updateStoreCountsDirectly(storeId, status)
     }
@@ -266,6 +374,16 @@ export const useStoresStore = defineStore('stores', () => {
         status
       )
     })
+
+    // The source of a move went to the trash once its copy was uploaded
+    unlistenDocumentMoved = await listen('document-moved', (event) => {
+      const { sourceStoreId } = event.payload
+      if (sourceStoreId && storeDocuments.value[sourceStoreId]) {
+        loadStoreDocuments(sourceStoreId)
+      } else {
+        loadStores()
+      }
+    })
   }
 
   const cleanupStatusListener = () => {
@@ -281,17 +399,34 @@ export const useStoresStore = defineStore('stores', () => {
       unlistenDocumentSync()
       unlistenDocumentSync = null
     }
+    if (unlistenDocumentMoved) {
+      unlistenDocumentMoved()
+      unlistenDocumentMoved = null
+    }
+  }
+
+  // Dismiss the rejections of the last upload once the user has seen them
+  const clearRejectedFiles = () => {
+    rejectedFiles.value = []
   }
 
   return {
     // State
     stores,
     storeDocuments,
+    documentListings,
+    rejectedFiles,
     // Actions
     loadStores,
     addStore,
+    clearRejectedFiles,
+    updateStore,
     deleteStore,
+    transferDocuments,
     loadStoreDocuments,
+    loadMoreStoreDocuments,
+    setDocumentQuery,
+    getListing,
     getStoreDocuments,
     updateDocumentStatus,
     setupStatusListener,
@@ -85,6 +85,20 @@ export function formatItemCount(count) {
   return `${count} ${count === 1 ? 'item' : 'items'}`
 }
 
+/**
+ * Get a document's tags from its custom metadata
+ * @param {Object} document - Document with customMetadata as a JSON array
+ * @returns {string[]} Tags, empty when there are none
+ */
+export function getDocumentTags(document) {
+  try {
+    const entries = JSON.parse(document.customMetadata || '[]')
+    return entries.find((entry) => entry.key === 'tags')?.stringListValue?.values || []
+  } catch {
+    return []
+  }
+}
+
 /**
  * Get friendly file type from MIME type
  * @param {string} mimeType - MIME type string
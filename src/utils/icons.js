@@ -527,3 +527,93 @@ export const DownloadIcon = {
       ]
     )
 }
+
+export const ServerIcon = {
+  render: () =>
+    h(
+      'svg',
+      {
+        width: 20,
+        height: 20,
+        viewBox: '0 0 24 24',
+        fill: 'none',
+        stroke: 'currentColor',
+        'stroke-width': 2,
+        'stroke-linecap': 'round',
+        'stroke-linejoin': 'round'
+      },
+      [
+        h('rect', { width: 20, height: 8, x: 2, y: 2, rx: 2, ry: 2 }),
+        h('rect', { width: 20, height: 8, x: 2, y: 14, rx: 2, ry: 2 }),
+        h('line', { x1: 6, x2: 6.01, y1: 6, y2: 6 }),
+        h('line', { x1: 6, x2: 6.01, y1: 18, y2: 18 })
+      ]
+    )
+}
+
+export const PlugIcon = {
+  render: () =>
+    h(
+      'svg',
+      {
+        width: 20,
+        height: 20,
+        viewBox: '0 0 24 24',
+        fill: 'none',
+        stroke: 'currentColor',
+        'stroke-width': 2,
+        'stroke-linecap': 'round',
+        'stroke-linejoin': 'round'
+      },
+      [
+        h('path', { d: 'M12 22v-5' }),
+        h('path', { d: 'M9 8V2' }),
+        h('path', { d: 'M15 8V2' }),
+        h('path', { d: 'M18 8v5a4 4 0 0 1-4 4h-4a4 4 0 0 1-4-4V8Z' })
+      ]
+    )
+}
+
+export const DatabaseIcon = {
+  render: () =>
+    h(
+      'svg',
+      {
+        width: 20,
+        height: 20,
+        viewBox: '0 0 24 24',
+        fill: 'none',
+        stroke: 'currentColor',
+        'stroke-width': 2,
+        'stroke-linecap': 'round',
+        'stroke-linejoin': 'round'
+      },
+      [
+        h('ellipse', { cx: 12, cy: 5, rx: 9, ry: 3 }),
+        h('path', { d: 'M3 5V19A9 3 0 0 0 21 19V5' }),
+        h('path', { d: 'M3 12A9 3 0 0 0 21 12' })
+      ]
+    )
+}
+
+export const TrashIcon = {
+  render: () =>
+    h(
+      'svg',
+      {
+        width: 20,
+        height: 20,
+        viewBox: '0 0 24 24',
+        fill: 'none',
+        stroke: 'currentColor',
+        'stroke-width': 2,
+        'stroke-linecap': 'round',
+        'stroke-linejoin': 'round'
+      },
+      [
+        h('path', { d: 'M3 6h18' }),
+        h('path', { d: 'M19 6v14c0 1-1 2-2 2H7c-1 0-2-1-2-2V6' }),
+        h('path', { d: 'M8 6V4c0-1 1-2 2-2h4c1 0 2 1 2 2v2' })
+      ]
+    )
+}